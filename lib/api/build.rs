@@ -131,8 +131,14 @@ fn configure_validation(builder: Builder) -> Builder {
             ("UpdatePointVectors.vectors", "custom(function = \"crate::grpc::validate::validate_named_vectors_not_empty\", message = \"must specify vectors to update\")"),
             ("DeletePointVectors.collection_name", "length(min = 1, max = 255)"),
             ("DeletePointVectors.vector_names", "length(min = 1, message = \"must specify vector names to delete\")"),
+            ("ReindexPointVectors.collection_name", "length(min = 1, max = 255)"),
+            ("ConcatPointVectors.collection_name", "length(min = 1, max = 255)"),
+            ("ConcatPointVectors.sources", "length(min = 1, message = \"must specify vectors to concatenate\")"),
+            ("RepairPointVectors.collection_name", "length(min = 1, max = 255)"),
+            ("SetDefaultPointVectors.collection_name", "length(min = 1, max = 255)"),
             ("GetPoints.collection_name", "length(min = 1, max = 255)"),
             ("SetPayloadPoints.collection_name", "length(min = 1, max = 255)"),
+            ("SetNestedPayloadPoints.collection_name", "length(min = 1, max = 255)"),
             ("DeletePayloadPoints.collection_name", "length(min = 1, max = 255)"),
             ("ClearPayloadPoints.collection_name", "length(min = 1, max = 255)"),
             ("CreateFieldIndexCollection.collection_name", "length(min = 1, max = 255)"),
@@ -142,6 +148,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("SearchPoints.collection_name", "length(min = 1, max = 255)"),
             ("SearchPoints.limit", "range(min = 1)"),
             ("SearchPoints.vector_name", "custom = \"crate::grpc::validate::validate_not_empty\""),
+            ("SearchPoints.filter", ""),
             ("SearchBatchPoints.collection_name", "length(min = 1, max = 255)"),
             ("SearchBatchPoints.search_points", ""),
             ("SearchPointGroups.collection_name", "length(min = 1, max = 255)"),
@@ -151,6 +158,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("SearchPointGroups.limit", "range(min = 1)"),
             ("ScrollPoints.collection_name", "length(min = 1, max = 255)"),
             ("ScrollPoints.limit", "custom = \"crate::grpc::validate::validate_u32_range_min_1\""),
+            ("ScrollPoints.filter", ""),
             ("RecommendPoints.collection_name", "length(min = 1, max = 255)"),
             ("RecommendBatchPoints.collection_name", "length(min = 1, max = 255)"),
             ("RecommendBatchPoints.recommend_points", ""),
@@ -159,6 +167,7 @@ fn configure_validation(builder: Builder) -> Builder {
             ("RecommendPointGroups.group_size", "range(min = 1)"),
             ("RecommendPointGroups.limit", "range(min = 1)"),
             ("CountPoints.collection_name", "length(min = 1, max = 255)"),
+            ("CountPoints.filter", ""),
         ], &[])
         .type_attribute("NamedVectors", "#[derive(serde::Serialize)]")
         .type_attribute("Vector", "#[derive(serde::Serialize)]")
@@ -168,7 +177,12 @@ fn configure_validation(builder: Builder) -> Builder {
             ("DeletePointsInternal.delete_points", ""),
             ("UpdateVectorsInternal.update_vectors", ""),
             ("DeleteVectorsInternal.delete_vectors", ""),
+            ("ReindexVectorsInternal.reindex_vectors", ""),
+            ("ConcatVectorsInternal.concat_vectors", ""),
+            ("RepairVectorsInternal.repair_vectors", ""),
+            ("SetDefaultVectorInternal.set_default_vector", ""),
             ("SetPayloadPointsInternal.set_payload_points", ""),
+            ("SetNestedPayloadPointsInternal.set_nested_payload_points", ""),
             ("DeletePayloadPointsInternal.delete_payload_points", ""),
             ("ClearPayloadPointsInternal.clear_payload_points", ""),
             ("CreateFieldIndexCollectionInternal.create_field_index_collection", ""),
@@ -15,20 +15,25 @@ use crate::grpc::models::{CollectionsResponse, VersionInfo};
 use crate::grpc::qdrant::condition::ConditionOneOf;
 use crate::grpc::qdrant::payload_index_params::IndexParams;
 use crate::grpc::qdrant::point_id::PointIdOptions;
+use crate::grpc::qdrant::position_condition::Position;
 use crate::grpc::qdrant::r#match::MatchValue;
 use crate::grpc::qdrant::value::Kind;
 use crate::grpc::qdrant::vectors::VectorsOptions;
 use crate::grpc::qdrant::with_payload_selector::SelectorOptions;
 use crate::grpc::qdrant::{
-    with_vectors_selector, CollectionDescription, CollectionOperationResponse, Condition, Distance,
-    FieldCondition, Filter, GeoBoundingBox, GeoPoint, GeoRadius, GroupId, HasIdCondition,
-    HealthCheckReply, HnswConfigDiff, IsEmptyCondition, IsNullCondition, ListCollectionsResponse,
-    ListValue, Match, NamedVectors, NestedCondition, PayloadExcludeSelector,
+    with_vectors_selector, AggregateCondition, AggregateFunction, ArithmeticOperator,
+    CollationLocale, CollectionDescription, CollectionOperationResponse, ComparisonCondition,
+    ComparisonOperator, Condition, Distance, ExpressionCondition, FieldCondition, Filter,
+    FractionRange, GeoBoundingBox, GeoPoint, GeoRadius, GroupId, HasDuplicatesCondition,
+    HasIdCondition, HasKeyCondition, HealthCheckReply, HnswConfigDiff, InnerArrayMatch,
+    IsEmptyCondition, IsNullCondition, JsonValueType, ListCollectionsResponse, ListValue, Match,
+    NamedVectors, NestedCondition, NestedNullPolicy, NestedQuantifier, PayloadExcludeSelector,
     PayloadIncludeSelector, PayloadIndexParams, PayloadSchemaInfo, PayloadSchemaType, PointGroup,
-    PointId, QuantizationConfig, QuantizationSearchParams, Range, RepeatedIntegers,
-    RepeatedStrings, ScalarQuantization, ScoredPoint, SearchParams, Struct, TextIndexParams,
-    TokenizerType, Value, ValuesCount, Vector, Vectors, VectorsSelector, WithPayloadSelector,
-    WithVectorsSelector,
+    PointId, PositionCondition, QuantizationConfig, QuantizationSearchParams, Range,
+    RepeatedIntegers, RepeatedStrings, ScalarQuantization, ScoredPoint, SearchParams,
+    SequenceMarker, Struct, TextIndexParams, TokenizerType, Value, ValueTypeCondition, ValuesCount,
+    Vector, VectorCountCondition, VectorNotNormalizedCondition, Vectors, VectorsSelector,
+    WithPayloadSelector, WithVectorsSelector,
 };
 
 pub fn payload_to_proto(payload: segment::types::Payload) -> HashMap<String, Value> {
@@ -686,9 +691,36 @@ impl TryFrom<Condition> for segment::types::Condition {
                 ConditionOneOf::IsNull(is_null) => {
                     Ok(segment::types::Condition::IsNull(is_null.into()))
                 }
+                ConditionOneOf::HasKey(has_key) => {
+                    Ok(segment::types::Condition::HasKey(has_key.into()))
+                }
                 ConditionOneOf::Nested(nested) => Ok(segment::types::Condition::Nested(
                     NestedContainer::new(nested.try_into()?),
                 )),
+                ConditionOneOf::VectorCount(vector_count) => Ok(
+                    segment::types::Condition::VectorCount(vector_count.try_into()?),
+                ),
+                ConditionOneOf::VectorNotNormalized(vector_not_normalized) => Ok(
+                    segment::types::Condition::VectorNotNormalized(vector_not_normalized.into()),
+                ),
+                ConditionOneOf::Aggregate(aggregate) => {
+                    Ok(segment::types::Condition::Aggregate(aggregate.try_into()?))
+                }
+                ConditionOneOf::Expression(expression) => Ok(
+                    segment::types::Condition::Expression(expression.try_into()?),
+                ),
+                ConditionOneOf::HasDuplicates(has_duplicates) => Ok(
+                    segment::types::Condition::HasDuplicates(has_duplicates.into()),
+                ),
+                ConditionOneOf::ValueType(value_type) => {
+                    Ok(segment::types::Condition::ValueType(value_type.try_into()?))
+                }
+                ConditionOneOf::Position(position) => {
+                    Ok(segment::types::Condition::Position(position.try_into()?))
+                }
+                ConditionOneOf::Compare(compare) => {
+                    Ok(segment::types::Condition::Compare(compare.try_into()?))
+                }
             };
         }
         Err(Status::invalid_argument("Malformed Condition type"))
@@ -703,7 +735,30 @@ impl From<segment::types::Condition> for Condition {
                 ConditionOneOf::IsEmpty(is_empty.into())
             }
             segment::types::Condition::IsNull(is_null) => ConditionOneOf::IsNull(is_null.into()),
+            segment::types::Condition::HasKey(has_key) => ConditionOneOf::HasKey(has_key.into()),
             segment::types::Condition::HasId(has_id) => ConditionOneOf::HasId(has_id.into()),
+            segment::types::Condition::VectorCount(vector_count) => {
+                ConditionOneOf::VectorCount(vector_count.into())
+            }
+            segment::types::Condition::VectorNotNormalized(vector_not_normalized) => {
+                ConditionOneOf::VectorNotNormalized(vector_not_normalized.into())
+            }
+            segment::types::Condition::Aggregate(aggregate) => {
+                ConditionOneOf::Aggregate(aggregate.into())
+            }
+            segment::types::Condition::Expression(expression) => {
+                ConditionOneOf::Expression(expression.into())
+            }
+            segment::types::Condition::Compare(compare) => ConditionOneOf::Compare(compare.into()),
+            segment::types::Condition::HasDuplicates(has_duplicates) => {
+                ConditionOneOf::HasDuplicates(has_duplicates.into())
+            }
+            segment::types::Condition::ValueType(value_type) => {
+                ConditionOneOf::ValueType(value_type.into())
+            }
+            segment::types::Condition::Position(position) => {
+                ConditionOneOf::Position(position.into())
+            }
             segment::types::Condition::Filter(filter) => ConditionOneOf::Filter(filter.into()),
             segment::types::Condition::Nested(nested) => {
                 ConditionOneOf::Nested(nested.nested.into())
@@ -716,6 +771,286 @@ impl From<segment::types::Condition> for Condition {
     }
 }
 
+impl TryFrom<VectorCountCondition> for segment::types::VectorCountCondition {
+    type Error = Status;
+
+    fn try_from(value: VectorCountCondition) -> Result<Self, Self::Error> {
+        match value.vector_count {
+            None => Err(Status::invalid_argument(
+                "Vector count condition must have a range",
+            )),
+            Some(vector_count) => Ok(Self {
+                vector_count: vector_count.into(),
+            }),
+        }
+    }
+}
+
+impl From<segment::types::VectorCountCondition> for VectorCountCondition {
+    fn from(value: segment::types::VectorCountCondition) -> Self {
+        Self {
+            vector_count: Some(value.vector_count.into()),
+        }
+    }
+}
+
+impl From<VectorNotNormalizedCondition> for segment::types::VectorNotNormalizedCondition {
+    fn from(value: VectorNotNormalizedCondition) -> Self {
+        Self::new(value.name, value.tolerance)
+    }
+}
+
+impl From<segment::types::VectorNotNormalizedCondition> for VectorNotNormalizedCondition {
+    fn from(value: segment::types::VectorNotNormalizedCondition) -> Self {
+        let segment::types::VectorNotNormalized { name, tolerance } = value.vector_not_normalized;
+        Self { name, tolerance }
+    }
+}
+
+impl TryFrom<ValueTypeCondition> for segment::types::ValueTypeCondition {
+    type Error = Status;
+
+    fn try_from(value: ValueTypeCondition) -> Result<Self, Self::Error> {
+        let r#type = match JsonValueType::from_i32(value.r#type) {
+            Some(JsonValueType::JsonNull) => segment::types::JsonValueType::Null,
+            Some(JsonValueType::JsonBool) => segment::types::JsonValueType::Bool,
+            Some(JsonValueType::JsonNumber) => segment::types::JsonValueType::Number,
+            Some(JsonValueType::JsonString) => segment::types::JsonValueType::String,
+            Some(JsonValueType::JsonArray) => segment::types::JsonValueType::Array,
+            Some(JsonValueType::JsonObject) => segment::types::JsonValueType::Object,
+            Some(JsonValueType::UnknownJsonValueType) | None => {
+                return Err(Status::invalid_argument("Unknown JSON value type"))
+            }
+        };
+        Ok(Self::new(value.key, r#type))
+    }
+}
+
+impl From<segment::types::ValueTypeCondition> for ValueTypeCondition {
+    fn from(value: segment::types::ValueTypeCondition) -> Self {
+        let segment::types::ValueType { key, r#type } = value.value_type;
+        let r#type = match r#type {
+            segment::types::JsonValueType::Null => JsonValueType::JsonNull,
+            segment::types::JsonValueType::Bool => JsonValueType::JsonBool,
+            segment::types::JsonValueType::Number => JsonValueType::JsonNumber,
+            segment::types::JsonValueType::String => JsonValueType::JsonString,
+            segment::types::JsonValueType::Array => JsonValueType::JsonArray,
+            segment::types::JsonValueType::Object => JsonValueType::JsonObject,
+        };
+        Self {
+            key,
+            r#type: r#type as i32,
+        }
+    }
+}
+
+impl TryFrom<PositionCondition> for segment::types::PositionCondition {
+    type Error = Status;
+
+    fn try_from(value: PositionCondition) -> Result<Self, Self::Error> {
+        let count = |n: u64| usize::try_from(n).unwrap_or(usize::MAX);
+        let position = match value.position {
+            Some(Position::Fraction(FractionRange { from, to })) => {
+                segment::types::PositionRange::Fraction(segment::types::FractionRange { from, to })
+            }
+            Some(Position::First(n)) => segment::types::PositionRange::First(count(n)),
+            Some(Position::Last(n)) => segment::types::PositionRange::Last(count(n)),
+            None => return Err(Status::invalid_argument("Malformed PositionCondition type")),
+        };
+        Ok(position.into())
+    }
+}
+
+impl From<segment::types::PositionCondition> for PositionCondition {
+    fn from(value: segment::types::PositionCondition) -> Self {
+        let position = match value.position {
+            segment::types::PositionRange::Fraction(segment::types::FractionRange { from, to }) => {
+                Position::Fraction(FractionRange { from, to })
+            }
+            segment::types::PositionRange::First(n) => Position::First(n as u64),
+            segment::types::PositionRange::Last(n) => Position::Last(n as u64),
+        };
+        Self {
+            position: Some(position),
+        }
+    }
+}
+
+impl TryFrom<AggregateCondition> for segment::types::AggregateCondition {
+    type Error = Status;
+
+    fn try_from(value: AggregateCondition) -> Result<Self, Self::Error> {
+        let function = match AggregateFunction::from_i32(value.function) {
+            Some(AggregateFunction::Sum) => segment::types::AggregateFunction::Sum,
+            Some(AggregateFunction::Min) => segment::types::AggregateFunction::Min,
+            Some(AggregateFunction::Max) => segment::types::AggregateFunction::Max,
+            Some(AggregateFunction::Avg) => segment::types::AggregateFunction::Avg,
+            Some(AggregateFunction::DistinctCount) => {
+                segment::types::AggregateFunction::DistinctCount
+            }
+            Some(AggregateFunction::UnknownAggregateFunction) | None => {
+                return Err(Status::invalid_argument("Unknown aggregate function"))
+            }
+        };
+        match value.range {
+            None => Err(Status::invalid_argument(
+                "Aggregate condition must have a range",
+            )),
+            Some(range) => Ok(Self {
+                aggregate: segment::types::Aggregate {
+                    key: value.key,
+                    function,
+                    range: range.into(),
+                },
+            }),
+        }
+    }
+}
+
+impl From<segment::types::AggregateCondition> for AggregateCondition {
+    fn from(value: segment::types::AggregateCondition) -> Self {
+        let segment::types::Aggregate {
+            key,
+            function,
+            range,
+        } = value.aggregate;
+        let function = match function {
+            segment::types::AggregateFunction::Sum => AggregateFunction::Sum,
+            segment::types::AggregateFunction::Min => AggregateFunction::Min,
+            segment::types::AggregateFunction::Max => AggregateFunction::Max,
+            segment::types::AggregateFunction::Avg => AggregateFunction::Avg,
+            segment::types::AggregateFunction::DistinctCount => AggregateFunction::DistinctCount,
+        };
+        Self {
+            key,
+            function: function as i32,
+            range: Some(range.into()),
+        }
+    }
+}
+
+impl TryFrom<ExpressionCondition> for segment::types::ExpressionCondition {
+    type Error = Status;
+
+    fn try_from(value: ExpressionCondition) -> Result<Self, Self::Error> {
+        let operator = match ArithmeticOperator::from_i32(value.operator) {
+            Some(ArithmeticOperator::Add) => segment::types::ArithmeticOperator::Add,
+            Some(ArithmeticOperator::Sub) => segment::types::ArithmeticOperator::Sub,
+            Some(ArithmeticOperator::Mul) => segment::types::ArithmeticOperator::Mul,
+            Some(ArithmeticOperator::Div) => segment::types::ArithmeticOperator::Div,
+            Some(ArithmeticOperator::UnknownArithmeticOperator) | None => {
+                return Err(Status::invalid_argument("Unknown arithmetic operator"))
+            }
+        };
+        match value.range {
+            None => Err(Status::invalid_argument(
+                "Expression condition must have a range",
+            )),
+            Some(range) => Ok(Self {
+                expression: segment::types::Expression {
+                    left: value.left,
+                    operator,
+                    right: value.right,
+                    range: range.into(),
+                },
+            }),
+        }
+    }
+}
+
+impl From<segment::types::ExpressionCondition> for ExpressionCondition {
+    fn from(value: segment::types::ExpressionCondition) -> Self {
+        let segment::types::Expression {
+            left,
+            operator,
+            right,
+            range,
+        } = value.expression;
+        let operator = match operator {
+            segment::types::ArithmeticOperator::Add => ArithmeticOperator::Add,
+            segment::types::ArithmeticOperator::Sub => ArithmeticOperator::Sub,
+            segment::types::ArithmeticOperator::Mul => ArithmeticOperator::Mul,
+            segment::types::ArithmeticOperator::Div => ArithmeticOperator::Div,
+        };
+        Self {
+            left,
+            operator: operator as i32,
+            right,
+            range: Some(range.into()),
+        }
+    }
+}
+
+impl TryFrom<ComparisonCondition> for segment::types::ComparisonCondition {
+    type Error = Status;
+
+    fn try_from(value: ComparisonCondition) -> Result<Self, Self::Error> {
+        let operator = match ComparisonOperator::from_i32(value.operator) {
+            Some(ComparisonOperator::Eq) => segment::types::ComparisonOperator::Eq,
+            Some(ComparisonOperator::Ne) => segment::types::ComparisonOperator::Ne,
+            Some(ComparisonOperator::Lt) => segment::types::ComparisonOperator::Lt,
+            Some(ComparisonOperator::Lte) => segment::types::ComparisonOperator::Lte,
+            Some(ComparisonOperator::Gt) => segment::types::ComparisonOperator::Gt,
+            Some(ComparisonOperator::Gte) => segment::types::ComparisonOperator::Gte,
+            Some(ComparisonOperator::UnknownComparisonOperator) | None => {
+                return Err(Status::invalid_argument("Unknown comparison operator"))
+            }
+        };
+        let collation = value
+            .collation
+            .map(|collation| match CollationLocale::from_i32(collation) {
+                Some(CollationLocale::RootCollation) => Ok(segment::types::CollationLocale::Root),
+                Some(CollationLocale::GermanCollation) => Ok(segment::types::CollationLocale::De),
+                Some(CollationLocale::SpanishCollation) => Ok(segment::types::CollationLocale::Es),
+                Some(CollationLocale::SwedishCollation) => Ok(segment::types::CollationLocale::Sv),
+                None => Err(Status::invalid_argument("Unknown collation locale")),
+            })
+            .transpose()?;
+        Ok(Self {
+            compare: segment::types::Comparison {
+                left: value.left,
+                operator,
+                right: value.right,
+                collation,
+            },
+        })
+    }
+}
+
+impl From<segment::types::ComparisonCondition> for ComparisonCondition {
+    fn from(value: segment::types::ComparisonCondition) -> Self {
+        let segment::types::Comparison {
+            left,
+            operator,
+            right,
+            collation,
+        } = value.compare;
+        let operator = match operator {
+            segment::types::ComparisonOperator::Eq => ComparisonOperator::Eq,
+            segment::types::ComparisonOperator::Ne => ComparisonOperator::Ne,
+            segment::types::ComparisonOperator::Lt => ComparisonOperator::Lt,
+            segment::types::ComparisonOperator::Lte => ComparisonOperator::Lte,
+            segment::types::ComparisonOperator::Gt => ComparisonOperator::Gt,
+            segment::types::ComparisonOperator::Gte => ComparisonOperator::Gte,
+        };
+        let collation = collation.map(|collation| {
+            let collation = match collation {
+                segment::types::CollationLocale::Root => CollationLocale::RootCollation,
+                segment::types::CollationLocale::De => CollationLocale::GermanCollation,
+                segment::types::CollationLocale::Es => CollationLocale::SpanishCollation,
+                segment::types::CollationLocale::Sv => CollationLocale::SwedishCollation,
+            };
+            collation as i32
+        });
+        Self {
+            left,
+            operator: operator as i32,
+            right,
+            collation,
+        }
+    }
+}
+
 impl TryFrom<NestedCondition> for segment::types::NestedCondition {
     type Error = Status;
 
@@ -724,19 +1059,87 @@ impl TryFrom<NestedCondition> for segment::types::NestedCondition {
             None => Err(Status::invalid_argument(
                 "Nested condition must have a filter",
             )),
-            Some(filter) => Ok(Self {
-                key: value.key,
-                filter: filter.try_into()?,
-            }),
+            Some(filter) => {
+                let quantifier = value
+                    .quantifier
+                    .map(|quantifier| match NestedQuantifier::from_i32(quantifier) {
+                        Some(NestedQuantifier::AnyElement) => {
+                            Ok(segment::types::NestedQuantifier::Any)
+                        }
+                        Some(NestedQuantifier::AllElements) => {
+                            Ok(segment::types::NestedQuantifier::All)
+                        }
+                        Some(NestedQuantifier::AllElementsOrEmpty) => {
+                            Ok(segment::types::NestedQuantifier::AllOrEmpty)
+                        }
+                        None => Err(Status::invalid_argument("Unknown nested quantifier")),
+                    })
+                    .transpose()?;
+                let quantifier = match (quantifier, value.min_ratio) {
+                    (Some(_), Some(_)) => {
+                        return Err(Status::invalid_argument(
+                            "Nested condition can't have both quantifier and min_ratio",
+                        ))
+                    }
+                    (None, Some(min_ratio)) => {
+                        Some(segment::types::NestedQuantifier::MinRatio(min_ratio))
+                    }
+                    (quantifier, None) => quantifier,
+                };
+                Ok(Self {
+                    key: value.key,
+                    filter: filter.try_into()?,
+                    index: value
+                        .index
+                        .map(|index| isize::try_from(index).unwrap_or(isize::MAX)),
+                    after: value.after.map(Into::into),
+                    quantifier,
+                })
+            }
         }
     }
 }
 
 impl From<segment::types::NestedCondition> for NestedCondition {
     fn from(value: segment::types::NestedCondition) -> Self {
+        let (quantifier, min_ratio) = match value.quantifier {
+            None => (None, None),
+            Some(segment::types::NestedQuantifier::Any) => {
+                (Some(NestedQuantifier::AnyElement), None)
+            }
+            Some(segment::types::NestedQuantifier::All) => {
+                (Some(NestedQuantifier::AllElements), None)
+            }
+            Some(segment::types::NestedQuantifier::AllOrEmpty) => {
+                (Some(NestedQuantifier::AllElementsOrEmpty), None)
+            }
+            Some(segment::types::NestedQuantifier::MinRatio(min_ratio)) => (None, Some(min_ratio)),
+        };
         Self {
             key: value.key,
             filter: Some(value.filter.into()),
+            index: value.index.map(|index| index as i64),
+            after: value.after.map(Into::into),
+            quantifier: quantifier.map(|quantifier| quantifier as i32),
+            min_ratio,
+        }
+    }
+}
+
+impl From<SequenceMarker> for segment::types::SequenceMarker {
+    fn from(value: SequenceMarker) -> Self {
+        Self {
+            key: value.key,
+            value: value.value,
+        }
+    }
+}
+
+impl From<segment::types::SequenceMarker> for SequenceMarker {
+    fn from(value: segment::types::SequenceMarker) -> Self {
+        Self {
+            key: value.key,
+            value: value.value,
         }
     }
 }
@@ -773,6 +1176,38 @@ impl From<segment::types::IsNullCondition> for IsNullCondition {
     }
 }
 
+impl From<HasKeyCondition> for segment::types::HasKeyCondition {
+    fn from(value: HasKeyCondition) -> Self {
+        segment::types::HasKeyCondition {
+            has_key: segment::types::PayloadField { key: value.key },
+        }
+    }
+}
+
+impl From<segment::types::HasKeyCondition> for HasKeyCondition {
+    fn from(value: segment::types::HasKeyCondition) -> Self {
+        Self {
+            key: value.has_key.key,
+        }
+    }
+}
+
+impl From<HasDuplicatesCondition> for segment::types::HasDuplicatesCondition {
+    fn from(value: HasDuplicatesCondition) -> Self {
+        segment::types::HasDuplicatesCondition {
+            has_duplicates: segment::types::PayloadField { key: value.key },
+        }
+    }
+}
+
+impl From<segment::types::HasDuplicatesCondition> for HasDuplicatesCondition {
+    fn from(value: segment::types::HasDuplicatesCondition) -> Self {
+        Self {
+            key: value.has_duplicates.key,
+        }
+    }
+}
+
 impl TryFrom<HasIdCondition> for segment::types::HasIdCondition {
     type Error = Status;
 
@@ -804,11 +1239,34 @@ impl TryFrom<FieldCondition> for segment::types::FieldCondition {
             geo_bounding_box,
             geo_radius,
             values_count,
+            null_policy,
+            inner_match,
         } = value;
 
         let geo_bounding_box =
             geo_bounding_box.map_or_else(|| Ok(None), |g| g.try_into().map(Some))?;
         let geo_radius = geo_radius.map_or_else(|| Ok(None), |g| g.try_into().map(Some))?;
+        let null_policy = null_policy
+            .map(
+                |null_policy| match NestedNullPolicy::from_i32(null_policy) {
+                    Some(NestedNullPolicy::NullNoMatch) => {
+                        Ok(segment::types::NestedNullPolicy::NoMatch)
+                    }
+                    Some(NestedNullPolicy::NullSkip) => Ok(segment::types::NestedNullPolicy::Skip),
+                    Some(NestedNullPolicy::NullError) => {
+                        Ok(segment::types::NestedNullPolicy::Error)
+                    }
+                    None => Err(Status::invalid_argument("Unknown nested null policy")),
+                },
+            )
+            .transpose()?;
+        let inner_match = inner_match
+            .map(|inner_match| match InnerArrayMatch::from_i32(inner_match) {
+                Some(InnerArrayMatch::InnerMatchAny) => Ok(segment::types::InnerArrayMatch::Any),
+                Some(InnerArrayMatch::InnerMatchAll) => Ok(segment::types::InnerArrayMatch::All),
+                None => Err(Status::invalid_argument("Unknown inner array match")),
+            })
+            .transpose()?;
         Ok(Self {
             key,
             r#match: r#match.map_or_else(|| Ok(None), |m| m.try_into().map(Some))?,
@@ -816,6 +1274,8 @@ impl TryFrom<FieldCondition> for segment::types::FieldCondition {
             geo_bounding_box,
             geo_radius,
             values_count: values_count.map(|r| r.into()),
+            null_policy,
+            inner_match,
         })
     }
 }
@@ -829,10 +1289,21 @@ impl From<segment::types::FieldCondition> for FieldCondition {
             geo_bounding_box,
             geo_radius,
             values_count,
+            null_policy,
+            inner_match,
         } = value;
 
         let geo_bounding_box = geo_bounding_box.map(|g| g.into());
         let geo_radius = geo_radius.map(|g| g.into());
+        let null_policy = null_policy.map(|null_policy| match null_policy {
+            segment::types::NestedNullPolicy::NoMatch => NestedNullPolicy::NullNoMatch,
+            segment::types::NestedNullPolicy::Skip => NestedNullPolicy::NullSkip,
+            segment::types::NestedNullPolicy::Error => NestedNullPolicy::NullError,
+        });
+        let inner_match = inner_match.map(|inner_match| match inner_match {
+            segment::types::InnerArrayMatch::Any => InnerArrayMatch::InnerMatchAny,
+            segment::types::InnerArrayMatch::All => InnerArrayMatch::InnerMatchAll,
+        });
         Self {
             key,
             r#match: r#match.map(|m| m.into()),
@@ -840,6 +1311,8 @@ impl From<segment::types::FieldCondition> for FieldCondition {
             geo_bounding_box,
             geo_radius,
             values_count: values_count.map(|r| r.into()),
+            null_policy: null_policy.map(|null_policy| null_policy as i32),
+            inner_match: inner_match.map(|inner_match| inner_match as i32),
         }
     }
 }
@@ -930,10 +1403,11 @@ impl From<segment::types::Range> for Range {
 impl From<ValuesCount> for segment::types::ValuesCount {
     fn from(value: ValuesCount) -> Self {
         Self {
-            lt: value.lt.map(|x| x as usize),
-            gt: value.gt.map(|x| x as usize),
-            gte: value.gte.map(|x| x as usize),
-            lte: value.lte.map(|x| x as usize),
+            // Saturate thresholds which do not fit into `usize` instead of truncating them
+            lt: value.lt.map(|x| usize::try_from(x).unwrap_or(usize::MAX)),
+            gt: value.gt.map(|x| usize::try_from(x).unwrap_or(usize::MAX)),
+            gte: value.gte.map(|x| usize::try_from(x).unwrap_or(usize::MAX)),
+            lte: value.lte.map(|x| usize::try_from(x).unwrap_or(usize::MAX)),
         }
     }
 }
@@ -941,10 +1415,10 @@ impl From<ValuesCount> for segment::types::ValuesCount {
 impl From<segment::types::ValuesCount> for ValuesCount {
     fn from(value: segment::types::ValuesCount) -> Self {
         Self {
-            lt: value.lt.map(|x| x as u64),
-            gt: value.gt.map(|x| x as u64),
-            gte: value.gte.map(|x| x as u64),
-            lte: value.lte.map(|x| x as u64),
+            lt: value.lt.map(|x| u64::try_from(x).unwrap_or(u64::MAX)),
+            gt: value.gt.map(|x| u64::try_from(x).unwrap_or(u64::MAX)),
+            gte: value.gte.map(|x| u64::try_from(x).unwrap_or(u64::MAX)),
+            lte: value.lte.map(|x| u64::try_from(x).unwrap_or(u64::MAX)),
         }
     }
 }
@@ -961,6 +1435,7 @@ impl TryFrom<Match> for segment::types::Match {
                 MatchValue::Text(text) => segment::types::Match::Text(text.into()),
                 MatchValue::Keywords(kwds) => kwds.strings.into(),
                 MatchValue::Integers(ints) => ints.integers.into(),
+                MatchValue::Regex(regex) => segment::types::Match::new_regex(regex),
             }),
             _ => Err(Status::invalid_argument("Malformed Match condition")),
         }
@@ -978,6 +1453,9 @@ impl From<segment::types::Match> for Match {
             segment::types::Match::Text(segment::types::MatchText { text }) => {
                 MatchValue::Text(text)
             }
+            segment::types::Match::Regex(segment::types::MatchRegex { regex }) => {
+                MatchValue::Regex(regex)
+            }
             segment::types::Match::Any(any) => match any.any {
                 segment::types::AnyVariants::Keywords(strings) => {
                     MatchValue::Keywords(RepeatedStrings { strings })
@@ -2566,6 +2566,12 @@ pub struct UpdatePointVectors {
     /// Write ordering guarantees
     #[prost(message, optional, tag = "4")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// If true - points which can't be updated, e.g. missing ones, are skipped instead of failing the whole update
+    #[prost(bool, optional, tag = "5")]
+    pub partial: ::core::option::Option<bool>,
+    /// Apply the whole operation to this shard, regardless of the ids of the points
+    #[prost(uint32, optional, tag = "6")]
+    pub target_shard: ::core::option::Option<u32>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -2591,12 +2597,115 @@ pub struct DeletePointVectors {
     /// Affected points
     #[prost(message, optional, tag = "3")]
     pub points_selector: ::core::option::Option<PointsSelector>,
-    /// List of vector names to delete
+    /// List of vector names to delete, names starting with `_` are reserved
     #[prost(message, optional, tag = "4")]
     pub vectors: ::core::option::Option<VectorsSelector>,
     /// Write ordering guarantees
     #[prost(message, optional, tag = "5")]
     pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Abort if the filter matches more points than this, counted before the deletion
+    #[prost(uint64, optional, tag = "6")]
+    pub max_points: ::core::option::Option<u64>,
+    /// Apply the whole operation to this shard, regardless of the ids of the points
+    #[prost(uint32, optional, tag = "7")]
+    pub target_shard: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReindexPointVectors {
+    /// name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Wait until the changes have been applied?
+    #[prost(bool, optional, tag = "2")]
+    pub wait: ::core::option::Option<bool>,
+    /// Name of the vector to reprocess according to its distance metric
+    #[prost(string, tag = "3")]
+    pub vector_name: ::prost::alloc::string::String,
+    /// Write ordering guarantees
+    #[prost(message, optional, tag = "4")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Apply the whole operation to this shard, regardless of the ids of the points
+    #[prost(uint32, optional, tag = "5")]
+    pub target_shard: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConcatPointVectors {
+    /// name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Wait until the changes have been applied?
+    #[prost(bool, optional, tag = "2")]
+    pub wait: ::core::option::Option<bool>,
+    /// Names of the vectors to concatenate, in order
+    #[prost(string, repeated, tag = "3")]
+    #[validate(length(min = 1, message = "must specify vectors to concatenate"))]
+    pub sources: ::prost::alloc::vec::Vec<::prost::alloc::string::String>,
+    /// Name of the vector to store the concatenation into
+    #[prost(string, tag = "4")]
+    pub target: ::prost::alloc::string::String,
+    /// Write ordering guarantees
+    #[prost(message, optional, tag = "5")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Apply the whole operation to this shard, regardless of the ids of the points
+    #[prost(uint32, optional, tag = "6")]
+    pub target_shard: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RepairPointVectors {
+    /// name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Wait until the changes have been applied?
+    #[prost(bool, optional, tag = "2")]
+    pub wait: ::core::option::Option<bool>,
+    /// Name of the vector to repair
+    #[prost(string, tag = "3")]
+    pub vector_name: ::prost::alloc::string::String,
+    /// What to do with vectors of a wrong dimension
+    #[prost(enumeration = "DimensionRepairPolicy", tag = "4")]
+    pub policy: i32,
+    /// Write ordering guarantees
+    #[prost(message, optional, tag = "5")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Apply the whole operation to this shard, regardless of the ids of the points
+    #[prost(uint32, optional, tag = "6")]
+    pub target_shard: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetDefaultPointVectors {
+    /// name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Wait until the changes have been applied?
+    #[prost(bool, optional, tag = "2")]
+    pub wait: ::core::option::Option<bool>,
+    /// Points to set the vector for
+    #[prost(message, optional, tag = "3")]
+    pub filter: ::core::option::Option<Filter>,
+    /// Name of the vector to set
+    #[prost(string, tag = "4")]
+    pub vector_name: ::prost::alloc::string::String,
+    /// Vector to set for points without one
+    #[prost(message, optional, tag = "5")]
+    pub default_vector: ::core::option::Option<Vector>,
+    /// Write ordering guarantees
+    #[prost(message, optional, tag = "6")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
+    /// Apply the whole operation to this shard, regardless of the ids of the points
+    #[prost(uint32, optional, tag = "7")]
+    pub target_shard: ::core::option::Option<u32>,
 }
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -2622,6 +2731,33 @@ pub struct SetPayloadPoints {
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetNestedPayloadPoints {
+    /// name of the collection
+    #[prost(string, tag = "1")]
+    #[validate(length(min = 1, max = 255))]
+    pub collection_name: ::prost::alloc::string::String,
+    /// Wait until the changes have been applied?
+    #[prost(bool, optional, tag = "2")]
+    pub wait: ::core::option::Option<bool>,
+    /// New payload values of the matching elements
+    #[prost(map = "string, message", tag = "3")]
+    pub payload: ::std::collections::HashMap<::prost::alloc::string::String, Value>,
+    /// Key of the nested array
+    #[prost(string, tag = "4")]
+    pub key: ::prost::alloc::string::String,
+    /// Selects the array elements to update, conditions are relative to an element
+    #[prost(message, optional, tag = "5")]
+    pub filter: ::core::option::Option<Filter>,
+    /// Affected points
+    #[prost(message, repeated, tag = "6")]
+    pub points: ::prost::alloc::vec::Vec<PointId>,
+    /// Write ordering guarantees
+    #[prost(message, optional, tag = "7")]
+    pub ordering: ::core::option::Option<WriteOrdering>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DeletePayloadPoints {
     /// name of the collection
     #[prost(string, tag = "1")]
@@ -2828,6 +2964,7 @@ pub struct SearchPoints {
     pub vector: ::prost::alloc::vec::Vec<f32>,
     /// Filter conditions - return only those points that satisfy the specified conditions
     #[prost(message, optional, tag = "3")]
+    #[validate]
     pub filter: ::core::option::Option<Filter>,
     /// Max number of result
     #[prost(uint64, tag = "4")]
@@ -2926,6 +3063,7 @@ pub struct ScrollPoints {
     pub collection_name: ::prost::alloc::string::String,
     /// Filter conditions - return only those points that satisfy the specified conditions
     #[prost(message, optional, tag = "2")]
+    #[validate]
     pub filter: ::core::option::Option<Filter>,
     /// Start with this ID
     #[prost(message, optional, tag = "3")]
@@ -3074,6 +3212,7 @@ pub struct CountPoints {
     pub collection_name: ::prost::alloc::string::String,
     /// Filter conditions - return only those points that satisfy the specified conditions
     #[prost(message, optional, tag = "2")]
+    #[validate]
     pub filter: ::core::option::Option<Filter>,
     /// If `true` - return exact count, if `false` - return approximate count
     #[prost(bool, optional, tag = "3")]
@@ -3097,6 +3236,32 @@ pub struct UpdateResult {
     /// Operation status
     #[prost(enumeration = "UpdateStatus", tag = "2")]
     pub status: i32,
+    /// Per-point outcome of a partial vector update
+    #[prost(message, optional, tag = "3")]
+    pub vectors_report: ::core::option::Option<UpdateVectorsReport>,
+    /// Number of deleted vectors per vector name, empty if no vectors were requested to be deleted
+    #[prost(map = "string, uint64", tag = "4")]
+    pub deleted_vectors: ::std::collections::HashMap<::prost::alloc::string::String, u64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct UpdateVectorsReport {
+    /// Points with updated vectors
+    #[prost(message, repeated, tag = "1")]
+    pub succeeded: ::prost::alloc::vec::Vec<PointId>,
+    /// Points which could not be updated
+    #[prost(message, repeated, tag = "2")]
+    pub failed: ::prost::alloc::vec::Vec<FailedPointVectors>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FailedPointVectors {
+    /// Point id
+    #[prost(message, optional, tag = "1")]
+    pub id: ::core::option::Option<PointId>,
+    /// Reason of the failure
+    #[prost(string, tag = "2")]
+    pub error: ::prost::alloc::string::String,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3278,7 +3443,10 @@ pub struct Filter {
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Condition {
-    #[prost(oneof = "condition::ConditionOneOf", tags = "1, 2, 3, 4, 5, 6")]
+    #[prost(
+        oneof = "condition::ConditionOneOf",
+        tags = "1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15"
+    )]
     pub condition_one_of: ::core::option::Option<condition::ConditionOneOf>,
 }
 /// Nested message and enum types in `Condition`.
@@ -3298,6 +3466,24 @@ pub mod condition {
         IsNull(super::IsNullCondition),
         #[prost(message, tag = "6")]
         Nested(super::NestedCondition),
+        #[prost(message, tag = "7")]
+        VectorCount(super::VectorCountCondition),
+        #[prost(message, tag = "8")]
+        Aggregate(super::AggregateCondition),
+        #[prost(message, tag = "9")]
+        HasKey(super::HasKeyCondition),
+        #[prost(message, tag = "10")]
+        Expression(super::ExpressionCondition),
+        #[prost(message, tag = "11")]
+        HasDuplicates(super::HasDuplicatesCondition),
+        #[prost(message, tag = "12")]
+        VectorNotNormalized(super::VectorNotNormalizedCondition),
+        #[prost(message, tag = "13")]
+        ValueType(super::ValueTypeCondition),
+        #[prost(message, tag = "14")]
+        Position(super::PositionCondition),
+        #[prost(message, tag = "15")]
+        Compare(super::ComparisonCondition),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3314,12 +3500,129 @@ pub struct IsNullCondition {
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HasKeyCondition {
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct HasDuplicatesCondition {
+    /// Payload key of the array to check
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct HasIdCondition {
     #[prost(message, repeated, tag = "1")]
     pub has_id: ::prost::alloc::vec::Vec<PointId>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VectorCountCondition {
+    /// Range of the number of present named vectors
+    #[prost(message, optional, tag = "1")]
+    pub vector_count: ::core::option::Option<ValuesCount>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct VectorNotNormalizedCondition {
+    /// Name of the vector to check
+    #[prost(string, tag = "1")]
+    pub name: ::prost::alloc::string::String,
+    /// Maximal allowed deviation of the vector norm from 1.0
+    #[prost(float, tag = "2")]
+    pub tolerance: f32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ValueTypeCondition {
+    /// Payload key of the value
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+    /// Expected JSON type of the value
+    #[prost(enumeration = "JsonValueType", tag = "2")]
+    pub r#type: i32,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct FractionRange {
+    /// Start of the range as a fraction of the array length, inclusive
+    #[prost(double, tag = "1")]
+    pub from: f64,
+    /// End of the range as a fraction of the array length, exclusive
+    #[prost(double, tag = "2")]
+    pub to: f64,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct PositionCondition {
+    #[prost(oneof = "position_condition::Position", tags = "1, 2, 3")]
+    pub position: ::core::option::Option<position_condition::Position>,
+}
+/// Nested message and enum types in `PositionCondition`.
+pub mod position_condition {
+    #[allow(clippy::derive_partial_eq_without_eq)]
+    #[derive(Clone, PartialEq, ::prost::Oneof)]
+    pub enum Position {
+        /// Elements from `from * length` to `to * length` of the array
+        #[prost(message, tag = "1")]
+        Fraction(super::FractionRange),
+        /// First `n` elements of the array
+        #[prost(uint64, tag = "2")]
+        First(u64),
+        /// Last `n` elements of the array
+        #[prost(uint64, tag = "3")]
+        Last(u64),
+    }
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct AggregateCondition {
+    /// Payload key of the numeric values
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+    /// Function used to aggregate the values
+    #[prost(enumeration = "AggregateFunction", tag = "2")]
+    pub function: i32,
+    /// Aggregated value should be in this range
+    #[prost(message, optional, tag = "3")]
+    pub range: ::core::option::Option<Range>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ExpressionCondition {
+    /// Payload key of the left operand
+    #[prost(string, tag = "1")]
+    pub left: ::prost::alloc::string::String,
+    /// Operator applied to the operands
+    #[prost(enumeration = "ArithmeticOperator", tag = "2")]
+    pub operator: i32,
+    /// Payload key of the right operand
+    #[prost(string, tag = "3")]
+    pub right: ::prost::alloc::string::String,
+    /// Computed value should be in this range
+    #[prost(message, optional, tag = "4")]
+    pub range: ::core::option::Option<Range>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ComparisonCondition {
+    /// Payload key of the left operand
+    #[prost(string, tag = "1")]
+    pub left: ::prost::alloc::string::String,
+    /// Operator comparing the operands
+    #[prost(enumeration = "ComparisonOperator", tag = "2")]
+    pub operator: i32,
+    /// Payload key of the right operand
+    #[prost(string, tag = "3")]
+    pub right: ::prost::alloc::string::String,
+    /// If specified - strings are ordered as in the dictionary of the locale
+    #[prost(enumeration = "CollationLocale", optional, tag = "4")]
+    pub collation: ::core::option::Option<i32>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct NestedCondition {
     /// Path to nested object
     #[prost(string, tag = "1")]
@@ -3327,6 +3630,28 @@ pub struct NestedCondition {
     /// Filter condition
     #[prost(message, optional, tag = "2")]
     pub filter: ::core::option::Option<Filter>,
+    /// If set - only the array element at this position is checked, negative positions count from the end
+    #[prost(int64, optional, tag = "3")]
+    pub index: ::core::option::Option<i64>,
+    /// If set - only the array elements added after the marker are checked
+    #[prost(message, optional, tag = "4")]
+    pub after: ::core::option::Option<SequenceMarker>,
+    /// How many of the array elements should match the filter, at least one by default
+    #[prost(enumeration = "NestedQuantifier", optional, tag = "5")]
+    pub quantifier: ::core::option::Option<i32>,
+    /// If set - share of the matching elements should be at least this ratio, missing or empty array does not match. Can't be combined with `quantifier`
+    #[prost(double, optional, tag = "6")]
+    pub min_ratio: ::core::option::Option<f64>,
+}
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SequenceMarker {
+    /// Field of the array element holding its sequence number
+    #[prost(string, tag = "1")]
+    pub key: ::prost::alloc::string::String,
+    /// Only elements with a greater sequence number are selected
+    #[prost(double, tag = "2")]
+    pub value: f64,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
@@ -3348,11 +3673,17 @@ pub struct FieldCondition {
     /// Check number of values for a specific field
     #[prost(message, optional, tag = "6")]
     pub values_count: ::core::option::Option<ValuesCount>,
+    /// Handling of null values of the field inside of nested filters, non-matching by default
+    #[prost(enumeration = "NestedNullPolicy", optional, tag = "7")]
+    pub null_policy: ::core::option::Option<i32>,
+    /// Matching of elements holding inner arrays inside of nested filters, any inner value by default
+    #[prost(enumeration = "InnerArrayMatch", optional, tag = "8")]
+    pub inner_match: ::core::option::Option<i32>,
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
 pub struct Match {
-    #[prost(oneof = "r#match::MatchValue", tags = "1, 2, 3, 4, 5, 6")]
+    #[prost(oneof = "r#match::MatchValue", tags = "1, 2, 3, 4, 5, 6, 7")]
     pub match_value: ::core::option::Option<r#match::MatchValue>,
 }
 /// Nested message and enum types in `Match`.
@@ -3378,6 +3709,9 @@ pub mod r#match {
         /// Match multiple integers
         #[prost(message, tag = "6")]
         Integers(super::RepeatedIntegers),
+        /// Match string by regular expression
+        #[prost(string, tag = "7")]
+        Regex(::prost::alloc::string::String),
     }
 }
 #[allow(clippy::derive_partial_eq_without_eq)]
@@ -3545,6 +3879,39 @@ impl ReadConsistencyType {
 }
 #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
 #[repr(i32)]
+pub enum DimensionRepairPolicy {
+    UnknownDimensionRepairPolicy = 0,
+    /// Truncate longer vectors and pad shorter ones with zeros
+    Resize = 1,
+    /// Delete the vector of the point
+    Clear = 2,
+}
+impl DimensionRepairPolicy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            DimensionRepairPolicy::UnknownDimensionRepairPolicy => {
+                "UnknownDimensionRepairPolicy"
+            }
+            DimensionRepairPolicy::Resize => "Resize",
+            DimensionRepairPolicy::Clear => "Clear",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UnknownDimensionRepairPolicy" => Some(Self::UnknownDimensionRepairPolicy),
+            "Resize" => Some(Self::Resize),
+            "Clear" => Some(Self::Clear),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
 pub enum FieldType {
     Keyword = 0,
     Integer = 1,
@@ -3609,47 +3976,331 @@ impl UpdateStatus {
         }
     }
 }
-/// Generated client implementations.
-pub mod points_client {
-    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
-    use tonic::codegen::*;
-    use tonic::codegen::http::Uri;
-    #[derive(Debug, Clone)]
-    pub struct PointsClient<T> {
-        inner: tonic::client::Grpc<T>,
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum JsonValueType {
+    UnknownJsonValueType = 0,
+    JsonNull = 1,
+    JsonBool = 2,
+    JsonNumber = 3,
+    JsonString = 4,
+    JsonArray = 5,
+    JsonObject = 6,
+}
+impl JsonValueType {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            JsonValueType::UnknownJsonValueType => "UnknownJsonValueType",
+            JsonValueType::JsonNull => "JsonNull",
+            JsonValueType::JsonBool => "JsonBool",
+            JsonValueType::JsonNumber => "JsonNumber",
+            JsonValueType::JsonString => "JsonString",
+            JsonValueType::JsonArray => "JsonArray",
+            JsonValueType::JsonObject => "JsonObject",
+        }
     }
-    impl PointsClient<tonic::transport::Channel> {
-        /// Attempt to create a new client by connecting to a given endpoint.
-        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
-        where
-            D: TryInto<tonic::transport::Endpoint>,
-            D::Error: Into<StdError>,
-        {
-            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
-            Ok(Self::new(conn))
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UnknownJsonValueType" => Some(Self::UnknownJsonValueType),
+            "JsonNull" => Some(Self::JsonNull),
+            "JsonBool" => Some(Self::JsonBool),
+            "JsonNumber" => Some(Self::JsonNumber),
+            "JsonString" => Some(Self::JsonString),
+            "JsonArray" => Some(Self::JsonArray),
+            "JsonObject" => Some(Self::JsonObject),
+            _ => None,
         }
     }
-    impl<T> PointsClient<T>
-    where
-        T: tonic::client::GrpcService<tonic::body::BoxBody>,
-        T::Error: Into<StdError>,
-        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
-        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
-    {
-        pub fn new(inner: T) -> Self {
-            let inner = tonic::client::Grpc::new(inner);
-            Self { inner }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum AggregateFunction {
+    UnknownAggregateFunction = 0,
+    Sum = 1,
+    Min = 2,
+    Max = 3,
+    Avg = 4,
+    /// Number of distinct values, which are not required to be numeric
+    DistinctCount = 5,
+}
+impl AggregateFunction {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            AggregateFunction::UnknownAggregateFunction => "UnknownAggregateFunction",
+            AggregateFunction::Sum => "Sum",
+            AggregateFunction::Min => "Min",
+            AggregateFunction::Max => "Max",
+            AggregateFunction::Avg => "Avg",
+            AggregateFunction::DistinctCount => "DistinctCount",
         }
-        pub fn with_origin(inner: T, origin: Uri) -> Self {
-            let inner = tonic::client::Grpc::with_origin(inner, origin);
-            Self { inner }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UnknownAggregateFunction" => Some(Self::UnknownAggregateFunction),
+            "Sum" => Some(Self::Sum),
+            "Min" => Some(Self::Min),
+            "Max" => Some(Self::Max),
+            "Avg" => Some(Self::Avg),
+            "DistinctCount" => Some(Self::DistinctCount),
+            _ => None,
         }
-        pub fn with_interceptor<F>(
-            inner: T,
-            interceptor: F,
-        ) -> PointsClient<InterceptedService<T, F>>
-        where
-            F: tonic::service::Interceptor,
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ArithmeticOperator {
+    UnknownArithmeticOperator = 0,
+    Add = 1,
+    Sub = 2,
+    Mul = 3,
+    Div = 4,
+}
+impl ArithmeticOperator {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ArithmeticOperator::UnknownArithmeticOperator => "UnknownArithmeticOperator",
+            ArithmeticOperator::Add => "Add",
+            ArithmeticOperator::Sub => "Sub",
+            ArithmeticOperator::Mul => "Mul",
+            ArithmeticOperator::Div => "Div",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UnknownArithmeticOperator" => Some(Self::UnknownArithmeticOperator),
+            "Add" => Some(Self::Add),
+            "Sub" => Some(Self::Sub),
+            "Mul" => Some(Self::Mul),
+            "Div" => Some(Self::Div),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum ComparisonOperator {
+    UnknownComparisonOperator = 0,
+    Eq = 1,
+    Ne = 2,
+    Lt = 3,
+    Lte = 4,
+    Gt = 5,
+    Gte = 6,
+}
+impl ComparisonOperator {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            ComparisonOperator::UnknownComparisonOperator => "UnknownComparisonOperator",
+            ComparisonOperator::Eq => "Eq",
+            ComparisonOperator::Ne => "Ne",
+            ComparisonOperator::Lt => "Lt",
+            ComparisonOperator::Lte => "Lte",
+            ComparisonOperator::Gt => "Gt",
+            ComparisonOperator::Gte => "Gte",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "UnknownComparisonOperator" => Some(Self::UnknownComparisonOperator),
+            "Eq" => Some(Self::Eq),
+            "Ne" => Some(Self::Ne),
+            "Lt" => Some(Self::Lt),
+            "Lte" => Some(Self::Lte),
+            "Gt" => Some(Self::Gt),
+            "Gte" => Some(Self::Gte),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum CollationLocale {
+    /// Letters with accents are ordered right after the same letters without them
+    RootCollation = 0,
+    /// German, same as the root order
+    GermanCollation = 1,
+    /// Spanish, `ñ` is a separate letter following `n`
+    SpanishCollation = 2,
+    /// Swedish, `å`, `ä` and `ö` are separate letters following `z`
+    SwedishCollation = 3,
+}
+impl CollationLocale {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            CollationLocale::RootCollation => "RootCollation",
+            CollationLocale::GermanCollation => "GermanCollation",
+            CollationLocale::SpanishCollation => "SpanishCollation",
+            CollationLocale::SwedishCollation => "SwedishCollation",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "RootCollation" => Some(Self::RootCollation),
+            "GermanCollation" => Some(Self::GermanCollation),
+            "SpanishCollation" => Some(Self::SpanishCollation),
+            "SwedishCollation" => Some(Self::SwedishCollation),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NestedQuantifier {
+    /// At least one element matches
+    AnyElement = 0,
+    /// Every element matches, missing or empty array does not match
+    AllElements = 1,
+    /// Every element matches, missing or empty array matches as well
+    AllElementsOrEmpty = 2,
+}
+impl NestedQuantifier {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            NestedQuantifier::AnyElement => "AnyElement",
+            NestedQuantifier::AllElements => "AllElements",
+            NestedQuantifier::AllElementsOrEmpty => "AllElementsOrEmpty",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "AnyElement" => Some(Self::AnyElement),
+            "AllElements" => Some(Self::AllElements),
+            "AllElementsOrEmpty" => Some(Self::AllElementsOrEmpty),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum NestedNullPolicy {
+    /// Element with `null` value does not match the condition
+    NullNoMatch = 0,
+    /// `null` values are ignored, element with only `null` values does not match
+    NullSkip = 1,
+    /// Reject the condition with an error, `null` values don't match in queries
+    NullError = 2,
+}
+impl NestedNullPolicy {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            NestedNullPolicy::NullNoMatch => "NullNoMatch",
+            NestedNullPolicy::NullSkip => "NullSkip",
+            NestedNullPolicy::NullError => "NullError",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "NullNoMatch" => Some(Self::NullNoMatch),
+            "NullSkip" => Some(Self::NullSkip),
+            "NullError" => Some(Self::NullError),
+            _ => None,
+        }
+    }
+}
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, ::prost::Enumeration)]
+#[repr(i32)]
+pub enum InnerArrayMatch {
+    /// Element matches if any of its inner values matches
+    InnerMatchAny = 0,
+    /// Element matches if all of its inner values match, empty inner array does not match
+    InnerMatchAll = 1,
+}
+impl InnerArrayMatch {
+    /// String value of the enum field names used in the ProtoBuf definition.
+    ///
+    /// The values are not transformed in any way and thus are considered stable
+    /// (if the ProtoBuf definition does not change) and safe for programmatic use.
+    pub fn as_str_name(&self) -> &'static str {
+        match self {
+            InnerArrayMatch::InnerMatchAny => "InnerMatchAny",
+            InnerArrayMatch::InnerMatchAll => "InnerMatchAll",
+        }
+    }
+    /// Creates an enum from field names used in the ProtoBuf definition.
+    pub fn from_str_name(value: &str) -> ::core::option::Option<Self> {
+        match value {
+            "InnerMatchAny" => Some(Self::InnerMatchAny),
+            "InnerMatchAll" => Some(Self::InnerMatchAll),
+            _ => None,
+        }
+    }
+}
+/// Generated client implementations.
+pub mod points_client {
+    #![allow(unused_variables, dead_code, missing_docs, clippy::let_unit_value)]
+    use tonic::codegen::*;
+    use tonic::codegen::http::Uri;
+    #[derive(Debug, Clone)]
+    pub struct PointsClient<T> {
+        inner: tonic::client::Grpc<T>,
+    }
+    impl PointsClient<tonic::transport::Channel> {
+        /// Attempt to create a new client by connecting to a given endpoint.
+        pub async fn connect<D>(dst: D) -> Result<Self, tonic::transport::Error>
+        where
+            D: TryInto<tonic::transport::Endpoint>,
+            D::Error: Into<StdError>,
+        {
+            let conn = tonic::transport::Endpoint::new(dst)?.connect().await?;
+            Ok(Self::new(conn))
+        }
+    }
+    impl<T> PointsClient<T>
+    where
+        T: tonic::client::GrpcService<tonic::body::BoxBody>,
+        T::Error: Into<StdError>,
+        T::ResponseBody: Body<Data = Bytes> + Send + 'static,
+        <T::ResponseBody as Body>::Error: Into<StdError> + Send,
+    {
+        pub fn new(inner: T) -> Self {
+            let inner = tonic::client::Grpc::new(inner);
+            Self { inner }
+        }
+        pub fn with_origin(inner: T, origin: Uri) -> Self {
+            let inner = tonic::client::Grpc::with_origin(inner, origin);
+            Self { inner }
+        }
+        pub fn with_interceptor<F>(
+            inner: T,
+            interceptor: F,
+        ) -> PointsClient<InterceptedService<T, F>>
+        where
+            F: tonic::service::Interceptor,
             T::ResponseBody: Default,
             T: tonic::codegen::Service<
                 http::Request<tonic::body::BoxBody>,
@@ -3818,6 +4469,114 @@ pub mod points_client {
             self.inner.unary(req, path, codec).await
         }
         ///
+        /// Reprocess named vectors of all points according to the current distance metric
+        pub async fn reindex_vectors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ReindexPointVectors>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/ReindexVectors",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "ReindexVectors"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
+        /// Concatenate vectors of each point into another named vector
+        pub async fn concat_vectors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::ConcatPointVectors>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/ConcatVectors",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "ConcatVectors"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
+        /// Repair vectors of the given name, which dimension differs from the one of the collection
+        pub async fn repair_vectors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::RepairPointVectors>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/RepairVectors",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "RepairVectors"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
+        /// Set a vector for points matching the filter, which don't have a vector with the given name
+        pub async fn set_default_vector(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetDefaultPointVectors>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/SetDefaultVector",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "SetDefaultVector"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
         /// Set payload for points
         pub async fn set_payload(
             &mut self,
@@ -3842,6 +4601,33 @@ pub mod points_client {
             self.inner.unary(req, path, codec).await
         }
         ///
+        /// Set payload of the elements of a nested array, which match the nested filter
+        pub async fn set_nested_payload(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SetNestedPayloadPoints>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.Points/SetNestedPayload",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.Points", "SetNestedPayload"));
+            self.inner.unary(req, path, codec).await
+        }
+        ///
         /// Overwrite payload for points
         pub async fn overwrite_payload(
             &mut self,
@@ -4222,6 +5008,42 @@ pub mod points_server {
             tonic::Status,
         >;
         ///
+        /// Reprocess named vectors of all points according to the current distance metric
+        async fn reindex_vectors(
+            &self,
+            request: tonic::Request<super::ReindexPointVectors>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        ///
+        /// Concatenate vectors of each point into another named vector
+        async fn concat_vectors(
+            &self,
+            request: tonic::Request<super::ConcatPointVectors>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        ///
+        /// Repair vectors of the given name, which dimension differs from the one of the collection
+        async fn repair_vectors(
+            &self,
+            request: tonic::Request<super::RepairPointVectors>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        ///
+        /// Set a vector for points matching the filter, which don't have a vector with the given name
+        async fn set_default_vector(
+            &self,
+            request: tonic::Request<super::SetDefaultPointVectors>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        ///
         /// Set payload for points
         async fn set_payload(
             &self,
@@ -4231,6 +5053,15 @@ pub mod points_server {
             tonic::Status,
         >;
         ///
+        /// Set payload of the elements of a nested array, which match the nested filter
+        async fn set_nested_payload(
+            &self,
+            request: tonic::Request<super::SetNestedPayloadPoints>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        ///
         /// Overwrite payload for points
         async fn overwrite_payload(
             &self,
@@ -4420,9 +5251,183 @@ pub mod points_server {
             match req.uri().path() {
                 "/qdrant.Points/Upsert" => {
                     #[allow(non_camel_case_types)]
-                    struct UpsertSvc<T: Points>(pub Arc<T>);
-                    impl<T: Points> tonic::server::UnaryService<super::UpsertPoints>
-                    for UpsertSvc<T> {
+                    struct UpsertSvc<T: Points>(pub Arc<T>);
+                    impl<T: Points> tonic::server::UnaryService<super::UpsertPoints>
+                    for UpsertSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpsertPoints>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).upsert(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpsertSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/Delete" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteSvc<T: Points>(pub Arc<T>);
+                    impl<T: Points> tonic::server::UnaryService<super::DeletePoints>
+                    for DeleteSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::DeletePoints>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).delete(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = DeleteSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/Get" => {
+                    #[allow(non_camel_case_types)]
+                    struct GetSvc<T: Points>(pub Arc<T>);
+                    impl<T: Points> tonic::server::UnaryService<super::GetPoints>
+                    for GetSvc<T> {
+                        type Response = super::GetResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::GetPoints>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move { (*inner).get(request).await };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = GetSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/UpdateVectors" => {
+                    #[allow(non_camel_case_types)]
+                    struct UpdateVectorsSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::UpdatePointVectors>
+                    for UpdateVectorsSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::UpdatePointVectors>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).update_vectors(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = UpdateVectorsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.Points/DeleteVectors" => {
+                    #[allow(non_camel_case_types)]
+                    struct DeleteVectorsSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::DeletePointVectors>
+                    for DeleteVectorsSvc<T> {
                         type Response = super::PointsOperationResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
@@ -4430,10 +5435,12 @@ pub mod points_server {
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpsertPoints>,
+                            request: tonic::Request<super::DeletePointVectors>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move { (*inner).upsert(request).await };
+                            let fut = async move {
+                                (*inner).delete_vectors(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -4444,7 +5451,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = UpsertSvc(inner);
+                        let method = DeleteVectorsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -4460,11 +5467,13 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
-                "/qdrant.Points/Delete" => {
+                "/qdrant.Points/ReindexVectors" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteSvc<T: Points>(pub Arc<T>);
-                    impl<T: Points> tonic::server::UnaryService<super::DeletePoints>
-                    for DeleteSvc<T> {
+                    struct ReindexVectorsSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::ReindexPointVectors>
+                    for ReindexVectorsSvc<T> {
                         type Response = super::PointsOperationResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
@@ -4472,10 +5481,12 @@ pub mod points_server {
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeletePoints>,
+                            request: tonic::Request<super::ReindexPointVectors>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move { (*inner).delete(request).await };
+                            let fut = async move {
+                                (*inner).reindex_vectors(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -4486,7 +5497,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = DeleteSvc(inner);
+                        let method = ReindexVectorsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -4502,22 +5513,26 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
-                "/qdrant.Points/Get" => {
+                "/qdrant.Points/ConcatVectors" => {
                     #[allow(non_camel_case_types)]
-                    struct GetSvc<T: Points>(pub Arc<T>);
-                    impl<T: Points> tonic::server::UnaryService<super::GetPoints>
-                    for GetSvc<T> {
-                        type Response = super::GetResponse;
+                    struct ConcatVectorsSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::ConcatPointVectors>
+                    for ConcatVectorsSvc<T> {
+                        type Response = super::PointsOperationResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
                             tonic::Status,
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::GetPoints>,
+                            request: tonic::Request<super::ConcatPointVectors>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
-                            let fut = async move { (*inner).get(request).await };
+                            let fut = async move {
+                                (*inner).concat_vectors(request).await
+                            };
                             Box::pin(fut)
                         }
                     }
@@ -4528,7 +5543,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = GetSvc(inner);
+                        let method = ConcatVectorsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -4544,13 +5559,13 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
-                "/qdrant.Points/UpdateVectors" => {
+                "/qdrant.Points/RepairVectors" => {
                     #[allow(non_camel_case_types)]
-                    struct UpdateVectorsSvc<T: Points>(pub Arc<T>);
+                    struct RepairVectorsSvc<T: Points>(pub Arc<T>);
                     impl<
                         T: Points,
-                    > tonic::server::UnaryService<super::UpdatePointVectors>
-                    for UpdateVectorsSvc<T> {
+                    > tonic::server::UnaryService<super::RepairPointVectors>
+                    for RepairVectorsSvc<T> {
                         type Response = super::PointsOperationResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
@@ -4558,11 +5573,11 @@ pub mod points_server {
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::UpdatePointVectors>,
+                            request: tonic::Request<super::RepairPointVectors>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).update_vectors(request).await
+                                (*inner).repair_vectors(request).await
                             };
                             Box::pin(fut)
                         }
@@ -4574,7 +5589,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = UpdateVectorsSvc(inner);
+                        let method = RepairVectorsSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -4590,13 +5605,13 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
-                "/qdrant.Points/DeleteVectors" => {
+                "/qdrant.Points/SetDefaultVector" => {
                     #[allow(non_camel_case_types)]
-                    struct DeleteVectorsSvc<T: Points>(pub Arc<T>);
+                    struct SetDefaultVectorSvc<T: Points>(pub Arc<T>);
                     impl<
                         T: Points,
-                    > tonic::server::UnaryService<super::DeletePointVectors>
-                    for DeleteVectorsSvc<T> {
+                    > tonic::server::UnaryService<super::SetDefaultPointVectors>
+                    for SetDefaultVectorSvc<T> {
                         type Response = super::PointsOperationResponse;
                         type Future = BoxFuture<
                             tonic::Response<Self::Response>,
@@ -4604,11 +5619,11 @@ pub mod points_server {
                         >;
                         fn call(
                             &mut self,
-                            request: tonic::Request<super::DeletePointVectors>,
+                            request: tonic::Request<super::SetDefaultPointVectors>,
                         ) -> Self::Future {
                             let inner = Arc::clone(&self.0);
                             let fut = async move {
-                                (*inner).delete_vectors(request).await
+                                (*inner).set_default_vector(request).await
                             };
                             Box::pin(fut)
                         }
@@ -4620,7 +5635,7 @@ pub mod points_server {
                     let inner = self.inner.clone();
                     let fut = async move {
                         let inner = inner.0;
-                        let method = DeleteVectorsSvc(inner);
+                        let method = SetDefaultVectorSvc(inner);
                         let codec = tonic::codec::ProstCodec::default();
                         let mut grpc = tonic::server::Grpc::new(codec)
                             .apply_compression_config(
@@ -4678,6 +5693,52 @@ pub mod points_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.Points/SetNestedPayload" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetNestedPayloadSvc<T: Points>(pub Arc<T>);
+                    impl<
+                        T: Points,
+                    > tonic::server::UnaryService<super::SetNestedPayloadPoints>
+                    for SetNestedPayloadSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetNestedPayloadPoints>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).set_nested_payload(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetNestedPayloadSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/qdrant.Points/OverwritePayload" => {
                     #[allow(non_camel_case_types)]
                     struct OverwritePayloadSvc<T: Points>(pub Arc<T>);
@@ -5370,6 +6431,46 @@ pub struct DeleteVectorsInternal {
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ReindexVectorsInternal {
+    #[prost(message, optional, tag = "1")]
+    #[validate]
+    pub reindex_vectors: ::core::option::Option<ReindexPointVectors>,
+    #[prost(uint32, optional, tag = "2")]
+    pub shard_id: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct ConcatVectorsInternal {
+    #[prost(message, optional, tag = "1")]
+    #[validate]
+    pub concat_vectors: ::core::option::Option<ConcatPointVectors>,
+    #[prost(uint32, optional, tag = "2")]
+    pub shard_id: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct RepairVectorsInternal {
+    #[prost(message, optional, tag = "1")]
+    #[validate]
+    pub repair_vectors: ::core::option::Option<RepairPointVectors>,
+    #[prost(uint32, optional, tag = "2")]
+    pub shard_id: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetDefaultVectorInternal {
+    #[prost(message, optional, tag = "1")]
+    #[validate]
+    pub set_default_vector: ::core::option::Option<SetDefaultPointVectors>,
+    #[prost(uint32, optional, tag = "2")]
+    pub shard_id: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct SetPayloadPointsInternal {
     #[prost(message, optional, tag = "1")]
     #[validate]
@@ -5380,6 +6481,16 @@ pub struct SetPayloadPointsInternal {
 #[derive(validator::Validate)]
 #[allow(clippy::derive_partial_eq_without_eq)]
 #[derive(Clone, PartialEq, ::prost::Message)]
+pub struct SetNestedPayloadPointsInternal {
+    #[prost(message, optional, tag = "1")]
+    #[validate]
+    pub set_nested_payload_points: ::core::option::Option<SetNestedPayloadPoints>,
+    #[prost(uint32, optional, tag = "2")]
+    pub shard_id: ::core::option::Option<u32>,
+}
+#[derive(validator::Validate)]
+#[allow(clippy::derive_partial_eq_without_eq)]
+#[derive(Clone, PartialEq, ::prost::Message)]
 pub struct DeletePayloadPointsInternal {
     #[prost(message, optional, tag = "1")]
     #[validate]
@@ -5569,9 +6680,134 @@ pub mod points_internal_client {
             self.inner = self.inner.max_encoding_message_size(limit);
             self
         }
-        pub async fn upsert(
+        pub async fn upsert(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpsertPointsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/Upsert",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "Upsert"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn sync(
+            &mut self,
+            request: impl tonic::IntoRequest<super::SyncPointsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/Sync",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "Sync"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeletePointsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/Delete",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "Delete"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn update_vectors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::UpdateVectorsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/UpdateVectors",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "UpdateVectors"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn delete_vectors(
+            &mut self,
+            request: impl tonic::IntoRequest<super::DeleteVectorsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        > {
+            self.inner
+                .ready()
+                .await
+                .map_err(|e| {
+                    tonic::Status::new(
+                        tonic::Code::Unknown,
+                        format!("Service was not ready: {}", e.into()),
+                    )
+                })?;
+            let codec = tonic::codec::ProstCodec::default();
+            let path = http::uri::PathAndQuery::from_static(
+                "/qdrant.PointsInternal/DeleteVectors",
+            );
+            let mut req = request.into_request();
+            req.extensions_mut()
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "DeleteVectors"));
+            self.inner.unary(req, path, codec).await
+        }
+        pub async fn reindex_vectors(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpsertPointsInternal>,
+            request: impl tonic::IntoRequest<super::ReindexVectorsInternal>,
         ) -> std::result::Result<
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
@@ -5587,16 +6823,16 @@ pub mod points_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.PointsInternal/Upsert",
+                "/qdrant.PointsInternal/ReindexVectors",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.PointsInternal", "Upsert"));
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "ReindexVectors"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn sync(
+        pub async fn concat_vectors(
             &mut self,
-            request: impl tonic::IntoRequest<super::SyncPointsInternal>,
+            request: impl tonic::IntoRequest<super::ConcatVectorsInternal>,
         ) -> std::result::Result<
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
@@ -5612,16 +6848,16 @@ pub mod points_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.PointsInternal/Sync",
+                "/qdrant.PointsInternal/ConcatVectors",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.PointsInternal", "Sync"));
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "ConcatVectors"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn delete(
+        pub async fn repair_vectors(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeletePointsInternal>,
+            request: impl tonic::IntoRequest<super::RepairVectorsInternal>,
         ) -> std::result::Result<
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
@@ -5637,16 +6873,16 @@ pub mod points_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.PointsInternal/Delete",
+                "/qdrant.PointsInternal/RepairVectors",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.PointsInternal", "Delete"));
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "RepairVectors"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn update_vectors(
+        pub async fn set_default_vector(
             &mut self,
-            request: impl tonic::IntoRequest<super::UpdateVectorsInternal>,
+            request: impl tonic::IntoRequest<super::SetDefaultVectorInternal>,
         ) -> std::result::Result<
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
@@ -5662,16 +6898,16 @@ pub mod points_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.PointsInternal/UpdateVectors",
+                "/qdrant.PointsInternal/SetDefaultVector",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.PointsInternal", "UpdateVectors"));
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "SetDefaultVector"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn delete_vectors(
+        pub async fn set_payload(
             &mut self,
-            request: impl tonic::IntoRequest<super::DeleteVectorsInternal>,
+            request: impl tonic::IntoRequest<super::SetPayloadPointsInternal>,
         ) -> std::result::Result<
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
@@ -5687,14 +6923,14 @@ pub mod points_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.PointsInternal/DeleteVectors",
+                "/qdrant.PointsInternal/SetPayload",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.PointsInternal", "DeleteVectors"));
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "SetPayload"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn set_payload(
+        pub async fn overwrite_payload(
             &mut self,
             request: impl tonic::IntoRequest<super::SetPayloadPointsInternal>,
         ) -> std::result::Result<
@@ -5712,16 +6948,16 @@ pub mod points_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.PointsInternal/SetPayload",
+                "/qdrant.PointsInternal/OverwritePayload",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.PointsInternal", "SetPayload"));
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "OverwritePayload"));
             self.inner.unary(req, path, codec).await
         }
-        pub async fn overwrite_payload(
+        pub async fn set_nested_payload(
             &mut self,
-            request: impl tonic::IntoRequest<super::SetPayloadPointsInternal>,
+            request: impl tonic::IntoRequest<super::SetNestedPayloadPointsInternal>,
         ) -> std::result::Result<
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
@@ -5737,11 +6973,11 @@ pub mod points_internal_client {
                 })?;
             let codec = tonic::codec::ProstCodec::default();
             let path = http::uri::PathAndQuery::from_static(
-                "/qdrant.PointsInternal/OverwritePayload",
+                "/qdrant.PointsInternal/SetNestedPayload",
             );
             let mut req = request.into_request();
             req.extensions_mut()
-                .insert(GrpcMethod::new("qdrant.PointsInternal", "OverwritePayload"));
+                .insert(GrpcMethod::new("qdrant.PointsInternal", "SetNestedPayload"));
             self.inner.unary(req, path, codec).await
         }
         pub async fn delete_payload(
@@ -6025,6 +7261,34 @@ pub mod points_internal_server {
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
         >;
+        async fn reindex_vectors(
+            &self,
+            request: tonic::Request<super::ReindexVectorsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        async fn concat_vectors(
+            &self,
+            request: tonic::Request<super::ConcatVectorsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        async fn repair_vectors(
+            &self,
+            request: tonic::Request<super::RepairVectorsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
+        async fn set_default_vector(
+            &self,
+            request: tonic::Request<super::SetDefaultVectorInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
         async fn set_payload(
             &self,
             request: tonic::Request<super::SetPayloadPointsInternal>,
@@ -6039,6 +7303,13 @@ pub mod points_internal_server {
             tonic::Response<super::PointsOperationResponse>,
             tonic::Status,
         >;
+        async fn set_nested_payload(
+            &self,
+            request: tonic::Request<super::SetNestedPayloadPointsInternal>,
+        ) -> std::result::Result<
+            tonic::Response<super::PointsOperationResponse>,
+            tonic::Status,
+        >;
         async fn delete_payload(
             &self,
             request: tonic::Request<super::DeletePayloadPointsInternal>,
@@ -6401,6 +7672,190 @@ pub mod points_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.PointsInternal/ReindexVectors" => {
+                    #[allow(non_camel_case_types)]
+                    struct ReindexVectorsSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::ReindexVectorsInternal>
+                    for ReindexVectorsSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ReindexVectorsInternal>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).reindex_vectors(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ReindexVectorsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.PointsInternal/ConcatVectors" => {
+                    #[allow(non_camel_case_types)]
+                    struct ConcatVectorsSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::ConcatVectorsInternal>
+                    for ConcatVectorsSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::ConcatVectorsInternal>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).concat_vectors(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = ConcatVectorsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.PointsInternal/RepairVectors" => {
+                    #[allow(non_camel_case_types)]
+                    struct RepairVectorsSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::RepairVectorsInternal>
+                    for RepairVectorsSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::RepairVectorsInternal>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).repair_vectors(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = RepairVectorsSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
+                "/qdrant.PointsInternal/SetDefaultVector" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetDefaultVectorSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::SetDefaultVectorInternal>
+                    for SetDefaultVectorSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetDefaultVectorInternal>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).set_default_vector(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetDefaultVectorSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/qdrant.PointsInternal/SetPayload" => {
                     #[allow(non_camel_case_types)]
                     struct SetPayloadSvc<T: PointsInternal>(pub Arc<T>);
@@ -6491,6 +7946,52 @@ pub mod points_internal_server {
                     };
                     Box::pin(fut)
                 }
+                "/qdrant.PointsInternal/SetNestedPayload" => {
+                    #[allow(non_camel_case_types)]
+                    struct SetNestedPayloadSvc<T: PointsInternal>(pub Arc<T>);
+                    impl<
+                        T: PointsInternal,
+                    > tonic::server::UnaryService<super::SetNestedPayloadPointsInternal>
+                    for SetNestedPayloadSvc<T> {
+                        type Response = super::PointsOperationResponse;
+                        type Future = BoxFuture<
+                            tonic::Response<Self::Response>,
+                            tonic::Status,
+                        >;
+                        fn call(
+                            &mut self,
+                            request: tonic::Request<super::SetNestedPayloadPointsInternal>,
+                        ) -> Self::Future {
+                            let inner = Arc::clone(&self.0);
+                            let fut = async move {
+                                (*inner).set_nested_payload(request).await
+                            };
+                            Box::pin(fut)
+                        }
+                    }
+                    let accept_compression_encodings = self.accept_compression_encodings;
+                    let send_compression_encodings = self.send_compression_encodings;
+                    let max_decoding_message_size = self.max_decoding_message_size;
+                    let max_encoding_message_size = self.max_encoding_message_size;
+                    let inner = self.inner.clone();
+                    let fut = async move {
+                        let inner = inner.0;
+                        let method = SetNestedPayloadSvc(inner);
+                        let codec = tonic::codec::ProstCodec::default();
+                        let mut grpc = tonic::server::Grpc::new(codec)
+                            .apply_compression_config(
+                                accept_compression_encodings,
+                                send_compression_encodings,
+                            )
+                            .apply_max_message_size_config(
+                                max_decoding_message_size,
+                                max_encoding_message_size,
+                            );
+                        let res = grpc.unary(method, req).await;
+                        Ok(res)
+                    };
+                    Box::pin(fut)
+                }
                 "/qdrant.PointsInternal/DeletePayload" => {
                     #[allow(non_camel_case_types)]
                     struct DeletePayloadSvc<T: PointsInternal>(pub Arc<T>);
@@ -91,6 +91,16 @@ impl Validate for crate::grpc::qdrant::quantization_config::Quantization {
     }
 }
 
+impl Validate for crate::grpc::qdrant::Filter {
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        // Malformed conditions are reported by the conversion, when the request is processed
+        match segment::types::Filter::try_from(self.clone()) {
+            Ok(filter) => filter.validate(),
+            Err(_) => Ok(()),
+        }
+    }
+}
+
 /// Validate that `value` is a non-empty string or `None`.
 pub fn validate_not_empty(value: &Option<String>) -> Result<(), ValidationError> {
     match value {
@@ -191,7 +201,11 @@ pub fn validate_named_vectors_not_empty(
 mod tests {
     use validator::Validate;
 
-    use crate::grpc::qdrant::{CreateCollection, CreateFieldIndexCollection, SearchPoints};
+    use crate::grpc::qdrant::condition::ConditionOneOf;
+    use crate::grpc::qdrant::{
+        Condition, CountPoints, CreateCollection, CreateFieldIndexCollection, FieldCondition,
+        Filter, ScrollPoints, SearchPoints,
+    };
 
     #[test]
     fn test_good_request() {
@@ -263,4 +277,58 @@ mod tests {
             "bad search request should error on validation"
         );
     }
+
+    #[test]
+    fn test_bad_filter_request() {
+        let bad_filter = Filter {
+            must: vec![Condition {
+                condition_one_of: Some(ConditionOneOf::Field(FieldCondition {
+                    key: "price".into(),
+                    ..Default::default()
+                })),
+            }],
+            ..Default::default()
+        };
+
+        let bad_request = SearchPoints {
+            collection_name: "test_collection".into(),
+            limit: 10,
+            filter: Some(bad_filter.clone()),
+            ..Default::default()
+        };
+        assert!(
+            bad_request.validate().is_err(),
+            "search request with bad filter should error on validation"
+        );
+
+        let bad_request = ScrollPoints {
+            collection_name: "test_collection".into(),
+            filter: Some(bad_filter.clone()),
+            ..Default::default()
+        };
+        assert!(
+            bad_request.validate().is_err(),
+            "scroll request with bad filter should error on validation"
+        );
+
+        let bad_request = CountPoints {
+            collection_name: "test_collection".into(),
+            filter: Some(bad_filter),
+            ..Default::default()
+        };
+        assert!(
+            bad_request.validate().is_err(),
+            "count request with bad filter should error on validation"
+        );
+
+        let good_request = CountPoints {
+            collection_name: "test_collection".into(),
+            filter: Some(Filter::default()),
+            ..Default::default()
+        };
+        assert!(
+            good_request.validate().is_ok(),
+            "count request with empty filter should not error on validation"
+        );
+    }
 }
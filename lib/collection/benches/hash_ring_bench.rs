@@ -2,8 +2,10 @@
 mod prof;
 
 use collection::hash_ring::HashRing;
+use collection::operations::split_iter_by_shard;
 use criterion::{criterion_group, criterion_main, Criterion};
 use rand::Rng;
+use segment::types::ExtendedPointId;
 
 fn hash_ring_bench(c: &mut Criterion) {
     let mut group = c.benchmark_group("hash-ring-bench");
@@ -32,6 +34,34 @@ fn hash_ring_bench(c: &mut Criterion) {
             let _shard = ring_raw.get(&point);
         })
     });
+
+    let router_fair = ring_fair.router();
+
+    group.bench_function("hash-ring-fair-router", |b| {
+        b.iter(|| {
+            let point = rnd.gen_range(0..100000);
+            let _shard = router_fair.route(&point);
+        })
+    });
+
+    let ids: Vec<ExtendedPointId> = (0..10_000)
+        .map(|_| ExtendedPointId::NumId(rnd.gen_range(0..100000)))
+        .collect();
+
+    group.bench_function("split-num-ids-each", |b| {
+        b.iter(|| {
+            let mut by_shard: Vec<Vec<ExtendedPointId>> = vec![Vec::new(); 10];
+            for id in ids.clone() {
+                by_shard[*ring_fair.get(&id).unwrap() as usize].push(id);
+            }
+        })
+    });
+
+    group.bench_function("split-num-ids-router", |b| {
+        b.iter(|| {
+            let _split = split_iter_by_shard(ids.clone(), |id| *id, &ring_fair);
+        })
+    });
 }
 
 criterion_group! {
@@ -10,9 +10,11 @@ use std::time::Duration;
 use futures::future::{join_all, try_join_all};
 use itertools::Itertools;
 use segment::common::version::StorageVersion;
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::vectors::VectorStruct;
 use segment::spaces::tools::{peek_top_largest_iterable, peek_top_smallest_iterable};
 use segment::types::{
-    ExtendedPointId, Order, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
+    ExtendedPointId, Filter, Order, ScoredPoint, WithPayload, WithPayloadInterface, WithVector,
 };
 use semver::Version;
 use tar::Builder as TarBuilder;
@@ -27,7 +29,7 @@ use crate::config::CollectionConfig;
 use crate::hash_ring::HashRing;
 use crate::operations::config_diff::{CollectionParamsDiff, DiffConfig, OptimizersConfigDiff};
 use crate::operations::consistency_params::ReadConsistency;
-use crate::operations::point_ops::WriteOrdering;
+use crate::operations::point_ops::{PointStruct, WriteOrdering};
 use crate::operations::shared_storage_config::SharedStorageConfig;
 use crate::operations::snapshot_ops::{
     get_snapshot_description, list_snapshots_in_directory, SnapshotDescription,
@@ -35,9 +37,9 @@ use crate::operations::snapshot_ops::{
 use crate::operations::types::{
     CollectionClusterInfo, CollectionError, CollectionInfo, CollectionResult, CountRequest,
     CountResult, LocalShardInfo, NodeType, PointRequest, Record, RemoteShardInfo, ScrollRequest,
-    ScrollResult, SearchRequest, SearchRequestBatch, UpdateResult,
+    ScrollResult, SearchRequest, SearchRequestBatch, UpdateResult, VectorCountsResult,
 };
-use crate::operations::CollectionUpdateOperations;
+use crate::operations::{collect_shard_results, CollectionUpdateOperations};
 use crate::optimizers_builder::OptimizersConfig;
 use crate::shards::channel_service::ChannelService;
 use crate::shards::collection_shard_distribution::CollectionShardDistribution;
@@ -47,6 +49,7 @@ use crate::shards::replica_set::ReplicaState::{Active, Dead, Initializing, Liste
 use crate::shards::replica_set::{
     Change, ChangePeerState, ReplicaState, ShardReplicaSet as ReplicaSetShard,
 }; // TODO rename ReplicaShard to ReplicaSetShard
+use crate::shards::resharding::{move_operations, plan_vector_transfer, VectorTransferPlan};
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_config::{self, ShardConfig};
 use crate::shards::shard_holder::{LockedShardHolder, ShardHolder};
@@ -65,6 +68,9 @@ pub type OnTransferFailure = Arc<dyn Fn(ShardTransfer, CollectionId, &str) + Sen
 pub type OnTransferSuccess = Arc<dyn Fn(ShardTransfer, CollectionId) + Send + Sync>;
 pub type RequestShardTransfer = Arc<dyn Fn(ShardTransfer) + Send + Sync>;
 
+/// Number of points read from shards at once when exporting or counting vectors
+const EXPORT_VECTORS_BATCH_SIZE: usize = 256;
+
 struct CollectionVersion;
 
 impl StorageVersion for CollectionVersion {
@@ -733,13 +739,31 @@ impl Collection {
         operation: CollectionUpdateOperations,
         wait: bool,
         ordering: WriteOrdering,
+    ) -> CollectionResult<UpdateResult> {
+        self.update_from_client_with_target(operation, None, wait, ordering)
+            .await
+    }
+
+    /// Same as `update_from_client`, but if `target_shard` is set, the whole operation is
+    /// applied to all replicas of this shard, ignoring the ring.
+    ///
+    /// Meant for repair tooling, which fixes a specific shard.
+    pub async fn update_from_client_with_target(
+        &self,
+        operation: CollectionUpdateOperations,
+        target_shard: Option<ShardId>,
+        wait: bool,
+        ordering: WriteOrdering,
     ) -> CollectionResult<UpdateResult> {
         operation.validate()?;
+        operation
+            .validate_named_vectors_count(self.shared_storage_config.max_named_vectors_per_point)?;
+        self.check_default_vector_dims(&operation).await?;
         let _update_lock = self.updates_lock.read().await;
 
-        let mut results = {
+        let results = {
             let shards_holder = self.shards_holder.read().await;
-            let shard_to_op = shards_holder.split_by_shard(operation);
+            let shard_to_op = shards_holder.split_by_shard_with_target(operation, target_shard)?;
 
             if shard_to_op.is_empty() {
                 return Err(CollectionError::bad_request(
@@ -755,38 +779,14 @@ impl Collection {
             join_all(shard_requests).await
         };
 
-        let with_error = results
-            .iter()
-            .filter(|result| matches!(result, Err(_)))
-            .count();
-
-        // one request per shard
-        let result_len = results.len();
-
-        if with_error > 0 {
-            let first_err = results
-                .into_iter()
-                .find(|result| matches!(result, Err(_)))
-                .unwrap();
-            // inconsistent if only a subset of the requests fail - one request per shard.
-            if with_error < result_len {
-                first_err.map_err(|err| {
-                    // compute final status code based on the first error
-                    // e.g. a partially successful batch update failing because of bad input is a client error
-                    CollectionError::InconsistentShardFailure {
-                        shards_total: result_len as u32, // report only the number of shards that took part in the update
-                        shards_failed: with_error as u32,
-                        first_err: Box::new(err),
-                    }
-                })
-            } else {
-                // all requests per shard failed - propagate first error (assume there are all the same)
-                first_err
-            }
-        } else {
-            // At least one result is always present.
-            results.pop().unwrap()
+        let mut results = collect_shard_results(results)?;
+        // At least one result is always present, one request per shard.
+        let mut result = results.pop().unwrap();
+        // Each shard reports outcome of its own points only
+        for shard_result in results {
+            result.merge_reports(shard_result);
         }
+        Ok(result)
     }
 
     pub async fn search_batch(
@@ -1065,6 +1065,295 @@ impl Collection {
         Ok(aggregated_count)
     }
 
+    /// Check dimensions of the default vectors set by `operation` against the collection config
+    ///
+    /// Shards check them too, this check rejects the operation before it is sent to any of them.
+    async fn check_default_vector_dims(
+        &self,
+        operation: &CollectionUpdateOperations,
+    ) -> CollectionResult<()> {
+        let default_vectors = match operation {
+            CollectionUpdateOperations::VectorOperation(vector_operation) => {
+                vector_operation.default_vectors()
+            }
+            _ => return Ok(()),
+        };
+        if default_vectors.is_empty() {
+            return Ok(());
+        }
+        let collection_params = self.collection_config.read().await.params.clone();
+        for (vector_name, default) in default_vectors {
+            let expected_dim =
+                collection_params.get_vector_params(vector_name)?.size.get() as usize;
+            if default.len() != expected_dim {
+                return Err(CollectionError::bad_input(format!(
+                    "default vector `{vector_name}` has dimension {}, expected {expected_dim}",
+                    default.len(),
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Check that `filter` matches at most `max_points` points across all target shards
+    ///
+    /// Used as a safety cap before destructive operations by filter. Points are counted exactly,
+    /// so the check is as expensive as a `count` request.
+    ///
+    /// The check is best-effort: the count and the following operation are separate requests,
+    /// so updates applied in between, e.g. by other peers, are not taken into account. A cap
+    /// enforced by each shard would not be exact either, as the limit is for the whole
+    /// collection.
+    pub async fn check_filter_points_limit(
+        &self,
+        filter: &Filter,
+        max_points: usize,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<()> {
+        let request = CountRequest {
+            filter: Some(filter.clone()),
+            exact: true,
+        };
+        let matched = self.count(request, shard_selection).await?.count;
+        if matched > max_points {
+            return Err(CollectionError::bad_request(format!(
+                "Filter matches {matched} points, which exceeds the limit of {max_points} points"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Export vectors with `vector_names` of all points matching `filter`, e.g. for migration
+    ///
+    /// Scrolls through all target shards page by page and merges the results ordered by id.
+    /// Names missing for a point are omitted, points without any of the requested vectors are
+    /// skipped.
+    pub async fn export_vectors_by_filter(
+        &self,
+        filter: Filter,
+        vector_names: Vec<String>,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<(ExtendedPointId, VectorStruct)>> {
+        let mut exported = vec![];
+        let mut offset = None;
+        loop {
+            let request = ScrollRequest {
+                offset,
+                limit: Some(EXPORT_VECTORS_BATCH_SIZE),
+                filter: Some(filter.clone()),
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                // Selector fails on missing vectors, so take all of them and filter
+                with_vector: WithVector::Bool(true),
+            };
+            let page = self
+                .scroll_by(request, read_consistency, shard_selection)
+                .await?;
+            for record in page.points {
+                let mut vectors = match record.vector {
+                    Some(vector) => vector.into_all_vectors().into_owned_map(),
+                    None => continue,
+                };
+                vectors.retain(|name, _| vector_names.contains(name));
+                if !vectors.is_empty() {
+                    exported.push((record.id, NamedVectors::from_map(vectors).into()));
+                }
+            }
+            match page.next_page_offset {
+                Some(next_page_offset) => offset = Some(next_page_offset),
+                None => break,
+            }
+        }
+        Ok(exported)
+    }
+
+    /// Count points with each of the named vectors among the points matching `filter`
+    ///
+    /// Scrolls through all target shards and aggregates the counts. Every vector name of the
+    /// collection is reported, names which none of the points have are counted as zero.
+    pub async fn count_vectors_by_filter(
+        &self,
+        filter: Option<Filter>,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<VectorCountsResult> {
+        let by_name = self
+            .collection_config
+            .read()
+            .await
+            .params
+            .vectors
+            .params_iter()
+            .map(|(name, _)| (name.to_string(), 0))
+            .collect();
+        let mut counts = VectorCountsResult { points: 0, by_name };
+
+        let mut offset = None;
+        loop {
+            let request = ScrollRequest {
+                offset,
+                limit: Some(EXPORT_VECTORS_BATCH_SIZE),
+                filter: filter.clone(),
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                with_vector: WithVector::Bool(true),
+            };
+            let page = self
+                .scroll_by(request, read_consistency, shard_selection)
+                .await?;
+            for record in page.points {
+                counts.points += 1;
+                let vectors = match record.vector {
+                    Some(vector) => vector.into_all_vectors().into_owned_map(),
+                    None => continue,
+                };
+                for name in vectors.into_keys() {
+                    *counts.by_name.entry(name).or_insert(0) += 1;
+                }
+            }
+            match page.next_page_offset {
+                Some(next_page_offset) => offset = Some(next_page_offset),
+                None => break,
+            }
+        }
+        Ok(counts)
+    }
+
+    /// Names of the vectors which at least one point of the collection has, sorted by name
+    ///
+    /// Unlike the vector names of the collection config, names which were never populated or
+    /// which vectors were all deleted are not included. Aggregated over all target shards.
+    pub async fn present_vector_names(
+        &self,
+        read_consistency: Option<ReadConsistency>,
+        shard_selection: Option<ShardId>,
+    ) -> CollectionResult<Vec<String>> {
+        let counts = self
+            .count_vectors_by_filter(None, read_consistency, shard_selection)
+            .await?;
+        let mut names: Vec<String> = counts
+            .by_name
+            .into_iter()
+            .filter(|(_, count)| *count > 0)
+            .map(|(name, _)| name)
+            .collect();
+        names.sort_unstable();
+        Ok(names)
+    }
+
+    /// Move points of the collection to their shards in the ring of `new_shards`, then route
+    /// with that ring
+    ///
+    /// Executes the plan of `plan_vector_transfer` from the current ring of the collection, page
+    /// by page: vectors and payload of each moving point are inserted into its new shard, and
+    /// only then removed from the old one. Updates are locked for the whole move, the ring is
+    /// replaced and saved on disk once all points are moved. All of `new_shards` have to be
+    /// present in the collection already. If the move fails, the current ring is kept and
+    /// resharding with the same `new_shards` can be repeated, already moved points are skipped.
+    ///
+    /// Only this peer switches to the new ring, other peers have to apply it with
+    /// `set_ring_shards`.
+    pub async fn reshard_points(
+        &self,
+        new_shards: Vec<ShardId>,
+    ) -> CollectionResult<VectorTransferPlan> {
+        let _update_lock = self.updates_lock.write().await;
+
+        {
+            let shard_holder = self.shards_holder.read().await;
+            for shard_id in &new_shards {
+                if !shard_holder.contains_shard(shard_id) {
+                    return Err(CollectionError::bad_input(format!(
+                        "Shard {shard_id} of the new ring does not exist"
+                    )));
+                }
+            }
+        }
+        let new_ring = ShardHolder::ring_of(&new_shards);
+
+        let mut plan = VectorTransferPlan::default();
+        let mut offset = None;
+        loop {
+            let request = ScrollRequest {
+                offset,
+                limit: Some(EXPORT_VECTORS_BATCH_SIZE),
+                filter: None,
+                with_payload: Some(WithPayloadInterface::Bool(false)),
+                with_vector: WithVector::Bool(false),
+            };
+            let page = self.scroll_by(request, None, None).await?;
+            let point_ids = page.points.into_iter().map(|record| record.id);
+            let page_plan = {
+                let shard_holder = self.shards_holder.read().await;
+                plan_vector_transfer(shard_holder.ring(), &new_ring, point_ids)
+            };
+            self.move_points(&page_plan).await?;
+            plan.extend(page_plan);
+            match page.next_page_offset {
+                Some(next_page_offset) => offset = Some(next_page_offset),
+                None => break,
+            }
+        }
+
+        self.shards_holder
+            .write()
+            .await
+            .set_ring_shards(new_shards)?;
+        Ok(plan)
+    }
+
+    /// Route the points with the ring of `shards`, without moving them
+    ///
+    /// Used to apply the ring of a resharding done by another peer.
+    pub async fn set_ring_shards(&self, shards: Vec<ShardId>) -> CollectionResult<()> {
+        let _update_lock = self.updates_lock.write().await;
+        self.shards_holder.write().await.set_ring_shards(shards)
+    }
+
+    /// Move the points of `plan` between the shards, in batches
+    async fn move_points(&self, plan: &VectorTransferPlan) -> CollectionResult<()> {
+        for ((from, to), ids) in &plan.moves {
+            for ids in ids.chunks(EXPORT_VECTORS_BATCH_SIZE) {
+                let request = PointRequest {
+                    ids: ids.to_vec(),
+                    with_payload: Some(WithPayloadInterface::Bool(true)),
+                    with_vector: WithVector::Bool(true),
+                };
+                let points = self
+                    .retrieve(request, None, Some(*from))
+                    .await?
+                    .into_iter()
+                    .map(|record| match record.vector {
+                        Some(vector) => Ok(PointStruct {
+                            id: record.id,
+                            vector,
+                            payload: record.payload,
+                        }),
+                        None => Err(CollectionError::service_error(format!(
+                            "Point {} has no vectors to move",
+                            record.id
+                        ))),
+                    })
+                    .collect::<CollectionResult<Vec<_>>>()?;
+                if points.is_empty() {
+                    continue;
+                }
+
+                let shard_holder = self.shards_holder.read().await;
+                for (shard_id, operation) in move_operations(*from, *to, points) {
+                    let replica_set = shard_holder.get_shard(&shard_id).ok_or_else(|| {
+                        CollectionError::service_error(format!(
+                            "No target shard {shard_id} found for update"
+                        ))
+                    })?;
+                    replica_set
+                        .update_with_consistency(operation, true, WriteOrdering::default())
+                        .await?;
+                }
+            }
+        }
+        Ok(())
+    }
+
     pub async fn retrieve(
         &self,
         request: PointRequest,
@@ -1306,6 +1595,7 @@ impl Collection {
     pub async fn state(&self) -> State {
         let shards_holder = self.shards_holder.read().await;
         let transfers = shards_holder.shard_transfers.read().clone();
+        let ring_shards = shards_holder.ring_shards();
         State {
             config: self.collection_config.read().await.clone(),
             shards: shards_holder
@@ -1318,6 +1608,7 @@ impl Collection {
                 })
                 .collect(),
             transfers,
+            ring_shards,
         }
     }
 
@@ -1400,6 +1691,7 @@ impl Collection {
                     .create_snapshot(&shard_snapshot_path, save_wal)
                     .await?;
             }
+            shards_holder.save_ring_shards_to(&snapshot_path_with_tmp_extension)?;
         }
 
         CollectionVersion::save(&snapshot_path_with_tmp_extension)?;
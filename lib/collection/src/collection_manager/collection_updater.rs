@@ -1,11 +1,56 @@
+use std::collections::HashMap;
+
 use parking_lot::RwLock;
 use segment::types::SeqNumberType;
 
 use crate::collection_manager::holders::segment_holder::SegmentHolder;
 use crate::collection_manager::segments_updater::*;
 use crate::operations::types::{CollectionError, CollectionResult};
+use crate::operations::vector_ops::{
+    merge_deleted_vectors, merge_vectors_reports, DeletedVectorsCount, UpdateVectorsReport,
+};
 use crate::operations::CollectionUpdateOperations;
 
+/// Outcome of an update operation applied to the segments
+#[derive(Debug, Default)]
+pub struct UpdateOutcome {
+    /// Number of processed points
+    pub processed: usize,
+    /// Per-point outcome of a partial vector update
+    pub vectors_report: Option<UpdateVectorsReport>,
+    /// Number of deleted vectors per vector name
+    pub deleted_vectors: Option<HashMap<String, usize>>,
+}
+
+impl UpdateOutcome {
+    /// Append outcome of the next operation of a batch
+    pub fn merge(&mut self, other: UpdateOutcome) {
+        self.processed += other.processed;
+        merge_vectors_reports(&mut self.vectors_report, other.vectors_report);
+        merge_deleted_vectors(&mut self.deleted_vectors, other.deleted_vectors);
+    }
+}
+
+impl From<usize> for UpdateOutcome {
+    fn from(processed: usize) -> Self {
+        Self {
+            processed,
+            vectors_report: None,
+            deleted_vectors: None,
+        }
+    }
+}
+
+impl From<DeletedVectorsCount> for UpdateOutcome {
+    fn from(deleted: DeletedVectorsCount) -> Self {
+        Self {
+            processed: deleted.points,
+            vectors_report: None,
+            deleted_vectors: Some(deleted.by_name),
+        }
+    }
+}
+
 /// Implementation of the update operation
 #[derive(Default)]
 pub struct CollectionUpdater {}
@@ -18,7 +63,7 @@ impl CollectionUpdater {
     fn handle_update_result(
         segments: &RwLock<SegmentHolder>,
         op_num: SeqNumberType,
-        operation_result: &CollectionResult<usize>,
+        operation_result: &CollectionResult<UpdateOutcome>,
     ) {
         match operation_result {
             Ok(_) => {
@@ -48,20 +93,31 @@ impl CollectionUpdater {
         op_num: SeqNumberType,
         operation: CollectionUpdateOperations,
     ) -> CollectionResult<usize> {
+        Self::update_with_outcome(segments, op_num, operation).map(|outcome| outcome.processed)
+    }
+
+    /// Same as `update`, but also returns details of the update, e.g. failed points
+    pub fn update_with_outcome(
+        segments: &RwLock<SegmentHolder>,
+        op_num: SeqNumberType,
+        operation: CollectionUpdateOperations,
+    ) -> CollectionResult<UpdateOutcome> {
         // Allow only one update at a time, ensure no data races between segments.
         // let _lock = self.update_lock.lock().unwrap();
         let operation_result = match operation {
             CollectionUpdateOperations::PointOperation(point_operation) => {
-                process_point_operation(segments, op_num, point_operation)
+                process_point_operation(segments, op_num, point_operation).map(UpdateOutcome::from)
             }
             CollectionUpdateOperations::VectorOperation(vector_operation) => {
                 process_vector_operation(segments, op_num, vector_operation)
             }
             CollectionUpdateOperations::PayloadOperation(payload_operation) => {
                 process_payload_operation(segments, op_num, payload_operation)
+                    .map(UpdateOutcome::from)
             }
             CollectionUpdateOperations::FieldIndexOperation(index_operation) => {
                 process_field_index_operation(segments, op_num, &index_operation)
+                    .map(UpdateOutcome::from)
             }
         };
 
@@ -73,17 +129,31 @@ impl CollectionUpdater {
 
 #[cfg(test)]
 mod tests {
-    use segment::data_types::vectors::{only_default_vector, DEFAULT_VECTOR_NAME};
-    use segment::types::{Payload, WithPayload};
+    use std::collections::HashMap;
+
+    use segment::data_types::named_vectors::NamedVectors;
+    use segment::data_types::vectors::{only_default_vector, VectorStruct, DEFAULT_VECTOR_NAME};
+    use segment::entry::entry_point::SegmentEntry;
+    use segment::segment_constructor::build_segment;
+    use segment::segment_constructor::simple_segment_constructor::build_multivec_segment;
+    use segment::types::{
+        Condition, Distance, FieldCondition, Filter, Indexes, Payload, Range, SegmentConfig,
+        VectorDataConfig, VectorStorageType, WithPayload,
+    };
     use serde_json::json;
     use tempfile::Builder;
 
     use super::*;
     use crate::collection_manager::fixtures::build_test_holder;
+    use crate::collection_manager::holders::segment_holder::LockedSegment;
     use crate::collection_manager::segments_searcher::SegmentsSearcher;
     use crate::collection_manager::segments_updater::upsert_points;
-    use crate::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
+    use crate::operations::payload_ops::{DeletePayload, PayloadOps, SetNestedPayload, SetPayload};
     use crate::operations::point_ops::{PointOperations, PointStruct};
+    use crate::operations::vector_ops::{
+        ConcatVectors, DimensionRepairPolicy, FailedPointVectors, PointVectors, ReindexVectors,
+        RepairVectors, SetDefaultVectorByFilter, UpdateVectors, VectorOperations,
+    };
 
     #[test]
     fn test_sync_ops() {
@@ -291,4 +361,642 @@ mod tests {
         assert_eq!(res.len(), 1);
         assert!(!res[0].payload.as_ref().unwrap().contains_key("color"));
     }
+
+    #[tokio::test]
+    async fn test_set_nested_payload() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+        let segments = build_test_holder(dir.path());
+
+        let reviews = json!([
+            { "stars": 5 },
+            { "stars": 2 },
+            { "stars": 4, "verified": false },
+        ]);
+        process_payload_operation(
+            &segments,
+            100,
+            PayloadOps::OverwritePayload(SetPayload {
+                payload: json!({ "title": "book", "reviews": reviews }).into(),
+                points: Some(vec![1.into(), 2.into()]),
+                filter: None,
+            }),
+        )
+        .unwrap();
+
+        let good_reviews = Filter::new_must(Condition::Field(FieldCondition::new_range(
+            "stars".to_string(),
+            Range {
+                lt: None,
+                gt: None,
+                gte: Some(4.0),
+                lte: None,
+            },
+        )));
+        let set_verified = |points: Vec<_>| {
+            PayloadOps::SetNestedPayload(SetNestedPayload {
+                payload: json!({ "verified": true }).into(),
+                key: "reviews".to_string(),
+                filter: good_reviews.clone(),
+                points,
+            })
+        };
+        process_payload_operation(&segments, 101, set_verified(vec![1.into()])).unwrap();
+
+        let res = SegmentsSearcher::retrieve(
+            &segments,
+            &[1.into(), 2.into()],
+            &WithPayload::from(true),
+            &false.into(),
+        )
+        .await
+        .unwrap();
+        let payload_of = |id: u64| {
+            res.iter()
+                .find(|record| record.id == id.into())
+                .and_then(|record| record.payload.clone())
+                .unwrap()
+        };
+
+        // Only the matching elements are updated, the rest of the payload is kept intact
+        let updated = payload_of(1);
+        assert_eq!(
+            updated.0.get("reviews"),
+            Some(&json!([
+                { "stars": 5, "verified": true },
+                { "stars": 2 },
+                { "stars": 4, "verified": true },
+            ]))
+        );
+        assert_eq!(updated.0.get("title"), Some(&json!("book")));
+        assert_eq!(payload_of(2).0.get("reviews"), Some(&reviews));
+
+        let missing = process_payload_operation(&segments, 102, set_verified(vec![500.into()]));
+        assert!(matches!(
+            missing,
+            Err(CollectionError::PointNotFound { .. })
+        ));
+    }
+
+    #[test]
+    fn test_delete_vectors_counts() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let mut segment = build_multivec_segment(dir.path(), 4, 4, Distance::Dot).unwrap();
+        for id in 1..=3u64 {
+            let mut vectors = NamedVectors::default();
+            vectors.insert("vector1".to_owned(), vec![1.0, 0.0, 1.0, 0.0]);
+            vectors.insert("vector2".to_owned(), vec![0.0, 1.0, 0.0, 1.0]);
+            segment.upsert_point(1, id.into(), &vectors).unwrap();
+        }
+
+        let mut holder = SegmentHolder::default();
+        holder.add(segment);
+        let segments = RwLock::new(holder);
+
+        let deleted =
+            delete_vectors(&segments.read(), 2, &[1.into()], &["vector1".to_string()]).unwrap();
+        assert_eq!(deleted.get("vector1"), 1);
+        assert_eq!(deleted.points, 1);
+
+        // `vector1` of point 1 is already absent, so it is not counted again
+        let deleted = delete_vectors(
+            &segments.read(),
+            3,
+            &[1.into(), 2.into(), 3.into()],
+            &["vector1".to_string(), "vector2".to_string()],
+        )
+        .unwrap();
+        assert_eq!(deleted.get("vector1"), 2);
+        assert_eq!(deleted.get("vector2"), 3);
+        assert_eq!(deleted.total(), 5);
+        assert_eq!(deleted.points, 2);
+
+        // Nothing left to delete, zero counts are still reported to the caller
+        let operation =
+            CollectionUpdateOperations::VectorOperation(VectorOperations::DeleteVectors(
+                vec![1.into(), 2.into(), 3.into()].into(),
+                vec!["vector2".to_string()],
+            ));
+        let outcome = CollectionUpdater::update_with_outcome(&segments, 4, operation).unwrap();
+        assert_eq!(outcome.processed, 0);
+        assert_eq!(
+            outcome.deleted_vectors,
+            Some(HashMap::from([("vector2".to_string(), 0)]))
+        );
+    }
+
+    #[test]
+    fn test_update_vectors_partial() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let mut segment = build_multivec_segment(dir.path(), 4, 4, Distance::Dot).unwrap();
+        for id in 1..=3u64 {
+            let mut vectors = NamedVectors::default();
+            vectors.insert("vector1".to_owned(), vec![1.0, 0.0, 1.0, 0.0]);
+            vectors.insert("vector2".to_owned(), vec![0.0, 1.0, 0.0, 1.0]);
+            segment.upsert_point(1, id.into(), &vectors).unwrap();
+        }
+
+        let mut holder = SegmentHolder::default();
+        let segment_id = holder.add(segment);
+        let segments = RwLock::new(holder);
+
+        let new_vector = vec![2.0, 2.0, 2.0, 2.0];
+        let points: Vec<_> = [1, 42, 3]
+            .into_iter()
+            .map(|id: u64| PointVectors {
+                id: id.into(),
+                vector: VectorStruct::Multi(HashMap::from([(
+                    "vector1".to_string(),
+                    new_vector.clone(),
+                )])),
+            })
+            .collect();
+
+        let update = |partial| {
+            CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(
+                UpdateVectors {
+                    points: points.clone(),
+                    partial,
+                },
+            ))
+        };
+
+        // Missing point fails the whole update by default
+        match CollectionUpdater::update(&segments, 2, update(false)) {
+            Err(CollectionError::PointNotFound { missed_point_id }) => {
+                assert_eq!(missed_point_id, 42.into())
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        // Partial update skips the missing point and updates the rest of the batch
+        let outcome = CollectionUpdater::update_with_outcome(&segments, 3, update(true)).unwrap();
+        assert_eq!(outcome.processed, 2);
+        let report = outcome.vectors_report.unwrap();
+        assert!(!report.is_complete());
+        assert_eq!(report.succeeded, vec![1.into(), 3.into()]);
+        assert_eq!(
+            report.failed,
+            vec![FailedPointVectors {
+                id: 42.into(),
+                error: CollectionError::PointNotFound {
+                    missed_point_id: 42.into()
+                }
+                .to_string(),
+            }]
+        );
+
+        let segment = segments.read().get(segment_id).unwrap().get();
+        for id in [1u64, 3] {
+            let vector = segment
+                .read()
+                .vector("vector1", id.into())
+                .unwrap()
+                .unwrap();
+            assert_eq!(vector, new_vector);
+        }
+    }
+
+    #[test]
+    fn test_unknown_vector_name() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let mut segment = build_multivec_segment(dir.path(), 4, 4, Distance::Dot).unwrap();
+        let mut vectors = NamedVectors::default();
+        vectors.insert("vector1".to_owned(), vec![1.0, 0.0, 1.0, 0.0]);
+        vectors.insert("vector2".to_owned(), vec![0.0, 1.0, 0.0, 1.0]);
+        segment.upsert_point(1, 1.into(), &vectors).unwrap();
+
+        let mut holder = SegmentHolder::default();
+        holder.add(segment);
+        let segments = RwLock::new(holder);
+
+        let assert_unknown = |result: CollectionResult<()>| match result {
+            Err(CollectionError::UnknownVectorName(name)) => assert_eq!(name, "unknown"),
+            Err(err) => panic!("unexpected error: {err}"),
+            Ok(()) => panic!("unknown vector name is accepted"),
+        };
+
+        let names = ["vector1".to_string(), "unknown".to_string()];
+        assert_unknown(delete_vectors(&segments.read(), 2, &[1.into()], &names).map(|_| ()));
+        // Reported even if there are no points to delete vectors from
+        assert_unknown(delete_vectors(&segments.read(), 2, &[42.into()], &names).map(|_| ()));
+
+        let points = vec![PointVectors {
+            id: 1.into(),
+            vector: VectorStruct::Multi(HashMap::from([(
+                "unknown".to_string(),
+                vec![2.0, 2.0, 2.0, 2.0],
+            )])),
+        }];
+        assert_unknown(update_vectors(&segments.read(), 3, &points).map(|_| ()));
+
+        // Nothing is changed
+        let segment = segments.read().iter().next().unwrap().1.get();
+        let vector = segment.read().vector("vector1", 1.into()).unwrap();
+        assert_eq!(vector, Some(vec![1.0, 0.0, 1.0, 0.0]));
+
+        // Without segments there are no vector names to check against
+        let empty = SegmentHolder::default();
+        let deleted = delete_vectors(&empty, 4, &[1.into()], &names).unwrap();
+        assert_eq!(deleted.points, 0);
+    }
+
+    #[test]
+    fn test_reindex_vectors() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let mut segment = build_multivec_segment(dir.path(), 4, 4, Distance::Dot).unwrap();
+        for id in 1..=3u64 {
+            let mut vectors = NamedVectors::default();
+            vectors.insert("vector1".to_owned(), vec![1.0, 0.0, id as f32 + 1.0, 0.0]);
+            vectors.insert("vector2".to_owned(), vec![0.0, 2.0, 0.0, 0.0]);
+            segment.upsert_point(1, id.into(), &vectors).unwrap();
+        }
+        // Distance of `vector1` is changed, stored vectors are not normalized yet
+        segment
+            .segment_config
+            .vector_data
+            .get_mut("vector1")
+            .unwrap()
+            .distance = Distance::Cosine;
+
+        let mut holder = SegmentHolder::default();
+        let segment_id = holder.add(segment);
+        let segments = RwLock::new(holder);
+
+        let operation = CollectionUpdateOperations::VectorOperation(
+            VectorOperations::ReindexVectors(ReindexVectors {
+                name: "vector1".to_string(),
+            }),
+        );
+        assert!(operation.is_write_operation());
+        let updated = CollectionUpdater::update(&segments, 2, operation.clone()).unwrap();
+        assert_eq!(updated, 3);
+
+        let segment = segments.read().get(segment_id).unwrap().get();
+        for id in 1..=3u64 {
+            let vector1 = segment
+                .read()
+                .vector("vector1", id.into())
+                .unwrap()
+                .unwrap();
+            let norm = vector1.iter().map(|x| x * x).sum::<f32>().sqrt();
+            assert!(
+                (norm - 1.0).abs() < 1e-5,
+                "vector of point {id} is not normalized"
+            );
+            assert!(vector1[2] > vector1[0]);
+
+            // Vectors with unchanged distance are kept as is
+            let vector2 = segment
+                .read()
+                .vector("vector2", id.into())
+                .unwrap()
+                .unwrap();
+            assert_eq!(vector2, vec![0.0, 2.0, 0.0, 0.0]);
+        }
+
+        // Vectors are normalized already, nothing to update
+        let updated = CollectionUpdater::update(&segments, 3, operation).unwrap();
+        assert_eq!(updated, 0);
+    }
+
+    #[test]
+    fn test_concat_vectors() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let vector_config = |size| VectorDataConfig {
+            size,
+            distance: Distance::Dot,
+            storage_type: VectorStorageType::Memory,
+            index: Indexes::Plain {},
+            quantization_config: None,
+        };
+        let config = SegmentConfig {
+            vector_data: HashMap::from([
+                ("image".to_owned(), vector_config(2)),
+                ("text".to_owned(), vector_config(3)),
+                ("combined".to_owned(), vector_config(5)),
+            ]),
+            payload_storage_type: Default::default(),
+        };
+        let mut segment = build_segment(dir.path(), &config).unwrap();
+        for id in 1..=2u64 {
+            let mut vectors = NamedVectors::default();
+            vectors.insert("image".to_owned(), vec![id as f32, 0.5]);
+            vectors.insert("text".to_owned(), vec![1.0, 2.0, 3.0]);
+            vectors.insert("combined".to_owned(), vec![0.0; 5]);
+            segment.upsert_point(1, id.into(), &vectors).unwrap();
+        }
+
+        let mut holder = SegmentHolder::default();
+        let segment_id = holder.add(segment);
+        let segments = RwLock::new(holder);
+
+        let operation = CollectionUpdateOperations::VectorOperation(
+            VectorOperations::ConcatVectors(ConcatVectors {
+                sources: vec!["image".to_string(), "text".to_string()],
+                target: "combined".to_string(),
+            }),
+        );
+        assert!(operation.is_write_operation());
+        let updated = CollectionUpdater::update(&segments, 2, operation).unwrap();
+        assert_eq!(updated, 2);
+
+        let segment = segments.read().get(segment_id).unwrap().get();
+        for id in 1..=2u64 {
+            let combined = segment
+                .read()
+                .vector("combined", id.into())
+                .unwrap()
+                .unwrap();
+            assert_eq!(combined.len(), 5);
+            assert_eq!(combined, vec![id as f32, 0.5, 1.0, 2.0, 3.0]);
+        }
+
+        // Wrong target dimension is rejected before any point is updated
+        let operation = CollectionUpdateOperations::VectorOperation(
+            VectorOperations::ConcatVectors(ConcatVectors {
+                sources: vec!["text".to_string()],
+                target: "combined".to_string(),
+            }),
+        );
+        let result = CollectionUpdater::update(&segments, 3, operation);
+        assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+        let combined = segment
+            .read()
+            .vector("combined", 1.into())
+            .unwrap()
+            .unwrap();
+        assert_eq!(combined, vec![1.0, 0.5, 1.0, 2.0, 3.0]);
+    }
+
+    #[test]
+    fn test_batch_vector_operations() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let vector_config = |size| VectorDataConfig {
+            size,
+            distance: Distance::Dot,
+            storage_type: VectorStorageType::Memory,
+            index: Indexes::Plain {},
+            quantization_config: None,
+        };
+        let config = SegmentConfig {
+            vector_data: HashMap::from([
+                ("image".to_owned(), vector_config(2)),
+                ("text".to_owned(), vector_config(3)),
+            ]),
+            payload_storage_type: Default::default(),
+        };
+        let mut segment = build_segment(dir.path(), &config).unwrap();
+        for id in 1..=2u64 {
+            let mut vectors = NamedVectors::default();
+            vectors.insert("image".to_owned(), vec![id as f32, 0.5]);
+            vectors.insert("text".to_owned(), vec![1.0, 2.0, 3.0]);
+            segment.upsert_point(1, id.into(), &vectors).unwrap();
+        }
+
+        let mut holder = SegmentHolder::default();
+        let segment_id = holder.add(segment);
+        let segments = RwLock::new(holder);
+        let vector = |name: &str, id: u64| {
+            let segment = segments.read().get(segment_id).unwrap().get();
+            let vector = segment.read().vector(name, id.into()).unwrap();
+            vector
+        };
+        let update_image = |id: u64, image: Vec<f32>| {
+            VectorOperations::UpdateVectors(UpdateVectors {
+                points: vec![PointVectors {
+                    id: id.into(),
+                    vector: VectorStruct::Multi(HashMap::from([("image".to_string(), image)])),
+                }],
+                partial: false,
+            })
+        };
+
+        // Unknown name in any of the operations rejects the whole batch
+        let operation = CollectionUpdateOperations::VectorOperation(VectorOperations::Batch(vec![
+            VectorOperations::DeleteVectors(
+                vec![1.into(), 2.into()].into(),
+                vec!["image".to_string()],
+            ),
+            VectorOperations::DeleteVectors(vec![1.into()].into(), vec!["audio".to_string()]),
+        ]));
+        let result = CollectionUpdater::update(&segments, 2, operation);
+        assert!(matches!(result, Err(CollectionError::UnknownVectorName(_))));
+        assert_eq!(vector("image", 2), Some(vec![2.0, 0.5]));
+
+        // Operations are applied in order, the update is not overridden by the prior deletion
+        let operation = CollectionUpdateOperations::VectorOperation(VectorOperations::Batch(vec![
+            VectorOperations::DeleteVectors(
+                vec![1.into(), 2.into()].into(),
+                vec!["image".to_string()],
+            ),
+            update_image(1, vec![5.0, 5.0]),
+        ]));
+        assert!(operation.is_write_operation());
+        let processed = CollectionUpdater::update(&segments, 3, operation).unwrap();
+        assert_eq!(processed, 3);
+
+        assert_eq!(vector("image", 1), Some(vec![5.0, 5.0]));
+        assert_eq!(vector("image", 2), None);
+        assert_eq!(vector("text", 1), Some(vec![1.0, 2.0, 3.0]));
+        assert_eq!(vector("text", 2), Some(vec![1.0, 2.0, 3.0]));
+
+        // Missing point in any of the operations rejects the whole batch
+        let operation = CollectionUpdateOperations::VectorOperation(VectorOperations::Batch(vec![
+            VectorOperations::DeleteVectors(vec![1.into()].into(), vec!["text".to_string()]),
+            update_image(42, vec![7.0, 7.0]),
+        ]));
+        match CollectionUpdater::update(&segments, 4, operation) {
+            Err(CollectionError::PointNotFound { missed_point_id }) => {
+                assert_eq!(missed_point_id, 42.into())
+            }
+            result => panic!("unexpected result: {result:?}"),
+        }
+        assert_eq!(vector("text", 1), Some(vec![1.0, 2.0, 3.0]));
+
+        // So does a vector of a wrong dimension
+        let operation = CollectionUpdateOperations::VectorOperation(VectorOperations::Batch(vec![
+            VectorOperations::DeleteVectors(vec![1.into()].into(), vec!["text".to_string()]),
+            update_image(2, vec![7.0, 7.0, 7.0]),
+        ]));
+        let result = CollectionUpdater::update(&segments, 5, operation);
+        assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+        assert_eq!(vector("text", 1), Some(vec![1.0, 2.0, 3.0]));
+        assert_eq!(vector("image", 2), None);
+    }
+
+    #[test]
+    fn test_repair_vectors() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let vector_config = |size| VectorDataConfig {
+            size,
+            distance: Distance::Dot,
+            storage_type: VectorStorageType::Memory,
+            index: Indexes::Plain {},
+            quantization_config: None,
+        };
+        let config = |image_dim| SegmentConfig {
+            vector_data: HashMap::from([
+                ("image".to_owned(), vector_config(image_dim)),
+                ("text".to_owned(), vector_config(3)),
+            ]),
+            payload_storage_type: Default::default(),
+        };
+        let old_segment = |image_dim: usize, id: u64| {
+            let mut segment = build_segment(dir.path(), &config(image_dim)).unwrap();
+            let mut vectors = NamedVectors::default();
+            vectors.insert(
+                "image".to_owned(),
+                (1..=image_dim).map(|i| i as f32).collect(),
+            );
+            vectors.insert("text".to_owned(), vec![1.0, 1.0, 1.0]);
+            segment.upsert_point(1, id.into(), &vectors).unwrap();
+            let payload: Payload = json!({ "id": id }).into();
+            segment.set_full_payload(1, id.into(), &payload).unwrap();
+            // Created with other parameters of the `image` vector before they were changed
+            segment.appendable_flag = false;
+            segment
+        };
+        let build_holder = || {
+            let mut holder = SegmentHolder::default();
+            let appendable_id = holder.add(build_segment(dir.path(), &config(4)).unwrap());
+            holder.add(old_segment(2, 1));
+            holder.add(old_segment(6, 2));
+            (appendable_id, RwLock::new(holder))
+        };
+        let repair = |segments: &RwLock<SegmentHolder>, policy| {
+            let operation = CollectionUpdateOperations::VectorOperation(
+                VectorOperations::RepairVectors(RepairVectors {
+                    name: "image".to_string(),
+                    policy,
+                }),
+            );
+            assert!(operation.is_write_operation());
+            CollectionUpdater::update(segments, 10, operation).unwrap()
+        };
+        let vector = |segment: &LockedSegment, name: &str, id: u64| {
+            segment.get().read().vector(name, id.into()).unwrap()
+        };
+
+        // Resize truncates longer vectors and pads shorter ones with zeros
+        let (appendable_id, segments) = build_holder();
+        assert_eq!(repair(&segments, DimensionRepairPolicy::Resize), 2);
+        {
+            let holder = segments.read();
+            let appendable = holder.get(appendable_id).unwrap();
+            assert_eq!(
+                vector(appendable, "image", 1),
+                Some(vec![1.0, 2.0, 0.0, 0.0])
+            );
+            assert_eq!(
+                vector(appendable, "image", 2),
+                Some(vec![1.0, 2.0, 3.0, 4.0])
+            );
+            // Other vectors and payload are moved along
+            assert_eq!(vector(appendable, "text", 1), Some(vec![1.0, 1.0, 1.0]));
+            let payload = appendable.get().read().payload(2.into()).unwrap();
+            assert_eq!(payload, Payload::from(json!({ "id": 2 })));
+            for (segment_id, segment) in holder.iter() {
+                if *segment_id != appendable_id {
+                    assert_eq!(segment.get().read().available_point_count(), 0);
+                }
+            }
+        }
+        // Nothing is left to repair
+        assert_eq!(repair(&segments, DimensionRepairPolicy::Resize), 0);
+
+        // Clear removes the vector, keeping the point
+        let (appendable_id, segments) = build_holder();
+        assert_eq!(repair(&segments, DimensionRepairPolicy::Clear), 2);
+        let holder = segments.read();
+        let appendable = holder.get(appendable_id).unwrap();
+        for id in 1..=2 {
+            assert_eq!(vector(appendable, "image", id), None);
+            assert_eq!(vector(appendable, "text", id), Some(vec![1.0, 1.0, 1.0]));
+        }
+    }
+
+    #[test]
+    fn test_set_default_vector_by_filter() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let vector_config = VectorDataConfig {
+            size: 2,
+            distance: Distance::Dot,
+            storage_type: VectorStorageType::Memory,
+            index: Indexes::Plain {},
+            quantization_config: None,
+        };
+        let config = SegmentConfig {
+            vector_data: HashMap::from([
+                ("image".to_owned(), vector_config.clone()),
+                ("thumbnail".to_owned(), vector_config),
+            ]),
+            payload_storage_type: Default::default(),
+        };
+        let mut segment = build_segment(dir.path(), &config).unwrap();
+        for id in 1..=4u64 {
+            let mut vectors = NamedVectors::default();
+            vectors.insert("image".to_owned(), vec![id as f32, 0.5]);
+            if id % 2 == 1 {
+                vectors.insert("thumbnail".to_owned(), vec![9.0, 9.0]);
+            }
+            segment.upsert_point(1, id.into(), &vectors).unwrap();
+            let kind = if id == 4 { "other" } else { "backfill" };
+            let payload: Payload = json!({ "kind": kind }).into();
+            segment.set_full_payload(1, id.into(), &payload).unwrap();
+        }
+
+        let mut holder = SegmentHolder::default();
+        let segment_id = holder.add(segment);
+        let segments = RwLock::new(holder);
+        let vector = |name: &str, id: u64| {
+            let segment = segments.read().get(segment_id).unwrap().get();
+            let vector = segment.read().vector(name, id.into()).unwrap();
+            vector
+        };
+        let set_default = |name: &str, default: Vec<f32>| {
+            CollectionUpdateOperations::VectorOperation(VectorOperations::SetDefaultVectorByFilter(
+                SetDefaultVectorByFilter {
+                    filter: Filter::new_must(Condition::Field(FieldCondition::new_match(
+                        "kind".to_string(),
+                        "backfill".to_string().into(),
+                    ))),
+                    name: name.to_string(),
+                    default: default.into(),
+                },
+            ))
+        };
+
+        let result = CollectionUpdater::update(&segments, 2, set_default("audio", vec![0.0, 0.0]));
+        assert!(matches!(result, Err(CollectionError::UnknownVectorName(_))));
+
+        // Wrong dimension is rejected before any point is updated
+        let result = CollectionUpdater::update(&segments, 2, set_default("thumbnail", vec![0.0]));
+        assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+        assert_eq!(vector("thumbnail", 2), None);
+
+        let operation = set_default("thumbnail", vec![0.0, 0.0]);
+        assert!(operation.is_write_operation());
+        let processed = CollectionUpdater::update(&segments, 3, operation).unwrap();
+        assert_eq!(processed, 1);
+
+        // Present vectors are untouched, points not matching the filter are skipped
+        assert_eq!(vector("thumbnail", 1), Some(vec![9.0, 9.0]));
+        assert_eq!(vector("thumbnail", 2), Some(vec![0.0, 0.0]));
+        assert_eq!(vector("thumbnail", 3), Some(vec![9.0, 9.0]));
+        assert_eq!(vector("thumbnail", 4), None);
+        assert_eq!(vector("image", 2), Some(vec![2.0, 0.5]));
+
+        // Repeated backfill has nothing to do
+        let operation = set_default("thumbnail", vec![0.0, 0.0]);
+        let processed = CollectionUpdater::update(&segments, 4, operation).unwrap();
+        assert_eq!(processed, 0);
+    }
 }
@@ -9,6 +9,8 @@ use std::time::Duration;
 use parking_lot::{RwLock, RwLockReadGuard, RwLockUpgradableReadGuard, RwLockWriteGuard};
 use rand::seq::SliceRandom;
 use rand::{thread_rng, Rng};
+use segment::data_types::named_vectors::NamedVectors;
+use segment::data_types::vectors::VectorElementType;
 use segment::entry::entry_point::{OperationError, OperationResult, SegmentEntry};
 use segment::segment::Segment;
 use segment::types::{PointIdType, SeqNumberType};
@@ -333,9 +335,26 @@ impl<'s> SegmentHolder {
         &self,
         op_num: SeqNumberType,
         ids: &[PointIdType],
+        f: F,
+    ) -> OperationResult<HashSet<PointIdType>>
+    where
+        F: FnMut(PointIdType, &mut RwLockWriteGuard<dyn SegmentEntry>) -> OperationResult<bool>,
+    {
+        self.apply_points_to_appendable_with(op_num, ids, |_point_id, _vectors| Ok(()), f)
+    }
+
+    /// Same as `apply_points_to_appendable`, but vectors of points moved out of un-appendable
+    /// segments are passed through `fit_vectors` first, e.g. to fit vector parameters of the
+    /// appendable segments.
+    pub fn apply_points_to_appendable_with<P, F>(
+        &self,
+        op_num: SeqNumberType,
+        ids: &[PointIdType],
+        mut fit_vectors: P,
         mut f: F,
     ) -> OperationResult<HashSet<PointIdType>>
     where
+        P: FnMut(PointIdType, &mut HashMap<String, Vec<VectorElementType>>) -> OperationResult<()>,
         F: FnMut(PointIdType, &mut RwLockWriteGuard<dyn SegmentEntry>) -> OperationResult<bool>,
     {
         // Choose random appendable segment from this
@@ -357,7 +376,9 @@ impl<'s> SegmentHolder {
                 self.aloha_random_write(
                     &appendable_segments,
                     |_appendable_idx, appendable_write_segment| {
-                        let all_vectors = write_segment.all_vectors(point_id)?;
+                        let mut all_vectors = write_segment.all_vectors(point_id)?.into_owned_map();
+                        fit_vectors(point_id, &mut all_vectors)?;
+                        let all_vectors = NamedVectors::from_map(all_vectors);
                         let payload = write_segment.payload(point_id)?;
 
                         appendable_write_segment.upsert_point(op_num, point_id, &all_vectors)?;
@@ -1,21 +1,30 @@
 //! A collection of functions for updating points and payloads stored in segments
 
 use std::collections::{HashMap, HashSet};
+use std::ops::Deref;
 
 use itertools::Itertools;
 use parking_lot::{RwLock, RwLockWriteGuard};
+use segment::common::utils::JsonPathPayload;
 use segment::data_types::named_vectors::NamedVectors;
-use segment::entry::entry_point::{OperationResult, SegmentEntry};
+use segment::data_types::vectors::{VectorElementType, VectorStruct, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::{OperationError, OperationResult, SegmentEntry};
+use segment::payload_storage::nested_query_checker::nested_filter_positions;
 use segment::types::{
     Filter, Payload, PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PointIdType,
     SeqNumberType,
 };
+use serde_json::Value;
 
+use crate::collection_manager::collection_updater::UpdateOutcome;
 use crate::collection_manager::holders::segment_holder::SegmentHolder;
 use crate::operations::payload_ops::PayloadOps;
 use crate::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
 use crate::operations::types::{CollectionError, CollectionResult};
-use crate::operations::vector_ops::{PointVectors, VectorOperations};
+use crate::operations::vector_ops::{
+    DeletedVectorsCount, DimensionRepairPolicy, FailedPointVectors, PointVectors,
+    SetDefaultVectorByFilter, UpdateVectorsReport, VectorOperations,
+};
 use crate::operations::FieldIndexOperations;
 
 pub(crate) fn check_unprocessed_points(
@@ -52,12 +61,38 @@ pub(crate) fn delete_points(
         .map_err(Into::into)
 }
 
+/// Check that all `vector_names` are configured in the segments of the collection
+///
+/// Unlike segment level checks, unknown names are reported even if no point is affected.
+/// Without segments there is no vector configuration to check against, nothing is reported.
+fn check_vector_names<'a>(
+    segments: &SegmentHolder,
+    vector_names: impl IntoIterator<Item = &'a str>,
+) -> CollectionResult<()> {
+    if segments.is_empty() {
+        return Ok(());
+    }
+    let mut known_names = HashSet::new();
+    segments.for_each_segment(|segment| {
+        known_names.extend(segment.config().vector_data.into_keys());
+        Ok(true)
+    })?;
+    for vector_name in vector_names {
+        if !known_names.contains(vector_name) {
+            return Err(CollectionError::UnknownVectorName(vector_name.to_string()));
+        }
+    }
+    Ok(())
+}
+
 /// Update the specified named vectors of a point, keeping unspecified vectors intact.
 pub(crate) fn update_vectors(
     segments: &SegmentHolder,
     op_num: SeqNumberType,
     points: &[PointVectors],
 ) -> CollectionResult<usize> {
+    check_vector_names(segments, point_vector_names(points))?;
+
     let points_map: HashMap<PointIdType, &PointVectors> =
         points.iter().map(|p| (p.id, p)).collect();
     let ids: Vec<PointIdType> = points_map.keys().copied().collect();
@@ -71,35 +106,377 @@ pub(crate) fn update_vectors(
     Ok(updated_points.len())
 }
 
+/// Same as `update_vectors`, but failure on one point does not stop the others
+///
+/// Returns: updated points and points which failed to update, e.g. missing ones, with the reason.
+pub(crate) fn update_vectors_partial(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    points: &[PointVectors],
+) -> CollectionResult<UpdateVectorsReport> {
+    check_vector_names(segments, point_vector_names(points))?;
+
+    let points_map: HashMap<PointIdType, &PointVectors> =
+        points.iter().map(|p| (p.id, p)).collect();
+    let ids: Vec<PointIdType> = points_map.keys().copied().collect();
+
+    let mut failures: HashMap<PointIdType, CollectionError> = HashMap::new();
+    let updated_points =
+        segments.apply_points_to_appendable(op_num, &ids, |id, write_segment| {
+            let vectors = points_map[&id].vector.clone().into_all_vectors();
+            match write_segment.update_vectors(op_num, id, vectors) {
+                Ok(is_applied) => Ok(is_applied),
+                Err(err) => {
+                    failures.insert(id, err.into());
+                    Ok(false)
+                }
+            }
+        })?;
+
+    let mut report = UpdateVectorsReport::default();
+    for id in points.iter().map(|p| p.id).unique() {
+        let error = match failures.remove(&id) {
+            Some(err) => err,
+            None if updated_points.contains(&id) => {
+                report.succeeded.push(id);
+                continue;
+            }
+            None => CollectionError::PointNotFound {
+                missed_point_id: id,
+            },
+        };
+        report.failed.push(FailedPointVectors {
+            id,
+            error: error.to_string(),
+        });
+    }
+    Ok(report)
+}
+
+fn point_vector_names(points: &[PointVectors]) -> impl Iterator<Item = &str> {
+    points.iter().flat_map(|point| match &point.vector {
+        VectorStruct::Single(_) => vec![DEFAULT_VECTOR_NAME],
+        VectorStruct::Multi(vectors) => vectors.keys().map(String::as_str).collect(),
+    })
+}
+
 /// Delete the given named vectors for the given points, keeping other vectors intact.
+///
+/// Returns the number of actually deleted vectors per name.
 pub(crate) fn delete_vectors(
     segments: &SegmentHolder,
     op_num: SeqNumberType,
     points: &[PointIdType],
     vector_names: &[String],
-) -> CollectionResult<usize> {
-    segments
-        .apply_points(points, |id, _idx, write_segment| {
-            let mut res = true;
-            for name in vector_names {
-                res &= write_segment.delete_vector(op_num, id, name)?;
+) -> CollectionResult<DeletedVectorsCount> {
+    check_vector_names(segments, vector_names.iter().map(String::as_str))?;
+
+    let mut by_name: HashMap<String, usize> =
+        vector_names.iter().map(|name| (name.clone(), 0)).collect();
+    let points = segments.apply_points(points, |id, _idx, write_segment| {
+        let mut res = true;
+        for name in vector_names {
+            let is_deleted = write_segment.delete_vector(op_num, id, name)?;
+            if is_deleted {
+                *by_name.entry(name.clone()).or_insert(0) += 1;
             }
-            Ok(res)
-        })
-        .map_err(Into::into)
+            res &= is_deleted;
+        }
+        Ok(res)
+    })?;
+    Ok(DeletedVectorsCount { points, by_name })
 }
 
 /// Delete the given named vectors for points matching the given filter, keeping otehr vectors intact.
+///
+/// Returns the number of actually deleted vectors per name.
 pub(crate) fn delete_vectors_by_filter(
     segments: &SegmentHolder,
     op_num: SeqNumberType,
     filter: &Filter,
     vector_names: &[String],
-) -> CollectionResult<usize> {
+) -> CollectionResult<DeletedVectorsCount> {
     let affected_points = points_by_filter(segments, filter)?;
     delete_vectors(segments, op_num, &affected_points, vector_names)
 }
 
+/// Reprocess vectors with the given name according to the distance metric of their segment,
+/// e.g. normalize them after the metric was changed to `Cosine`.
+///
+/// Only points which vectors are actually changed by the reprocessing are updated.
+pub(crate) fn reindex_vectors(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    vector_name: &str,
+) -> CollectionResult<usize> {
+    let reprocess = |segment: &dyn SegmentEntry,
+                     point_id: PointIdType|
+     -> OperationResult<Option<Vec<VectorElementType>>> {
+        let distance = match segment.config().vector_data.get(vector_name) {
+            Some(vector_config) => vector_config.distance,
+            None => return Ok(None),
+        };
+        let processed = segment.vector(vector_name, point_id)?.and_then(|vector| {
+            distance
+                .preprocess_vector(&vector)
+                .filter(|processed| processed != &vector)
+        });
+        Ok(processed)
+    };
+
+    // Same point may be found in several segments, while it is being moved by an optimizer
+    let mut affected_points: HashSet<PointIdType> = HashSet::new();
+    segments.for_each_segment(|segment| {
+        for point_id in segment.iter_points() {
+            if reprocess(segment.deref(), point_id)?.is_some() {
+                affected_points.insert(point_id);
+            }
+        }
+        Ok(true)
+    })?;
+    let affected_points = affected_points.into_iter().collect_vec();
+
+    let updated_points =
+        segments.apply_points_to_appendable(op_num, &affected_points, |id, write_segment| {
+            match reprocess(write_segment.deref(), id)? {
+                Some(vector) => write_segment.update_vectors(
+                    op_num,
+                    id,
+                    NamedVectors::from([(vector_name.to_string(), vector)]),
+                ),
+                // Already processed when moved into the appendable segment
+                None => Ok(true),
+            }
+        })?;
+    Ok(updated_points.len())
+}
+
+/// Dimension of `vector_name` vectors in the appendable segments
+///
+/// Appendable segments are created with the current vector parameters of the collection.
+fn appendable_vector_dim(segments: &SegmentHolder, vector_name: &str) -> CollectionResult<usize> {
+    let appendable_dims: HashSet<_> = segments
+        .appendable_segments()
+        .iter()
+        .filter_map(|segment_id| segments.get(*segment_id))
+        .filter_map(|segment| {
+            segment
+                .get()
+                .read()
+                .config()
+                .vector_data
+                .get(vector_name)
+                .map(|vector_config| vector_config.size)
+        })
+        .collect();
+    if appendable_dims.len() > 1 {
+        return Err(CollectionError::bad_input(format!(
+            "appendable segments disagree on the dimension of vector `{vector_name}`"
+        )));
+    }
+    appendable_dims
+        .into_iter()
+        .next()
+        .ok_or_else(|| CollectionError::UnknownVectorName(vector_name.to_string()))
+}
+
+/// Check that `vector` fits the dimension of `vector_name` vectors in the appendable segments
+///
+/// Same as `check_vector_names`, nothing is reported without segments.
+fn check_vector_dim(
+    segments: &SegmentHolder,
+    vector_name: &str,
+    vector: &[VectorElementType],
+) -> CollectionResult<()> {
+    if segments.is_empty() {
+        return Ok(());
+    }
+    let expected_dim = appendable_vector_dim(segments, vector_name)?;
+    if vector.len() != expected_dim {
+        return Err(OperationError::WrongVector {
+            expected_dim,
+            received_dim: vector.len(),
+        }
+        .into());
+    }
+    Ok(())
+}
+
+/// Check that concatenation of `sources` vectors fits the dimension of the `target` vector
+fn check_concat_dims(
+    segments: &SegmentHolder,
+    sources: &[String],
+    target: &str,
+) -> CollectionResult<()> {
+    let target_dim = appendable_vector_dim(segments, target)?;
+    let mut concatenated_dim = 0;
+    for source in sources {
+        concatenated_dim += appendable_vector_dim(segments, source)?;
+    }
+    if concatenated_dim != target_dim {
+        return Err(CollectionError::bad_input(format!(
+            "concatenation of {} has dimension {concatenated_dim}, \
+             but vector `{target}` has dimension {target_dim}",
+            sources
+                .iter()
+                .map(|source| format!("`{source}`"))
+                .join(", "),
+        )));
+    }
+    Ok(())
+}
+
+/// Concatenate `sources` vectors of each point in order and store them as `target` vector
+///
+/// Points missing any of the source vectors are skipped.
+/// Dimension of the `target` vector is checked before any point is updated.
+/// Returns: number of points with updated target vector.
+pub(crate) fn concat_vectors(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    sources: &[String],
+    target: &str,
+) -> CollectionResult<usize> {
+    check_concat_dims(segments, sources, target)?;
+
+    let concatenate = |segment: &dyn SegmentEntry,
+                       point_id: PointIdType|
+     -> OperationResult<Option<Vec<VectorElementType>>> {
+        let mut concatenated = Vec::new();
+        for source in sources {
+            match segment.vector(source, point_id)? {
+                Some(vector) => concatenated.extend(vector),
+                None => return Ok(None),
+            }
+        }
+        Ok(Some(concatenated))
+    };
+
+    // Same point may be found in several segments, while it is being moved by an optimizer
+    let mut affected_points: HashSet<PointIdType> = HashSet::new();
+    segments.for_each_segment(|segment| {
+        for point_id in segment.iter_points() {
+            if concatenate(segment.deref(), point_id)?.is_some() {
+                affected_points.insert(point_id);
+            }
+        }
+        Ok(true)
+    })?;
+    let affected_points = affected_points.into_iter().collect_vec();
+
+    let updated_points =
+        segments.apply_points_to_appendable(op_num, &affected_points, |id, write_segment| {
+            match concatenate(write_segment.deref(), id)? {
+                Some(vector) => write_segment.update_vectors(
+                    op_num,
+                    id,
+                    NamedVectors::from([(target.to_string(), vector)]),
+                ),
+                None => Ok(false),
+            }
+        })?;
+    Ok(updated_points.len())
+}
+
+/// Fix `vector_name` vectors, which dimension differs from the one of the appendable segments
+///
+/// Such vectors are stored in segments created with other vector parameters. Affected points
+/// are moved into an appendable segment with the vector repaired according to `policy`.
+/// Returns: number of repaired points.
+pub(crate) fn repair_vectors(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    vector_name: &str,
+    policy: DimensionRepairPolicy,
+) -> CollectionResult<usize> {
+    let vector_dim = |segment: &dyn SegmentEntry| {
+        segment
+            .config()
+            .vector_data
+            .get(vector_name)
+            .map(|vector_config| vector_config.size)
+    };
+
+    let expected_dim = appendable_vector_dim(segments, vector_name)?;
+
+    let mut affected_points: Vec<PointIdType> = Vec::new();
+    segments.for_each_segment(|segment| {
+        if vector_dim(segment.deref()).map_or(true, |dim| dim == expected_dim) {
+            return Ok(true);
+        }
+        for point_id in segment.iter_points() {
+            if segment.vector(vector_name, point_id)?.is_some() {
+                affected_points.push(point_id);
+            }
+        }
+        Ok(true)
+    })?;
+
+    let mut repaired_points = 0;
+    segments.apply_points_to_appendable_with(
+        op_num,
+        &affected_points,
+        |_id, vectors| {
+            match policy {
+                DimensionRepairPolicy::Resize => {
+                    if let Some(vector) = vectors.get_mut(vector_name) {
+                        vector.resize(expected_dim, 0.0);
+                    }
+                }
+                DimensionRepairPolicy::Clear => {
+                    vectors.remove(vector_name);
+                }
+            }
+            repaired_points += 1;
+            Ok(())
+        },
+        // Vectors are repaired while moved into an appendable segment, points already there
+        // have nothing to repair
+        |_id, _write_segment| Ok(false),
+    )?;
+    Ok(repaired_points)
+}
+
+/// Set `vector_name` vector of points matching `filter`, which don't have it, to `default`
+///
+/// Points already having the vector are not changed.
+/// Dimension of the `default` vector is checked before any point is updated.
+/// Returns: number of points with the default vector set.
+pub(crate) fn set_default_vector_by_filter(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    filter: &Filter,
+    vector_name: &str,
+    default: &[VectorElementType],
+) -> CollectionResult<usize> {
+    check_vector_names(segments, [vector_name])?;
+    check_vector_dim(segments, vector_name, default)?;
+
+    let mut affected_points: Vec<PointIdType> = Vec::new();
+    segments.for_each_segment(|segment| {
+        for point_id in segment.read_filtered(None, None, Some(filter)) {
+            if segment.vector(vector_name, point_id)?.is_none() {
+                affected_points.push(point_id);
+            }
+        }
+        Ok(true)
+    })?;
+
+    let updated_points =
+        segments.apply_points_to_appendable(op_num, &affected_points, |id, write_segment| {
+            // Never overwrite an existing vector
+            if write_segment.vector(vector_name, id)?.is_some() {
+                return Ok(false);
+            }
+            write_segment.update_vectors(
+                op_num,
+                id,
+                NamedVectors::from([(vector_name.to_string(), default.to_vec())]),
+            )
+        })?;
+    Ok(updated_points.len())
+}
+
 pub(crate) fn overwrite_payload(
     segments: &SegmentHolder,
     op_num: SeqNumberType,
@@ -142,6 +519,51 @@ pub(crate) fn set_payload(
     Ok(updated_points.len())
 }
 
+/// Array located at the nested `key`, e.g. `reviews` or `product.reviews[]`
+fn nested_array_mut<'a>(payload: &'a mut Payload, key: &str) -> Option<&'a mut Vec<Value>> {
+    let mut path = key.trim_end_matches("[]").split('.');
+    let mut value = payload.0.get_mut(path.next()?)?;
+    for segment in path {
+        value = value.as_object_mut()?.get_mut(segment)?;
+    }
+    value.as_array_mut()
+}
+
+/// Set `payload` values of the elements of the nested array at `key` matching `filter`
+///
+/// The elements are selected and updated under the same segment lock, so concurrent updates of
+/// the point can not shift the positions in between.
+pub(crate) fn set_nested_payload(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    payload: &Payload,
+    key: &str,
+    filter: &Filter,
+    points: &[PointIdType],
+) -> CollectionResult<usize> {
+    let nested_path = JsonPathPayload::new(format!("{}[]", key.trim_end_matches("[]")));
+    let updated_points =
+        segments.apply_points_to_appendable(op_num, points, |id, write_segment| {
+            let mut full_payload = write_segment.payload(id)?;
+            let positions = nested_filter_positions(&nested_path, filter, &full_payload);
+            let elements = match nested_array_mut(&mut full_payload, key) {
+                Some(elements) if positions.any() => elements,
+                _ => return Ok(false),
+            };
+            for position in positions.iter_ones() {
+                if let Some(Value::Object(element)) = elements.get_mut(position) {
+                    for (payload_key, value) in payload.0.iter() {
+                        element.insert(payload_key.clone(), value.clone());
+                    }
+                }
+            }
+            write_segment.set_full_payload(op_num, id, &full_payload)
+        })?;
+
+    check_unprocessed_points(points, &updated_points)?;
+    Ok(updated_points.len())
+}
+
 fn points_by_filter(
     segments: &SegmentHolder,
     filter: &Filter,
@@ -456,16 +878,161 @@ pub(crate) fn process_vector_operation(
     segments: &RwLock<SegmentHolder>,
     op_num: SeqNumberType,
     vector_operation: VectorOperations,
-) -> CollectionResult<usize> {
+) -> CollectionResult<UpdateOutcome> {
     match vector_operation {
+        VectorOperations::Batch(operations) => {
+            // Whole batch is applied under one lock, so it is never observed applied partially.
+            // Applied operations are not rolled back, so the batch is rejected as a whole
+            // if any of its operations is known to fail.
+            let segments = segments.write();
+            for operation in &operations {
+                check_vector_operation(&segments, operation)?;
+            }
+            // Operations share the sequence number, which doesn't make later ones skipped
+            // on the points updated by earlier ones.
+            let mut outcome = UpdateOutcome::default();
+            for operation in operations {
+                outcome.merge(apply_vector_operation(&segments, op_num, operation)?);
+            }
+            Ok(outcome)
+        }
+        operation => apply_vector_operation(&segments.read(), op_num, operation),
+    }
+}
+
+fn apply_vector_operation(
+    segments: &SegmentHolder,
+    op_num: SeqNumberType,
+    vector_operation: VectorOperations,
+) -> CollectionResult<UpdateOutcome> {
+    let processed = match vector_operation {
+        VectorOperations::UpdateVectors(operation) if operation.partial => {
+            let report = update_vectors_partial(segments, op_num, &operation.points)?;
+            return Ok(UpdateOutcome {
+                processed: report.succeeded.len(),
+                vectors_report: Some(report),
+                deleted_vectors: None,
+            });
+        }
         VectorOperations::UpdateVectors(operation) => {
-            update_vectors(&segments.read(), op_num, &operation.points)
+            update_vectors(segments, op_num, &operation.points)
         }
         VectorOperations::DeleteVectors(ids, vector_names) => {
-            delete_vectors(&segments.read(), op_num, &ids.points, &vector_names)
+            let deleted = delete_vectors(segments, op_num, &ids.points, &vector_names)?;
+            return Ok(deleted.into());
         }
         VectorOperations::DeleteVectorsByFilter(filter, vector_names) => {
-            delete_vectors_by_filter(&segments.read(), op_num, &filter, &vector_names)
+            let deleted = delete_vectors_by_filter(segments, op_num, &filter, &vector_names)?;
+            return Ok(deleted.into());
+        }
+        VectorOperations::ReindexVectors(operation) => {
+            reindex_vectors(segments, op_num, &operation.name)
+        }
+        VectorOperations::ConcatVectors(operation) => {
+            concat_vectors(segments, op_num, &operation.sources, &operation.target)
+        }
+        VectorOperations::RepairVectors(operation) => {
+            repair_vectors(segments, op_num, &operation.name, operation.policy)
+        }
+        VectorOperations::SetDefaultVectorByFilter(operation) => set_default_vector_by_filter(
+            segments,
+            op_num,
+            &operation.filter,
+            &operation.name,
+            specified_default_vector(&operation)?,
+        ),
+        VectorOperations::Batch(_) => Err(nested_batch_error()),
+    }?;
+    Ok(UpdateOutcome::from(processed))
+}
+
+fn nested_batch_error() -> CollectionError {
+    CollectionError::bad_input("batches of vector operations can't be nested".to_string())
+}
+
+fn specified_default_vector(
+    operation: &SetDefaultVectorByFilter,
+) -> CollectionResult<&[VectorElementType]> {
+    operation.default_vector().ok_or_else(|| {
+        CollectionError::bad_input(format!(
+            "default vector `{}` is not specified",
+            operation.name,
+        ))
+    })
+}
+
+/// Check that `operation` can be applied, without applying it
+///
+/// Covers failures, which only depend on the request: unknown vector names, missing points
+/// and mismatching vector dimensions.
+fn check_vector_operation(
+    segments: &SegmentHolder,
+    operation: &VectorOperations,
+) -> CollectionResult<()> {
+    check_vector_names(segments, checked_vector_names(operation))?;
+    match operation {
+        VectorOperations::UpdateVectors(operation) => {
+            for point in &operation.points {
+                match &point.vector {
+                    VectorStruct::Single(vector) => {
+                        check_vector_dim(segments, DEFAULT_VECTOR_NAME, vector)?
+                    }
+                    VectorStruct::Multi(vectors) => {
+                        for (vector_name, vector) in vectors {
+                            check_vector_dim(segments, vector_name, vector)?;
+                        }
+                    }
+                }
+            }
+            if !operation.partial {
+                let ids = operation.points.iter().map(|point| point.id).collect_vec();
+                let mut found_points = HashSet::new();
+                segments.read_points(&ids, |id, _segment| Ok(found_points.insert(id)))?;
+                check_unprocessed_points(&ids, &found_points)?;
+            }
+        }
+        VectorOperations::DeleteVectors(..)
+        | VectorOperations::DeleteVectorsByFilter(..)
+        | VectorOperations::ReindexVectors(_) => {}
+        VectorOperations::ConcatVectors(operation) => {
+            check_concat_dims(segments, &operation.sources, &operation.target)?
+        }
+        VectorOperations::RepairVectors(operation) => {
+            appendable_vector_dim(segments, &operation.name)?;
+        }
+        VectorOperations::SetDefaultVectorByFilter(operation) => {
+            check_vector_dim(
+                segments,
+                &operation.name,
+                specified_default_vector(operation)?,
+            )?;
+        }
+        VectorOperations::Batch(_) => return Err(nested_batch_error()),
+    }
+    Ok(())
+}
+
+/// Names of the vectors, which are checked to be configured before the operation is applied
+fn checked_vector_names(operation: &VectorOperations) -> Vec<&str> {
+    match operation {
+        VectorOperations::UpdateVectors(operation) => operation
+            .points
+            .iter()
+            .flat_map(|point| match &point.vector {
+                VectorStruct::Single(_) => vec![DEFAULT_VECTOR_NAME],
+                VectorStruct::Multi(vectors) => vectors.keys().map(String::as_str).collect(),
+            })
+            .collect(),
+        VectorOperations::DeleteVectors(_, vector_names)
+        | VectorOperations::DeleteVectorsByFilter(_, vector_names) => {
+            vector_names.iter().map(String::as_str).collect()
+        }
+        VectorOperations::ReindexVectors(_)
+        | VectorOperations::ConcatVectors(_)
+        | VectorOperations::RepairVectors(_) => vec![],
+        VectorOperations::SetDefaultVectorByFilter(operation) => vec![operation.name.as_str()],
+        VectorOperations::Batch(operations) => {
+            operations.iter().flat_map(checked_vector_names).collect()
         }
     }
 }
@@ -517,6 +1084,14 @@ pub(crate) fn process_payload_operation(
                 })
             }
         }
+        PayloadOps::SetNestedPayload(operation) => set_nested_payload(
+            &segments.read(),
+            op_num,
+            &operation.payload,
+            &operation.key,
+            &operation.filter,
+            &operation.points,
+        ),
     }
 }
 
@@ -22,6 +22,9 @@ pub struct State {
     pub shards: HashMap<ShardId, ShardInfo>,
     #[serde(default)]
     pub transfers: HashSet<ShardTransfer>,
+    /// Shards of the ring set by resharding, `None` if the ring routes to all shards
+    #[serde(default)]
+    pub ring_shards: Option<Vec<ShardId>>,
 }
 
 impl State {
@@ -35,6 +38,9 @@ impl State {
         Self::apply_shard_transfers(self.transfers, collection, this_peer_id, abort_transfer)
             .await?;
         Self::apply_shard_info(self.shards, collection).await?;
+        if let Some(ring_shards) = self.ring_shards {
+            collection.set_ring_shards(ring_shards).await?;
+        }
         Ok(())
     }
 
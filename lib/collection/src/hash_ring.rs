@@ -1,16 +1,56 @@
-use std::hash::Hash;
+use std::hash::{Hash, Hasher};
+
+use siphasher::sip::SipHasher;
 
 pub enum HashRing<T: Hash + Copy> {
-    Raw(hashring::HashRing<T>),
+    Raw {
+        ring: hashring::HashRing<T>,
+        shards: Vec<T>,
+    },
     Fair {
         ring: hashring::HashRing<(T, u32)>,
+        shards: Vec<T>,
         scale: u32,
     },
 }
 
-impl<T: Hash + Copy> HashRing<T> {
+/// Part of the key hash space, which belongs to different shards in two rings
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingTransition<T> {
+    /// First hash of the range, inclusive
+    pub start: u64,
+    /// Last hash of the range, inclusive
+    pub end: u64,
+    /// Shard owning the range in the original ring, `None` if the ring is empty
+    pub from: Option<T>,
+    /// Shard owning the range in the new ring, `None` if the ring is empty
+    pub to: Option<T>,
+}
+
+/// Flattened copy of a ring, for routing many keys at once
+///
+/// Routes the same way as `HashRing::get`, but nodes are kept in a plain sorted table and
+/// shards are identified by their index in `shards`, so callers can group keys in a `Vec`.
+#[derive(Debug, Clone)]
+pub struct RingRouter<T> {
+    /// Sorted positions of the ring nodes with the indices of their shards
+    positions: Vec<(u64, usize)>,
+    shards: Vec<T>,
+}
+
+/// Key ranges moving between shards when one ring is replaced by another
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RingDiff<T> {
+    /// Sorted, non-overlapping ranges of the key hash space
+    pub transitions: Vec<RingTransition<T>>,
+}
+
+impl<T: Hash + Copy + PartialEq> HashRing<T> {
     pub fn raw() -> Self {
-        Self::Raw(hashring::HashRing::new())
+        Self::Raw {
+            ring: hashring::HashRing::new(),
+            shards: Vec::new(),
+        }
     }
 
     /// Constructs a HashRing that tries to give all shards equal space on the ring.
@@ -19,25 +59,38 @@ impl<T: Hash + Copy> HashRing<T> {
     pub fn fair(scale: u32) -> Self {
         Self::Fair {
             ring: hashring::HashRing::new(),
+            shards: Vec::new(),
             scale,
         }
     }
 
+    /// Add `shard` to the ring, nothing changes if the ring already has it
     pub fn add(&mut self, shard: T) {
+        if self.shards().contains(&shard) {
+            return;
+        }
         match self {
-            HashRing::Raw(ring) => ring.add(shard),
-            HashRing::Fair { ring, scale } => {
+            HashRing::Raw { ring, shards } => {
+                ring.add(shard);
+                shards.push(shard);
+            }
+            HashRing::Fair {
+                ring,
+                shards,
+                scale,
+            } => {
                 for i in 0..*scale {
                     ring.add((shard, i))
                 }
+                shards.push(shard);
             }
         }
     }
 
     pub fn remove(&mut self, shard: &T) -> bool {
-        match self {
-            HashRing::Raw(ring) => ring.remove(shard).is_some(),
-            HashRing::Fair { ring, scale } => {
+        let removed = match self {
+            HashRing::Raw { ring, .. } => ring.remove(shard).is_some(),
+            HashRing::Fair { ring, scale, .. } => {
                 let mut removed = false;
                 for i in 0..*scale {
                     if ring.remove(&(*shard, i)).is_some() {
@@ -46,13 +99,291 @@ impl<T: Hash + Copy> HashRing<T> {
                 }
                 removed
             }
+        };
+        let shards = match self {
+            HashRing::Raw { shards, .. } | HashRing::Fair { shards, .. } => shards,
+        };
+        if let Some(index) = shards.iter().position(|existing| existing == shard) {
+            shards.remove(index);
         }
+        removed
     }
 
     pub fn get<U: Hash>(&self, key: &U) -> Option<&T> {
         match self {
-            HashRing::Raw(ring) => ring.get(key),
+            HashRing::Raw { ring, .. } => ring.get(key),
             HashRing::Fair { ring, .. } => ring.get(key).map(|(shard, _)| shard),
         }
     }
+
+    /// Shards of the ring, in the order they were added
+    pub fn shards(&self) -> &[T] {
+        match self {
+            HashRing::Raw { shards, .. } | HashRing::Fair { shards, .. } => shards,
+        }
+    }
+
+    /// Flattened copy of the ring, cheaper to query when routing large batches of keys
+    pub fn router(&self) -> RingRouter<T> {
+        let shards = match self {
+            HashRing::Raw { shards, .. } | HashRing::Fair { shards, .. } => shards.clone(),
+        };
+        let positions = self
+            .positions()
+            .into_iter()
+            .map(|(position, shard)| {
+                let index = shards
+                    .iter()
+                    .position(|existing| *existing == shard)
+                    .expect("Ring nodes belong to the shards of the ring");
+                (position, index)
+            })
+            .collect();
+        RingRouter { positions, shards }
+    }
+
+    /// Key ranges changing their shard if this ring is replaced by `other`
+    ///
+    /// Ranges are expressed in the key hash space, the same one `get` uses to route the keys.
+    /// Adjacent ranges with the same transition are merged.
+    pub fn diff(&self, other: &HashRing<T>) -> RingDiff<T> {
+        let old_positions = self.positions();
+        let new_positions = other.positions();
+
+        let mut boundaries: Vec<u64> = old_positions
+            .iter()
+            .chain(&new_positions)
+            .map(|(position, _)| *position)
+            .chain([u64::MAX])
+            .collect();
+        boundaries.sort_unstable();
+        boundaries.dedup();
+
+        let mut transitions: Vec<RingTransition<T>> = Vec::new();
+        let mut start = 0;
+        for end in boundaries {
+            // No position of either ring lies inside of `start..end`,
+            // so all hashes of the range have the same owners as `end`
+            let from = owner(&old_positions, end);
+            let to = owner(&new_positions, end);
+            if from != to {
+                match transitions.last_mut() {
+                    Some(last) if last.end + 1 == start && last.from == from && last.to == to => {
+                        last.end = end;
+                    }
+                    _ => transitions.push(RingTransition {
+                        start,
+                        end,
+                        from,
+                        to,
+                    }),
+                }
+            }
+            start = end.wrapping_add(1);
+        }
+
+        RingDiff { transitions }
+    }
+
+    /// Sorted positions of the ring nodes with the shards they belong to
+    fn positions(&self) -> Vec<(u64, T)> {
+        let mut positions: Vec<(u64, T)> = match self {
+            HashRing::Raw { shards, .. } => shards
+                .iter()
+                .map(|shard| (key_hash(shard), *shard))
+                .collect(),
+            HashRing::Fair { shards, scale, .. } => shards
+                .iter()
+                .flat_map(|shard| (0..*scale).map(|i| (key_hash(&(*shard, i)), *shard)))
+                .collect(),
+        };
+        positions.sort_by_key(|(position, _)| *position);
+        positions
+    }
+}
+
+impl<T> RingRouter<T> {
+    /// Shards of the ring, in the order they were added
+    pub fn shards(&self) -> &[T] {
+        &self.shards
+    }
+
+    /// Index in `shards` of the shard owning `key`, `None` if the ring is empty
+    pub fn route<U: Hash>(&self, key: &U) -> Option<usize> {
+        owner(&self.positions, key_hash(key))
+    }
+}
+
+impl<T: Copy> RingDiff<T> {
+    pub fn is_empty(&self) -> bool {
+        self.transitions.is_empty()
+    }
+
+    /// Transition of the range containing `key`, `None` if the key stays on its shard
+    pub fn transition<U: Hash>(&self, key: &U) -> Option<&RingTransition<T>> {
+        let hash = key_hash(key);
+        let index = self
+            .transitions
+            .partition_point(|transition| transition.end < hash);
+        self.transitions
+            .get(index)
+            .filter(|transition| transition.start <= hash)
+    }
+
+    /// Fraction of the key hash space moving to another shard
+    pub fn moved_fraction(&self) -> f64 {
+        let moved: f64 = self
+            .transitions
+            .iter()
+            .map(|transition| (transition.end - transition.start) as f64 + 1.0)
+            .sum();
+        moved / (u64::MAX as f64 + 1.0)
+    }
+}
+
+/// Hash of a key on the ring, same as computed by the default hasher of `hashring`
+///
+/// `hashring` doesn't expose its hashing, so the version of the crate is pinned.
+/// `test_router_routes_as_ring` checks both agree.
+fn key_hash<U: Hash>(key: &U) -> u64 {
+    let mut hasher = SipHasher::new();
+    key.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Shard owning `hash`: the first node at or after it, wrapping around the ring
+fn owner<T: Copy>(positions: &[(u64, T)], hash: u64) -> Option<T> {
+    let index = positions.partition_point(|(position, _)| *position < hash);
+    positions
+        .get(index)
+        .or_else(|| positions.first())
+        .map(|(_, shard)| *shard)
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashSet;
+
+    use super::*;
+
+    fn fair_ring(shards: u32) -> HashRing<u32> {
+        let mut ring = HashRing::fair(100);
+        for shard in 0..shards {
+            ring.add(shard);
+        }
+        ring
+    }
+
+    #[test]
+    fn test_router_routes_as_ring() {
+        let mut raw_ring = HashRing::raw();
+        for shard in 0..5 {
+            raw_ring.add(shard);
+        }
+        for ring in [&fair_ring(5), &raw_ring] {
+            let router = ring.router();
+            for key in 0..100_000u64 {
+                let key = key.wrapping_mul(0x9E37_79B9_7F4A_7C15);
+                let routed = router.route(&key).map(|index| router.shards()[index]);
+                assert_eq!(routed, ring.get(&key).copied(), "key {key}");
+            }
+            for key in ["", "a", "point"] {
+                let routed = router.route(&key).map(|index| router.shards()[index]);
+                assert_eq!(routed, ring.get(&key).copied(), "key {key:?}");
+            }
+        }
+        assert_eq!(HashRing::<u32>::raw().router().route(&0), None);
+    }
+
+    #[test]
+    fn test_add_existing_shard() {
+        let mut ring = fair_ring(3);
+        ring.add(1);
+        assert_eq!(ring.shards(), &[0, 1, 2]);
+        assert!(ring.diff(&fair_ring(3)).is_empty());
+
+        // Shard is removed completely, no copy is left behind
+        assert!(ring.remove(&1));
+        assert_eq!(ring.shards(), &[0, 2]);
+        assert!((0..1000u64).all(|key| ring.get(&key) != Some(&1)));
+    }
+
+    #[test]
+    fn test_diff_with_new_shard() {
+        let old_ring = fair_ring(3);
+        let new_ring = fair_ring(4);
+
+        assert!(old_ring.diff(&old_ring).is_empty());
+
+        let diff = old_ring.diff(&new_ring);
+        assert!(!diff.is_empty());
+
+        // Only ranges taken by the new shard move, and all of the old shards give up some
+        let mut sources = HashSet::new();
+        for transition in &diff.transitions {
+            assert!(transition.start <= transition.end);
+            assert_eq!(transition.to, Some(3));
+            sources.insert(transition.from.unwrap());
+        }
+        assert_eq!(sources, HashSet::from([0, 1, 2]));
+        for pair in diff.transitions.windows(2) {
+            assert!(pair[0].end < pair[1].start);
+        }
+
+        // The new shard gets about a quarter of the keys
+        let moved = diff.moved_fraction();
+        assert!(moved > 0.15 && moved < 0.35, "moved fraction {moved}");
+
+        // Reported transitions agree with the routing of the rings
+        let mut moved_points = 0;
+        for point in 0..10_000u64 {
+            let old_shard = old_ring.get(&point).copied();
+            let new_shard = new_ring.get(&point).copied();
+            match diff.transition(&point) {
+                Some(transition) => {
+                    assert_eq!(transition.from, old_shard);
+                    assert_eq!(transition.to, new_shard);
+                    moved_points += 1;
+                }
+                None => assert_eq!(old_shard, new_shard),
+            }
+        }
+        assert!(moved_points > 0);
+
+        // Reverting the change moves the same ranges back
+        let reverse = new_ring.diff(&old_ring);
+        assert_eq!(reverse.transitions.len(), diff.transitions.len());
+        for (forward, backward) in diff.transitions.iter().zip(&reverse.transitions) {
+            assert_eq!((forward.start, forward.end), (backward.start, backward.end));
+            assert_eq!((forward.from, forward.to), (backward.to, backward.from));
+        }
+    }
+
+    #[test]
+    fn test_diff_with_removed_shard() {
+        let old_ring = fair_ring(4);
+        let mut new_ring = fair_ring(4);
+        assert!(new_ring.remove(&1));
+        assert!(!new_ring.remove(&1));
+
+        let diff = old_ring.diff(&new_ring);
+        assert!(diff
+            .transitions
+            .iter()
+            .all(|transition| transition.from == Some(1) && transition.to != Some(1)));
+
+        let mut raw_ring = HashRing::raw();
+        let diff = raw_ring.diff(&fair_ring(1));
+        assert_eq!(
+            diff.transitions,
+            vec![RingTransition {
+                start: 0,
+                end: u64::MAX,
+                from: None,
+                to: Some(0),
+            }]
+        );
+        raw_ring.add(0);
+        assert!(raw_ring.diff(&fair_ring(1)).is_empty());
+    }
 }
@@ -33,6 +33,9 @@ use crate::operations::types::{
     LocalShardInfo, LookupLocation, OptimizersStatus, RecommendRequest, Record, RemoteShardInfo,
     SearchRequest, ShardTransferInfo, UpdateResult, UpdateStatus, VectorParams, VectorsConfig,
 };
+use crate::operations::vector_ops::{
+    DimensionRepairPolicy, FailedPointVectors, UpdateVectorsReport,
+};
 use crate::optimizers_builder::OptimizersConfig;
 use crate::shards::remote_shard::CollectionSearchRequest;
 
@@ -71,6 +74,23 @@ pub fn write_ordering_from_proto(
     })
 }
 
+pub fn dimension_repair_policy_to_proto(policy: DimensionRepairPolicy) -> i32 {
+    match policy {
+        DimensionRepairPolicy::Resize => api::grpc::qdrant::DimensionRepairPolicy::Resize as i32,
+        DimensionRepairPolicy::Clear => api::grpc::qdrant::DimensionRepairPolicy::Clear as i32,
+    }
+}
+
+pub fn dimension_repair_policy_from_proto(policy: i32) -> Result<DimensionRepairPolicy, Status> {
+    match api::grpc::qdrant::DimensionRepairPolicy::from_i32(policy) {
+        Some(api::grpc::qdrant::DimensionRepairPolicy::Resize) => Ok(DimensionRepairPolicy::Resize),
+        Some(api::grpc::qdrant::DimensionRepairPolicy::Clear) => Ok(DimensionRepairPolicy::Clear),
+        Some(api::grpc::qdrant::DimensionRepairPolicy::UnknownDimensionRepairPolicy) | None => Err(
+            Status::invalid_argument(format!("cannot convert dimension repair policy: {policy}")),
+        ),
+    }
+}
+
 pub fn try_record_from_grpc(
     point: api::grpc::qdrant::RetrievedPoint,
     with_payload: bool,
@@ -628,6 +648,13 @@ impl From<UpdateResult> for api::grpc::qdrant::UpdateResult {
                 UpdateStatus::Acknowledged => api::grpc::qdrant::UpdateStatus::Acknowledged as i32,
                 UpdateStatus::Completed => api::grpc::qdrant::UpdateStatus::Completed as i32,
             },
+            vectors_report: value.vectors_report.map(Into::into),
+            deleted_vectors: value
+                .deleted_vectors
+                .unwrap_or_default()
+                .into_iter()
+                .map(|(vector_name, count)| (vector_name, count as u64))
+                .collect(),
         }
     }
 }
@@ -647,6 +674,58 @@ impl TryFrom<api::grpc::qdrant::UpdateResult> for UpdateResult {
                 }
                 _ => return Err(Status::invalid_argument("Malformed UpdateStatus type")),
             },
+            vectors_report: value.vectors_report.map(TryInto::try_into).transpose()?,
+            // Deletions always name at least one vector, so an empty map means no deletion
+            deleted_vectors: (!value.deleted_vectors.is_empty()).then(|| {
+                value
+                    .deleted_vectors
+                    .into_iter()
+                    .map(|(vector_name, count)| (vector_name, count as usize))
+                    .collect()
+            }),
+        })
+    }
+}
+
+impl From<UpdateVectorsReport> for api::grpc::qdrant::UpdateVectorsReport {
+    fn from(value: UpdateVectorsReport) -> Self {
+        Self {
+            succeeded: value.succeeded.into_iter().map(Into::into).collect(),
+            failed: value
+                .failed
+                .into_iter()
+                .map(|failed| api::grpc::qdrant::FailedPointVectors {
+                    id: Some(failed.id.into()),
+                    error: failed.error,
+                })
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<api::grpc::qdrant::UpdateVectorsReport> for UpdateVectorsReport {
+    type Error = Status;
+
+    fn try_from(value: api::grpc::qdrant::UpdateVectorsReport) -> Result<Self, Self::Error> {
+        Ok(Self {
+            succeeded: value
+                .succeeded
+                .into_iter()
+                .map(TryInto::try_into)
+                .collect::<Result<_, Status>>()?,
+            failed: value
+                .failed
+                .into_iter()
+                .map(|failed| {
+                    Ok(FailedPointVectors {
+                        id: failed
+                            .id
+                            .ok_or_else(|| Status::invalid_argument("Empty ID is not allowed"))?
+                            .try_into()?,
+                        error: failed.error,
+                    })
+                })
+                .collect::<Result<_, Status>>()?,
         })
     }
 }
@@ -12,12 +12,18 @@ pub mod validation;
 pub mod vector_ops;
 
 use std::collections::HashMap;
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
 
+use futures::future::join_all;
+use itertools::Either;
+use segment::entry::entry_point::check_process_stopped;
 use segment::types::{ExtendedPointId, PayloadFieldSchema};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
-use crate::hash_ring::HashRing;
+use crate::hash_ring::{HashRing, RingRouter};
+use crate::operations::types::{CollectionError, CollectionResult};
 use crate::shards::shard::ShardId;
 
 #[derive(Debug, Deserialize, Serialize, Validate, Default, Clone)]
@@ -77,6 +83,314 @@ impl<O> OperationToShard<O> {
             OperationToShard::ToAll(to_all) => OperationToShard::ToAll(f(to_all)),
         }
     }
+
+    /// Iterate over the operation of each shard, without applying or consuming them.
+    ///
+    /// Operation sent to all shards is yielded once for each shard of `all_shards`.
+    pub fn iter<'a>(
+        &'a self,
+        all_shards: &'a [ShardId],
+    ) -> impl Iterator<Item = (ShardId, &'a O)> + 'a {
+        match self {
+            OperationToShard::ByShard(operation_to_shard) => Either::Left(
+                operation_to_shard
+                    .iter()
+                    .map(|(shard_id, operation)| (*shard_id, operation)),
+            ),
+            OperationToShard::ToAll(operation) => Either::Right(
+                all_shards
+                    .iter()
+                    .map(move |shard_id| (*shard_id, operation)),
+            ),
+        }
+    }
+
+    /// Materialize the operation of each shard, e.g. to preview them before applying.
+    ///
+    /// Operation sent to all shards is copied to each shard of `all_shards`.
+    pub fn into_vec(self, all_shards: &[ShardId]) -> Vec<(ShardId, O)>
+    where
+        O: Clone,
+    {
+        match self {
+            OperationToShard::ByShard(operation_to_shard) => operation_to_shard,
+            OperationToShard::ToAll(operation) => all_shards
+                .iter()
+                .map(|shard_id| (*shard_id, operation.clone()))
+                .collect(),
+        }
+    }
+
+    /// Order per-shard operations by the estimated shard load, least loaded shards first.
+    ///
+    /// Shards with equal load are ordered by id. Operations sent to all shards are not affected.
+    pub fn order_by_load(self, shard_load: impl Fn(ShardId) -> usize) -> Self {
+        match self {
+            OperationToShard::ByShard(mut operation_to_shard) => {
+                operation_to_shard
+                    .sort_by_cached_key(|(shard_id, _)| (shard_load(*shard_id), *shard_id));
+                OperationToShard::ByShard(operation_to_shard)
+            }
+            to_all @ OperationToShard::ToAll(_) => to_all,
+        }
+    }
+
+    /// Apply the operation of each shard concurrently, results are returned in the shard order.
+    ///
+    /// Operation sent to all shards is copied to each shard of `all_shards`.
+    /// Failures of the shards are combined as in `collect_shard_results`.
+    pub async fn apply_concurrently<R, F, Fut>(
+        self,
+        all_shards: &[ShardId],
+        apply: F,
+    ) -> CollectionResult<Vec<(ShardId, R)>>
+    where
+        O: Clone,
+        F: Fn(ShardId, O) -> Fut,
+        Fut: Future<Output = CollectionResult<R>>,
+    {
+        let shard_requests = self
+            .into_vec(all_shards)
+            .into_iter()
+            .map(|(shard_id, operation)| {
+                let request = apply(shard_id, operation);
+                async move { request.await.map(|result| (shard_id, result)) }
+            });
+        collect_shard_results(join_all(shard_requests).await)
+    }
+}
+
+/// Per-shard operation stamped with the sequence number of the operation it was split from
+#[derive(Debug, Clone, PartialEq)]
+pub struct SequencedOperation<O> {
+    pub sequence: u64,
+    pub operation: O,
+}
+
+impl<O> OperationToShard<O> {
+    /// Stamp the operation of each shard with the same `sequence` number
+    pub fn stamp(self, sequence: u64) -> OperationToShard<SequencedOperation<O>> {
+        match self {
+            OperationToShard::ByShard(operation_to_shard) => OperationToShard::ByShard(
+                operation_to_shard
+                    .into_iter()
+                    .map(|(shard_id, operation)| {
+                        (
+                            shard_id,
+                            SequencedOperation {
+                                sequence,
+                                operation,
+                            },
+                        )
+                    })
+                    .collect(),
+            ),
+            OperationToShard::ToAll(operation) => OperationToShard::ToAll(SequencedOperation {
+                sequence,
+                operation,
+            }),
+        }
+    }
+}
+
+/// Source of sequence numbers for operations split by shard, shared by concurrent callers
+///
+/// Sequence number is taken before the operation is split, so it reflects the order in which
+/// operations were submitted, not the order in which their splits complete.
+#[derive(Debug, Default)]
+pub struct OperationSequencer {
+    next_sequence: AtomicU64,
+}
+
+impl OperationSequencer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn next_sequence(&self) -> u64 {
+        self.next_sequence.fetch_add(1, Ordering::SeqCst)
+    }
+
+    /// Split `operation` by shard, stamping the per-shard operations with the next sequence number
+    pub fn split_by_shard<O: SplitByShard>(
+        &self,
+        operation: O,
+        ring: &HashRing<ShardId>,
+    ) -> OperationToShard<SequencedOperation<O>> {
+        let sequence = self.next_sequence();
+        operation.split_by_shard(ring).stamp(sequence)
+    }
+}
+
+/// Gather stamped operations of several splits into a queue per shard, ordered by sequence
+///
+/// The result does not depend on the order of `splits`, so splits may be collected as they
+/// complete. Operations sent to all shards are queued to each shard of `all_shards`.
+pub fn sequenced_by_shard<O: Clone>(
+    splits: impl IntoIterator<Item = OperationToShard<SequencedOperation<O>>>,
+    all_shards: &[ShardId],
+) -> HashMap<ShardId, Vec<SequencedOperation<O>>> {
+    let mut by_shard: HashMap<ShardId, Vec<SequencedOperation<O>>> = HashMap::new();
+    for split in splits {
+        for (shard_id, operation) in split.into_vec(all_shards) {
+            by_shard.entry(shard_id).or_default().push(operation);
+        }
+    }
+    for operations in by_shard.values_mut() {
+        operations.sort_by_key(|operation| operation.sequence);
+    }
+    by_shard
+}
+
+/// Status of the operation of a single shard of a `RetryablePlan`
+#[derive(Debug, Clone)]
+pub enum ShardOperationStatus {
+    /// Not applied yet
+    Pending,
+    Done,
+    /// Last attempt failed with the error, the operation is applied again on the next attempt
+    Failed(CollectionError),
+}
+
+/// Operation of a single shard of a `RetryablePlan`
+#[derive(Debug, Clone)]
+pub struct PlannedShardOperation<O> {
+    pub shard_id: ShardId,
+    pub operation: O,
+    pub status: ShardOperationStatus,
+    /// Number of times the operation was applied, including the failed attempts
+    pub attempts: usize,
+}
+
+/// Per-shard operations which are applied and retried independently of each other
+///
+/// Each attempt applies the operations of the shards which are not done yet, so a failure of
+/// one shard only requires that shard to be retried.
+#[derive(Debug, Clone)]
+pub struct RetryablePlan<O> {
+    shards: Vec<PlannedShardOperation<O>>,
+}
+
+impl<O> OperationToShard<O> {
+    /// Materialize the operation of each shard into a plan, all of them pending
+    ///
+    /// Operation sent to all shards is copied to each shard of `all_shards`.
+    pub fn into_retryable_plan(self, all_shards: &[ShardId]) -> RetryablePlan<O>
+    where
+        O: Clone,
+    {
+        let shards = self
+            .into_vec(all_shards)
+            .into_iter()
+            .map(|(shard_id, operation)| PlannedShardOperation {
+                shard_id,
+                operation,
+                status: ShardOperationStatus::Pending,
+                attempts: 0,
+            })
+            .collect();
+        RetryablePlan { shards }
+    }
+}
+
+impl<O> RetryablePlan<O> {
+    pub fn shards(&self) -> &[PlannedShardOperation<O>] {
+        &self.shards
+    }
+
+    pub fn status(&self, shard_id: ShardId) -> Option<&ShardOperationStatus> {
+        self.shards
+            .iter()
+            .find(|shard| shard.shard_id == shard_id)
+            .map(|shard| &shard.status)
+    }
+
+    /// Whether the operations of all shards are applied successfully
+    pub fn is_done(&self) -> bool {
+        self.shards
+            .iter()
+            .all(|shard| matches!(shard.status, ShardOperationStatus::Done))
+    }
+
+    /// Shards which operations failed on the last attempt
+    pub fn failed_shards(&self) -> impl Iterator<Item = ShardId> + '_ {
+        self.shards
+            .iter()
+            .filter(|shard| matches!(shard.status, ShardOperationStatus::Failed(_)))
+            .map(|shard| shard.shard_id)
+    }
+
+    /// Apply the operations of pending and failed shards concurrently, updating their status
+    ///
+    /// Returns the results of the shards applied by this attempt, in the shard order. Failures
+    /// are combined as in `collect_shard_results`, but only the shards of this attempt count.
+    /// Calling it again retries only the failed shards, done shards are never applied twice.
+    pub async fn apply_remaining<R, F, Fut>(
+        &mut self,
+        apply: F,
+    ) -> CollectionResult<Vec<(ShardId, R)>>
+    where
+        O: Clone,
+        F: Fn(ShardId, O) -> Fut,
+        Fut: Future<Output = CollectionResult<R>>,
+    {
+        let remaining: Vec<_> = self
+            .shards
+            .iter_mut()
+            .filter(|shard| !matches!(shard.status, ShardOperationStatus::Done))
+            .collect();
+        let shard_requests = remaining.iter().map(|shard| {
+            let shard_id = shard.shard_id;
+            let request = apply(shard_id, shard.operation.clone());
+            async move { request.await.map(|result| (shard_id, result)) }
+        });
+        let results = join_all(shard_requests).await;
+        for (shard, result) in remaining.into_iter().zip(&results) {
+            shard.attempts += 1;
+            shard.status = match result {
+                Ok(_) => ShardOperationStatus::Done,
+                Err(err) => ShardOperationStatus::Failed(err.clone()),
+            };
+        }
+        collect_shard_results(results)
+    }
+}
+
+/// Combine the results of an operation applied to several shards, one result per shard
+///
+/// If all shards failed, the first error is returned as is. If only some of them failed,
+/// the shards are left inconsistent, reported as `InconsistentShardFailure` with the first error.
+pub fn collect_shard_results<R>(results: Vec<CollectionResult<R>>) -> CollectionResult<Vec<R>> {
+    let shards_total = results.len();
+    let mut shards_failed = 0;
+    let mut first_err = None;
+    let mut successful = Vec::with_capacity(shards_total);
+    for result in results {
+        match result {
+            Ok(result) => successful.push(result),
+            Err(err) => {
+                shards_failed += 1;
+                if first_err.is_none() {
+                    first_err = Some(err);
+                }
+            }
+        }
+    }
+
+    match first_err {
+        None => Ok(successful),
+        // compute final status code based on the first error
+        // e.g. a partially successful batch update failing because of bad input is a client error
+        Some(err) if shards_failed < shards_total => {
+            Err(CollectionError::InconsistentShardFailure {
+                shards_total: shards_total as u32,
+                shards_failed: shards_failed as u32,
+                first_err: Box::new(err),
+            })
+        }
+        // all requests per shard failed - propagate first error (assume there are all the same)
+        Some(err) => Err(err),
+    }
 }
 
 impl FieldIndexOperations {
@@ -108,14 +422,15 @@ impl Validate for CollectionUpdateOperations {
     }
 }
 
-fn point_to_shard(point_id: ExtendedPointId, ring: &HashRing<ShardId>) -> ShardId {
-    *ring
-        .get(&point_id)
-        .expect("Hash ring is guaranteed to be non-empty")
+fn point_to_shard(point_id: ExtendedPointId, router: &RingRouter<ShardId>) -> ShardId {
+    router.shards()[route_to_index(router, point_id)]
 }
 
 /// Split iterator of items that have point ids by shard
-fn split_iter_by_shard<I, F, O>(
+///
+/// The ring is flattened once into a `RingRouter`, and items are grouped by the index of their
+/// shard instead of a hash map lookup per item. Shards without items are omitted.
+pub fn split_iter_by_shard<I, F, O>(
     iter: I,
     id_extractor: F,
     ring: &HashRing<ShardId>,
@@ -124,15 +439,81 @@ where
     I: IntoIterator<Item = O>,
     F: Fn(&O) -> ExtendedPointId,
 {
-    let mut op_vec_by_shard: HashMap<ShardId, Vec<O>> = HashMap::new();
+    let router = ring.router();
+    let mut op_vec_by_index: Vec<Vec<O>> = router.shards().iter().map(|_| Vec::new()).collect();
     for operation in iter {
-        let shard_id = point_to_shard(id_extractor(&operation), ring);
-        op_vec_by_shard
-            .entry(shard_id)
-            .or_insert_with(Vec::new)
-            .push(operation);
+        op_vec_by_index[route_to_index(&router, id_extractor(&operation))].push(operation);
     }
-    OperationToShard::by_shard(op_vec_by_shard)
+    by_shard_index(&router, op_vec_by_index)
+}
+
+fn route_to_index(router: &RingRouter<ShardId>, point_id: ExtendedPointId) -> usize {
+    router
+        .route(&point_id)
+        .expect("Hash ring is guaranteed to be non-empty")
+}
+
+fn by_shard_index<O>(
+    router: &RingRouter<ShardId>,
+    op_vec_by_index: Vec<Vec<O>>,
+) -> OperationToShard<Vec<O>> {
+    OperationToShard::by_shard(
+        router
+            .shards()
+            .iter()
+            .copied()
+            .zip(op_vec_by_index)
+            .filter(|(_, operations)| !operations.is_empty()),
+    )
+}
+
+/// Same as `split_iter_by_shard`, but checks `stopped` flag before routing each item
+///
+/// Returns `Cancelled` error as soon as the flag is set, remaining items are not processed.
+fn split_iter_by_shard_cancellable<I, F, O>(
+    iter: I,
+    id_extractor: F,
+    ring: &HashRing<ShardId>,
+    stopped: &AtomicBool,
+) -> CollectionResult<OperationToShard<Vec<O>>>
+where
+    I: IntoIterator<Item = O>,
+    F: Fn(&O) -> ExtendedPointId,
+{
+    let router = ring.router();
+    let mut op_vec_by_index: Vec<Vec<O>> = router.shards().iter().map(|_| Vec::new()).collect();
+    for operation in iter {
+        check_process_stopped(stopped)?;
+        op_vec_by_index[route_to_index(&router, id_extractor(&operation))].push(operation);
+    }
+    Ok(by_shard_index(&router, op_vec_by_index))
+}
+
+/// Count items that have point ids by shard
+///
+/// Uses the same routing as `split_iter_by_shard`, but only reports how many items would go to
+/// each shard. Useful for planning large operations without materializing the split.
+pub fn count_iter_by_shard<I, F, O>(
+    iter: I,
+    id_extractor: F,
+    ring: &HashRing<ShardId>,
+) -> HashMap<ShardId, usize>
+where
+    I: IntoIterator<Item = O>,
+    F: Fn(&O) -> ExtendedPointId,
+{
+    let router = ring.router();
+    let mut count_by_index = vec![0; router.shards().len()];
+    for operation in iter {
+        count_by_index[route_to_index(&router, id_extractor(&operation))] += 1;
+    }
+    router
+        .shards()
+        .iter()
+        .copied()
+        .zip(count_by_index)
+        .filter(|(_, count)| *count > 0)
+        .collect()
 }
 
 /// Trait for Operation enums to split them by shard.
@@ -140,6 +521,36 @@ pub trait SplitByShard {
     fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self>
     where
         Self: Sized;
+
+    /// Same as `split_by_shard`, but per-shard operations are ordered by the load reported by
+    /// `shard_load`, least loaded shards first. Lets throttled schedulers process light shards
+    /// before the busy ones.
+    fn split_by_shard_ordered_by_load(
+        self,
+        ring: &HashRing<ShardId>,
+        shard_load: impl Fn(ShardId) -> usize,
+    ) -> OperationToShard<Self>
+    where
+        Self: Sized,
+    {
+        self.split_by_shard(ring).order_by_load(shard_load)
+    }
+
+    /// Same as `split_by_shard`, but if `target_shard` is set, the whole operation is routed to
+    /// this shard, ignoring the ring. Meant for repair tooling, which fixes a specific shard.
+    fn split_by_shard_with_target(
+        self,
+        ring: &HashRing<ShardId>,
+        target_shard: Option<ShardId>,
+    ) -> OperationToShard<Self>
+    where
+        Self: Sized,
+    {
+        match target_shard {
+            Some(shard_id) => OperationToShard::by_shard([(shard_id, self)]),
+            None => self.split_by_shard(ring),
+        }
+    }
 }
 
 impl SplitByShard for CollectionUpdateOperations {
@@ -176,11 +587,35 @@ impl CollectionUpdateOperations {
             }
         }
     }
+
+    /// Validate no point of the operation has more than `max` named vectors
+    ///
+    /// Oversized vector structs are likely a client bug and could exhaust the memory, the limit
+    /// is configured per node by `SharedStorageConfig::max_named_vectors_per_point`.
+    pub fn validate_named_vectors_count(
+        &self,
+        max: usize,
+    ) -> Result<(), validator::ValidationErrors> {
+        match self {
+            CollectionUpdateOperations::PointOperation(operation) => {
+                operation.validate_named_vectors_count(max)
+            }
+            CollectionUpdateOperations::VectorOperation(operation) => {
+                operation.validate_named_vectors_count(max)
+            }
+            CollectionUpdateOperations::PayloadOperation(_)
+            | CollectionUpdateOperations::FieldIndexOperation(_) => Ok(()),
+        }
+    }
 }
 
 #[cfg(test)]
 mod tests {
+    use std::time::Duration;
+
     use serde_json;
+    use tokio::sync::Barrier;
+    use tokio::time::timeout;
 
     use super::*;
 
@@ -194,4 +629,515 @@ mod tests {
         let json = serde_json::to_string_pretty(&op).unwrap();
         println!("{json}")
     }
+
+    #[test]
+    fn test_count_iter_by_shard() {
+        let mut ring = HashRing::fair(100);
+        ring.add(0);
+        ring.add(1);
+        ring.add(2);
+
+        let ids: Vec<ExtendedPointId> = (0..1000u64).map(ExtendedPointId::from).collect();
+
+        let router = ring.router();
+        let mut expected: HashMap<ShardId, usize> = HashMap::new();
+        for id in &ids {
+            *expected.entry(point_to_shard(*id, &router)).or_insert(0) += 1;
+        }
+
+        let counts = count_iter_by_shard(&ids, |id| **id, &ring);
+        assert_eq!(counts, expected);
+        assert_eq!(counts.values().sum::<usize>(), ids.len());
+
+        // Counts must agree with the actual split
+        match split_iter_by_shard(ids.clone(), |id| *id, &ring) {
+            OperationToShard::ByShard(by_shard) => {
+                assert_eq!(by_shard.len(), counts.len());
+                for (shard_id, shard_ids) in by_shard {
+                    assert_eq!(counts[&shard_id], shard_ids.len());
+                }
+            }
+            OperationToShard::ToAll(_) => panic!("id list must be split by shard"),
+        }
+
+        // Single shard ring routes everything to that shard
+        let mut single_ring = HashRing::fair(100);
+        single_ring.add(7);
+        let counts = count_iter_by_shard(&ids, |id| **id, &single_ring);
+        assert_eq!(counts, HashMap::from([(7, ids.len())]));
+    }
+
+    #[test]
+    fn test_split_iter_by_shard_cancellable() {
+        let mut ring = HashRing::fair(100);
+        ring.add(0);
+        ring.add(1);
+
+        let ids: Vec<ExtendedPointId> = (0..1000u64).map(ExtendedPointId::from).collect();
+
+        let stopped = AtomicBool::new(false);
+        match split_iter_by_shard_cancellable(ids.clone(), |id| *id, &ring, &stopped) {
+            Ok(OperationToShard::ByShard(by_shard)) => {
+                let total: usize = by_shard.iter().map(|(_, ids)| ids.len()).sum();
+                assert_eq!(total, ids.len());
+            }
+            Ok(OperationToShard::ToAll(_)) => panic!("id list must be split by shard"),
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+
+        // Flag set in the middle of the split stops consuming the ids
+        let mut consumed = 0;
+        let iter = ids.iter().copied().inspect(|_| {
+            consumed += 1;
+            if consumed == 10 {
+                stopped.store(true, std::sync::atomic::Ordering::Relaxed);
+            }
+        });
+        let result = split_iter_by_shard_cancellable(iter, |id| *id, &ring, &stopped);
+        assert!(matches!(result, Err(CollectionError::Cancelled { .. })));
+        assert_eq!(consumed, 10);
+    }
+
+    #[tokio::test]
+    async fn test_apply_concurrently() {
+        let all_shards: [ShardId; 3] = [0, 1, 2];
+        // Each mock shard waits for all the others, sequential dispatch would never complete
+        let barrier = Barrier::new(all_shards.len());
+        let apply = |shard_id: ShardId, value: u32| {
+            let barrier = &barrier;
+            async move {
+                barrier.wait().await;
+                match value {
+                    0 => Err(CollectionError::bad_input(format!(
+                        "shard {shard_id} failed"
+                    ))),
+                    value => Ok(value + shard_id),
+                }
+            }
+        };
+        let run = |operation: OperationToShard<u32>| {
+            timeout(
+                Duration::from_secs(10),
+                operation.apply_concurrently(&all_shards, apply),
+            )
+        };
+        let expect_concurrent = "shard operations must be applied concurrently";
+
+        let results = run(OperationToShard::by_shard([(0, 10), (1, 20), (2, 30)]))
+            .await
+            .expect(expect_concurrent);
+        assert_eq!(results.unwrap(), vec![(0, 10), (1, 21), (2, 32)]);
+
+        let results = run(OperationToShard::to_all(5))
+            .await
+            .expect(expect_concurrent);
+        assert_eq!(results.unwrap(), vec![(0, 5), (1, 6), (2, 7)]);
+
+        // Failure of some of the shards is reported as inconsistent
+        let result = run(OperationToShard::by_shard([(0, 10), (1, 0), (2, 0)]))
+            .await
+            .expect(expect_concurrent);
+        match result {
+            Err(CollectionError::InconsistentShardFailure {
+                shards_total,
+                shards_failed,
+                first_err,
+            }) => {
+                assert_eq!((shards_total, shards_failed), (3, 2));
+                assert!(first_err.to_string().contains("shard 1 failed"));
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+
+        // Failure of all the shards propagates the first error
+        let result = run(OperationToShard::to_all(0))
+            .await
+            .expect(expect_concurrent);
+        match result {
+            Err(CollectionError::BadInput { description }) => {
+                assert!(description.contains("shard 0 failed"))
+            }
+            other => panic!("unexpected result: {other:?}"),
+        }
+    }
+
+    #[test]
+    fn test_sequenced_split_by_shard() {
+        let mut ring = HashRing::fair(100);
+        let all_shards = [0, 1, 2];
+        for shard_id in all_shards {
+            ring.add(shard_id);
+        }
+
+        let operations: Vec<vector_ops::VectorOperations> = (0..20u64)
+            .map(|i| {
+                let ids = (i * 10..i * 10 + 10).map(ExtendedPointId::from).collect();
+                vector_ops::VectorOperations::DeleteVectors(
+                    point_ops::PointIdsList { points: ids },
+                    vec![format!("vector_{i}")],
+                )
+            })
+            .collect();
+
+        // Operations are split concurrently, splits are collected in the order they complete
+        let sequencer = OperationSequencer::new();
+        let completed = std::sync::Mutex::new(vec![]);
+        std::thread::scope(|scope| {
+            for operation in operations.clone() {
+                let (sequencer, ring, completed) = (&sequencer, &ring, &completed);
+                scope.spawn(move || {
+                    let split = sequencer.split_by_shard(operation, ring);
+                    completed.lock().unwrap().push(split);
+                });
+            }
+        });
+        let splits = completed.into_inner().unwrap();
+        assert_eq!(splits.len(), operations.len());
+
+        // Each operation got its own sequence number, shared by all of its shards
+        let vector_name = |operation: &vector_ops::VectorOperations| match operation {
+            vector_ops::VectorOperations::DeleteVectors(_, names) => names[0].clone(),
+            _ => panic!("unexpected operation"),
+        };
+        let mut sequence_by_name: HashMap<String, u64> = HashMap::new();
+        for split in &splits {
+            for (_, stamped) in split.iter(&all_shards) {
+                let sequence = *sequence_by_name
+                    .entry(vector_name(&stamped.operation))
+                    .or_insert(stamped.sequence);
+                assert_eq!(sequence, stamped.sequence);
+            }
+        }
+        let mut sequences: Vec<u64> = sequence_by_name.values().copied().collect();
+        sequences.sort_unstable();
+        assert_eq!(sequences, (0..operations.len() as u64).collect::<Vec<_>>());
+
+        // Queues of the shards are ordered by sequence, regardless of the completion order
+        let by_shard = sequenced_by_shard(splits, &all_shards);
+        for operations in by_shard.values() {
+            assert!(operations
+                .windows(2)
+                .all(|pair| pair[0].sequence < pair[1].sequence));
+            for stamped in operations {
+                assert_eq!(
+                    sequence_by_name[&vector_name(&stamped.operation)],
+                    stamped.sequence
+                );
+            }
+        }
+        let reversed = (0..5u64)
+            .rev()
+            .map(|sequence| OperationToShard::by_shard([(1, sequence)]).stamp(sequence));
+        let queued = sequenced_by_shard(reversed, &all_shards);
+        let queued_sequences: Vec<u64> = queued[&1].iter().map(|op| op.operation).collect();
+        assert_eq!(queued_sequences, vec![0, 1, 2, 3, 4]);
+
+        // Operation sent to all shards is queued to each of them with the same sequence
+        let to_all = OperationToShard::to_all("clear").stamp(7);
+        let queued = sequenced_by_shard([to_all], &all_shards);
+        assert_eq!(queued.len(), all_shards.len());
+        assert!(queued.values().all(|operations| operations
+            == &[SequencedOperation {
+                sequence: 7,
+                operation: "clear",
+            }]));
+    }
+
+    #[test]
+    fn test_split_by_shard_with_target() {
+        let mut ring = HashRing::fair(100);
+        let all_shards = [0, 1, 2];
+        for shard_id in all_shards {
+            ring.add(shard_id);
+        }
+
+        let ids: Vec<ExtendedPointId> = (0..100u64).map(ExtendedPointId::from).collect();
+        let delete_vectors = || {
+            vector_ops::VectorOperations::DeleteVectors(
+                point_ops::PointIdsList {
+                    points: ids.clone(),
+                },
+                vec!["image".to_string()],
+            )
+        };
+        // Ids are spread over all shards by the ring
+        assert_eq!(count_iter_by_shard(&ids, |id| **id, &ring).len(), 3);
+
+        for target_shard in all_shards {
+            match delete_vectors().split_by_shard_with_target(&ring, Some(target_shard)) {
+                OperationToShard::ByShard(by_shard) => {
+                    assert_eq!(by_shard.len(), 1);
+                    let (shard_id, operation) = by_shard.into_iter().next().unwrap();
+                    assert_eq!(shard_id, target_shard);
+                    match operation {
+                        vector_ops::VectorOperations::DeleteVectors(shard_ids, _) => {
+                            assert_eq!(shard_ids.points, ids)
+                        }
+                        _ => panic!("unexpected operation"),
+                    }
+                }
+                OperationToShard::ToAll(_) => panic!("operation must go to the target shard"),
+            }
+        }
+
+        // Shard outside of the ring is not checked here, `ShardHolder` validates the target
+        match delete_vectors().split_by_shard_with_target(&ring, Some(42)) {
+            OperationToShard::ByShard(by_shard) => assert_eq!(by_shard[0].0, 42),
+            OperationToShard::ToAll(_) => panic!("operation must go to the target shard"),
+        }
+
+        // Filter-based operations, normally sent to all shards, are targeted as well
+        let by_filter = vector_ops::VectorOperations::ReindexVectors(vector_ops::ReindexVectors {
+            name: "image".to_string(),
+        });
+        match by_filter.split_by_shard_with_target(&ring, Some(1)) {
+            OperationToShard::ByShard(by_shard) => {
+                assert_eq!(by_shard.iter().map(|(id, _)| *id).collect::<Vec<_>>(), [1])
+            }
+            OperationToShard::ToAll(_) => panic!("operation must go to the target shard"),
+        }
+
+        // Without the override the ring is used as usual
+        match delete_vectors().split_by_shard_with_target(&ring, None) {
+            OperationToShard::ByShard(by_shard) => assert_eq!(by_shard.len(), 3),
+            OperationToShard::ToAll(_) => panic!("id list must be split by shard"),
+        }
+    }
+
+    #[test]
+    fn test_preview_split_by_shard() {
+        let mut ring = HashRing::fair(100);
+        let all_shards = [0, 1, 2];
+        for shard_id in all_shards {
+            ring.add(shard_id);
+        }
+
+        let ids: Vec<ExtendedPointId> = (0..100u64).map(ExtendedPointId::from).collect();
+        let operation = vector_ops::VectorOperations::DeleteVectors(
+            point_ops::PointIdsList {
+                points: ids.clone(),
+            },
+            vec!["image".to_string()],
+        );
+        let split = operation.split_by_shard(&ring);
+        let router = ring.router();
+
+        // Inspecting the split does not consume it
+        let previewed: HashMap<ShardId, usize> = split
+            .iter(&all_shards)
+            .map(|(shard_id, operation)| match operation {
+                vector_ops::VectorOperations::DeleteVectors(shard_ids, vector_names) => {
+                    assert_eq!(vector_names, &vec!["image".to_string()]);
+                    (shard_id, shard_ids.points.len())
+                }
+                _ => panic!("unexpected operation"),
+            })
+            .collect();
+        assert_eq!(previewed, count_iter_by_shard(&ids, |id| **id, &ring));
+        assert_eq!(previewed.len(), all_shards.len());
+
+        let preview = split.into_vec(&all_shards);
+        assert_eq!(preview.len(), all_shards.len());
+        let mut previewed_ids: Vec<_> = preview
+            .into_iter()
+            .flat_map(|(shard_id, operation)| match operation {
+                vector_ops::VectorOperations::DeleteVectors(shard_ids, _) => {
+                    assert!(shard_ids
+                        .points
+                        .iter()
+                        .all(|id| point_to_shard(*id, &router) == shard_id));
+                    shard_ids.points
+                }
+                _ => panic!("unexpected operation"),
+            })
+            .collect();
+        previewed_ids.sort();
+        assert_eq!(previewed_ids, ids);
+
+        // Operation sent to all shards is previewed for each of them
+        let by_filter = vector_ops::VectorOperations::DeleteVectorsByFilter(
+            segment::types::Filter::default(),
+            vec!["image".to_string()],
+        );
+        let preview = by_filter.split_by_shard(&ring).into_vec(&all_shards);
+        let preview_shards: Vec<_> = preview.iter().map(|(shard_id, _)| *shard_id).collect();
+        assert_eq!(preview_shards, all_shards);
+    }
+
+    #[test]
+    fn test_split_iter_by_shard() {
+        let sorted = |split: OperationToShard<Vec<ExtendedPointId>>| match split {
+            OperationToShard::ByShard(mut by_shard) => {
+                by_shard.sort_by_key(|(shard_id, _)| *shard_id);
+                by_shard
+            }
+            OperationToShard::ToAll(_) => panic!("ids must be split by shard"),
+        };
+        // Reference routing, with a ring lookup per id
+        let split_each = |ids: &[ExtendedPointId], ring: &HashRing<ShardId>| {
+            let mut by_shard: HashMap<ShardId, Vec<ExtendedPointId>> = HashMap::new();
+            for id in ids {
+                by_shard
+                    .entry(*ring.get(id).unwrap())
+                    .or_default()
+                    .push(*id);
+            }
+            let mut by_shard: Vec<_> = by_shard.into_iter().collect();
+            by_shard.sort_by_key(|(shard_id, _)| *shard_id);
+            by_shard
+        };
+        let mut ids: Vec<ExtendedPointId> = (0..10_000u64)
+            .map(|id| ExtendedPointId::from(id.wrapping_mul(0x9E37_79B9_7F4A_7C15)))
+            .collect();
+        ids.extend((0..100u128).map(|id| ExtendedPointId::Uuid(uuid::Uuid::from_u128(id))));
+
+        let mut fair_ring = HashRing::fair(100);
+        let mut raw_ring = HashRing::raw();
+        for shard_id in 0..5 {
+            fair_ring.add(shard_id);
+            raw_ring.add(shard_id);
+        }
+        for ring in [&fair_ring, &raw_ring] {
+            // Router routes numeric and UUID ids the same way as the ring itself
+            let expected = split_each(&ids, ring);
+            assert_eq!(
+                sorted(split_iter_by_shard(ids.clone(), |id| *id, ring)),
+                expected
+            );
+            let cancellable = split_iter_by_shard_cancellable(
+                ids.clone(),
+                |id| *id,
+                ring,
+                &AtomicBool::new(false),
+            )
+            .unwrap();
+            assert_eq!(sorted(cancellable), expected);
+        }
+
+        // Shards without ids are omitted
+        let few_ids = &ids[..2];
+        assert_eq!(
+            sorted(split_iter_by_shard(few_ids.to_vec(), |id| *id, &fair_ring)),
+            split_each(few_ids, &fair_ring),
+        );
+    }
+
+    #[test]
+    fn test_split_by_shard_ordered_by_load() {
+        let mut ring = HashRing::fair(100);
+        ring.add(0);
+        ring.add(1);
+        ring.add(2);
+        ring.add(3);
+
+        let ids: Vec<ExtendedPointId> = (0..1000u64).map(ExtendedPointId::from).collect();
+        let operation = vector_ops::VectorOperations::DeleteVectors(
+            point_ops::PointIdsList {
+                points: ids.clone(),
+            },
+            vec!["image".to_string()],
+        );
+
+        // Shards 0 and 3 have equal load, so they are ordered by id
+        let mock_load: HashMap<ShardId, usize> =
+            HashMap::from([(0, 10), (1, 100), (2, 0), (3, 10)]);
+        let split =
+            operation.split_by_shard_ordered_by_load(&ring, |shard_id| mock_load[&shard_id]);
+
+        let by_shard = match split {
+            OperationToShard::ByShard(by_shard) => by_shard,
+            OperationToShard::ToAll(_) => panic!("id list must be split by shard"),
+        };
+        let shard_order: Vec<_> = by_shard.iter().map(|(shard_id, _)| *shard_id).collect();
+        assert_eq!(shard_order, vec![2, 0, 3, 1]);
+
+        // Ordering does not change which ids go to which shard
+        let counts = count_iter_by_shard(&ids, |id| **id, &ring);
+        let router = ring.router();
+        for (shard_id, operation) in by_shard {
+            match operation {
+                vector_ops::VectorOperations::DeleteVectors(shard_ids, _) => {
+                    assert_eq!(shard_ids.points.len(), counts[&shard_id]);
+                    assert!(shard_ids
+                        .points
+                        .iter()
+                        .all(|id| point_to_shard(*id, &router) == shard_id));
+                }
+                _ => panic!("unexpected operation"),
+            }
+        }
+
+        // Filter operations are still sent to all shards
+        let by_filter = vector_ops::VectorOperations::DeleteVectorsByFilter(
+            segment::types::Filter::default(),
+            vec!["image".to_string()],
+        );
+        assert!(matches!(
+            by_filter.split_by_shard_ordered_by_load(&ring, |shard_id| mock_load[&shard_id]),
+            OperationToShard::ToAll(_),
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_retryable_plan() {
+        let all_shards: [ShardId; 3] = [0, 1, 2];
+        let applied: std::sync::Mutex<Vec<ShardId>> = Default::default();
+        let unavailable_shard = std::sync::Mutex::new(Some(1));
+        let apply = |shard_id: ShardId, value: u32| {
+            applied.lock().unwrap().push(shard_id);
+            let unavailable = *unavailable_shard.lock().unwrap() == Some(shard_id);
+            async move {
+                if unavailable {
+                    Err(CollectionError::service_error(format!(
+                        "shard {shard_id} is unavailable"
+                    )))
+                } else {
+                    Ok(value + shard_id)
+                }
+            }
+        };
+
+        let mut plan = OperationToShard::to_all(10).into_retryable_plan(&all_shards);
+        assert!(matches!(
+            plan.status(1),
+            Some(ShardOperationStatus::Pending)
+        ));
+        assert!(plan.status(3).is_none());
+
+        // Failure of a single shard does not affect the others
+        let result = plan.apply_remaining(apply).await;
+        assert!(matches!(
+            result,
+            Err(CollectionError::InconsistentShardFailure {
+                shards_total: 3,
+                shards_failed: 1,
+                ..
+            })
+        ));
+        assert!(!plan.is_done());
+        assert_eq!(plan.failed_shards().collect::<Vec<_>>(), vec![1]);
+        assert!(matches!(plan.status(0), Some(ShardOperationStatus::Done)));
+        match plan.status(1) {
+            Some(ShardOperationStatus::Failed(err)) => {
+                assert!(err.to_string().contains("shard 1 is unavailable"))
+            }
+            other => panic!("unexpected status: {other:?}"),
+        }
+
+        // Only the failed shard is retried
+        applied.lock().unwrap().clear();
+        *unavailable_shard.lock().unwrap() = None;
+        let results = plan.apply_remaining(apply).await.unwrap();
+        assert_eq!(results, vec![(1, 11)]);
+        assert_eq!(*applied.lock().unwrap(), vec![1]);
+        assert!(plan.is_done());
+        assert_eq!(plan.failed_shards().count(), 0);
+        let attempts: Vec<_> = plan.shards().iter().map(|shard| shard.attempts).collect();
+        assert_eq!(attempts, vec![1, 2, 1]);
+
+        // Nothing left to apply
+        applied.lock().unwrap().clear();
+        assert!(plan.apply_remaining(apply).await.unwrap().is_empty());
+        assert!(applied.lock().unwrap().is_empty());
+    }
 }
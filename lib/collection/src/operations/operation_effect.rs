@@ -78,6 +78,29 @@ impl EstimateOperationEffectArea for vector_ops::VectorOperations {
             vector_ops::VectorOperations::DeleteVectorsByFilter(filter, _) => {
                 OperationEffectArea::Filter(filter.clone())
             }
+            vector_ops::VectorOperations::SetDefaultVectorByFilter(set_default) => {
+                OperationEffectArea::Filter(set_default.filter.clone())
+            }
+            // Empty filter matches all points
+            vector_ops::VectorOperations::ReindexVectors(_)
+            | vector_ops::VectorOperations::ConcatVectors(_)
+            | vector_ops::VectorOperations::RepairVectors(_) => {
+                OperationEffectArea::Filter(Filter::default())
+            }
+            vector_ops::VectorOperations::Batch(operations) => {
+                let mut ids = vec![];
+                for operation in operations {
+                    match operation.estimate_effect_area() {
+                        OperationEffectArea::Empty => {}
+                        OperationEffectArea::Points(points) => ids.extend(points),
+                        // Filters of different operations are not merged, assume all points
+                        OperationEffectArea::Filter(_) => {
+                            return OperationEffectArea::Filter(Filter::default())
+                        }
+                    }
+                }
+                OperationEffectArea::Points(ids)
+            }
         }
     }
 }
@@ -127,6 +150,9 @@ impl EstimateOperationEffectArea for PayloadOps {
                     OperationEffectArea::Empty
                 }
             }
+            PayloadOps::SetNestedPayload(set_nested_payload) => {
+                OperationEffectArea::Points(set_nested_payload.points.clone())
+            }
         }
     }
 }
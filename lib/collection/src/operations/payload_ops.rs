@@ -86,6 +86,21 @@ impl TryFrom<DeletePayloadShadow> for DeletePayload {
     }
 }
 
+/// Set payload of the elements of a nested array, which match the nested filter
+///
+/// Other elements of the array are kept intact.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct SetNestedPayload {
+    /// Payload values to assign to each matching element of the array
+    pub payload: Payload,
+    /// Key of the nested array, e.g. `reviews`
+    pub key: PayloadKeyType,
+    /// Selects the elements of the array to update, conditions are relative to an element
+    pub filter: Filter,
+    /// Updates the array of each point in this list
+    pub points: Vec<PointIdType>,
+}
+
 /// Define operations description for point payloads manipulation
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -100,6 +115,8 @@ pub enum PayloadOps {
     ClearPayloadByFilter(Filter),
     /// Overwrite full payload with given keys
     OverwritePayload(SetPayload),
+    /// Set payload values of the matching elements of a nested array
+    SetNestedPayload(SetNestedPayload),
 }
 
 impl PayloadOps {
@@ -110,6 +127,7 @@ impl PayloadOps {
             PayloadOps::ClearPayload { .. } => false,
             PayloadOps::ClearPayloadByFilter(_) => false,
             PayloadOps::OverwritePayload(_) => true,
+            PayloadOps::SetNestedPayload(_) => true,
         }
     }
 }
@@ -122,6 +140,7 @@ impl Validate for PayloadOps {
             PayloadOps::ClearPayload { .. } => Ok(()),
             PayloadOps::ClearPayloadByFilter(_) => Ok(()),
             PayloadOps::OverwritePayload(operation) => operation.validate(),
+            PayloadOps::SetNestedPayload(operation) => operation.validate(),
         }
     }
 }
@@ -141,6 +160,22 @@ impl SplitByShard for PayloadOps {
             PayloadOps::OverwritePayload(operation) => operation
                 .split_by_shard(ring)
                 .map(PayloadOps::OverwritePayload),
+            PayloadOps::SetNestedPayload(operation) => {
+                let SetNestedPayload {
+                    payload,
+                    key,
+                    filter,
+                    points,
+                } = operation;
+                split_iter_by_shard(points, |id| *id, ring).map(|points| {
+                    PayloadOps::SetNestedPayload(SetNestedPayload {
+                        payload: payload.clone(),
+                        key: key.clone(),
+                        filter: filter.clone(),
+                        points,
+                    })
+                })
+            }
         }
     }
 }
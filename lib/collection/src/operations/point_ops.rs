@@ -12,6 +12,7 @@ use segment::types::{Filter, Payload, PointIdType};
 use serde::{Deserialize, Serialize};
 use validator::Validate;
 
+use super::vector_ops::{validate_named_vectors_count, validate_named_vectors_number};
 use super::{point_to_shard, split_iter_by_shard, OperationToShard, SplitByShard};
 use crate::hash_ring::HashRing;
 use crate::operations::types::Record;
@@ -132,6 +133,34 @@ impl Validate for PointsSelector {
     }
 }
 
+/// Shards targeted by a [`PointsSelector`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RoutingPlan {
+    /// Selected point ids, partitioned by the shard they belong to
+    ByShard(HashMap<ShardId, Vec<PointIdType>>),
+    /// Filter may select points in any shard, so all shards are targeted
+    Broadcast,
+}
+
+impl PointsSelector {
+    /// Determine which shards hold the selected points
+    ///
+    /// Ids are routed the same way as points of update operations, see `SplitByShard`.
+    pub fn routing_plan(&self, ring: &HashRing<ShardId>) -> RoutingPlan {
+        match self {
+            PointsSelector::PointIdsSelector(PointIdsList { points }) => {
+                match split_iter_by_shard(points.iter().copied(), |id| *id, ring) {
+                    OperationToShard::ByShard(by_shard) => {
+                        RoutingPlan::ByShard(by_shard.into_iter().collect())
+                    }
+                    OperationToShard::ToAll(_) => RoutingPlan::Broadcast,
+                }
+            }
+            PointsSelector::FilterSelector(_) => RoutingPlan::Broadcast,
+        }
+    }
+}
+
 // Structure used for deriving custom JsonSchema only
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
 struct PointsList {
@@ -267,6 +296,41 @@ impl Validate for PointInsertOperations {
     }
 }
 
+impl PointInsertOperations {
+    /// Validate no point of the operation has more than `max` named vectors
+    pub fn validate_named_vectors_count(
+        &self,
+        max: usize,
+    ) -> Result<(), validator::ValidationErrors> {
+        let result = match self {
+            PointInsertOperations::PointsList(points) => {
+                validate_points_named_vectors_count(points, max)
+            }
+            // All points of the batch have the same named vectors
+            PointInsertOperations::PointsBatch(batch) => match &batch.vectors {
+                BatchVectorStruct::Single(_) => Ok(()),
+                BatchVectorStruct::Multi(named_vectors) => {
+                    validate_named_vectors_number(named_vectors.len(), max)
+                }
+            },
+        };
+        result.map_err(|error| {
+            let mut errors = validator::ValidationErrors::new();
+            errors.add("vector", error);
+            errors
+        })
+    }
+}
+
+fn validate_points_named_vectors_count(
+    points: &[PointStruct],
+    max: usize,
+) -> Result<(), validator::ValidationError> {
+    points
+        .iter()
+        .try_for_each(|point| validate_named_vectors_count(&point.vector, max))
+}
+
 impl SplitByShard for PointInsertOperations {
     fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
         match self {
@@ -316,6 +380,30 @@ impl PointOperations {
     }
 }
 
+impl PointOperations {
+    /// Validate no point of the operation has more than `max` named vectors
+    pub fn validate_named_vectors_count(
+        &self,
+        max: usize,
+    ) -> Result<(), validator::ValidationErrors> {
+        match self {
+            PointOperations::UpsertPoints(upsert_points) => {
+                upsert_points.validate_named_vectors_count(max)
+            }
+            PointOperations::SyncPoints(sync_points) => {
+                validate_points_named_vectors_count(&sync_points.points, max).map_err(|error| {
+                    let mut errors = validator::ValidationErrors::new();
+                    errors.add("points", error);
+                    errors
+                })
+            }
+            PointOperations::DeletePoints { .. } | PointOperations::DeletePointsByFilter(_) => {
+                Ok(())
+            }
+        }
+    }
+}
+
 impl Validate for PointOperations {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
@@ -330,6 +418,7 @@ impl Validate for PointOperations {
 impl SplitByShard for Batch {
     fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
         let batch = self;
+        let router = ring.router();
         let mut batch_by_shard: HashMap<ShardId, Batch> = HashMap::new();
         let Batch {
             ids,
@@ -341,7 +430,7 @@ impl SplitByShard for Batch {
             match vectors {
                 BatchVectorStruct::Single(vectors) => {
                     for (id, vector, payload) in izip!(ids, vectors, payloads) {
-                        let shard_id = point_to_shard(id, ring);
+                        let shard_id = point_to_shard(id, &router);
                         let batch = batch_by_shard.entry(shard_id).or_insert_with(|| Batch {
                             ids: vec![],
                             vectors: BatchVectorStruct::Single(vec![]),
@@ -359,7 +448,7 @@ impl SplitByShard for Batch {
                         vec![NamedVectors::default(); ids.len()]
                     };
                     for (id, named_vector, payload) in izip!(ids, named_vectors_list, payloads) {
-                        let shard_id = point_to_shard(id, ring);
+                        let shard_id = point_to_shard(id, &router);
                         let batch = batch_by_shard.entry(shard_id).or_insert_with(|| Batch {
                             ids: vec![],
                             vectors: BatchVectorStruct::Multi(HashMap::new()),
@@ -383,7 +472,7 @@ impl SplitByShard for Batch {
             match vectors {
                 BatchVectorStruct::Single(vectors) => {
                     for (id, vector) in izip!(ids, vectors) {
-                        let shard_id = point_to_shard(id, ring);
+                        let shard_id = point_to_shard(id, &router);
                         let batch = batch_by_shard.entry(shard_id).or_insert_with(|| Batch {
                             ids: vec![],
                             vectors: BatchVectorStruct::Single(vec![]),
@@ -400,7 +489,7 @@ impl SplitByShard for Batch {
                         vec![NamedVectors::default(); ids.len()]
                     };
                     for (id, named_vector) in izip!(ids, named_vectors_list) {
-                        let shard_id = point_to_shard(id, ring);
+                        let shard_id = point_to_shard(id, &router);
                         let batch = batch_by_shard.entry(shard_id).or_insert_with(|| Batch {
                             ids: vec![],
                             vectors: BatchVectorStruct::Multi(HashMap::new()),
@@ -500,4 +589,38 @@ mod tests {
         });
         assert!(matches!(batch.validate(), Err(_)));
     }
+
+    #[test]
+    fn test_points_selector_routing_plan() {
+        let mut ring = HashRing::fair(100);
+        ring.add(1);
+        ring.add(2);
+
+        let ids: Vec<PointIdType> = (0..50_u64).map(PointIdType::from).collect();
+        let selector = PointsSelector::PointIdsSelector(ids.clone().into());
+        let by_shard = match selector.routing_plan(&ring) {
+            RoutingPlan::ByShard(by_shard) => by_shard,
+            RoutingPlan::Broadcast => panic!("ids must not be broadcasted"),
+        };
+
+        // Every id is routed exactly once, to the shard of the hash ring
+        assert_eq!(by_shard.values().map(Vec::len).sum::<usize>(), ids.len());
+        for (shard_id, shard_ids) in &by_shard {
+            for id in shard_ids {
+                assert_eq!(ring.get(id), Some(shard_id));
+            }
+        }
+
+        // No ids - no shards
+        let selector = PointsSelector::PointIdsSelector(PointIdsList { points: vec![] });
+        assert_eq!(
+            selector.routing_plan(&ring),
+            RoutingPlan::ByShard(HashMap::new())
+        );
+
+        let selector = PointsSelector::FilterSelector(FilterSelector {
+            filter: Filter::default(),
+        });
+        assert_eq!(selector.routing_plan(&ring), RoutingPlan::Broadcast);
+    }
 }
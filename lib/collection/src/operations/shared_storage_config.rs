@@ -2,6 +2,9 @@ use crate::operations::types::NodeType;
 
 const DEFAULT_UPDATE_QUEUE_SIZE: usize = 100;
 const DEFAULT_UPDATE_QUEUE_SIZE_LISTENER: usize = 10_000;
+/// Collections rarely have more than a few dozens of named vectors, much larger structs are
+/// likely a client bug
+pub const DEFAULT_MAX_NAMED_VECTORS_PER_POINT: usize = 1024;
 
 /// Storage configuration shared between all collections.
 /// Represents a per-node configuration, which might be changes with restart.
@@ -12,6 +15,8 @@ pub struct SharedStorageConfig {
     pub node_type: NodeType,
     pub handle_collection_load_errors: bool,
     pub recovery_mode: Option<String>,
+    /// Maximum number of named vectors of a single point in an update
+    pub max_named_vectors_per_point: usize,
 }
 
 impl Default for SharedStorageConfig {
@@ -21,6 +26,7 @@ impl Default for SharedStorageConfig {
             node_type: Default::default(),
             handle_collection_load_errors: false,
             recovery_mode: None,
+            max_named_vectors_per_point: DEFAULT_MAX_NAMED_VECTORS_PER_POINT,
         }
     }
 }
@@ -31,6 +37,7 @@ impl SharedStorageConfig {
         node_type: NodeType,
         handle_collection_load_errors: bool,
         recovery_mode: Option<String>,
+        max_named_vectors_per_point: Option<usize>,
     ) -> Self {
         let update_queue_size = update_queue_size.unwrap_or(match node_type {
             NodeType::Normal => DEFAULT_UPDATE_QUEUE_SIZE,
@@ -42,6 +49,8 @@ impl SharedStorageConfig {
             node_type,
             handle_collection_load_errors,
             recovery_mode,
+            max_named_vectors_per_point: max_named_vectors_per_point
+                .unwrap_or(DEFAULT_MAX_NAMED_VECTORS_PER_POINT),
         }
     }
 }
@@ -28,6 +28,9 @@ use validator::{Validate, ValidationErrors};
 
 use crate::config::CollectionConfig;
 use crate::operations::config_diff::HnswConfigDiff;
+use crate::operations::vector_ops::{
+    merge_deleted_vectors, merge_vectors_reports, UpdateVectorsReport,
+};
 use crate::save_on_disk;
 use crate::shards::replica_set::ReplicaState;
 use crate::shards::shard::{PeerId, ShardId};
@@ -166,6 +169,20 @@ pub struct UpdateResult {
     pub operation_id: SeqNumberType,
     /// Update status
     pub status: UpdateStatus,
+    /// Per-point outcome of a partial vector update, reported once the update is completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub vectors_report: Option<UpdateVectorsReport>,
+    /// Number of deleted vectors per vector name, reported once a vector deletion is completed
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_vectors: Option<HashMap<String, usize>>,
+}
+
+impl UpdateResult {
+    /// Append reports of the same update applied to another shard
+    pub fn merge_reports(&mut self, other: UpdateResult) {
+        merge_vectors_reports(&mut self.vectors_report, other.vectors_report);
+        merge_deleted_vectors(&mut self.deleted_vectors, other.deleted_vectors);
+    }
 }
 
 /// Scroll request - paginate over all points which matches given condition
@@ -178,6 +195,7 @@ pub struct ScrollRequest {
     #[validate(range(min = 1))]
     pub limit: Option<usize>,
     /// Look only for points which satisfies this conditions. If not provided - all points.
+    #[validate]
     pub filter: Option<Filter>,
     /// Select which payload to return with the response. Default: All
     pub with_payload: Option<WithPayloadInterface>,
@@ -217,6 +235,7 @@ pub struct SearchRequest {
     /// Look for vectors closest to this
     pub vector: NamedVectorStruct,
     /// Look only for points which satisfies this conditions
+    #[validate]
     pub filter: Option<Filter>,
     /// Additional search params
     pub params: Option<SearchParams>,
@@ -418,6 +437,7 @@ pub struct GroupsResult {
 #[serde(rename_all = "snake_case")]
 pub struct CountRequest {
     /// Look only for points which satisfies this conditions
+    #[validate]
     pub filter: Option<Filter>,
     /// If true, count exact number of points. If false, count approximate number of points faster.
     /// Approximate count might be unreliable during the indexing process. Default: true
@@ -436,6 +456,23 @@ pub struct CountResult {
     pub count: usize,
 }
 
+/// Number of points having each of the named vectors, among the points matching a filter
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct VectorCountsResult {
+    /// Number of points which satisfy the conditions
+    pub points: usize,
+    /// Number of points with a populated vector, per vector name
+    pub by_name: HashMap<String, usize>,
+}
+
+impl VectorCountsResult {
+    /// Number of points with the named vector populated
+    pub fn get(&self, vector_name: &str) -> usize {
+        self.by_name.get(vector_name).copied().unwrap_or(0)
+    }
+}
+
 #[derive(Error, Debug, Clone)]
 #[error("{0}")]
 pub enum CollectionError {
@@ -468,6 +505,9 @@ pub enum CollectionError {
     ForwardProxyError { peer_id: PeerId, error: Box<Self> },
     #[error("Out of memory, free: {free}, {description}")]
     OutOfMemory { description: String, free: u64 },
+    /// Operation refers to a vector name, which is not configured in the collection
+    #[error("Not existing vector name error: {0}")]
+    UnknownVectorName(String),
 }
 
 impl CollectionError {
@@ -529,9 +569,9 @@ impl From<OperationError> for CollectionError {
             OperationError::WrongVector { .. } => Self::BadInput {
                 description: format!("{err}"),
             },
-            OperationError::VectorNameNotExists { .. } => Self::BadInput {
-                description: format!("{err}"),
-            },
+            OperationError::VectorNameNotExists { received_name } => {
+                Self::UnknownVectorName(received_name)
+            }
             OperationError::MissedVectorName { .. } => Self::BadInput {
                 description: format!("{err}"),
             },
@@ -555,6 +595,11 @@ impl From<OperationError> for CollectionError {
             OperationError::OutOfMemory { description, free } => {
                 Self::OutOfMemory { description, free }
             }
+            OperationError::NestedPathNotArray { .. } | OperationError::NestedNullValue { .. } => {
+                Self::BadInput {
+                    description: format!("{err}"),
+                }
+            }
         }
     }
 }
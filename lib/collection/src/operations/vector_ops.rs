@@ -1,23 +1,42 @@
 use std::borrow::Cow;
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeSet, HashMap, HashSet};
+use std::mem::size_of;
+use std::sync::atomic::AtomicBool;
 
 use schemars::JsonSchema;
-use segment::data_types::vectors::VectorStruct;
-use segment::types::{Filter, PointIdType};
-use serde::{Deserialize, Serialize};
+use segment::data_types::vectors::{VectorElementType, VectorStruct, DEFAULT_VECTOR_NAME};
+use segment::entry::entry_point::check_process_stopped;
+use segment::types::{
+    Condition, ExtendedPointId, FieldCondition, Filter, FloatPayloadType, PayloadKeyType,
+    PointIdType, Range,
+};
+use serde::{Deserialize, Deserializer, Serialize};
 use validator::{Validate, ValidationError};
 
 use super::point_ops::PointIdsList;
-use super::{point_to_shard, split_iter_by_shard, OperationToShard, SplitByShard};
+use super::types::{CollectionError, CollectionResult};
+use super::{
+    count_iter_by_shard, split_iter_by_shard, split_iter_by_shard_cancellable, OperationToShard,
+    SplitByShard,
+};
 use crate::hash_ring::HashRing;
 use crate::shards::shard::ShardId;
 
+/// Prefix of vector names reserved for internal use
+///
+/// Vectors with such names are managed by the service itself and can not be deleted by clients.
+pub const RESERVED_VECTOR_NAME_PREFIX: &str = "_";
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 pub struct UpdateVectors {
     /// Points with named vectors
     #[validate]
     #[validate(length(min = 1, message = "must specify points to update"))]
     pub points: Vec<PointVectors>,
+    /// If true - points which can't be updated, e.g. missing ones, are skipped and the rest of
+    /// them are updated. Otherwise the update stops at the first failure
+    #[serde(default)]
+    pub partial: bool,
 }
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
 pub struct PointVectors {
@@ -32,27 +51,286 @@ pub struct PointVectors {
     pub vector: VectorStruct,
 }
 
+/// Per-point outcome of a partial vector update, applied past individual failures
+#[derive(Debug, Default, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct UpdateVectorsReport {
+    /// Points with updated vectors, in the order of the update
+    pub succeeded: Vec<PointIdType>,
+    /// Points which could not be updated, with the reason of failure
+    pub failed: Vec<FailedPointVectors>,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct FailedPointVectors {
+    /// Point id
+    pub id: PointIdType,
+    /// Reason of the failure
+    pub error: String,
+}
+
+impl UpdateVectorsReport {
+    /// All points of the update are updated
+    pub fn is_complete(&self) -> bool {
+        self.failed.is_empty()
+    }
+
+    /// Append outcome of another part of the update, e.g. of another shard
+    pub fn merge(&mut self, other: UpdateVectorsReport) {
+        self.succeeded.extend(other.succeeded);
+        self.failed.extend(other.failed);
+    }
+}
+
+/// Append report of another part of the update, if it has one
+pub(crate) fn merge_vectors_reports(
+    report: &mut Option<UpdateVectorsReport>,
+    other: Option<UpdateVectorsReport>,
+) {
+    if let Some(other) = other {
+        match report {
+            Some(report) => report.merge(other),
+            None => *report = Some(other),
+        }
+    }
+}
+
+/// Sum up numbers of deleted vectors per name with another part of the update, if it has them
+pub(crate) fn merge_deleted_vectors(
+    deleted: &mut Option<HashMap<String, usize>>,
+    other: Option<HashMap<String, usize>>,
+) {
+    if let Some(other) = other {
+        let deleted = deleted.get_or_insert_with(HashMap::new);
+        for (vector_name, count) in other {
+            *deleted.entry(vector_name).or_insert(0) += count;
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Validate)]
 pub struct DeleteVectors {
     /// Deletes values from each point in this list
     pub points: Option<Vec<PointIdType>>,
     /// Deletes values from points that satisfy this filter condition
     pub filter: Option<Filter>,
-    /// Vector names
+    /// Vector names, names starting with `_` are reserved and can not be deleted
+    ///
+    /// Kept sorted, so names are always listed and applied in the same order.
     #[serde(alias = "vectors")]
     #[validate(length(min = 1, message = "must specify vector names to delete"))]
-    pub vector: HashSet<String>,
+    #[validate(custom = "validate_vector_names_not_reserved")]
+    pub vector: BTreeSet<String>,
+    /// Abort the deletion if the filter matches more than this number of points.
+    /// Protects from accidental mass deletion, no limit by default.
+    /// Points are counted before the deletion, so points matching the filter by concurrent
+    /// updates may be deleted in excess of the limit
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_points: Option<usize>,
+}
+
+impl DeleteVectors {
+    /// Delete vectors of points, which have `timestamp_key` payload value older than `timestamp`
+    ///
+    /// Timestamps are compared as numbers, e.g. seconds since the Unix epoch.
+    /// Points without a numeric value in `timestamp_key` are not affected.
+    pub fn older_than(
+        timestamp_key: impl Into<PayloadKeyType>,
+        timestamp: FloatPayloadType,
+        vector_names: impl IntoIterator<Item = impl Into<String>>,
+    ) -> Self {
+        let older = Range {
+            lt: Some(timestamp),
+            gt: None,
+            gte: None,
+            lte: None,
+        };
+        Self {
+            points: None,
+            filter: Some(Filter::new_must(Condition::Field(
+                FieldCondition::new_range(timestamp_key, older),
+            ))),
+            vector: vector_names.into_iter().map(Into::into).collect(),
+            max_points: None,
+        }
+    }
+}
+
+/// Reprocess stored vectors according to the current distance metric of the vector
+///
+/// Required after the distance metric was changed, e.g. from `Dot` to `Cosine`,
+/// as vectors stored before the change were not normalized.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct ReindexVectors {
+    /// Name of the vector to reindex
+    pub name: String,
+}
+
+/// Concatenate vectors of each point into another named vector
+///
+/// Source vectors are joined in the given order, so the dimension of the target vector has
+/// to be the sum of the source dimensions. Points missing any of the sources are skipped.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct ConcatVectors {
+    /// Names of the vectors to concatenate, in order
+    pub sources: Vec<String>,
+    /// Name of the vector to store the concatenation into
+    pub target: String,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone)]
+/// How to repair stored vectors, which dimension differs from the expected one
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DimensionRepairPolicy {
+    /// Truncate longer vectors and pad shorter ones with zeros
+    Resize,
+    /// Delete the vector of the point
+    Clear,
+}
+
+/// Repair vectors with the given name, which dimension differs from the one of the collection
+///
+/// Such vectors may remain in segments created before the vector parameters were changed.
+/// The expected dimension is the one of the appendable segments.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Validate, Clone)]
+pub struct RepairVectors {
+    /// Name of the vector to repair
+    pub name: String,
+    /// What to do with vectors of a wrong dimension
+    pub policy: DimensionRepairPolicy,
+}
+
+/// Set a vector for points matching the filter, which don't have a vector with the given name
+///
+/// Points already having the vector are not changed. Used to backfill a vector added to the
+/// collection after the points were inserted.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
+pub struct SetDefaultVectorByFilter {
+    /// Points to set the vector for
+    pub filter: Filter,
+    /// Name of the vector to set
+    pub name: String,
+    /// Vector to set, only the vector with `name` is used if several named vectors are given
+    pub default: VectorStruct,
+}
+
+impl SetDefaultVectorByFilter {
+    /// Vector to set for the points without one, `None` if it is not specified
+    pub fn default_vector(&self) -> Option<&[VectorElementType]> {
+        match &self.default {
+            VectorStruct::Single(vector) => Some(vector),
+            VectorStruct::Multi(vectors) => vectors.get(&self.name).map(Vec::as_slice),
+        }
+    }
+}
+
+impl Validate for SetDefaultVectorByFilter {
+    fn validate(&self) -> Result<(), validator::ValidationErrors> {
+        let mut errors = validator::ValidationErrors::new();
+        match self.default_vector() {
+            None => {
+                let mut err = ValidationError::new("missing_vector");
+                err.add_param(Cow::from("name"), &self.name);
+                err.message.replace(Cow::from(format!(
+                    "default vector `{}` is not specified",
+                    self.name,
+                )));
+                errors.add("default", err);
+            }
+            Some(vector) if vector.is_empty() => {
+                let mut err = ValidationError::new("length");
+                err.add_param(Cow::from("min"), &1);
+                err.message
+                    .replace(Cow::from("default vector can not be empty"));
+                errors.add("default", err);
+            }
+            Some(_) => {}
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Validate for ConcatVectors {
+    fn validate(&self) -> Result<(), validator::ValidationErrors> {
+        let mut errors = validator::ValidationErrors::new();
+        if self.sources.is_empty() {
+            let mut err = ValidationError::new("length");
+            err.add_param(Cow::from("min"), &1);
+            err.message
+                .replace(Cow::from("must specify vectors to concatenate"));
+            errors.add("sources", err);
+        }
+        if self.sources.contains(&self.target) {
+            let mut err = ValidationError::new("target_in_sources");
+            err.add_param(Cow::from("target"), &self.target);
+            err.message.replace(Cow::from(format!(
+                "target vector `{}` can not be one of the sources",
+                self.target,
+            )));
+            errors.add("target", err);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+/// Number of vectors actually removed by a vector delete operation
+///
+/// Requested vectors which were already absent on a point are not counted.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct DeletedVectorsCount {
+    /// Number of points, on which all requested vectors were deleted
+    pub points: usize,
+    /// Number of deleted vectors per vector name
+    pub by_name: HashMap<String, usize>,
+}
+
+impl DeletedVectorsCount {
+    /// Number of deleted vectors with the given name
+    pub fn get(&self, vector_name: &str) -> usize {
+        self.by_name.get(vector_name).copied().unwrap_or(0)
+    }
+
+    /// Total number of deleted vectors across all names
+    pub fn total(&self) -> usize {
+        self.by_name.values().sum()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone)]
 #[serde(rename_all = "snake_case")]
 pub enum VectorOperations {
     /// Update vectors
     UpdateVectors(UpdateVectors),
     /// Delete vectors if exists
+    #[serde(deserialize_with = "deserialize_delete_vectors")]
     DeleteVectors(PointIdsList, Vec<String>),
     /// Delete vectors by given filter criteria
     DeleteVectorsByFilter(Filter, Vec<String>),
+    /// Reprocess all stored vectors with the given name
+    ReindexVectors(ReindexVectors),
+    /// Concatenate stored vectors into another named vector
+    ConcatVectors(ConcatVectors),
+    /// Fix stored vectors with a wrong dimension
+    RepairVectors(RepairVectors),
+    /// Set a vector for points matching the filter, which don't have it
+    SetDefaultVectorByFilter(SetDefaultVectorByFilter),
+    /// Apply several operations in order, as a single operation on each shard
+    ///
+    /// Operations of the batch are written into the WAL of a local shard as one entry, and applied
+    /// under one lock of the shard segments, so other updates and searches don't come in between.
+    /// Applied operations are not rolled back: every operation is checked against the segments
+    /// before any of them is applied, and the batch is rejected as a whole if one would fail.
+    /// Only internal errors, e.g. of the storage, may leave the batch applied partially.
+    /// Remote shards receive the operations one by one, in order, so a concurrent update may
+    /// come in between there.
+    Batch(Vec<VectorOperations>),
 }
 
 impl VectorOperations {
@@ -61,20 +339,247 @@ impl VectorOperations {
             VectorOperations::UpdateVectors(_) => true,
             VectorOperations::DeleteVectors(..) => false,
             VectorOperations::DeleteVectorsByFilter(..) => false,
+            VectorOperations::ReindexVectors(_) => true,
+            VectorOperations::ConcatVectors(_) => true,
+            VectorOperations::RepairVectors(_) => true,
+            VectorOperations::SetDefaultVectorByFilter(_) => true,
+            VectorOperations::Batch(operations) => {
+                operations.iter().any(VectorOperations::is_write_operation)
+            }
+        }
+    }
+
+    /// Default vectors set by the operation, with the names of the vectors
+    pub fn default_vectors(&self) -> Vec<(&str, &[VectorElementType])> {
+        match self {
+            VectorOperations::SetDefaultVectorByFilter(operation) => operation
+                .default_vector()
+                .map(|default| (operation.name.as_str(), default))
+                .into_iter()
+                .collect(),
+            VectorOperations::Batch(operations) => operations
+                .iter()
+                .flat_map(VectorOperations::default_vectors)
+                .collect(),
+            VectorOperations::UpdateVectors(_)
+            | VectorOperations::DeleteVectors(..)
+            | VectorOperations::DeleteVectorsByFilter(..)
+            | VectorOperations::ReindexVectors(_)
+            | VectorOperations::ConcatVectors(_)
+            | VectorOperations::RepairVectors(_) => vec![],
+        }
+    }
+
+    /// Whether the operation is applied to explicitly listed points, and can be split by shard
+    fn selects_points(&self) -> bool {
+        match self {
+            VectorOperations::UpdateVectors(_) | VectorOperations::DeleteVectors(..) => true,
+            VectorOperations::DeleteVectorsByFilter(..)
+            | VectorOperations::ReindexVectors(_)
+            | VectorOperations::ConcatVectors(_)
+            | VectorOperations::RepairVectors(_)
+            | VectorOperations::SetDefaultVectorByFilter(_)
+            | VectorOperations::Batch(_) => false,
+        }
+    }
+
+    /// Count how many points of this operation go to each shard, without splitting it.
+    ///
+    /// Returns `None` for filter-based operations, which are sent to all shards.
+    pub fn count_points_by_shard(
+        &self,
+        ring: &HashRing<ShardId>,
+    ) -> Option<HashMap<ShardId, usize>> {
+        match self {
+            VectorOperations::UpdateVectors(update_vectors) => Some(count_iter_by_shard(
+                &update_vectors.points,
+                |point| point.id,
+                ring,
+            )),
+            VectorOperations::DeleteVectors(ids, _) => {
+                Some(count_iter_by_shard(&ids.points, |id| **id, ring))
+            }
+            VectorOperations::DeleteVectorsByFilter(..)
+            | VectorOperations::ReindexVectors(_)
+            | VectorOperations::ConcatVectors(_)
+            | VectorOperations::RepairVectors(_)
+            | VectorOperations::SetDefaultVectorByFilter(_) => None,
+            VectorOperations::Batch(operations) => {
+                let mut counts = HashMap::new();
+                for operation in operations {
+                    for (shard_id, count) in operation.count_points_by_shard(ring)? {
+                        *counts.entry(shard_id).or_insert(0) += count;
+                    }
+                }
+                Some(counts)
+            }
+        }
+    }
+
+    /// Rough estimate of the size in bytes of this operation once written into the WAL
+    ///
+    /// Accounts for the bulk data only: vector elements, point ids and vector names. Encoding
+    /// overhead is not included, so the actual entry is usually slightly larger.
+    pub fn estimated_wal_size(&self) -> usize {
+        let names_size = |names: &[String]| names.iter().map(String::len).sum::<usize>();
+        match self {
+            VectorOperations::UpdateVectors(update_vectors) => update_vectors
+                .points
+                .iter()
+                .map(|point| {
+                    let vectors_size = match &point.vector {
+                        VectorStruct::Single(vector) => {
+                            vector.len() * size_of::<VectorElementType>()
+                        }
+                        VectorStruct::Multi(vectors) => vectors
+                            .iter()
+                            .map(|(name, vector)| {
+                                name.len() + vector.len() * size_of::<VectorElementType>()
+                            })
+                            .sum(),
+                    };
+                    estimated_point_id_size(&point.id) + vectors_size
+                })
+                .sum(),
+            VectorOperations::DeleteVectors(ids, vector_names) => {
+                ids.points
+                    .iter()
+                    .map(estimated_point_id_size)
+                    .sum::<usize>()
+                    + names_size(vector_names)
+            }
+            VectorOperations::DeleteVectorsByFilter(filter, vector_names) => {
+                serde_cbor::to_vec(filter).map_or(0, |filter| filter.len())
+                    + names_size(vector_names)
+            }
+            VectorOperations::ReindexVectors(ReindexVectors { name })
+            | VectorOperations::RepairVectors(RepairVectors { name, .. }) => name.len(),
+            VectorOperations::ConcatVectors(ConcatVectors { sources, target }) => {
+                names_size(sources) + target.len()
+            }
+            VectorOperations::SetDefaultVectorByFilter(set_default) => {
+                serde_cbor::to_vec(&set_default.filter).map_or(0, |filter| filter.len())
+                    + set_default.name.len()
+                    + set_default
+                        .default_vector()
+                        .map_or(0, |vector| vector.len() * size_of::<VectorElementType>())
+            }
+            VectorOperations::Batch(operations) => operations
+                .iter()
+                .map(VectorOperations::estimated_wal_size)
+                .sum(),
         }
     }
 }
 
+/// Size of the point id in the WAL, UUIDs are stored in the hyphenated text form
+fn estimated_point_id_size(id: &PointIdType) -> usize {
+    match id {
+        ExtendedPointId::NumId(_) => size_of::<u64>(),
+        ExtendedPointId::Uuid(_) => uuid::fmt::Hyphenated::LENGTH,
+    }
+}
+
 impl Validate for VectorOperations {
     fn validate(&self) -> Result<(), validator::ValidationErrors> {
         match self {
             VectorOperations::UpdateVectors(update_vectors) => update_vectors.validate(),
-            VectorOperations::DeleteVectors(..) => Ok(()),
-            VectorOperations::DeleteVectorsByFilter(..) => Ok(()),
+            VectorOperations::DeleteVectors(_, vector_names)
+            | VectorOperations::DeleteVectorsByFilter(_, vector_names) => {
+                validate_vector_names_not_reserved(vector_names).map_err(|error| {
+                    let mut errors = validator::ValidationErrors::new();
+                    errors.add("vector", error);
+                    errors
+                })
+            }
+            VectorOperations::ReindexVectors(_) => Ok(()),
+            VectorOperations::RepairVectors(repair_vectors) => repair_vectors.validate(),
+            VectorOperations::ConcatVectors(concat_vectors) => concat_vectors.validate(),
+            VectorOperations::SetDefaultVectorByFilter(set_default) => set_default.validate(),
+            VectorOperations::Batch(operations) => validate_batch_operations(operations),
         }
     }
 }
 
+impl VectorOperations {
+    /// Validate no point of the operation has more than `max` named vectors
+    ///
+    /// The limit is configured per node, so it can't be checked by the static validation.
+    pub fn validate_named_vectors_count(
+        &self,
+        max: usize,
+    ) -> Result<(), validator::ValidationErrors> {
+        let result = match self {
+            VectorOperations::UpdateVectors(update_vectors) => update_vectors
+                .points
+                .iter()
+                .try_for_each(|point| validate_named_vectors_count(&point.vector, max)),
+            VectorOperations::SetDefaultVectorByFilter(set_default) => {
+                validate_named_vectors_count(&set_default.default, max)
+            }
+            VectorOperations::Batch(operations) => {
+                return operations
+                    .iter()
+                    .try_for_each(|operation| operation.validate_named_vectors_count(max));
+            }
+            VectorOperations::DeleteVectors(..)
+            | VectorOperations::DeleteVectorsByFilter(..)
+            | VectorOperations::ReindexVectors(_)
+            | VectorOperations::ConcatVectors(_)
+            | VectorOperations::RepairVectors(_) => Ok(()),
+        };
+        result.map_err(|error| {
+            let mut errors = validator::ValidationErrors::new();
+            errors.add("vector", error);
+            errors
+        })
+    }
+}
+
+/// Validate the batch itself and each of its operations
+///
+/// Batches can't be nested. Operations on explicitly listed points can't be mixed with
+/// collection-wide ones, as the batch is either split by shard or sent to all shards as a whole.
+/// Same named vector of a point can't be both updated and deleted within the batch.
+fn validate_batch_operations(
+    operations: &[VectorOperations],
+) -> Result<(), validator::ValidationErrors> {
+    let batch_error = |code: &'static str, message: Cow<'static, str>| {
+        let mut err = ValidationError::new(code);
+        err.message.replace(message);
+        let mut errors = validator::ValidationErrors::new();
+        errors.add("batch", err);
+        Err(errors)
+    };
+    if operations.is_empty() {
+        return batch_error("length", "must specify operations to apply".into());
+    }
+    if operations
+        .iter()
+        .any(|operation| matches!(operation, VectorOperations::Batch(_)))
+    {
+        return batch_error(
+            "nested_batch",
+            "batches of vector operations can't be nested".into(),
+        );
+    }
+    let selects_points = operations[0].selects_points();
+    if operations
+        .iter()
+        .any(|operation| operation.selects_points() != selects_points)
+    {
+        return batch_error(
+            "mixed_batch",
+            "operations on listed points can't be mixed with operations on all points".into(),
+        );
+    }
+    operations.iter().try_for_each(Validate::validate)?;
+    match updated_and_deleted_vector(operations) {
+        Some(message) => batch_error("conflicting_batch", message.into()),
+        None => Ok(()),
+    }
+}
+
 impl SplitByShard for Vec<PointVectors> {
     fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
         split_iter_by_shard(self, |point| point.id, ring)
@@ -83,45 +588,1180 @@ impl SplitByShard for Vec<PointVectors> {
 
 impl SplitByShard for VectorOperations {
     fn split_by_shard(self, ring: &HashRing<ShardId>) -> OperationToShard<Self> {
-        match self {
+        let span = tracing::debug_span!(
+            "split_vector_operation",
+            shards = tracing::field::Empty,
+            to_all = tracing::field::Empty,
+        );
+        let _entered = span.enter();
+        // Client updates have no stop signal, so the split is never cancelled
+        let split = self
+            .split_by_shard_cancellable(ring, &AtomicBool::new(false))
+            .expect("split without stop signal is not cancelled");
+        match &split {
+            OperationToShard::ByShard(operations) => span.record("shards", operations.len()),
+            OperationToShard::ToAll(_) => span.record("to_all", true),
+        };
+        split
+    }
+}
+
+impl VectorOperations {
+    /// Same as `split_by_shard`, but splitting of point lists is interrupted as soon as `stopped`
+    /// flag is set, e.g. on shutdown, instead of routing all of the ids first.
+    pub fn split_by_shard_cancellable(
+        self,
+        ring: &HashRing<ShardId>,
+        stopped: &AtomicBool,
+    ) -> CollectionResult<OperationToShard<Self>> {
+        let split = match self {
             VectorOperations::UpdateVectors(update_vectors) => {
-                let shard_points = update_vectors
-                    .points
-                    .into_iter()
-                    .map(|point| {
-                        let shard_id = point_to_shard(point.id, ring);
-                        (shard_id, point)
-                    })
-                    .fold(HashMap::new(), |mut map, (shard_id, points)| {
-                        map.entry(shard_id).or_insert(vec![]).push(points);
-                        map
-                    });
-                let shard_ops = shard_points.into_iter().map(|(shard_id, points)| {
-                    (
-                        shard_id,
-                        VectorOperations::UpdateVectors(UpdateVectors { points }),
-                    )
-                });
-                OperationToShard::by_shard(shard_ops)
+                let partial = update_vectors.partial;
+                split_iter_by_shard_cancellable(
+                    dedup_points_by_id(update_vectors.points, stopped)?,
+                    |point| point.id,
+                    ring,
+                    stopped,
+                )?
+                .map(|points| VectorOperations::UpdateVectors(UpdateVectors { points, partial }))
             }
             VectorOperations::DeleteVectors(ids, vector_names) => {
-                split_iter_by_shard(ids.points, |id| *id, ring)
+                split_iter_by_shard_cancellable(ids.points, |id| *id, ring, stopped)?
                     .map(|ids| VectorOperations::DeleteVectors(ids.into(), vector_names.clone()))
             }
             by_filter @ VectorOperations::DeleteVectorsByFilter(..) => {
                 OperationToShard::to_all(by_filter)
             }
+            reindex @ VectorOperations::ReindexVectors(_) => OperationToShard::to_all(reindex),
+            concat @ VectorOperations::ConcatVectors(_) => OperationToShard::to_all(concat),
+            repair @ VectorOperations::RepairVectors(_) => OperationToShard::to_all(repair),
+            set_default @ VectorOperations::SetDefaultVectorByFilter(_) => {
+                OperationToShard::to_all(set_default)
+            }
+            VectorOperations::Batch(operations) => split_batch_by_shard(operations, ring, stopped)?,
+        };
+        Ok(split)
+    }
+}
+
+/// Split each operation of the batch and group the parts of the same shard into a batch again
+///
+/// Order of the operations is kept within each shard. Batches with collection-wide operations
+/// are sent to all shards as a whole.
+fn split_batch_by_shard(
+    operations: Vec<VectorOperations>,
+    ring: &HashRing<ShardId>,
+    stopped: &AtomicBool,
+) -> CollectionResult<OperationToShard<VectorOperations>> {
+    if !operations.iter().all(VectorOperations::selects_points) {
+        return Ok(OperationToShard::to_all(VectorOperations::Batch(
+            operations,
+        )));
+    }
+    let mut shard_operations: HashMap<ShardId, Vec<VectorOperations>> = HashMap::new();
+    for operation in operations {
+        match operation.split_by_shard_cancellable(ring, stopped)? {
+            OperationToShard::ByShard(by_shard) => {
+                for (shard_id, operation) in by_shard {
+                    shard_operations
+                        .entry(shard_id)
+                        .or_default()
+                        .push(operation);
+                }
+            }
+            OperationToShard::ToAll(_) => unreachable!("operations on points are split by shard"),
+        }
+    }
+    Ok(OperationToShard::by_shard(
+        shard_operations
+            .into_iter()
+            .map(|(shard_id, operations)| (shard_id, VectorOperations::Batch(operations))),
+    ))
+}
+
+/// Keep only the last update of each point, updates stay in the order of their last occurrence
+///
+/// Updates of the same point are routed to the same shard, where the earlier ones would only be
+/// overwritten. Named vectors are not merged, the last update of the point is applied as is.
+/// Returns `Cancelled` error as soon as `stopped` flag is set.
+fn dedup_points_by_id(
+    points: Vec<PointVectors>,
+    stopped: &AtomicBool,
+) -> CollectionResult<Vec<PointVectors>> {
+    let mut seen = HashSet::with_capacity(points.len());
+    let mut deduped = Vec::with_capacity(points.len());
+    for point in points.into_iter().rev() {
+        check_process_stopped(stopped)?;
+        if seen.insert(point.id) {
+            deduped.push(point);
+        }
+    }
+    deduped.reverse();
+    Ok(deduped)
+}
+
+/// Check that operations applied together as one batch don't both update and delete the same
+/// named vector of the same point
+///
+/// The result of such a batch would depend on the order of application, so it is rejected.
+/// Deletions by filter are not checked, as affected points are unknown until applied.
+pub fn validate_vector_operations_batch(operations: &[VectorOperations]) -> CollectionResult<()> {
+    match updated_and_deleted_vector(operations) {
+        Some(message) => Err(CollectionError::bad_input(message)),
+        None => Ok(()),
+    }
+}
+
+/// Description of the first named vector both updated and deleted by `operations`, if any
+fn updated_and_deleted_vector(operations: &[VectorOperations]) -> Option<String> {
+    let mut updated: HashSet<(PointIdType, &str)> = HashSet::new();
+    for operation in operations {
+        if let VectorOperations::UpdateVectors(update_vectors) = operation {
+            for point in &update_vectors.points {
+                match &point.vector {
+                    VectorStruct::Single(_) => {
+                        updated.insert((point.id, DEFAULT_VECTOR_NAME));
+                    }
+                    VectorStruct::Multi(vectors) => {
+                        updated.extend(vectors.keys().map(|name| (point.id, name.as_str())));
+                    }
+                }
+            }
         }
     }
+    for operation in operations {
+        if let VectorOperations::DeleteVectors(ids, vector_names) = operation {
+            for id in &ids.points {
+                if let Some(name) = vector_names
+                    .iter()
+                    .find(|name| updated.contains(&(*id, name.as_str())))
+                {
+                    return Some(format!(
+                        "vector `{name}` of point {id} is both updated and deleted \
+                         in the same batch"
+                    ));
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Accumulates vector operations into a `VectorOperations::Batch`, validating each one as added
+///
+/// Invalid operations are rejected right away instead of when the whole batch is submitted.
+/// A rejected operation is not added, so the builder can still be used afterwards.
+#[derive(Debug, Default)]
+pub struct VectorOperationsBuilder {
+    operations: Vec<VectorOperations>,
+}
+
+impl VectorOperationsBuilder {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an operation to the batch, if it is valid on its own and together with the others
+    pub fn add(&mut self, operation: VectorOperations) -> CollectionResult<&mut Self> {
+        if matches!(operation, VectorOperations::Batch(_)) {
+            return Err(CollectionError::bad_input(
+                "batches of vector operations can't be nested".to_string(),
+            ));
+        }
+        operation.validate()?;
+        if let Some(first) = self.operations.first() {
+            if first.selects_points() != operation.selects_points() {
+                return Err(CollectionError::bad_input(
+                    "operations on listed points can't be mixed with operations on all points"
+                        .to_string(),
+                ));
+            }
+        }
+        self.operations.push(operation);
+        if let Err(error) = validate_vector_operations_batch(&self.operations) {
+            self.operations.pop();
+            return Err(error);
+        }
+        Ok(self)
+    }
+
+    /// Number of operations added so far
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+
+    /// Batch of the added operations, in the order they were added
+    pub fn build(self) -> CollectionResult<VectorOperations> {
+        if self.operations.is_empty() {
+            return Err(CollectionError::bad_input(
+                "must specify operations to apply".to_string(),
+            ));
+        }
+        Ok(VectorOperations::Batch(self.operations))
+    }
+}
+
+/// Accepted shapes of the `delete_vectors` operation
+///
+/// Besides the legacy `[{"points": [..]}, [..names]]` tuple, also accepts the struct form
+/// `{"points": [..], "vector": [..names]}`. Operations stored in the WAL keep being readable
+/// whichever of the two shapes was used to write them.
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum DeleteVectorsShape {
+    Tuple(PointIdsList, Vec<String>),
+    Struct {
+        points: Vec<PointIdType>,
+        #[serde(alias = "vectors")]
+        vector: Vec<String>,
+    },
+}
+
+fn deserialize_delete_vectors<'de, D>(
+    deserializer: D,
+) -> Result<(PointIdsList, Vec<String>), D::Error>
+where
+    D: Deserializer<'de>,
+{
+    Ok(match DeleteVectorsShape::deserialize(deserializer)? {
+        DeleteVectorsShape::Tuple(ids, vector_names) => (ids, vector_names),
+        DeleteVectorsShape::Struct { points, vector } => (points.into(), vector),
+    })
 }
 
 /// Validate the vector struct is not empty.
+///
+/// Named vectors must not be empty either, the first empty name is reported in the `name` param.
 fn validate_vector_struct_not_empty(value: &VectorStruct) -> Result<(), ValidationError> {
-    if !value.is_empty() {
-        return Ok(());
-    }
+    let empty_name = match value {
+        VectorStruct::Single(vector) if vector.is_empty() => None,
+        VectorStruct::Multi(vectors) if vectors.is_empty() => None,
+        VectorStruct::Single(_) => return Ok(()),
+        VectorStruct::Multi(vectors) => match vectors
+            .iter()
+            .filter(|(_, vector)| vector.is_empty())
+            .map(|(name, _)| name)
+            .min()
+        {
+            Some(name) => Some(name),
+            None => return Ok(()),
+        },
+    };
 
     let mut err = ValidationError::new("length");
     err.add_param(Cow::from("min"), &1);
+    if let Some(name) = empty_name {
+        err.add_param(Cow::from("name"), name);
+    }
+    Err(err)
+}
+
+/// Validate the vector struct has at most `max` named vectors.
+pub fn validate_named_vectors_count(
+    value: &VectorStruct,
+    max: usize,
+) -> Result<(), ValidationError> {
+    match value {
+        VectorStruct::Single(_) => validate_named_vectors_number(1, max),
+        VectorStruct::Multi(vectors) => validate_named_vectors_number(vectors.len(), max),
+    }
+}
+
+/// Validate `count` named vectors of a point are within the limit of `max`.
+pub(crate) fn validate_named_vectors_number(
+    count: usize,
+    max: usize,
+) -> Result<(), ValidationError> {
+    if count <= max {
+        return Ok(());
+    }
+    let mut err = ValidationError::new("named_vectors_limit");
+    err.add_param(Cow::from("max"), &max);
+    err.add_param(Cow::from("value"), &count);
+    err.message.replace(Cow::from(format!(
+        "too many named vectors ({count}), at most {max} are allowed per point"
+    )));
     Err(err)
 }
+
+/// Validate none of the vector names starts with the reserved prefix.
+fn validate_vector_names_not_reserved<'a>(
+    vector_names: impl IntoIterator<Item = &'a String>,
+) -> Result<(), ValidationError> {
+    let reserved = match vector_names
+        .into_iter()
+        .find(|name| name.starts_with(RESERVED_VECTOR_NAME_PREFIX))
+    {
+        Some(reserved) => reserved,
+        None => return Ok(()),
+    };
+
+    let mut err = ValidationError::new("reserved_vector_name");
+    err.add_param(Cow::from("name"), reserved);
+    err.message.replace(Cow::from(format!(
+        "vector name `{reserved}` is reserved for internal use, \
+         names starting with `{RESERVED_VECTOR_NAME_PREFIX}` can not be deleted"
+    )));
+    Err(err)
+}
+
+#[cfg(test)]
+mod tests {
+    use schemars::schema_for;
+    use segment::fixtures::tracing_fixtures::SpanCapture;
+    use segment::payload_storage::query_checker::{check_field_condition, check_filter};
+    use segment::types::Payload;
+    use serde_json::{json, Value};
+
+    use super::*;
+    use crate::operations::point_to_shard;
+
+    #[test]
+    fn test_vector_operations_schema() {
+        let schema = serde_json::to_value(schema_for!(VectorOperations)).unwrap();
+        let variants = schema["oneOf"].as_array().unwrap();
+        assert_eq!(variants.len(), 8);
+
+        let variant_schema = |name: &str| -> Value {
+            let variant = variants
+                .iter()
+                .find(|variant| variant["required"] == json!([name]))
+                .unwrap_or_else(|| panic!("schema of `{name}` variant is missing"));
+            assert_eq!(variant["additionalProperties"], json!(false));
+            variant["properties"][name].clone()
+        };
+
+        assert_eq!(
+            variant_schema("update_vectors"),
+            json!({ "$ref": "#/definitions/UpdateVectors" }),
+        );
+        assert_eq!(
+            variant_schema("reindex_vectors"),
+            json!({ "$ref": "#/definitions/ReindexVectors" }),
+        );
+        assert_eq!(
+            variant_schema("concat_vectors"),
+            json!({ "$ref": "#/definitions/ConcatVectors" }),
+        );
+        assert_eq!(
+            variant_schema("repair_vectors"),
+            json!({ "$ref": "#/definitions/RepairVectors" }),
+        );
+        assert_eq!(
+            variant_schema("set_default_vector_by_filter"),
+            json!({ "$ref": "#/definitions/SetDefaultVectorByFilter" }),
+        );
+        assert_eq!(
+            variant_schema("batch"),
+            json!({ "type": "array", "items": { "$ref": "#/definitions/VectorOperations" } }),
+        );
+
+        // Tuple variants are represented as arrays of fixed length
+        for (name, first_item) in [
+            ("delete_vectors", "#/definitions/PointIdsList"),
+            ("delete_vectors_by_filter", "#/definitions/Filter"),
+        ] {
+            assert_eq!(
+                variant_schema(name),
+                json!({
+                    "type": "array",
+                    "items": [
+                        { "$ref": first_item },
+                        { "type": "array", "items": { "type": "string" } },
+                    ],
+                    "maxItems": 2,
+                    "minItems": 2,
+                }),
+            );
+        }
+
+        // Schema agrees with the actual serialization
+        let operation = VectorOperations::DeleteVectors(
+            PointIdsList {
+                points: vec![1.into()],
+            },
+            vec!["image".to_string()],
+        );
+        let serialized = serde_json::to_value(operation).unwrap();
+        assert_eq!(serialized["delete_vectors"].as_array().unwrap().len(), 2);
+    }
+
+    #[test]
+    fn test_deserialize_legacy_delete_vectors() {
+        let legacy = json!({
+            "delete_vectors": [{ "points": [1, 2] }, ["image", "text"]]
+        });
+        let structured = json!({
+            "delete_vectors": { "points": [1, 2], "vectors": ["image", "text"] }
+        });
+
+        for value in [legacy, structured] {
+            let operation: VectorOperations = serde_json::from_value(value).unwrap();
+            match operation {
+                VectorOperations::DeleteVectors(ids, vector_names) => {
+                    assert_eq!(ids.points, vec![PointIdType::from(1), PointIdType::from(2)]);
+                    assert_eq!(vector_names, vec!["image".to_string(), "text".to_string()]);
+                }
+                operation => panic!("unexpected operation: {operation:?}"),
+            }
+        }
+
+        // Legacy shape is what is written to the WAL, it must survive a round trip
+        let operation = VectorOperations::DeleteVectors(
+            PointIdsList {
+                points: vec![3.into()],
+            },
+            vec!["image".to_string()],
+        );
+        let raw_cbor = serde_cbor::to_vec(&operation).unwrap();
+        let restored: VectorOperations = serde_cbor::from_slice(&raw_cbor).unwrap();
+        match restored {
+            VectorOperations::DeleteVectors(ids, vector_names) => {
+                assert_eq!(ids.points, vec![PointIdType::from(3)]);
+                assert_eq!(vector_names, vec!["image".to_string()]);
+            }
+            operation => panic!("unexpected operation: {operation:?}"),
+        }
+    }
+
+    #[test]
+    fn test_delete_reserved_vector_names() {
+        let reserved = vec!["image".to_string(), "_internal".to_string()];
+
+        let operations = [
+            VectorOperations::DeleteVectors(
+                PointIdsList {
+                    points: vec![1.into()],
+                },
+                reserved.clone(),
+            ),
+            VectorOperations::DeleteVectorsByFilter(Filter::default(), reserved.clone()),
+        ];
+        for operation in operations {
+            let errors = operation.validate().unwrap_err();
+            let message = errors.field_errors()["vector"][0].message.clone().unwrap();
+            assert!(
+                message.contains("_internal"),
+                "unexpected message: {message}"
+            );
+        }
+
+        let request = DeleteVectors {
+            points: Some(vec![1.into()]),
+            filter: None,
+            vector: reserved.into_iter().collect(),
+            max_points: None,
+        };
+        assert!(request.validate().is_err());
+
+        let allowed = VectorOperations::DeleteVectorsByFilter(
+            Filter::default(),
+            vec!["image".to_string(), "text_".to_string()],
+        );
+        assert!(allowed.validate().is_ok());
+    }
+
+    #[test]
+    fn test_delete_vectors_names_order() {
+        let request: DeleteVectors = serde_json::from_value(json!({
+            "points": [1],
+            "vector": ["text", "image", "audio", "image"],
+        }))
+        .unwrap();
+        assert!(request.validate().is_ok());
+
+        let sorted = vec!["audio".to_string(), "image".to_string(), "text".to_string()];
+        let serialized = serde_json::to_value(&request).unwrap();
+        assert_eq!(serialized["vector"], json!(sorted));
+
+        // Same order is used when the request is turned into an operation, and after replay
+        let applied: Vec<_> = request.vector.into_iter().collect();
+        assert_eq!(applied, sorted);
+        let operation = VectorOperations::DeleteVectors(
+            PointIdsList {
+                points: vec![1.into()],
+            },
+            applied,
+        );
+        let raw_cbor = serde_cbor::to_vec(&operation).unwrap();
+        assert_eq!(raw_cbor, serde_cbor::to_vec(&operation).unwrap());
+        match serde_cbor::from_slice(&raw_cbor).unwrap() {
+            VectorOperations::DeleteVectors(_, vector_names) => assert_eq!(vector_names, sorted),
+            operation => panic!("unexpected operation: {operation:?}"),
+        }
+
+        let empty: DeleteVectors =
+            serde_json::from_value(json!({ "points": [1], "vector": [] })).unwrap();
+        assert!(empty.validate().is_err());
+    }
+
+    #[test]
+    fn test_concat_vectors_validation() {
+        let concat = |sources: &[&str], target: &str| ConcatVectors {
+            sources: sources.iter().map(|name| name.to_string()).collect(),
+            target: target.to_string(),
+        };
+
+        assert!(concat(&["image", "text"], "combined").validate().is_ok());
+
+        let errors = concat(&[], "combined").validate().unwrap_err();
+        assert!(errors.field_errors().contains_key("sources"));
+
+        let errors = concat(&["image", "combined"], "combined")
+            .validate()
+            .unwrap_err();
+        assert!(errors.field_errors().contains_key("target"));
+    }
+
+    #[test]
+    fn test_split_by_shard_tracing_span() {
+        let mut ring = HashRing::fair(100);
+        ring.add(1);
+        ring.add(2);
+
+        let operations = [
+            VectorOperations::DeleteVectors(
+                PointIdsList {
+                    points: (0..100_u64).map(PointIdType::from).collect(),
+                },
+                vec!["image".to_string()],
+            ),
+            VectorOperations::DeleteVectorsByFilter(Filter::default(), vec!["image".to_string()]),
+        ];
+
+        let capture = SpanCapture::default();
+        tracing::subscriber::with_default(capture.clone(), || {
+            for operation in operations {
+                operation.split_by_shard(&ring);
+            }
+        });
+
+        let spans = capture.spans("split_vector_operation");
+        assert_eq!(spans.len(), 2);
+        assert_eq!(spans[0].fields["shards"], "2");
+        assert!(!spans[0].fields.contains_key("to_all"));
+        assert_eq!(spans[1].fields["to_all"], "true");
+        assert!(!spans[1].fields.contains_key("shards"));
+    }
+
+    #[test]
+    fn test_split_by_shard_cancellable() {
+        let mut ring = HashRing::fair(100);
+        ring.add(1);
+        ring.add(2);
+
+        let delete_vectors = || {
+            VectorOperations::DeleteVectors(
+                PointIdsList {
+                    points: (0..100_u64).map(PointIdType::from).collect(),
+                },
+                vec!["image".to_string()],
+            )
+        };
+
+        let stopped = AtomicBool::new(false);
+        match delete_vectors().split_by_shard_cancellable(&ring, &stopped) {
+            Ok(OperationToShard::ByShard(by_shard)) => assert_eq!(by_shard.len(), 2),
+            Ok(OperationToShard::ToAll(_)) => panic!("ids must be split by shard"),
+            Err(err) => panic!("unexpected error: {err}"),
+        }
+
+        stopped.store(true, std::sync::atomic::Ordering::Relaxed);
+        let result = delete_vectors().split_by_shard_cancellable(&ring, &stopped);
+        assert!(matches!(result, Err(CollectionError::Cancelled { .. })));
+        // Operations of a batch are split with the same flag
+        let batch = VectorOperations::Batch(vec![delete_vectors()]);
+        let result = batch.split_by_shard_cancellable(&ring, &stopped);
+        assert!(matches!(result, Err(CollectionError::Cancelled { .. })));
+
+        // Operations without point lists are not affected
+        let by_filter =
+            VectorOperations::DeleteVectorsByFilter(Filter::default(), vec!["image".to_string()]);
+        assert!(matches!(
+            by_filter.split_by_shard_cancellable(&ring, &stopped),
+            Ok(OperationToShard::ToAll(_))
+        ));
+    }
+
+    #[test]
+    fn test_split_update_vectors_dedup() {
+        let mut ring = HashRing::fair(100);
+        ring.add(1);
+        ring.add(2);
+
+        let point = |id: u64, value: VectorElementType| PointVectors {
+            id: id.into(),
+            vector: vec![value].into(),
+        };
+        let update_vectors = || {
+            VectorOperations::UpdateVectors(UpdateVectors {
+                points: vec![
+                    point(1, 1.0),
+                    point(2, 2.0),
+                    point(1, 3.0),
+                    point(3, 4.0),
+                    point(2, 5.0),
+                    point(1, 6.0),
+                ],
+                partial: false,
+            })
+        };
+        let expected: HashMap<PointIdType, VectorStruct> = HashMap::from([
+            (1.into(), vec![6.0].into()),
+            (2.into(), vec![5.0].into()),
+            (3.into(), vec![4.0].into()),
+        ]);
+
+        let stopped = AtomicBool::new(false);
+        let router = ring.router();
+        for split in [
+            update_vectors().split_by_shard(&ring),
+            update_vectors()
+                .split_by_shard_cancellable(&ring, &stopped)
+                .unwrap(),
+        ] {
+            let by_shard = match split {
+                OperationToShard::ByShard(by_shard) => by_shard,
+                OperationToShard::ToAll(_) => panic!("points must be split by shard"),
+            };
+            let mut retained = HashMap::new();
+            for (shard_id, operation) in by_shard {
+                let points = match operation {
+                    VectorOperations::UpdateVectors(update_vectors) => update_vectors.points,
+                    operation => panic!("unexpected operation: {operation:?}"),
+                };
+                for point in points {
+                    assert_eq!(point_to_shard(point.id, &router), shard_id);
+                    // Every point is updated only once
+                    assert!(retained.insert(point.id, point.vector).is_none());
+                }
+            }
+            assert_eq!(retained, expected);
+        }
+
+        // Relative order of the last updates is preserved
+        let points = || match update_vectors() {
+            VectorOperations::UpdateVectors(update_vectors) => update_vectors.points,
+            _ => unreachable!(),
+        };
+        let ids: Vec<_> = dedup_points_by_id(points(), &stopped)
+            .unwrap()
+            .into_iter()
+            .map(|point| point.id)
+            .collect();
+        let expected_ids: Vec<PointIdType> = vec![3.into(), 2.into(), 1.into()];
+        assert_eq!(ids, expected_ids);
+
+        stopped.store(true, std::sync::atomic::Ordering::Relaxed);
+        assert!(matches!(
+            dedup_points_by_id(points(), &stopped),
+            Err(CollectionError::Cancelled { .. })
+        ));
+    }
+
+    #[test]
+    fn test_delete_vectors_older_than() {
+        let operation = DeleteVectors::older_than("created_at", 1_000.0, ["thumbnail"]);
+        assert!(operation.validate().is_ok());
+        assert_eq!(operation.points, None);
+        assert_eq!(operation.vector, BTreeSet::from(["thumbnail".to_string()]),);
+
+        let filter = operation.filter.unwrap();
+        let matches = |payload: Value| -> bool {
+            let payload = Payload::from(payload);
+            let checker = |condition: &Condition| match condition {
+                Condition::Field(field_condition) => {
+                    check_field_condition(field_condition, &payload)
+                }
+                condition => panic!("unexpected condition: {condition:?}"),
+            };
+            check_filter(&checker, &filter)
+        };
+
+        assert!(matches(json!({ "created_at": 999 })));
+        assert!(matches(json!({ "created_at": 0.5 })));
+        // Created exactly at the timestamp is not older
+        assert!(!matches(json!({ "created_at": 1_000 })));
+        assert!(!matches(json!({ "created_at": 1_001 })));
+        // Points without timestamp are kept
+        assert!(!matches(json!({ "created_at": "yesterday" })));
+        assert!(!matches(json!({})));
+    }
+
+    #[test]
+    fn test_validate_vector_operations_batch() {
+        let update = |id: u64, names: &[&str]| {
+            VectorOperations::UpdateVectors(UpdateVectors {
+                points: vec![PointVectors {
+                    id: id.into(),
+                    vector: VectorStruct::Multi(
+                        names
+                            .iter()
+                            .map(|name| (name.to_string(), vec![1.0, 2.0]))
+                            .collect(),
+                    ),
+                }],
+                partial: false,
+            })
+        };
+        let delete = |ids: &[u64], names: &[&str]| {
+            VectorOperations::DeleteVectors(
+                PointIdsList {
+                    points: ids.iter().copied().map(PointIdType::from).collect(),
+                },
+                names.iter().map(|name| name.to_string()).collect(),
+            )
+        };
+
+        // Different names of the same point, or the same name of different points
+        let batch = vec![
+            update(1, &["image"]),
+            delete(&[1], &["text"]),
+            delete(&[2, 3], &["image"]),
+        ];
+        assert!(validate_vector_operations_batch(&batch).is_ok());
+
+        // Deletion of an updated vector, regardless of the order in the batch
+        let batch = vec![
+            delete(&[2, 3], &["text", "image"]),
+            update(3, &["audio", "image"]),
+        ];
+        let error = validate_vector_operations_batch(&batch).unwrap_err();
+        assert!(matches!(error, CollectionError::BadInput { .. }));
+        assert!(error.to_string().contains("vector `image` of point 3"));
+
+        // Unnamed vector conflicts with the deletion of the default name
+        let unnamed = VectorOperations::UpdateVectors(UpdateVectors {
+            points: vec![PointVectors {
+                id: 1.into(),
+                vector: VectorStruct::Single(vec![1.0]),
+            }],
+            partial: false,
+        });
+        let batch = vec![unnamed, delete(&[1], &[DEFAULT_VECTOR_NAME])];
+        assert!(validate_vector_operations_batch(&batch).is_err());
+    }
+
+    #[test]
+    fn test_estimated_wal_size() {
+        let assert_close = |operation: VectorOperations, expected: usize| {
+            let estimated = operation.estimated_wal_size();
+            assert_eq!(estimated, expected);
+            // Within 25% of the actually written entry
+            let actual = serde_cbor::to_vec(&operation).unwrap().len();
+            assert!(
+                estimated.abs_diff(actual) <= actual / 4,
+                "estimated {estimated}, actual {actual}",
+            );
+        };
+
+        let update_vectors = VectorOperations::UpdateVectors(UpdateVectors {
+            points: (0..10_u64)
+                .map(|id| PointVectors {
+                    id: id.into(),
+                    vector: VectorStruct::Multi(HashMap::from([(
+                        "image".to_string(),
+                        vec![0.1; 256],
+                    )])),
+                })
+                .collect(),
+            partial: false,
+        });
+        assert_close(update_vectors, 10 * (8 + "image".len() + 256 * 4));
+
+        let unnamed = VectorOperations::UpdateVectors(UpdateVectors {
+            points: vec![PointVectors {
+                id: ExtendedPointId::Uuid(uuid::Uuid::new_v4()),
+                vector: VectorStruct::Single(vec![0.1; 1024]),
+            }],
+            partial: false,
+        });
+        assert_close(unnamed, 36 + 1024 * 4);
+
+        let delete_vectors = VectorOperations::DeleteVectors(
+            PointIdsList {
+                points: (0..1000_u64)
+                    .map(|id| PointIdType::from(id << 40))
+                    .collect(),
+            },
+            vec!["image".to_string(), "text".to_string()],
+        );
+        assert_close(delete_vectors, 1000 * 8 + 9);
+
+        let by_filter = VectorOperations::DeleteVectorsByFilter(
+            Filter::new_must(Condition::Field(FieldCondition::new_range(
+                "created_at",
+                Range {
+                    lt: Some(1_000.0),
+                    gt: None,
+                    gte: None,
+                    lte: None,
+                },
+            ))),
+            vec!["image".to_string()],
+        );
+        assert!(by_filter.estimated_wal_size() > "created_at".len() + "image".len());
+
+        let concat = VectorOperations::ConcatVectors(ConcatVectors {
+            sources: vec!["image".to_string(), "text".to_string()],
+            target: "joint".to_string(),
+        });
+        assert_eq!(concat.estimated_wal_size(), 14);
+    }
+
+    #[test]
+    fn test_split_batch_by_shard() {
+        let mut ring = HashRing::fair(100);
+        ring.add(1);
+        ring.add(2);
+
+        let ids: Vec<PointIdType> = (0..100_u64).map(PointIdType::from).collect();
+        let batch = VectorOperations::Batch(vec![
+            VectorOperations::DeleteVectors(ids.clone().into(), vec!["image".to_string()]),
+            VectorOperations::UpdateVectors(UpdateVectors {
+                points: ids
+                    .iter()
+                    .map(|id| PointVectors {
+                        id: *id,
+                        vector: VectorStruct::Multi(HashMap::from([(
+                            "image".to_string(),
+                            vec![1.0, 2.0],
+                        )])),
+                    })
+                    .collect(),
+                partial: false,
+            }),
+        ]);
+        assert!(batch.validate().is_ok());
+        assert!(batch.is_write_operation());
+        let counts = batch.count_points_by_shard(&ring).unwrap();
+        assert_eq!(counts.values().sum::<usize>(), 200);
+
+        let by_shard = match batch.split_by_shard(&ring) {
+            OperationToShard::ByShard(by_shard) => by_shard,
+            OperationToShard::ToAll(_) => panic!("batch of point operations must be split"),
+        };
+        assert_eq!(by_shard.len(), 2);
+
+        let router = ring.router();
+        let mut total = 0;
+        for (shard_id, operation) in by_shard {
+            let operations = match operation {
+                VectorOperations::Batch(operations) => operations,
+                operation => panic!("unexpected operation: {operation:?}"),
+            };
+            // Deletion stays before the update of the same points
+            assert_eq!(operations.len(), 2);
+            let deleted = match &operations[0] {
+                VectorOperations::DeleteVectors(ids, _) => ids.points.clone(),
+                operation => panic!("unexpected operation: {operation:?}"),
+            };
+            let updated: Vec<_> = match &operations[1] {
+                VectorOperations::UpdateVectors(update_vectors) => {
+                    update_vectors.points.iter().map(|point| point.id).collect()
+                }
+                operation => panic!("unexpected operation: {operation:?}"),
+            };
+            assert_eq!(deleted, updated);
+            assert!(deleted
+                .iter()
+                .all(|id| point_to_shard(*id, &router) == shard_id));
+            total += deleted.len();
+        }
+        assert_eq!(total, 100);
+    }
+
+    #[test]
+    fn test_batch_validation() {
+        let mut ring = HashRing::fair(100);
+        ring.add(1);
+        ring.add(2);
+
+        let delete = |name: &str| {
+            VectorOperations::DeleteVectors(
+                vec![PointIdType::from(1)].into(),
+                vec![name.to_string()],
+            )
+        };
+        let by_filter = || {
+            VectorOperations::DeleteVectorsByFilter(Filter::default(), vec!["image".to_string()])
+        };
+
+        let batch = VectorOperations::Batch(vec![delete("image"), delete("text")]);
+        assert!(batch.validate().is_ok());
+
+        for invalid in [
+            VectorOperations::Batch(vec![]),
+            VectorOperations::Batch(vec![VectorOperations::Batch(vec![delete("image")])]),
+            VectorOperations::Batch(vec![delete("image"), by_filter()]),
+        ] {
+            let errors = invalid.validate().unwrap_err();
+            assert!(errors.field_errors().contains_key("batch"));
+        }
+
+        // Conflicting operations are rejected, as the result would depend on their order
+        let update = VectorOperations::UpdateVectors(UpdateVectors {
+            points: vec![PointVectors {
+                id: 1.into(),
+                vector: VectorStruct::Multi(HashMap::from([("image".to_string(), vec![1.0])])),
+            }],
+            partial: false,
+        });
+        let batch = VectorOperations::Batch(vec![update, delete("image")]);
+        let errors = batch.validate().unwrap_err();
+        assert_eq!(errors.field_errors()["batch"][0].code, "conflicting_batch");
+
+        // Inner operations are validated as well
+        let reserved = format!("{RESERVED_VECTOR_NAME_PREFIX}internal");
+        let batch = VectorOperations::Batch(vec![delete("image"), delete(&reserved)]);
+        assert!(batch.validate().is_err());
+
+        // Batch of collection-wide operations is sent to all shards as a whole
+        let batch = VectorOperations::Batch(vec![by_filter(), by_filter()]);
+        assert!(batch.validate().is_ok());
+        assert!(batch.count_points_by_shard(&ring).is_none());
+        match batch.split_by_shard(&ring) {
+            OperationToShard::ToAll(VectorOperations::Batch(operations)) => {
+                assert_eq!(operations.len(), 2)
+            }
+            _ => panic!("batch of collection-wide operations must be sent to all shards"),
+        }
+    }
+
+    #[test]
+    fn test_vector_operations_builder() {
+        let update = |id: u64| {
+            VectorOperations::UpdateVectors(UpdateVectors {
+                points: vec![PointVectors {
+                    id: id.into(),
+                    vector: VectorStruct::Multi(HashMap::from([(
+                        "image".to_string(),
+                        vec![1.0, 2.0],
+                    )])),
+                }],
+                partial: false,
+            })
+        };
+        let delete = |id: u64, name: &str| {
+            VectorOperations::DeleteVectors(
+                vec![PointIdType::from(id)].into(),
+                vec![name.to_string()],
+            )
+        };
+
+        let mut builder = VectorOperationsBuilder::new();
+        assert!(builder
+            .add(update(1))
+            .unwrap()
+            .add(delete(2, "image"))
+            .is_ok());
+        assert_eq!(builder.len(), 2);
+
+        // Invalid operations are rejected immediately and leave the builder unchanged
+        let reserved = format!("{RESERVED_VECTOR_NAME_PREFIX}internal");
+        let by_filter =
+            VectorOperations::DeleteVectorsByFilter(Filter::default(), vec!["image".to_string()]);
+        let empty_update = VectorOperations::UpdateVectors(UpdateVectors {
+            points: vec![],
+            partial: false,
+        });
+        for invalid in [
+            delete(3, &reserved),
+            empty_update,
+            VectorOperations::Batch(vec![delete(3, "image")]),
+            by_filter,
+            delete(1, "image"),
+        ] {
+            let error = builder.add(invalid).unwrap_err();
+            assert!(matches!(error, CollectionError::BadInput { .. }), "{error}");
+            assert_eq!(builder.len(), 2);
+        }
+
+        let batch = builder.build().unwrap();
+        assert!(batch.validate().is_ok());
+        match batch {
+            VectorOperations::Batch(operations) => assert_eq!(operations.len(), 2),
+            _ => panic!("builder must produce a batch"),
+        }
+
+        assert!(VectorOperationsBuilder::new().build().is_err());
+    }
+
+    #[test]
+    fn test_set_default_vector_by_filter() {
+        let mut ring = HashRing::fair(100);
+        ring.add(1);
+        ring.add(2);
+
+        let set_default = |default: VectorStruct| {
+            VectorOperations::SetDefaultVectorByFilter(SetDefaultVectorByFilter {
+                filter: Filter::default(),
+                name: "thumbnail".to_string(),
+                default,
+            })
+        };
+
+        let single = set_default(vec![0.0; 4].into());
+        assert!(single.validate().is_ok());
+        assert!(single.is_write_operation());
+        assert!(single.count_points_by_shard(&ring).is_none());
+        assert!(matches!(
+            single.split_by_shard(&ring),
+            OperationToShard::ToAll(VectorOperations::SetDefaultVectorByFilter(_)),
+        ));
+
+        // Only the vector with the given name is used
+        let named = |name: &str, vector: Vec<VectorElementType>| {
+            set_default(VectorStruct::Multi(HashMap::from([(
+                name.to_string(),
+                vector,
+            )])))
+        };
+        match named("thumbnail", vec![1.0, 2.0]) {
+            VectorOperations::SetDefaultVectorByFilter(operation) => {
+                assert_eq!(operation.default_vector(), Some([1.0, 2.0].as_slice()))
+            }
+            _ => unreachable!(),
+        }
+
+        for invalid in [named("image", vec![1.0]), named("thumbnail", vec![])] {
+            let errors = invalid.validate().unwrap_err();
+            assert!(errors.field_errors().contains_key("default"));
+        }
+    }
+
+    #[test]
+    fn test_empty_vector_struct_validation() {
+        let point = |vector: VectorStruct| PointVectors {
+            id: 1.into(),
+            vector,
+        };
+        let named = |vectors: &[(&str, Vec<VectorElementType>)]| {
+            VectorStruct::Multi(
+                vectors
+                    .iter()
+                    .map(|(name, vector)| (name.to_string(), vector.clone()))
+                    .collect(),
+            )
+        };
+        let vector_error = |vector: VectorStruct| {
+            let errors = point(vector).validate().unwrap_err();
+            let field_errors = errors.field_errors();
+            let error = field_errors["vector"][0].clone();
+            assert_eq!(error.code, "length");
+            assert_eq!(error.params["min"], json!(1));
+            error
+        };
+
+        assert!(point(vec![1.0].into()).validate().is_ok());
+        assert!(point(named(&[("image", vec![1.0])])).validate().is_ok());
+
+        // Without names to report
+        assert!(!vector_error(VectorStruct::Single(vec![]))
+            .params
+            .contains_key("name"));
+        assert!(!vector_error(named(&[])).params.contains_key("name"));
+
+        // Empty named vector is reported by name, even if others are specified
+        let error = vector_error(named(&[("image", vec![1.0]), ("text", vec![])]));
+        assert_eq!(error.params["name"], json!("text"));
+        let error = vector_error(named(&[("text", vec![]), ("audio", vec![])]));
+        assert_eq!(error.params["name"], json!("audio"));
+    }
+
+    #[test]
+    fn test_validate_named_vectors_count() {
+        use segment::data_types::vectors::BatchVectorStruct;
+
+        use crate::operations::point_ops::{
+            Batch, PointInsertOperations, PointOperations, PointStruct,
+        };
+        use crate::operations::CollectionUpdateOperations;
+
+        let named = |count: usize| {
+            VectorStruct::Multi(
+                (0..count)
+                    .map(|i| (format!("vector_{i}"), vec![1.0]))
+                    .collect(),
+            )
+        };
+        let max = 4;
+
+        // At the limit
+        assert!(validate_named_vectors_count(&named(max), max).is_ok());
+        assert!(validate_named_vectors_count(&vec![1.0].into(), max).is_ok());
+
+        // Above the limit
+        let error = validate_named_vectors_count(&named(max + 1), max).unwrap_err();
+        assert_eq!(error.code, "named_vectors_limit");
+        assert_eq!(error.params["max"], json!(max));
+        assert_eq!(error.params["value"], json!(max + 1));
+        assert!(validate_named_vectors_count(&vec![1.0].into(), 0).is_err());
+
+        let upsert = |vector: VectorStruct| {
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(vec![
+                    PointStruct {
+                        id: 1.into(),
+                        vector: named(1),
+                        payload: None,
+                    },
+                    PointStruct {
+                        id: 2.into(),
+                        vector,
+                        payload: None,
+                    },
+                ]),
+            ))
+        };
+        assert!(upsert(named(max)).validate_named_vectors_count(max).is_ok());
+        let errors = upsert(named(max + 1))
+            .validate_named_vectors_count(max)
+            .unwrap_err();
+        assert!(errors.errors().contains_key("vector"));
+
+        let batch = |count: usize| {
+            let vectors = (0..count)
+                .map(|i| (format!("vector_{i}"), vec![vec![1.0]]))
+                .collect();
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsBatch(Batch {
+                    ids: vec![1.into()],
+                    vectors: BatchVectorStruct::Multi(vectors),
+                    payloads: None,
+                }),
+            ))
+        };
+        assert!(batch(max).validate_named_vectors_count(max).is_ok());
+        assert!(batch(max + 1).validate_named_vectors_count(max).is_err());
+
+        let update = |vector: VectorStruct| {
+            CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(
+                UpdateVectors {
+                    points: vec![PointVectors {
+                        id: 1.into(),
+                        vector,
+                    }],
+                    partial: false,
+                },
+            ))
+        };
+        assert!(update(named(max)).validate_named_vectors_count(max).is_ok());
+        assert!(update(named(max + 1))
+            .validate_named_vectors_count(max)
+            .is_err());
+        let nested =
+            VectorOperations::Batch(vec![VectorOperations::UpdateVectors(UpdateVectors {
+                points: vec![PointVectors {
+                    id: 1.into(),
+                    vector: named(max + 1),
+                }],
+                partial: false,
+            })]);
+        assert!(nested.validate_named_vectors_count(max).is_err());
+    }
+}
@@ -1,22 +1,26 @@
 use api::grpc::conversions::payload_to_proto;
 use api::grpc::qdrant::points_selector::PointsSelectorOneOf;
 use api::grpc::qdrant::{
-    ClearPayloadPoints, ClearPayloadPointsInternal, CreateFieldIndexCollection,
-    CreateFieldIndexCollectionInternal, DeleteFieldIndexCollection,
+    ClearPayloadPoints, ClearPayloadPointsInternal, ConcatPointVectors, ConcatVectorsInternal,
+    CreateFieldIndexCollection, CreateFieldIndexCollectionInternal, DeleteFieldIndexCollection,
     DeleteFieldIndexCollectionInternal, DeletePayloadPoints, DeletePayloadPointsInternal,
     DeletePointVectors, DeletePoints, DeletePointsInternal, DeleteVectorsInternal, NamedVectors,
-    PointVectors, PointsIdsList, PointsSelector, SetPayloadPoints, SetPayloadPointsInternal,
-    SyncPoints, SyncPointsInternal, UpdatePointVectors, UpdateVectorsInternal, UpsertPoints,
-    UpsertPointsInternal, VectorsSelector,
+    PointVectors, PointsIdsList, PointsSelector, ReindexPointVectors, ReindexVectorsInternal,
+    RepairPointVectors, RepairVectorsInternal, SetDefaultPointVectors, SetDefaultVectorInternal,
+    SetNestedPayloadPoints, SetNestedPayloadPointsInternal, SetPayloadPoints,
+    SetPayloadPointsInternal, SyncPoints, SyncPointsInternal, UpdatePointVectors,
+    UpdateVectorsInternal, UpsertPoints, UpsertPointsInternal, Vector, VectorsSelector,
 };
 use segment::types::{Filter, PayloadFieldSchema, PayloadSchemaParams, PointIdType, ScoredPoint};
 use tonic::Status;
 
-use crate::operations::conversions::write_ordering_to_proto;
-use crate::operations::payload_ops::{DeletePayload, SetPayload};
+use crate::operations::conversions::{dimension_repair_policy_to_proto, write_ordering_to_proto};
+use crate::operations::payload_ops::{DeletePayload, SetNestedPayload, SetPayload};
 use crate::operations::point_ops::{PointInsertOperations, PointSyncOperation, WriteOrdering};
 use crate::operations::types::CollectionResult;
-use crate::operations::vector_ops::UpdateVectors;
+use crate::operations::vector_ops::{
+    ConcatVectors, RepairVectors, SetDefaultVectorByFilter, UpdateVectors,
+};
 use crate::operations::CreateIndex;
 use crate::shards::shard::ShardId;
 
@@ -138,6 +142,8 @@ pub fn internal_update_vectors(
                 })
                 .collect(),
             ordering: ordering.map(write_ordering_to_proto),
+            partial: Some(update_vectors.partial),
+            target_shard: None,
         }),
     }
 }
@@ -164,6 +170,8 @@ pub fn internal_delete_vectors(
                 names: vector_names,
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            max_points: None,
+            target_shard: None,
         }),
     }
 }
@@ -188,6 +196,90 @@ pub fn internal_delete_vectors_by_filter(
                 names: vector_names,
             }),
             ordering: ordering.map(write_ordering_to_proto),
+            max_points: None,
+            target_shard: None,
+        }),
+    }
+}
+
+pub fn internal_reindex_vectors(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    vector_name: String,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> ReindexVectorsInternal {
+    ReindexVectorsInternal {
+        shard_id,
+        reindex_vectors: Some(ReindexPointVectors {
+            collection_name,
+            wait: Some(wait),
+            vector_name,
+            ordering: ordering.map(write_ordering_to_proto),
+            target_shard: None,
+        }),
+    }
+}
+
+pub fn internal_concat_vectors(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    concat_vectors: ConcatVectors,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> ConcatVectorsInternal {
+    ConcatVectorsInternal {
+        shard_id,
+        concat_vectors: Some(ConcatPointVectors {
+            collection_name,
+            wait: Some(wait),
+            sources: concat_vectors.sources,
+            target: concat_vectors.target,
+            ordering: ordering.map(write_ordering_to_proto),
+            target_shard: None,
+        }),
+    }
+}
+
+pub fn internal_repair_vectors(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    repair_vectors: RepairVectors,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> RepairVectorsInternal {
+    RepairVectorsInternal {
+        shard_id,
+        repair_vectors: Some(RepairPointVectors {
+            collection_name,
+            wait: Some(wait),
+            vector_name: repair_vectors.name,
+            policy: dimension_repair_policy_to_proto(repair_vectors.policy),
+            ordering: ordering.map(write_ordering_to_proto),
+            target_shard: None,
+        }),
+    }
+}
+
+pub fn internal_set_default_vector(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    set_default: SetDefaultVectorByFilter,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> SetDefaultVectorInternal {
+    SetDefaultVectorInternal {
+        shard_id,
+        set_default_vector: Some(SetDefaultPointVectors {
+            collection_name,
+            wait: Some(wait),
+            default_vector: set_default.default_vector().map(|vector| Vector {
+                data: vector.to_vec(),
+            }),
+            filter: Some(set_default.filter.into()),
+            vector_name: set_default.name,
+            ordering: ordering.map(write_ordering_to_proto),
+            target_shard: None,
         }),
     }
 }
@@ -223,6 +315,31 @@ pub fn internal_set_payload(
     }
 }
 
+pub fn internal_set_nested_payload(
+    shard_id: Option<ShardId>,
+    collection_name: String,
+    set_nested_payload: SetNestedPayload,
+    wait: bool,
+    ordering: Option<WriteOrdering>,
+) -> SetNestedPayloadPointsInternal {
+    SetNestedPayloadPointsInternal {
+        shard_id,
+        set_nested_payload_points: Some(SetNestedPayloadPoints {
+            collection_name,
+            wait: Some(wait),
+            payload: payload_to_proto(set_nested_payload.payload),
+            key: set_nested_payload.key,
+            filter: Some(set_nested_payload.filter.into()),
+            points: set_nested_payload
+                .points
+                .into_iter()
+                .map(|id| id.into())
+                .collect(),
+            ordering: ordering.map(write_ordering_to_proto),
+        }),
+    }
+}
+
 pub fn internal_delete_payload(
     shard_id: Option<ShardId>,
     collection_name: String,
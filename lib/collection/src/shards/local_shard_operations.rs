@@ -50,15 +50,19 @@ impl ShardOperation for LocalShard {
         };
 
         if let Some(receiver) = callback_receiver {
-            let _res = receiver.await??;
+            let outcome = receiver.await??;
             Ok(UpdateResult {
                 operation_id,
                 status: UpdateStatus::Completed,
+                vectors_report: outcome.vectors_report,
+                deleted_vectors: outcome.deleted_vectors,
             })
         } else {
             Ok(UpdateResult {
                 operation_id,
                 status: UpdateStatus::Acknowledged,
+                vectors_report: None,
+                deleted_vectors: None,
             })
         }
     }
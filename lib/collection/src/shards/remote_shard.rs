@@ -7,7 +7,7 @@ use api::grpc::qdrant::points_internal_client::PointsInternalClient;
 use api::grpc::qdrant::{
     CollectionOperationResponse, CountPoints, CountPointsInternal, GetCollectionInfoRequest,
     GetCollectionInfoRequestInternal, GetPoints, GetPointsInternal, InitiateShardTransferRequest,
-    ScrollPoints, ScrollPointsInternal, SearchBatchPointsInternal,
+    PointsOperationResponse, ScrollPoints, ScrollPointsInternal, SearchBatchPointsInternal,
 };
 use async_trait::async_trait;
 use parking_lot::Mutex;
@@ -22,7 +22,9 @@ use tonic::transport::{Channel, Uri};
 use tonic::Status;
 
 use super::conversions::{
-    internal_delete_vectors, internal_delete_vectors_by_filter, internal_update_vectors,
+    internal_concat_vectors, internal_delete_vectors, internal_delete_vectors_by_filter,
+    internal_reindex_vectors, internal_repair_vectors, internal_set_default_vector,
+    internal_update_vectors,
 };
 use crate::operations::conversions::try_record_from_grpc;
 use crate::operations::payload_ops::PayloadOps;
@@ -31,14 +33,16 @@ use crate::operations::types::{
     CollectionError, CollectionInfo, CollectionResult, CountRequest, CountResult, PointRequest,
     Record, SearchRequest, SearchRequestBatch, UpdateResult,
 };
-use crate::operations::vector_ops::VectorOperations;
+use crate::operations::vector_ops::{
+    merge_deleted_vectors, merge_vectors_reports, VectorOperations,
+};
 use crate::operations::{CollectionUpdateOperations, FieldIndexOperations};
 use crate::shards::channel_service::ChannelService;
 use crate::shards::conversions::{
     internal_clear_payload, internal_clear_payload_by_filter, internal_create_index,
     internal_delete_index, internal_delete_payload, internal_delete_points,
-    internal_delete_points_by_filter, internal_set_payload, internal_sync_points,
-    internal_upsert_points, try_scored_point_from_grpc,
+    internal_delete_points_by_filter, internal_set_nested_payload, internal_set_payload,
+    internal_sync_points, internal_upsert_points, try_scored_point_from_grpc,
 };
 use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_trait::ShardOperation;
@@ -229,58 +233,49 @@ impl RemoteShard {
                     .into_inner()
                 }
             },
-            CollectionUpdateOperations::VectorOperation(vector_ops) => match vector_ops {
-                VectorOperations::UpdateVectors(update_operation) => {
-                    let request = &internal_update_vectors(
-                        shard_id,
-                        collection_name,
-                        update_operation,
-                        wait,
-                        ordering,
-                    );
-                    self.with_points_client(|mut client| async move {
-                        client
-                            .update_vectors(tonic::Request::new(request.clone()))
-                            .await
-                    })
-                    .await?
-                    .into_inner()
+            CollectionUpdateOperations::VectorOperation(VectorOperations::Batch(operations)) => {
+                // Operations of the batch are forwarded one by one, in order
+                let mut batch_result: Option<UpdateResult> = None;
+                for operation in operations {
+                    let response = self
+                        .execute_vector_operation(
+                            shard_id,
+                            collection_name.clone(),
+                            operation,
+                            wait,
+                            ordering,
+                        )
+                        .await?;
+                    let operation_result = update_result_from_response(response)?;
+                    batch_result = Some(match batch_result {
+                        None => operation_result,
+                        // Reports of all operations are kept in order, status is of the last one
+                        Some(mut batch_result) => {
+                            let UpdateResult {
+                                operation_id,
+                                status,
+                                vectors_report,
+                                deleted_vectors,
+                            } = operation_result;
+                            batch_result.operation_id = operation_id;
+                            batch_result.status = status;
+                            merge_vectors_reports(&mut batch_result.vectors_report, vectors_report);
+                            merge_deleted_vectors(
+                                &mut batch_result.deleted_vectors,
+                                deleted_vectors,
+                            );
+                            batch_result
+                        }
+                    });
                 }
-                VectorOperations::DeleteVectors(ids, vector_names) => {
-                    let request = &internal_delete_vectors(
-                        shard_id,
-                        collection_name,
-                        ids.points,
-                        vector_names.clone(),
-                        wait,
-                        ordering,
-                    );
-                    self.with_points_client(|mut client| async move {
-                        client
-                            .delete_vectors(tonic::Request::new(request.clone()))
-                            .await
-                    })
-                    .await?
-                    .into_inner()
-                }
-                VectorOperations::DeleteVectorsByFilter(filter, vector_names) => {
-                    let request = &internal_delete_vectors_by_filter(
-                        shard_id,
-                        collection_name,
-                        filter,
-                        vector_names.clone(),
-                        wait,
-                        ordering,
-                    );
-                    self.with_points_client(|mut client| async move {
-                        client
-                            .delete_vectors(tonic::Request::new(request.clone()))
-                            .await
-                    })
+                return batch_result.ok_or_else(|| {
+                    CollectionError::bad_request("batch of vector operations is empty".to_string())
+                });
+            }
+            CollectionUpdateOperations::VectorOperation(vector_ops) => {
+                self.execute_vector_operation(shard_id, collection_name, vector_ops, wait, ordering)
                     .await?
-                    .into_inner()
-                }
-            },
+            }
             CollectionUpdateOperations::PayloadOperation(payload_ops) => match payload_ops {
                 PayloadOps::SetPayload(set_payload) => {
                     let request = &internal_set_payload(
@@ -357,6 +352,22 @@ impl RemoteShard {
                     .await?
                     .into_inner()
                 }
+                PayloadOps::SetNestedPayload(set_nested_payload) => {
+                    let request = &internal_set_nested_payload(
+                        shard_id,
+                        collection_name,
+                        set_nested_payload,
+                        wait,
+                        ordering,
+                    );
+                    self.with_points_client(|mut client| async move {
+                        client
+                            .set_nested_payload(tonic::Request::new(request.clone()))
+                            .await
+                    })
+                    .await?
+                    .into_inner()
+                }
             },
             CollectionUpdateOperations::FieldIndexOperation(field_index_op) => match field_index_op
             {
@@ -394,12 +405,150 @@ impl RemoteShard {
                 }
             },
         };
-        match point_operation_response.result {
-            None => Err(CollectionError::service_error(
-                "Malformed UpdateResult type".to_string(),
-            )),
-            Some(update_result) => update_result.try_into().map_err(|e: Status| e.into()),
-        }
+        update_result_from_response(point_operation_response)
+    }
+
+    async fn execute_vector_operation(
+        &self,
+        shard_id: Option<ShardId>,
+        collection_name: String,
+        vector_ops: VectorOperations,
+        wait: bool,
+        ordering: Option<WriteOrdering>,
+    ) -> CollectionResult<PointsOperationResponse> {
+        let response = match vector_ops {
+            VectorOperations::UpdateVectors(update_operation) => {
+                let request = &internal_update_vectors(
+                    shard_id,
+                    collection_name,
+                    update_operation,
+                    wait,
+                    ordering,
+                );
+                self.with_points_client(|mut client| async move {
+                    client
+                        .update_vectors(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
+            VectorOperations::DeleteVectors(ids, vector_names) => {
+                let request = &internal_delete_vectors(
+                    shard_id,
+                    collection_name,
+                    ids.points,
+                    vector_names.clone(),
+                    wait,
+                    ordering,
+                );
+                self.with_points_client(|mut client| async move {
+                    client
+                        .delete_vectors(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
+            VectorOperations::DeleteVectorsByFilter(filter, vector_names) => {
+                let request = &internal_delete_vectors_by_filter(
+                    shard_id,
+                    collection_name,
+                    filter,
+                    vector_names.clone(),
+                    wait,
+                    ordering,
+                );
+                self.with_points_client(|mut client| async move {
+                    client
+                        .delete_vectors(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
+            VectorOperations::ReindexVectors(reindex_vectors) => {
+                let request = &internal_reindex_vectors(
+                    shard_id,
+                    collection_name,
+                    reindex_vectors.name,
+                    wait,
+                    ordering,
+                );
+                self.with_points_client(|mut client| async move {
+                    client
+                        .reindex_vectors(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
+            VectorOperations::ConcatVectors(concat_vectors) => {
+                let request = &internal_concat_vectors(
+                    shard_id,
+                    collection_name,
+                    concat_vectors,
+                    wait,
+                    ordering,
+                );
+                self.with_points_client(|mut client| async move {
+                    client
+                        .concat_vectors(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
+            VectorOperations::RepairVectors(repair_vectors) => {
+                let request = &internal_repair_vectors(
+                    shard_id,
+                    collection_name,
+                    repair_vectors,
+                    wait,
+                    ordering,
+                );
+                self.with_points_client(|mut client| async move {
+                    client
+                        .repair_vectors(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
+            VectorOperations::SetDefaultVectorByFilter(set_default) => {
+                let request = &internal_set_default_vector(
+                    shard_id,
+                    collection_name,
+                    set_default,
+                    wait,
+                    ordering,
+                );
+                self.with_points_client(|mut client| async move {
+                    client
+                        .set_default_vector(tonic::Request::new(request.clone()))
+                        .await
+                })
+                .await?
+                .into_inner()
+            }
+            VectorOperations::Batch(_) => {
+                return Err(CollectionError::bad_request(
+                    "batches of vector operations can't be nested".to_string(),
+                ))
+            }
+        };
+        Ok(response)
+    }
+}
+
+fn update_result_from_response(
+    response: PointsOperationResponse,
+) -> CollectionResult<UpdateResult> {
+    match response.result {
+        None => Err(CollectionError::service_error(
+            "Malformed UpdateResult type".to_string(),
+        )),
+        Some(update_result) => update_result.try_into().map_err(|e: Status| e.into()),
     }
 }
 
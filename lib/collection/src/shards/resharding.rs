@@ -0,0 +1,91 @@
+use std::collections::BTreeMap;
+
+use segment::types::PointIdType;
+
+use crate::hash_ring::HashRing;
+use crate::operations::point_ops::{PointInsertOperations, PointOperations, PointStruct};
+use crate::operations::CollectionUpdateOperations;
+use crate::shards::shard::ShardId;
+
+/// Points moving between shards when one ring is replaced by another
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VectorTransferPlan {
+    /// Ids of the moving points by source and target shard
+    pub moves: BTreeMap<(ShardId, ShardId), Vec<PointIdType>>,
+}
+
+impl VectorTransferPlan {
+    pub fn is_empty(&self) -> bool {
+        self.moves.is_empty()
+    }
+
+    /// Total number of the moving points
+    pub fn points_count(&self) -> usize {
+        self.moves.values().map(Vec::len).sum()
+    }
+
+    /// Add the moves of `other`, e.g. planned for the next batch of points
+    pub fn extend(&mut self, other: VectorTransferPlan) {
+        for (shards, point_ids) in other.moves {
+            self.moves.entry(shards).or_default().extend(point_ids);
+        }
+    }
+
+    /// Shards receiving any of the moving points
+    pub fn target_shards(&self) -> impl Iterator<Item = ShardId> + '_ {
+        let mut targets: Vec<_> = self.moves.keys().map(|(_, to)| *to).collect();
+        targets.sort_unstable();
+        targets.dedup();
+        targets.into_iter()
+    }
+}
+
+/// Plan which of `point_ids` move to another shard if `old_ring` is replaced by `new_ring`
+///
+/// Points are routed the same way as by the rings, points which stay on their shard are not
+/// included. Nothing moves from or to an empty ring, as there is no shard to take it from or to.
+pub fn plan_vector_transfer(
+    old_ring: &HashRing<ShardId>,
+    new_ring: &HashRing<ShardId>,
+    point_ids: impl IntoIterator<Item = PointIdType>,
+) -> VectorTransferPlan {
+    let diff = old_ring.diff(new_ring);
+    let mut plan = VectorTransferPlan::default();
+    if diff.is_empty() {
+        return plan;
+    }
+    for point_id in point_ids {
+        let transition = match diff.transition(&point_id) {
+            Some(transition) => transition,
+            None => continue,
+        };
+        if let (Some(from), Some(to)) = (transition.from, transition.to) {
+            plan.moves.entry((from, to)).or_default().push(point_id);
+        }
+    }
+    plan
+}
+
+/// Per-shard operations moving `points` from shard `from` to shard `to`
+///
+/// Points are inserted into the target shard first, so they are not lost if the removal from
+/// the source shard is never applied.
+pub fn move_operations(
+    from: ShardId,
+    to: ShardId,
+    points: Vec<PointStruct>,
+) -> [(ShardId, CollectionUpdateOperations); 2] {
+    let ids = points.iter().map(|point| point.id).collect();
+    [
+        (
+            to,
+            CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+                PointInsertOperations::PointsList(points),
+            )),
+        ),
+        (
+            from,
+            CollectionUpdateOperations::PointOperation(PointOperations::DeletePoints { ids }),
+        ),
+    ]
+}
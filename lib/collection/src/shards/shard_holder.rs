@@ -10,7 +10,7 @@ use tokio::sync::{RwLock, RwLockReadGuard, RwLockWriteGuard};
 use crate::config::CollectionConfig;
 use crate::hash_ring::HashRing;
 use crate::operations::shared_storage_config::SharedStorageConfig;
-use crate::operations::types::{CollectionResult, ShardTransferInfo};
+use crate::operations::types::{CollectionError, CollectionResult, ShardTransferInfo};
 use crate::operations::{OperationToShard, SplitByShard};
 use crate::save_on_disk::SaveOnDisk;
 use crate::shards::channel_service::ChannelService;
@@ -20,13 +20,16 @@ use crate::shards::shard::{PeerId, ShardId};
 use crate::shards::shard_config::{ShardConfig, ShardType};
 use crate::shards::shard_versioning::latest_shard_paths;
 use crate::shards::transfer::shard_transfer::{ShardTransfer, ShardTransferKey};
-use crate::shards::CollectionId;
+use crate::shards::{CollectionId, HASH_RING_SHARD_SCALE};
 
 const SHARD_TRANSFERS_FILE: &str = "shard_transfers";
+const RING_SHARDS_FILE: &str = "ring_shards";
 
 pub struct ShardHolder {
     shards: HashMap<ShardId, ShardReplicaSet>,
     pub(crate) shard_transfers: SaveOnDisk<HashSet<ShardTransfer>>,
+    /// Shards of the ring set by resharding, `None` if the ring routes to all shards
+    ring_shards: SaveOnDisk<Option<Vec<ShardId>>>,
     ring: HashRing<ShardId>,
 }
 
@@ -35,9 +38,11 @@ pub struct LockedShardHolder(pub RwLock<ShardHolder>);
 impl ShardHolder {
     pub fn new(collection_path: &Path, hashring: HashRing<ShardId>) -> CollectionResult<Self> {
         let shard_transfers = SaveOnDisk::load_or_init(collection_path.join(SHARD_TRANSFERS_FILE))?;
+        let ring_shards = SaveOnDisk::load_or_init(collection_path.join(RING_SHARDS_FILE))?;
         Ok(Self {
             shards: HashMap::new(),
             shard_transfers,
+            ring_shards,
             ring: hashring,
         })
     }
@@ -71,6 +76,51 @@ impl ShardHolder {
         self.shards.insert(shard_id, shard)
     }
 
+    /// Ring routing the points to the shards
+    pub fn ring(&self) -> &HashRing<ShardId> {
+        &self.ring
+    }
+
+    /// Ring routing the points to `shards`
+    pub fn ring_of(shards: &[ShardId]) -> HashRing<ShardId> {
+        let mut ring = HashRing::fair(HASH_RING_SHARD_SCALE);
+        for shard_id in shards {
+            ring.add(*shard_id);
+        }
+        ring
+    }
+
+    /// Shards of the ring set by resharding, `None` if the ring routes to all shards
+    pub fn ring_shards(&self) -> Option<Vec<ShardId>> {
+        self.ring_shards.read().clone()
+    }
+
+    /// Route the points to `shards` only, points already in the shards are not moved
+    ///
+    /// The shards are saved on disk, so the ring survives a restart of the collection.
+    pub fn set_ring_shards(&mut self, shards: Vec<ShardId>) -> CollectionResult<()> {
+        for shard_id in &shards {
+            if !self.contains_shard(shard_id) {
+                return Err(CollectionError::bad_input(format!(
+                    "Shard {shard_id} of the new ring does not exist"
+                )));
+            }
+        }
+        self.ring = Self::ring_of(&shards);
+        self.ring_shards
+            .write(|ring_shards| *ring_shards = Some(shards))?;
+        Ok(())
+    }
+
+    /// Save the shards of the ring into `collection_path`, if they were set by resharding
+    pub fn save_ring_shards_to(&self, collection_path: &Path) -> CollectionResult<()> {
+        if self.ring_shards.read().is_some() {
+            self.ring_shards
+                .save_to(collection_path.join(RING_SHARDS_FILE))?;
+        }
+        Ok(())
+    }
+
     pub fn contains_shard(&self, shard_id: &ShardId) -> bool {
         self.shards.contains_key(shard_id)
     }
@@ -95,7 +145,29 @@ impl ShardHolder {
         &self,
         operation: O,
     ) -> Vec<(&ShardReplicaSet, O)> {
-        let operation_to_shard = operation.split_by_shard(&self.ring);
+        self.route_operation(operation.split_by_shard(&self.ring))
+    }
+
+    /// Same as `split_by_shard`, but if `target_shard` is set, the whole operation goes to it
+    pub fn split_by_shard_with_target<O: SplitByShard + Clone>(
+        &self,
+        operation: O,
+        target_shard: Option<ShardId>,
+    ) -> CollectionResult<Vec<(&ShardReplicaSet, O)>> {
+        if let Some(shard_id) = target_shard {
+            if !self.contains_shard(&shard_id) {
+                return Err(CollectionError::bad_input(format!(
+                    "Target shard {shard_id} does not exist"
+                )));
+            }
+        }
+        Ok(self.route_operation(operation.split_by_shard_with_target(&self.ring, target_shard)))
+    }
+
+    fn route_operation<O: Clone>(
+        &self,
+        operation_to_shard: OperationToShard<O>,
+    ) -> Vec<(&ShardReplicaSet, O)> {
         let shard_ops: Vec<_> = match operation_to_shard {
             OperationToShard::ByShard(by_shard) => by_shard
                 .into_iter()
@@ -109,6 +181,15 @@ impl ShardHolder {
         shard_ops
     }
 
+    /// Split the operation by shard without applying it, to inspect what each shard would receive
+    pub fn preview_split_by_shard<O: SplitByShard + Clone>(
+        &self,
+        operation: O,
+    ) -> Vec<(ShardId, O)> {
+        let shard_ids: Vec<_> = self.shards.keys().copied().collect();
+        operation.split_by_shard(&self.ring).into_vec(&shard_ids)
+    }
+
     pub fn register_start_shard_transfer(&self, transfer: ShardTransfer) -> CollectionResult<bool> {
         Ok(self
             .shard_transfers
@@ -280,6 +361,10 @@ impl ShardHolder {
                 self.add_shard(shard_id, replica_set);
             }
         }
+
+        if let Some(shards) = self.ring_shards() {
+            self.ring = Self::ring_of(&shards);
+        }
     }
 }
 
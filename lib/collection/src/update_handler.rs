@@ -12,7 +12,7 @@ use tokio::sync::{oneshot, Mutex as TokioMutex};
 use tokio::task::JoinHandle;
 use tokio::time::Duration;
 
-use crate::collection_manager::collection_updater::CollectionUpdater;
+use crate::collection_manager::collection_updater::{CollectionUpdater, UpdateOutcome};
 use crate::collection_manager::holders::segment_holder::LockedSegmentHolder;
 use crate::collection_manager::optimizers::segment_optimizer::SegmentOptimizer;
 use crate::common::stoppable_task::{spawn_stoppable, StoppableTaskHandle};
@@ -34,7 +34,7 @@ pub struct OperationData {
     /// If operation was requested to wait for result
     pub wait: bool,
     /// Callback notification channel
-    pub sender: Option<oneshot::Sender<CollectionResult<usize>>>,
+    pub sender: Option<oneshot::Sender<CollectionResult<UpdateOutcome>>>,
 }
 
 /// Signal, used to inform Updater process
@@ -338,8 +338,9 @@ impl UpdateHandler {
                         Ok(())
                     };
 
-                    let operation_result = flush_res
-                        .and_then(|_| CollectionUpdater::update(&segments, op_num, operation));
+                    let operation_result = flush_res.and_then(|_| {
+                        CollectionUpdater::update_with_outcome(&segments, op_num, operation)
+                    });
 
                     let res = match operation_result {
                         Ok(update_res) => optimize_sender
@@ -1,14 +1,23 @@
 use std::collections::HashSet;
+use std::path::Path;
 
+use collection::collection::Collection;
+use collection::hash_ring::HashRing;
 use collection::operations::payload_ops::{PayloadOps, SetPayload};
-use collection::operations::point_ops::{Batch, PointOperations, PointStruct, WriteOrdering};
+use collection::operations::point_ops::{
+    Batch, PointInsertOperations, PointOperations, PointStruct, WriteOrdering,
+};
 use collection::operations::types::{
-    CountRequest, PointRequest, RecommendRequest, ScrollRequest, SearchRequest, UpdateStatus,
+    CollectionError, CountRequest, PointRequest, RecommendRequest, ScrollRequest, SearchRequest,
+    UpdateStatus,
 };
+use collection::operations::vector_ops::{PointVectors, UpdateVectors, VectorOperations};
 use collection::operations::CollectionUpdateOperations;
 use collection::recommendations::recommend_by;
+use collection::shards::resharding::plan_vector_transfer;
+use collection::shards::HASH_RING_SHARD_SCALE;
 use itertools::Itertools;
-use segment::data_types::vectors::VectorStruct;
+use segment::data_types::vectors::{VectorStruct, DEFAULT_VECTOR_NAME};
 use segment::types::{
     Condition, FieldCondition, Filter, HasIdCondition, Payload, PointIdType, WithPayloadInterface,
 };
@@ -151,6 +160,8 @@ async fn test_collection_search_with_payload_and_vector_with_shards(shard_number
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
+            null_policy: None,
+            inner_match: None,
         }))),
         exact: true,
     };
@@ -492,6 +503,296 @@ async fn test_collection_delete_points_by_filter_with_shards(shard_number: u32)
     collection.before_drop().await;
 }
 
+#[tokio::test]
+async fn test_collection_delete_vectors_by_filter_limit() {
+    test_collection_delete_vectors_by_filter_limit_with_shards(1).await;
+    test_collection_delete_vectors_by_filter_limit_with_shards(N_SHARDS).await;
+}
+
+async fn test_collection_delete_vectors_by_filter_limit_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+
+    let mut collection = simple_collection_fixture(collection_dir.path(), shard_number).await;
+
+    let insert_points = CollectionUpdateOperations::PointOperation(
+        Batch {
+            ids: vec![0, 1, 2, 3, 4]
+                .into_iter()
+                .map(|x| x.into())
+                .collect_vec(),
+            vectors: vec![
+                vec![1.0, 0.0, 1.0, 1.0],
+                vec![1.0, 0.0, 1.0, 0.0],
+                vec![1.0, 1.0, 1.0, 1.0],
+                vec![1.0, 1.0, 0.0, 1.0],
+                vec![1.0, 0.0, 0.0, 0.0],
+            ]
+            .into(),
+            payloads: None,
+        }
+        .into(),
+    );
+
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let affected: HashSet<PointIdType> = vec![0.into(), 2.into(), 3.into()].into_iter().collect();
+    let filter = Filter::new_must(Condition::HasId(HasIdCondition::from(affected)));
+
+    // Filter matches more points than allowed, nothing should be deleted
+    let result = collection.check_filter_points_limit(&filter, 2, None).await;
+    match result {
+        Err(CollectionError::BadRequest { description }) => {
+            assert!(description.contains("exceeds the limit"), "{description}")
+        }
+        other => panic!("expected limit error, got {other:?}"),
+    }
+
+    // Limit is inclusive
+    collection
+        .check_filter_points_limit(&filter, 3, None)
+        .await
+        .unwrap();
+    collection
+        .check_filter_points_limit(&filter, 10, None)
+        .await
+        .unwrap();
+
+    let delete_vectors = CollectionUpdateOperations::VectorOperation(
+        VectorOperations::DeleteVectorsByFilter(filter, vec![DEFAULT_VECTOR_NAME.to_string()]),
+    );
+    let delete_result = collection
+        .update_from_client(delete_vectors, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(delete_result.status, UpdateStatus::Completed);
+
+    collection.before_drop().await;
+}
+
+/// Assert that every one of `point_ids` is in its shard of `ring`, with its vector and payload
+async fn assert_points_placed_by_ring(
+    collection: &Collection,
+    ring: &HashRing<u32>,
+    point_ids: &[PointIdType],
+) {
+    let mut retrieved = 0;
+    for shard_id in 0..N_SHARDS {
+        let request = PointRequest {
+            ids: point_ids.to_vec(),
+            with_payload: Some(WithPayloadInterface::Bool(true)),
+            with_vector: true.into(),
+        };
+        let records = collection
+            .retrieve(request, None, Some(shard_id))
+            .await
+            .unwrap();
+        for record in &records {
+            assert_eq!(ring.get(&record.id), Some(&shard_id));
+            let n = record.payload.as_ref().unwrap().0["n"].as_u64().unwrap();
+            assert_eq!(PointIdType::from(n), record.id);
+            let vector = record.vector.clone().unwrap();
+            assert_eq!(vector, VectorStruct::from(vec![n as f32, 1.0, 0.0, 0.0]));
+        }
+        retrieved += records.len();
+    }
+    assert_eq!(retrieved, point_ids.len());
+}
+
+fn upsert_numbered_points(point_ids: &[PointIdType]) -> CollectionUpdateOperations {
+    let points = point_ids
+        .iter()
+        .map(|point_id| {
+            let n = match point_id {
+                PointIdType::NumId(n) => *n,
+                PointIdType::Uuid(_) => unreachable!("points are numbered"),
+            };
+            PointStruct {
+                id: *point_id,
+                vector: vec![n as f32, 1.0, 0.0, 0.0].into(),
+                payload: Some(serde_json::json!({ "n": n }).into()),
+            }
+        })
+        .collect();
+    CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ))
+}
+
+#[tokio::test]
+async fn test_update_vectors_in_target_shard() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let mut collection = simple_collection_fixture(collection_dir.path(), N_SHARDS).await;
+
+    let point_ids: Vec<PointIdType> = (0..100u64).map(PointIdType::from).collect();
+    collection
+        .update_from_client(
+            upsert_numbered_points(&point_ids),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+
+    let new_vector = || VectorStruct::from(vec![0.0, 0.0, 0.0, 1.0]);
+    let update_vectors = || {
+        CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(
+            UpdateVectors {
+                points: point_ids
+                    .iter()
+                    .map(|point_id| PointVectors {
+                        id: *point_id,
+                        vector: new_vector(),
+                    })
+                    .collect(),
+                partial: true,
+            },
+        ))
+    };
+
+    // Unknown shard is rejected
+    let result = collection
+        .update_from_client_with_target(
+            update_vectors(),
+            Some(N_SHARDS),
+            true,
+            WriteOrdering::default(),
+        )
+        .await;
+    assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+
+    // Whole operation goes to the target shard, points of other shards are not found there
+    let target_shard = 1;
+    let result = collection
+        .update_from_client_with_target(
+            update_vectors(),
+            Some(target_shard),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    let report = result.vectors_report.unwrap();
+    assert!(!report.succeeded.is_empty());
+    assert_eq!(
+        report.succeeded.len() + report.failed.len(),
+        point_ids.len()
+    );
+
+    for shard_id in 0..N_SHARDS {
+        let request = PointRequest {
+            ids: point_ids.clone(),
+            with_payload: Some(WithPayloadInterface::Bool(false)),
+            with_vector: true.into(),
+        };
+        let records = collection
+            .retrieve(request, None, Some(shard_id))
+            .await
+            .unwrap();
+        for record in records {
+            let updated = record.vector.unwrap() == new_vector();
+            assert_eq!(updated, shard_id == target_shard);
+            assert_eq!(updated, report.succeeded.contains(&record.id));
+        }
+    }
+
+    collection.before_drop().await;
+}
+
+async fn reload_collection(mut collection: Collection, collection_path: &Path) -> Collection {
+    collection.before_drop().await;
+    drop(collection);
+    load_local_collection(
+        "test".to_string(),
+        collection_path,
+        &collection_path.join("snapshots"),
+    )
+    .await
+}
+
+#[tokio::test]
+async fn test_reshard_points_on_ring_growth() {
+    let collection_dir = Builder::new().prefix("collection").tempdir().unwrap();
+    let collection_path = collection_dir.path();
+    let mut collection = simple_collection_fixture(collection_path, N_SHARDS).await;
+
+    let shards = |shards: u32| (0..shards).collect_vec();
+    let ring = |shards: u32| {
+        let mut ring = HashRing::fair(HASH_RING_SHARD_SCALE);
+        for shard_id in 0..shards {
+            ring.add(shard_id);
+        }
+        ring
+    };
+    let small_ring = ring(N_SHARDS - 1);
+    let full_ring = ring(N_SHARDS);
+
+    // Route to all shards but the last one
+    let mut point_ids: Vec<PointIdType> = (0..100u64).map(PointIdType::from).collect();
+    collection
+        .update_from_client(
+            upsert_numbered_points(&point_ids),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    collection
+        .reshard_points(shards(N_SHARDS - 1))
+        .await
+        .unwrap();
+    assert_points_placed_by_ring(&collection, &small_ring, &point_ids).await;
+
+    // Ring with a missing shard is rejected before anything moves
+    let result = collection.reshard_points(shards(N_SHARDS + 1)).await;
+    assert!(matches!(result, Err(CollectionError::BadInput { .. })));
+
+    // Reloaded collection keeps routing with the smaller ring
+    let mut collection = reload_collection(collection, collection_path).await;
+    let moved_id = (100..1000u64)
+        .map(PointIdType::from)
+        .find(|point_id| full_ring.get(point_id) != small_ring.get(point_id))
+        .unwrap();
+    collection
+        .update_from_client(
+            upsert_numbered_points(&[moved_id]),
+            true,
+            WriteOrdering::default(),
+        )
+        .await
+        .unwrap();
+    point_ids.push(moved_id);
+    assert_points_placed_by_ring(&collection, &small_ring, &point_ids).await;
+
+    // Growing the ring only moves points taken by the new shard
+    let expected_plan = plan_vector_transfer(&small_ring, &full_ring, point_ids.iter().copied());
+    assert!(!expected_plan.is_empty());
+    assert!(expected_plan
+        .moves
+        .keys()
+        .all(|(from, to)| *from < N_SHARDS - 1 && *to == N_SHARDS - 1));
+    let plan = collection.reshard_points(shards(N_SHARDS)).await.unwrap();
+    assert_eq!(plan, expected_plan);
+    assert_points_placed_by_ring(&collection, &full_ring, &point_ids).await;
+
+    // Every point is found after reload, nothing is left to move
+    let mut collection = reload_collection(collection, collection_path).await;
+    assert_points_placed_by_ring(&collection, &full_ring, &point_ids).await;
+    let request = PointRequest {
+        ids: point_ids.clone(),
+        with_payload: Some(WithPayloadInterface::Bool(false)),
+        with_vector: false.into(),
+    };
+    let records = collection.retrieve(request, None, None).await.unwrap();
+    assert_eq!(records.len(), point_ids.len());
+    let plan = collection.reshard_points(shards(N_SHARDS)).await.unwrap();
+    assert!(plan.is_empty());
+
+    collection.before_drop().await;
+}
+
 mod grouping {
 
     use collection::collection::Collection;
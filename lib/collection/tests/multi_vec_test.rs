@@ -10,11 +10,15 @@ use collection::operations::point_ops::{
 use collection::operations::types::{
     CollectionError, PointRequest, RecommendRequest, SearchRequest, VectorParams, VectorsConfig,
 };
+use collection::operations::vector_ops::{PointVectors, UpdateVectors, VectorOperations};
 use collection::operations::CollectionUpdateOperations;
 use collection::recommendations::recommend_by;
 use segment::data_types::named_vectors::NamedVectors;
 use segment::data_types::vectors::{NamedVector, VectorStruct};
-use segment::types::{Distance, WithPayloadInterface, WithVector};
+use segment::types::{
+    Condition, Distance, FieldCondition, Filter, Match, PointIdType, WithPayloadInterface,
+    WithVector,
+};
 use tempfile::Builder;
 
 use crate::common::{new_local_collection, N_SHARDS, TEST_OPTIMIZERS_CONFIG};
@@ -271,3 +275,273 @@ async fn test_multi_vec_with_shards(shard_number: u32) {
 
     collection.before_drop().await;
 }
+
+#[tokio::test]
+async fn test_export_vectors_by_filter() {
+    test_export_vectors_by_filter_with_shards(1).await;
+    test_export_vectors_by_filter_with_shards(N_SHARDS).await;
+}
+
+async fn test_export_vectors_by_filter_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_export_vectors_by_filter")
+        .tempdir()
+        .unwrap();
+
+    let mut collection = multi_vec_collection_fixture(collection_dir.path(), shard_number).await;
+
+    // More points than a single export batch
+    let mut points = Vec::new();
+    for i in 0..600 {
+        let mut vectors = NamedVectors::default();
+        vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+        vectors.insert(VEC_NAME2.to_string(), vec![0.0, i as f32, 0.0, 0.0]);
+
+        points.push(PointStruct {
+            id: i.into(),
+            vector: vectors.into(),
+            payload: Some(serde_json::from_str(&format!(r#"{{"group": {}}}"#, i % 3)).unwrap()),
+        });
+    }
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Some of the points miss one of the requested vectors
+    let delete_vectors =
+        CollectionUpdateOperations::VectorOperation(VectorOperations::DeleteVectors(
+            vec![0.into(), 3.into()].into(),
+            vec![VEC_NAME2.to_string()],
+        ));
+    collection
+        .update_from_client(delete_vectors, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+        "group".to_string(),
+        Match::from(0_i64),
+    )));
+    let exported = collection
+        .export_vectors_by_filter(
+            filter.clone(),
+            vec![VEC_NAME1.to_string(), VEC_NAME2.to_string()],
+            None,
+            None,
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(exported.len(), 200);
+    for (position, (id, vector)) in exported.iter().enumerate() {
+        let i = position * 3;
+        assert_eq!(*id, (i as u64).into());
+        assert_eq!(vector.get(VEC_NAME1), Some(&vec![i as f32, 0.0, 0.0, 0.0]));
+        if i == 0 || i == 3 {
+            assert_eq!(vector.get(VEC_NAME2), None);
+        } else {
+            assert_eq!(vector.get(VEC_NAME2), Some(&vec![0.0, i as f32, 0.0, 0.0]));
+        }
+    }
+
+    // Only requested vectors are exported, points missing all of them are skipped
+    let exported = collection
+        .export_vectors_by_filter(filter, vec![VEC_NAME2.to_string()], None, None)
+        .await
+        .unwrap();
+    assert_eq!(exported.len(), 198);
+    assert!(exported.iter().all(|(id, vector)| {
+        *id != 0.into() && *id != 3.into() && vector.get(VEC_NAME1).is_none()
+    }));
+
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_count_vectors_by_filter() {
+    test_count_vectors_by_filter_with_shards(1).await;
+    test_count_vectors_by_filter_with_shards(N_SHARDS).await;
+}
+
+async fn test_count_vectors_by_filter_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_count_vectors_by_filter")
+        .tempdir()
+        .unwrap();
+
+    let mut collection = multi_vec_collection_fixture(collection_dir.path(), shard_number).await;
+
+    // More points than a single scroll batch
+    let points = (0..300u64)
+        .map(|i| {
+            let mut vectors = NamedVectors::default();
+            vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+            vectors.insert(VEC_NAME2.to_string(), vec![0.0, i as f32, 0.0, 0.0]);
+            PointStruct {
+                id: i.into(),
+                vector: vectors.into(),
+                payload: Some(serde_json::from_str(&format!(r#"{{"group": {}}}"#, i % 3)).unwrap()),
+            }
+        })
+        .collect();
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+
+    // Points have different subsets of the named vectors, some of them have none
+    fn without_vec1(i: &u64) -> bool {
+        i % 7 == 0
+    }
+    fn without_vec2(i: &u64) -> bool {
+        i % 5 == 0
+    }
+    let deleted: [(&str, fn(&u64) -> bool); 2] =
+        [(VEC_NAME1, without_vec1), (VEC_NAME2, without_vec2)];
+    for (name, without) in deleted {
+        let ids: Vec<PointIdType> = (0..300u64).filter(without).map(Into::into).collect();
+        let delete_vectors = CollectionUpdateOperations::VectorOperation(
+            VectorOperations::DeleteVectors(ids.into(), vec![name.to_string()]),
+        );
+        collection
+            .update_from_client(delete_vectors, true, WriteOrdering::default())
+            .await
+            .unwrap();
+    }
+
+    let group = |group: i64| {
+        Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "group".to_string(),
+            Match::from(group),
+        )))
+    };
+    let counts = collection
+        .count_vectors_by_filter(Some(group(0)), None, None)
+        .await
+        .unwrap();
+    let matching = || (0..300u64).filter(|i| i % 3 == 0);
+    assert_eq!(counts.points, matching().count());
+    assert_eq!(
+        counts.get(VEC_NAME1),
+        matching().filter(|i| !without_vec1(i)).count()
+    );
+    assert_eq!(
+        counts.get(VEC_NAME2),
+        matching().filter(|i| !without_vec2(i)).count()
+    );
+    assert_eq!(counts.by_name.len(), 2);
+
+    // All points are counted without a filter
+    let counts = collection
+        .count_vectors_by_filter(None, None, None)
+        .await
+        .unwrap();
+    assert_eq!(counts.points, 300);
+    assert_eq!(
+        counts.get(VEC_NAME1),
+        300 - (0..300u64).filter(without_vec1).count()
+    );
+    assert_eq!(
+        counts.get(VEC_NAME2),
+        300 - (0..300u64).filter(without_vec2).count()
+    );
+
+    // Names are reported even if no matching point has them
+    let counts = collection
+        .count_vectors_by_filter(Some(group(5)), None, None)
+        .await
+        .unwrap();
+    assert_eq!(counts.points, 0);
+    assert_eq!(counts.by_name.get(VEC_NAME1), Some(&0));
+    assert_eq!(counts.by_name.get(VEC_NAME2), Some(&0));
+
+    collection.before_drop().await;
+}
+
+#[tokio::test]
+async fn test_present_vector_names() {
+    test_present_vector_names_with_shards(1).await;
+    test_present_vector_names_with_shards(N_SHARDS).await;
+}
+
+async fn test_present_vector_names_with_shards(shard_number: u32) {
+    let collection_dir = Builder::new()
+        .prefix("test_present_vector_names")
+        .tempdir()
+        .unwrap();
+
+    let mut collection = multi_vec_collection_fixture(collection_dir.path(), shard_number).await;
+    assert!(collection
+        .present_vector_names(None, None)
+        .await
+        .unwrap()
+        .is_empty());
+
+    // Second vector is declared by the config, but none of the points has it
+    let points = (0..50u64)
+        .map(|i| {
+            let mut vectors = NamedVectors::default();
+            vectors.insert(VEC_NAME1.to_string(), vec![i as f32, 0.0, 0.0, 0.0]);
+            PointStruct {
+                id: i.into(),
+                vector: vectors.into(),
+                payload: None,
+            }
+        })
+        .collect();
+    let insert_points = CollectionUpdateOperations::PointOperation(PointOperations::UpsertPoints(
+        PointInsertOperations::PointsList(points),
+    ));
+    collection
+        .update_from_client(insert_points, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        collection.present_vector_names(None, None).await.unwrap(),
+        vec![VEC_NAME1.to_string()]
+    );
+
+    // Single point with the second vector is enough for it to be present
+    let mut vectors = NamedVectors::default();
+    vectors.insert(VEC_NAME2.to_string(), vec![1.0, 0.0, 0.0, 0.0]);
+    let update_vectors = CollectionUpdateOperations::VectorOperation(
+        VectorOperations::UpdateVectors(UpdateVectors {
+            points: vec![PointVectors {
+                id: 7.into(),
+                vector: vectors.into(),
+            }],
+            partial: false,
+        }),
+    );
+    collection
+        .update_from_client(update_vectors, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        collection.present_vector_names(None, None).await.unwrap(),
+        vec![VEC_NAME1.to_string(), VEC_NAME2.to_string()]
+    );
+
+    // Vectors deleted from all points are not present anymore
+    let ids: Vec<PointIdType> = (0..50u64).map(Into::into).collect();
+    let delete_vectors = CollectionUpdateOperations::VectorOperation(
+        VectorOperations::DeleteVectors(ids.into(), vec![VEC_NAME1.to_string()]),
+    );
+    collection
+        .update_from_client(delete_vectors, true, WriteOrdering::default())
+        .await
+        .unwrap();
+    assert_eq!(
+        collection.present_vector_names(None, None).await.unwrap(),
+        vec![VEC_NAME2.to_string()]
+    );
+
+    collection.before_drop().await;
+}
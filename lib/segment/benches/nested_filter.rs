@@ -0,0 +1,183 @@
+#[cfg(not(target_os = "windows"))]
+mod prof;
+
+use std::sync::Arc;
+
+use atomic_refcell::AtomicRefCell;
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use segment::fixtures::payload_context_fixture::FixtureIdTracker;
+use segment::index::struct_payload_index::StructPayloadIndex;
+use segment::index::PayloadIndex;
+use segment::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
+use segment::payload_storage::PayloadStorage;
+use segment::types::{Condition, FieldCondition, Filter, Payload, PointOffsetType, Range};
+use serde_json::json;
+use tempfile::{Builder, TempDir};
+
+const NUM_POINTS: usize = 1000;
+const FIELDS: [&str; 4] = ["a", "b", "c", "d"];
+/// Small, medium and large nested arrays
+const ARRAY_SIZES: [usize; 3] = [4, 32, 256];
+const CONDITION_COUNTS: [usize; 3] = [1, 2, 4];
+
+/// Number of fields not referenced by conditions in elements of wide nested arrays
+const WIDE_FILLER_FIELDS: usize = 64;
+
+/// Index over points with `NUM_POINTS` nested arrays of `array_size` random elements
+///
+/// Elements of `wide` arrays have additional `WIDE_FILLER_FIELDS` fields, which are never
+/// referenced by conditions.
+fn nested_index(array_size: usize, wide: bool, rng: &mut StdRng) -> (TempDir, StructPayloadIndex) {
+    let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+    let mut payload_storage = InMemoryPayloadStorage::default();
+    for idx in 0..NUM_POINTS {
+        let elements: Vec<_> = (0..array_size)
+            .map(|_| {
+                let mut element = json!({
+                    "a": rng.gen_range(0..10),
+                    "b": rng.gen_range(0..10),
+                    "c": rng.gen_range(0..10),
+                    "d": rng.gen_range(0..10),
+                });
+                if wide {
+                    for i in 0..WIDE_FILLER_FIELDS {
+                        element[format!("filler_{i}")] = json!({ "a": i, "text": "filler" });
+                    }
+                }
+                element
+            })
+            .collect();
+        let payload: Payload = json!({ "arr": elements }).into();
+        payload_storage
+            .assign(idx as PointOffsetType, &payload)
+            .unwrap();
+    }
+
+    let index = StructPayloadIndex::open(
+        Arc::new(AtomicRefCell::new(payload_storage.into())),
+        Arc::new(AtomicRefCell::new(FixtureIdTracker::new(NUM_POINTS))),
+        Default::default(),
+        dir.path(),
+    )
+    .unwrap();
+    (dir, index)
+}
+
+/// Conditions on the first `count` fields, each matching about half of the elements
+fn nested_conditions(count: usize) -> Vec<Condition> {
+    FIELDS[..count]
+        .iter()
+        .map(|field| {
+            Condition::Field(FieldCondition::new_range(
+                *field,
+                Range {
+                    lt: Some(5.0),
+                    gt: None,
+                    gte: None,
+                    lte: None,
+                },
+            ))
+        })
+        .collect()
+}
+
+fn nested_filter_benchmark(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut group = c.benchmark_group("nested-filter-group");
+
+    for array_size in ARRAY_SIZES {
+        let (_dir, index) = nested_index(array_size, false, &mut rng);
+
+        for condition_count in CONDITION_COUNTS {
+            let conditions = nested_conditions(condition_count);
+            let parameter = format!("array-{array_size}-conditions-{condition_count}");
+
+            // Some element has to match all conditions
+            let must_filter = Filter::new_must(Condition::new_nested(
+                "arr",
+                Filter {
+                    should: None,
+                    must: Some(conditions.clone()),
+                    must_not: None,
+                },
+            ));
+            // Some element has to match none of the conditions
+            let must_not_filter = Filter::new_must(Condition::new_nested(
+                "arr",
+                Filter {
+                    should: None,
+                    must: None,
+                    must_not: Some(conditions),
+                },
+            ));
+
+            for (name, filter) in [("must", &must_filter), ("must-not", &must_not_filter)] {
+                group.bench_with_input(
+                    BenchmarkId::new(format!("nested-{name}-context-check"), &parameter),
+                    filter,
+                    |b, filter| {
+                        b.iter(|| {
+                            let context = index.filter_context(filter);
+                            (0..NUM_POINTS as PointOffsetType)
+                                .filter(|point_id| context.check(*point_id))
+                                .count()
+                        })
+                    },
+                );
+            }
+        }
+    }
+
+    group.finish();
+}
+
+/// Conditions reference a single field of wide elements, which are projected to that field
+fn nested_wide_elements_benchmark(c: &mut Criterion) {
+    let mut rng = StdRng::seed_from_u64(42);
+    let mut group = c.benchmark_group("nested-filter-wide-elements");
+
+    for array_size in ARRAY_SIZES {
+        for wide in [false, true] {
+            let (_dir, index) = nested_index(array_size, wide, &mut rng);
+            let filter = Filter::new_must(Condition::new_nested(
+                "arr",
+                Filter::new_must(nested_conditions(1).remove(0)),
+            ));
+            let parameter = format!("array-{array_size}-wide-{wide}");
+
+            group.bench_with_input(
+                BenchmarkId::new("nested-must-context-check", &parameter),
+                &filter,
+                |b, filter| {
+                    b.iter(|| {
+                        let context = index.filter_context(filter);
+                        (0..NUM_POINTS as PointOffsetType)
+                            .filter(|point_id| context.check(*point_id))
+                            .count()
+                    })
+                },
+            );
+        }
+    }
+
+    group.finish();
+}
+
+#[cfg(not(target_os = "windows"))]
+criterion_group! {
+    name = benches;
+    config = Criterion::default().with_profiler(prof::FlamegraphProfiler::new(100));
+    targets = nested_filter_benchmark, nested_wide_elements_benchmark
+}
+
+#[cfg(target_os = "windows")]
+criterion_group! {
+    name = benches;
+    config = Criterion::default();
+    targets = nested_filter_benchmark, nested_wide_elements_benchmark
+}
+
+criterion_main!(benches);
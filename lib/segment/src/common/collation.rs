@@ -0,0 +1,159 @@
+use std::cmp::Ordering;
+
+use unicode_normalization::char::is_combining_mark;
+use unicode_normalization::UnicodeNormalization;
+
+use crate::types::CollationLocale;
+
+/// Collation element of a single letter of the string
+///
+/// `primary` is the base letter, with the rank of the letter tailored by the locale to sort
+/// right after it. Accents and case only matter if all base letters are the same.
+#[derive(Debug)]
+struct CollationElement {
+    primary: (char, u8),
+    accents: Vec<char>,
+    uppercase: bool,
+}
+
+/// Letters with their own place in the alphabet of the locale, sorted right after the given one
+fn tailored_letter(locale: CollationLocale, letter: char) -> Option<(char, u8)> {
+    match (locale, letter) {
+        (CollationLocale::Es, 'ñ') => Some(('n', 1)),
+        (CollationLocale::Sv, 'å') => Some(('z', 1)),
+        (CollationLocale::Sv, 'ä' | 'æ') => Some(('z', 2)),
+        (CollationLocale::Sv, 'ö' | 'ø') => Some(('z', 3)),
+        _ => None,
+    }
+}
+
+fn collation_elements(locale: CollationLocale, value: &str) -> Vec<CollationElement> {
+    let mut elements: Vec<CollationElement> = vec![];
+    for letter in value.nfc() {
+        let lowercase = letter.to_lowercase().next().unwrap_or(letter);
+        let uppercase = letter != lowercase;
+        if let Some(primary) = tailored_letter(locale, lowercase) {
+            elements.push(CollationElement {
+                primary,
+                accents: vec![],
+                uppercase,
+            });
+            continue;
+        }
+        for decomposed in std::iter::once(lowercase).nfd() {
+            match elements.last_mut() {
+                Some(element) if is_combining_mark(decomposed) => element.accents.push(decomposed),
+                _ => elements.push(CollationElement {
+                    primary: (decomposed, 0),
+                    accents: vec![],
+                    uppercase,
+                }),
+            }
+        }
+    }
+    elements
+}
+
+/// Compare strings the way they are ordered in a dictionary of the `locale`
+///
+/// Base letters are compared first, then accents and then case, so `école` is ordered before
+/// `zèbre`, while the byte order puts it after. Different strings are never equal, the ones
+/// differing otherwise fall back to the byte order.
+pub fn collate(locale: CollationLocale, left: &str, right: &str) -> Ordering {
+    let left_elements = collation_elements(locale, left);
+    let right_elements = collation_elements(locale, right);
+    let primary = |elements: &[CollationElement]| -> Vec<(char, u8)> {
+        elements.iter().map(|element| element.primary).collect()
+    };
+    let accents = |elements: &[CollationElement]| -> Vec<Vec<char>> {
+        elements
+            .iter()
+            .map(|element| element.accents.clone())
+            .collect()
+    };
+    let case = |elements: &[CollationElement]| -> Vec<bool> {
+        elements.iter().map(|element| element.uppercase).collect()
+    };
+    primary(&left_elements)
+        .cmp(&primary(&right_elements))
+        .then_with(|| accents(&left_elements).cmp(&accents(&right_elements)))
+        .then_with(|| case(&left_elements).cmp(&case(&right_elements)))
+        .then_with(|| left.cmp(right))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sorted(locale: CollationLocale, words: &[&str]) -> Vec<String> {
+        let mut words: Vec<String> = words.iter().map(ToString::to_string).collect();
+        words.sort_by(|left, right| collate(locale, left, right));
+        words
+    }
+
+    #[test]
+    fn test_collate_accented_letters() {
+        // Byte order puts accented letters after all ASCII letters
+        assert!("école" > "zèbre");
+        assert_eq!(
+            collate(CollationLocale::Root, "école", "zèbre"),
+            Ordering::Less
+        );
+
+        // Accents only break ties of the same base letters
+        assert_eq!(
+            sorted(
+                CollationLocale::Root,
+                &["ecole", "école", "ecoles", "eclair"]
+            ),
+            vec!["eclair", "ecole", "école", "ecoles"]
+        );
+
+        // Case is compared after accents
+        assert_eq!(
+            sorted(
+                CollationLocale::Root,
+                &["Apple", "banana", "apple", "Äpple"]
+            ),
+            vec!["apple", "Apple", "Äpple", "banana"]
+        );
+
+        // Precomposed and decomposed forms of the same letter are ordered the same way
+        let decomposed = "e\u{301}cole";
+        assert_eq!(
+            collate(CollationLocale::Root, decomposed, "ecoles"),
+            collate(CollationLocale::Root, "école", "ecoles")
+        );
+        assert_eq!(
+            collate(CollationLocale::Root, "école", "école"),
+            Ordering::Equal
+        );
+    }
+
+    #[test]
+    fn test_collate_tailored_locales() {
+        // Letters with accents in other languages are separate letters of Swedish alphabet
+        assert_eq!(
+            sorted(CollationLocale::Root, &["zebra", "öl", "ål", "ara"]),
+            vec!["ål", "ara", "öl", "zebra"]
+        );
+        assert_eq!(
+            sorted(CollationLocale::Sv, &["zebra", "öl", "ål", "ara", "äpple"]),
+            vec!["ara", "zebra", "ål", "äpple", "öl"]
+        );
+        assert_eq!(
+            sorted(CollationLocale::De, &["zebra", "öl", "ara"]),
+            vec!["ara", "öl", "zebra"]
+        );
+
+        // Spanish `ñ` follows all words with `n`
+        assert_eq!(
+            sorted(CollationLocale::Es, &["ñu", "nube", "oso", "nz"]),
+            vec!["nube", "nz", "ñu", "oso"]
+        );
+        assert_eq!(
+            sorted(CollationLocale::Root, &["ñu", "nube", "oso", "nz"]),
+            vec!["ñu", "nube", "nz", "oso"]
+        );
+    }
+}
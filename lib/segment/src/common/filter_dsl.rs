@@ -0,0 +1,588 @@
+//! Concise text representation of filters, meant for CLI tools and quick testing
+//!
+//! E.g. `reviews[rating >= 4 AND verified = true] OR NOT status = "archived"`.
+//!
+//! Grammar, keywords are case-insensitive and `AND` binds tighter than `OR`:
+//!
+//! ```text
+//! expression  := conjunction ("OR" conjunction)*
+//! conjunction := unary ("AND" unary)*
+//! unary       := "NOT" unary | "(" expression ")" | key "[" expression "]" | key op value
+//! op          := "=" | "!=" | ">" | ">=" | "<" | "<="
+//! value       := integer | float | "true" | "false" | quoted string | bare word
+//! key         := [A-Za-z_][A-Za-z0-9_.]*
+//! ```
+//!
+//! `key[...]` is a nested filter over the array `key`, all conditions inside are applied to the
+//! same element. `=` and `!=` accept integers, booleans and strings, while comparisons accept
+//! numbers only. Strings are quoted with `"` or `'`, `\` escapes the next character. Bare words
+//! are ASCII only, same as keys, other values have to be quoted.
+//!
+//! Parentheses, nested filters and `NOT` can be nested up to [`MAX_DEPTH`] levels deep.
+
+use thiserror::Error;
+
+use crate::types::{
+    Condition, FieldCondition, Filter, FloatPayloadType, IntPayloadType, Match, NestedContainer,
+    Range,
+};
+
+/// Maximal nesting of parentheses, nested filters and negations in an expression
+///
+/// Parser is recursive, deeper expressions are rejected instead of overflowing the stack.
+pub const MAX_DEPTH: usize = 64;
+
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("Invalid filter expression at position {position}: {description}")]
+pub struct FilterDslError {
+    /// Byte offset in the parsed expression
+    pub position: usize,
+    pub description: String,
+}
+
+fn error(position: usize, description: impl Into<String>) -> FilterDslError {
+    FilterDslError {
+        position,
+        description: description.into(),
+    }
+}
+
+/// Parse a filter expression, see the module documentation for the grammar
+pub fn parse_filter(input: &str) -> Result<Filter, FilterDslError> {
+    let condition = Parser::new(input)?.parse()?;
+    Ok(match condition {
+        Condition::Filter(filter) => filter,
+        condition => Filter::new_must(condition),
+    })
+}
+
+/// Parse a single nested filter expression, e.g. `reviews[rating >= 4]`
+pub fn parse_nested(input: &str) -> Result<NestedContainer, FilterDslError> {
+    match Parser::new(input)?.parse()? {
+        Condition::Nested(nested) => Ok(nested),
+        _ => Err(error(0, "expected a single nested filter `key[...]`")),
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Comparison {
+    Eq,
+    NotEq,
+    Gt,
+    Gte,
+    Lt,
+    Lte,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Word(String),
+    Integer(IntPayloadType),
+    Float(FloatPayloadType),
+    Str(String),
+    Op(Comparison),
+    LParen,
+    RParen,
+    LBracket,
+    RBracket,
+}
+
+fn tokenize(input: &str) -> Result<Vec<(usize, Token)>, FilterDslError> {
+    let mut tokens = vec![];
+    let mut chars = input.char_indices().peekable();
+    while let Some(&(position, c)) = chars.peek() {
+        if c.is_whitespace() {
+            chars.next();
+            continue;
+        }
+        let token = match c {
+            '(' | ')' | '[' | ']' | '=' => {
+                chars.next();
+                match c {
+                    '(' => Token::LParen,
+                    ')' => Token::RParen,
+                    '[' => Token::LBracket,
+                    ']' => Token::RBracket,
+                    _ => Token::Op(Comparison::Eq),
+                }
+            }
+            '!' | '>' | '<' => {
+                chars.next();
+                let or_equal = chars.next_if(|&(_, next)| next == '=').is_some();
+                match (c, or_equal) {
+                    ('!', true) => Token::Op(Comparison::NotEq),
+                    ('!', false) => return Err(error(position, "expected `!=`")),
+                    ('>', false) => Token::Op(Comparison::Gt),
+                    ('>', true) => Token::Op(Comparison::Gte),
+                    ('<', false) => Token::Op(Comparison::Lt),
+                    _ => Token::Op(Comparison::Lte),
+                }
+            }
+            '"' | '\'' => {
+                chars.next();
+                let mut value = String::new();
+                loop {
+                    match chars.next() {
+                        Some((_, next)) if next == c => break,
+                        Some((_, '\\')) => match chars.next() {
+                            Some((_, escaped)) => value.push(escaped),
+                            None => return Err(error(position, "unterminated string")),
+                        },
+                        Some((_, next)) => value.push(next),
+                        None => return Err(error(position, "unterminated string")),
+                    }
+                }
+                Token::Str(value)
+            }
+            c if c.is_ascii_digit() || c == '-' => {
+                let mut literal = String::new();
+                while let Some((_, next)) = chars.next_if(|&(_, next)| {
+                    next.is_ascii_alphanumeric()
+                        || next == '.'
+                        || (literal.is_empty() && next == '-')
+                        || ((next == '-' || next == '+')
+                            && literal.ends_with(|last| last == 'e' || last == 'E'))
+                }) {
+                    literal.push(next);
+                }
+                if let Ok(integer) = literal.parse() {
+                    Token::Integer(integer)
+                } else if let Ok(float) = literal.parse() {
+                    Token::Float(float)
+                } else {
+                    return Err(error(position, format!("invalid number `{literal}`")));
+                }
+            }
+            c if c.is_ascii_alphabetic() || c == '_' => {
+                let mut word = String::new();
+                while let Some((_, next)) = chars.next_if(|&(_, next)| {
+                    next.is_ascii_alphanumeric() || next == '_' || next == '.'
+                }) {
+                    word.push(next);
+                }
+                Token::Word(word)
+            }
+            c => return Err(error(position, format!("unexpected character `{c}`"))),
+        };
+        tokens.push((position, token));
+    }
+    Ok(tokens)
+}
+
+/// Parsed operand of a conjunction, negated ones are collected into `must_not`
+enum Term {
+    Positive(Condition),
+    Negative(Condition),
+}
+
+impl Term {
+    fn into_condition(self) -> Condition {
+        match self {
+            Term::Positive(condition) => condition,
+            Term::Negative(condition) => Condition::Filter(Filter::new_must_not(condition)),
+        }
+    }
+}
+
+struct Parser {
+    tokens: Vec<(usize, Token)>,
+    current: usize,
+    input_len: usize,
+    /// Number of enclosing parentheses, nested filters and negations of the current token
+    depth: usize,
+}
+
+impl Parser {
+    fn new(input: &str) -> Result<Self, FilterDslError> {
+        Ok(Self {
+            tokens: tokenize(input)?,
+            current: 0,
+            input_len: input.len(),
+            depth: 0,
+        })
+    }
+
+    /// Parse the whole input as a single expression
+    fn parse(mut self) -> Result<Condition, FilterDslError> {
+        let condition = self.expression()?;
+        match self.peek() {
+            None => Ok(condition),
+            Some(_) => Err(error(
+                self.position(),
+                "expected `AND`, `OR` or end of input",
+            )),
+        }
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.current).map(|(_, token)| token)
+    }
+
+    /// Position of the current token in the input, for error reporting
+    fn position(&self) -> usize {
+        self.tokens
+            .get(self.current)
+            .map_or(self.input_len, |(position, _)| *position)
+    }
+
+    fn next(&mut self) -> Option<Token> {
+        let token = self
+            .tokens
+            .get(self.current)
+            .map(|(_, token)| token.clone());
+        self.current += 1;
+        token
+    }
+
+    fn next_is_keyword(&self, keyword: &str) -> bool {
+        matches!(self.peek(), Some(Token::Word(word)) if word.eq_ignore_ascii_case(keyword))
+    }
+
+    fn expect(&mut self, expected: Token, description: &str) -> Result<(), FilterDslError> {
+        let position = self.position();
+        match self.next() {
+            Some(token) if token == expected => Ok(()),
+            _ => Err(error(position, format!("expected {description}"))),
+        }
+    }
+
+    fn expression(&mut self) -> Result<Condition, FilterDslError> {
+        let mut branches = vec![self.conjunction()?];
+        while self.next_is_keyword("OR") {
+            self.next();
+            branches.push(self.conjunction()?);
+        }
+        if branches.len() == 1 {
+            return Ok(branches.remove(0));
+        }
+        Ok(Condition::Filter(Filter {
+            should: Some(branches),
+            must: None,
+            must_not: None,
+        }))
+    }
+
+    fn conjunction(&mut self) -> Result<Condition, FilterDslError> {
+        let mut terms = vec![self.unary()?];
+        while self.next_is_keyword("AND") {
+            self.next();
+            terms.push(self.unary()?);
+        }
+        if terms.len() == 1 {
+            return Ok(terms.remove(0).into_condition());
+        }
+        let mut must = vec![];
+        let mut must_not = vec![];
+        for term in terms {
+            match term {
+                Term::Positive(condition) => must.push(condition),
+                Term::Negative(condition) => must_not.push(condition),
+            }
+        }
+        Ok(Condition::Filter(Filter {
+            should: None,
+            must: (!must.is_empty()).then_some(must),
+            must_not: (!must_not.is_empty()).then_some(must_not),
+        }))
+    }
+
+    fn unary(&mut self) -> Result<Term, FilterDslError> {
+        if self.depth == MAX_DEPTH {
+            return Err(error(
+                self.position(),
+                format!("expression is nested deeper than {MAX_DEPTH} levels"),
+            ));
+        }
+        self.depth += 1;
+        let term = self.operand();
+        self.depth -= 1;
+        term
+    }
+
+    fn operand(&mut self) -> Result<Term, FilterDslError> {
+        if self.next_is_keyword("NOT") {
+            self.next();
+            return Ok(match self.unary()? {
+                Term::Positive(condition) => Term::Negative(condition),
+                Term::Negative(condition) => Term::Positive(condition),
+            });
+        }
+        let position = self.position();
+        match self.next() {
+            Some(Token::LParen) => {
+                let condition = self.expression()?;
+                self.expect(Token::RParen, "`)`")?;
+                Ok(Term::Positive(condition))
+            }
+            Some(Token::Word(key)) => {
+                let position = self.position();
+                match self.next() {
+                    Some(Token::LBracket) => {
+                        let filter = match self.expression()? {
+                            Condition::Filter(filter) => filter,
+                            condition => Filter::new_must(condition),
+                        };
+                        self.expect(Token::RBracket, "`]`")?;
+                        Ok(Term::Positive(Condition::new_nested(key, filter)))
+                    }
+                    Some(Token::Op(comparison)) => self.comparison(key, comparison),
+                    _ => Err(error(
+                        position,
+                        format!("expected operator or `[` after `{key}`"),
+                    )),
+                }
+            }
+            _ => Err(error(position, "expected condition")),
+        }
+    }
+
+    fn comparison(&mut self, key: String, comparison: Comparison) -> Result<Term, FilterDslError> {
+        let position = self.position();
+        let value = self.next();
+        let number = match value {
+            Some(Token::Integer(integer)) => Some(integer as FloatPayloadType),
+            Some(Token::Float(float)) => Some(float),
+            _ => None,
+        };
+        let range = match (comparison, number) {
+            (Comparison::Eq | Comparison::NotEq, _) => None,
+            (_, None) => return Err(error(position, "comparison requires a number")),
+            (Comparison::Gt, number) => Some((None, number, None, None)),
+            (Comparison::Gte, number) => Some((None, None, number, None)),
+            (Comparison::Lt, number) => Some((number, None, None, None)),
+            (Comparison::Lte, number) => Some((None, None, None, number)),
+        };
+        if let Some((lt, gt, gte, lte)) = range {
+            let range = Range { lt, gt, gte, lte };
+            return Ok(Term::Positive(Condition::Field(FieldCondition::new_range(
+                key, range,
+            ))));
+        }
+
+        let r#match: Match = match value {
+            Some(Token::Integer(integer)) => integer.into(),
+            Some(Token::Str(string)) => string.into(),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("true") => true.into(),
+            Some(Token::Word(word)) if word.eq_ignore_ascii_case("false") => false.into(),
+            Some(Token::Word(word)) => word.into(),
+            Some(Token::Float(_)) => {
+                return Err(error(
+                    position,
+                    "exact match requires an integer, use a range for floats",
+                ))
+            }
+            _ => return Err(error(position, "expected value")),
+        };
+        let condition = Condition::Field(FieldCondition::new_match(key, r#match));
+        match comparison {
+            Comparison::NotEq => Ok(Term::Negative(condition)),
+            _ => Ok(Term::Positive(condition)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+    use crate::common::utils::JsonPathPayload;
+    use crate::payload_storage::nested_query_checker::check_nested_filter;
+    use crate::payload_storage::query_checker::{check_field_condition, check_filter};
+    use crate::types::{OwnedPayloadRef, Payload};
+
+    fn range(lt: Option<f64>, gt: Option<f64>, gte: Option<f64>, lte: Option<f64>) -> Range {
+        Range { lt, gt, gte, lte }
+    }
+
+    fn field_match(key: &str, r#match: impl Into<Match>) -> Condition {
+        Condition::Field(FieldCondition::new_match(key.to_string(), r#match.into()))
+    }
+
+    fn field_range(key: &str, range: Range) -> Condition {
+        Condition::Field(FieldCondition::new_range(key.to_string(), range))
+    }
+
+    fn check(filter: &Filter, payload: &Payload) -> bool {
+        let checker = |condition: &Condition| match condition {
+            Condition::Field(field_condition) => check_field_condition(field_condition, payload),
+            Condition::Nested(nested) => check_nested_filter(
+                &JsonPathPayload::new(nested.array_key()),
+                nested.filter(),
+                || OwnedPayloadRef::from(payload),
+            ),
+            _ => unreachable!(),
+        };
+        check_filter(&checker, filter)
+    }
+
+    #[test]
+    fn test_parse_nested_filter() {
+        let parsed = parse_nested("reviews[rating >= 4 AND verified = true]").unwrap();
+        let expected = Condition::new_nested(
+            "reviews",
+            Filter {
+                should: None,
+                must: Some(vec![
+                    field_range("rating", range(None, None, Some(4.0), None)),
+                    field_match("verified", true),
+                ]),
+                must_not: None,
+            },
+        );
+        assert_eq!(Condition::Nested(parsed), expected);
+
+        // Keywords are case-insensitive and whitespace is optional
+        let compact = parse_filter("reviews[rating>=4 and verified=TRUE]").unwrap();
+        assert_eq!(compact, Filter::new_must(expected));
+
+        // Nested filter inside nested filter
+        let parsed = parse_filter("orders[items[price < 9.5] AND status != 'cancelled']").unwrap();
+        let expected = Filter::new_must(Condition::new_nested(
+            "orders",
+            Filter {
+                should: None,
+                must: Some(vec![Condition::new_nested(
+                    "items",
+                    Filter::new_must(field_range("price", range(Some(9.5), None, None, None))),
+                )]),
+                must_not: Some(vec![field_match("status", "cancelled".to_string())]),
+            },
+        ));
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_parse_boolean_operators() {
+        // AND binds tighter than OR
+        let parsed = parse_filter("a = 1 OR b > 2 AND NOT c = x").unwrap();
+        let expected = Filter {
+            should: Some(vec![
+                field_match("a", 1_i64),
+                Condition::Filter(Filter {
+                    should: None,
+                    must: Some(vec![field_range("b", range(None, Some(2.0), None, None))]),
+                    must_not: Some(vec![field_match("c", "x".to_string())]),
+                }),
+            ]),
+            must: None,
+            must_not: None,
+        };
+        assert_eq!(parsed, expected);
+
+        let parsed = parse_filter("NOT (a = 1 OR a = 2)").unwrap();
+        let expected = Filter::new_must_not(Condition::Filter(Filter {
+            should: Some(vec![field_match("a", 1_i64), field_match("a", 2_i64)]),
+            must: None,
+            must_not: None,
+        }));
+        assert_eq!(parsed, expected);
+
+        // Double negation cancels out
+        let parsed = parse_filter("NOT NOT user.age <= -1.5e1").unwrap();
+        let expected = Filter::new_must(field_range(
+            "user.age",
+            range(None, None, None, Some(-15.0)),
+        ));
+        assert_eq!(parsed, expected);
+
+        let parsed = parse_filter(r#"name = "Don \"Jr\" Doe""#).unwrap();
+        let expected = Filter::new_must(field_match("name", r#"Don "Jr" Doe"#.to_string()));
+        assert_eq!(parsed, expected);
+    }
+
+    #[test]
+    fn test_parsed_filters_evaluation() {
+        let payloads: Vec<Payload> = vec![
+            json!({ "reviews": [{ "rating": 5, "verified": true }] }).into(),
+            json!({
+                "reviews": [
+                    { "rating": 5, "verified": false },
+                    { "rating": 1, "verified": true },
+                ]
+            })
+            .into(),
+            json!({ "reviews": [], "status": "draft" }).into(),
+            json!({ "status": "published" }).into(),
+        ];
+
+        let hand_built = Filter {
+            should: Some(vec![
+                Condition::new_nested(
+                    "reviews",
+                    Filter {
+                        should: None,
+                        must: Some(vec![
+                            field_range("rating", range(None, None, Some(4.0), None)),
+                            field_match("verified", true),
+                        ]),
+                        must_not: None,
+                    },
+                ),
+                field_match("status", "published".to_string()),
+            ]),
+            must: None,
+            must_not: None,
+        };
+        let expressions = [
+            "reviews[rating >= 4 AND verified = true] OR status = published",
+            "(reviews[verified = true AND rating >= 4]) or status = 'published'",
+            "reviews[NOT rating < 4 AND NOT verified != true] OR status = \"published\"",
+        ];
+        for expression in expressions {
+            let parsed = parse_filter(expression).unwrap();
+            let results: Vec<_> = payloads.iter().map(|p| check(&parsed, p)).collect();
+            let expected: Vec<_> = payloads.iter().map(|p| check(&hand_built, p)).collect();
+            assert_eq!(results, expected, "{expression}");
+            assert_eq!(results, vec![true, false, false, true], "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_parse_errors() {
+        let position = |input: &str| parse_filter(input).unwrap_err().position;
+
+        assert_eq!(position(""), 0);
+        assert_eq!(position("a = 1 AND"), 9);
+        assert_eq!(position("a = 1 b = 2"), 6);
+        assert_eq!(position("a >= x"), 5);
+        assert_eq!(position("a = 1.5"), 4);
+        assert_eq!(position("reviews[a = 1"), 13);
+        assert_eq!(position("(a = 1"), 6);
+        assert_eq!(position("a ! 1"), 2);
+        assert_eq!(position("a = 'open"), 4);
+        assert_eq!(position("a = 1 # comment"), 6);
+        assert_eq!(position("a"), 1);
+
+        let err = parse_nested("a = 1").unwrap_err();
+        assert!(err.to_string().contains("nested"), "{err}");
+
+        // Keys and bare words are ASCII only
+        assert_eq!(position("ключ = 1"), 0);
+        assert_eq!(position("a = é"), 4);
+        assert!(parse_filter("a = 'é'").is_ok());
+    }
+
+    #[test]
+    fn test_parse_depth_limit() {
+        let nested =
+            |depth: usize| format!("{}a = 1{}", "(".repeat(depth - 1), ")".repeat(depth - 1));
+        assert!(parse_filter(&nested(MAX_DEPTH)).is_ok());
+        let err = parse_filter(&nested(MAX_DEPTH + 1)).unwrap_err();
+        assert_eq!(err.position, MAX_DEPTH);
+        assert!(err.to_string().contains("deeper"), "{err}");
+
+        // Negations and nested filters count as levels too
+        let negations = format!("{}a = 1", "NOT ".repeat(MAX_DEPTH));
+        assert_eq!(
+            parse_filter(&negations).unwrap_err().position,
+            MAX_DEPTH * 4
+        );
+        let arrays = format!("{}a = 1{}", "a[".repeat(MAX_DEPTH), "]".repeat(MAX_DEPTH));
+        assert!(parse_filter(&arrays).is_err());
+
+        // Long flat expressions are not limited
+        let flat = vec!["a = 1"; MAX_DEPTH * 4].join(" OR ");
+        assert!(parse_filter(&flat).is_ok());
+    }
+}
@@ -1,8 +1,10 @@
 pub mod anonymize;
 pub mod arc_atomic_ref_cell_iterator;
+pub mod collation;
 pub mod cpu;
 pub mod error_logging;
 pub mod file_operations;
+pub mod filter_dsl;
 pub mod mmap_ops;
 pub mod mmap_type;
 pub mod operation_time_statistics;
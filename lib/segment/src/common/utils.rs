@@ -120,8 +120,10 @@ pub fn rev_range(a: usize, b: usize) -> impl Iterator<Item = usize> {
 
 /// Parse array path and index from path
 ///
-/// return Some((path, Some(index))) if path is an array path with index
-fn parse_array_path(path: &str) -> Option<(&str, Option<u32>)> {
+/// return Some((path, Some(index))) if path is an array path with index.
+/// If `negative_index` is set, negative index counts from the end of the array, e.g.
+/// `reviews[-1]` is the last review. Otherwise the path with a negative index is not an array path.
+fn parse_array_path(path: &str, negative_index: bool) -> Option<(&str, Option<i64>)> {
     // shortcut no array path
     if !path.contains('[') || !path.ends_with(']') {
         return None;
@@ -135,7 +137,8 @@ fn parse_array_path(path: &str) -> Option<(&str, Option<u32>)> {
         (Some(element), Some(index)) => {
             let trimmed_index = index.trim_matches(']');
             // get numeric index
-            match trimmed_index.parse::<u32>() {
+            match trimmed_index.parse::<i64>() {
+                Ok(num_index) if num_index < 0 && !negative_index => None,
                 Ok(num_index) => Some((element, Some(num_index))),
                 Err(_) => None, // not a well formed path array
             }
@@ -144,26 +147,51 @@ fn parse_array_path(path: &str) -> Option<(&str, Option<u32>)> {
     }
 }
 
+/// Position in an array of `len` elements, addressed by a possibly negative `index`
+///
+/// `None` if there is no element at this position
+fn resolve_array_index(index: i64, len: usize) -> Option<usize> {
+    let position = match index {
+        index if index < 0 => (len as i64).checked_add(index)?,
+        index => index,
+    };
+    usize::try_from(position)
+        .ok()
+        .filter(|position| *position < len)
+}
+
 /// Focus on array values references according to array path
 ///
 /// Expects to be called with a path that is a path to an Array
 fn focus_array_path<'a>(
     array_path: &str,
-    array_index: Option<u32>,
+    array_index: Option<i64>,
     rest_path: Option<&str>,
     value: &'a serde_json::Map<String, Value>,
+    negative_index: bool,
 ) -> MultiValue<&'a Value> {
     match value.get(array_path) {
         Some(Value::Array(array)) => {
+            let array_index = match array_index {
+                Some(index) => match resolve_array_index(index, array.len()) {
+                    Some(position) => Some(position),
+                    None => return MultiValue::default(),
+                },
+                None => None,
+            };
             let mut values: MultiValue<_> = MultiValue::default();
             for (i, value) in array.iter().enumerate() {
                 if let Some(array_index) = array_index {
-                    if i == array_index as usize {
+                    if i == array_index {
                         match rest_path {
                             Some(rest_path) => {
                                 // expect an Object if there is a rest path
                                 if let Value::Object(map) = value {
-                                    values.extend(get_value_from_json_map(rest_path, map))
+                                    values.extend(get_json_map_value(
+                                        rest_path,
+                                        map,
+                                        negative_index,
+                                    ))
                                 }
                             }
                             None => values.push(value),
@@ -174,7 +202,7 @@ fn focus_array_path<'a>(
                         Some(rest_path) => {
                             // expect an Object if there is a rest path
                             if let Value::Object(map) = value {
-                                values.extend(get_value_from_json_map(rest_path, map))
+                                values.extend(get_json_map_value(rest_path, map, negative_index))
                             }
                         }
                         None => values.push(value),
@@ -204,19 +232,43 @@ fn focus_array_path<'a>(
 pub fn get_value_from_json_map<'a>(
     path: &str,
     value: &'a serde_json::Map<String, Value>,
+) -> MultiValue<&'a Value> {
+    get_json_map_value(path, value, false)
+}
+
+/// Same as `get_value_from_json_map`, but negative array indices count from the end of the array
+///
+/// Only meant for paths of nested filters, e.g. `reviews[-1]` of the last review.
+pub fn get_nested_value_from_json_map<'a>(
+    path: &str,
+    value: &'a serde_json::Map<String, Value>,
+) -> MultiValue<&'a Value> {
+    get_json_map_value(path, value, true)
+}
+
+fn get_json_map_value<'a>(
+    path: &str,
+    value: &'a serde_json::Map<String, Value>,
+    negative_index: bool,
 ) -> MultiValue<&'a Value> {
     // check if leaf path element
     match path.split_once('.') {
         Some((element, rest_path)) => {
             // check if targeting array
-            match parse_array_path(element) {
-                Some((array_element_path, array_index)) => {
-                    focus_array_path(array_element_path, array_index, Some(rest_path), value)
-                }
+            match parse_array_path(element, negative_index) {
+                Some((array_element_path, array_index)) => focus_array_path(
+                    array_element_path,
+                    array_index,
+                    Some(rest_path),
+                    value,
+                    negative_index,
+                ),
                 None => {
                     // no array notation
                     match value.get(element) {
-                        Some(Value::Object(map)) => get_value_from_json_map(rest_path, map),
+                        Some(Value::Object(map)) => {
+                            get_json_map_value(rest_path, map, negative_index)
+                        }
                         Some(value) => match rest_path.is_empty() {
                             true => MultiValue::one(value),
                             false => MultiValue::default(),
@@ -226,9 +278,9 @@ pub fn get_value_from_json_map<'a>(
                 }
             }
         }
-        None => match parse_array_path(path) {
+        None => match parse_array_path(path, negative_index) {
             Some((array_element_path, array_index)) => {
-                focus_array_path(array_element_path, array_index, None, value)
+                focus_array_path(array_element_path, array_index, None, value, negative_index)
             }
             None => match value.get(path) {
                 Some(value) => MultiValue::one(value),
@@ -243,21 +295,25 @@ pub fn get_value_from_json_map<'a>(
 /// Expects to be called with a path that is a path to an Array
 fn delete_array_path(
     array_path: &str,
-    array_index: Option<u32>,
+    array_index: Option<i64>,
     rest_path: Option<&str>,
     value: &mut serde_json::Map<String, Value>,
 ) -> MultiValue<Value> {
     if let Some(Value::Array(array)) = value.get_mut(array_path) {
+        let array_index = match array_index {
+            Some(index) => match resolve_array_index(index, array.len()) {
+                Some(position) => Some(position),
+                None => return MultiValue::default(),
+            },
+            None => None,
+        };
         match rest_path {
             None => {
                 // end of path - delete and collect
-                if let Some(array_index) = array_index {
-                    if array.len() > array_index as usize {
-                        return MultiValue::one(array.remove(array_index as usize));
-                    }
-                } else {
-                    return MultiValue::one(Value::Array(array.drain(..).collect()));
-                }
+                return match array_index {
+                    Some(array_index) => MultiValue::one(array.remove(array_index)),
+                    None => MultiValue::one(Value::Array(array.drain(..).collect())),
+                };
             }
             Some(rest_path) => {
                 // dig deeper
@@ -265,7 +321,7 @@ fn delete_array_path(
                 for (i, value) in array.iter_mut().enumerate() {
                     if let Value::Object(map) = value {
                         if let Some(array_index) = array_index {
-                            if i == array_index as usize {
+                            if i == array_index {
                                 values.extend(remove_value_from_json_map(rest_path, map));
                             }
                         } else {
@@ -289,7 +345,7 @@ pub fn remove_value_from_json_map(
     match path.split_once('.') {
         Some((element, rest_path)) => {
             // check if targeting array
-            match parse_array_path(element) {
+            match parse_array_path(element, false) {
                 Some((array_element_path, array_index)) => {
                     delete_array_path(array_element_path, array_index, Some(rest_path), value)
                 }
@@ -307,7 +363,7 @@ pub fn remove_value_from_json_map(
                 }
             }
         }
-        None => match parse_array_path(path) {
+        None => match parse_array_path(path, false) {
             Some((array_element_path, array_index)) => {
                 delete_array_path(array_element_path, array_index, None, value)
             }
@@ -329,7 +385,79 @@ pub fn transpose_map_into_named_vector(
     result
 }
 
+/// Reconstruct nested JSON structure from a flattened map
+///
+/// Keys of the flattened map are full paths with explicit array positions,
+/// e.g. `reviews[0].rating` or `reviews[1].author.name`.
+/// Element positions are preserved, missing positions are filled with `null`.
+/// Keys without array index are treated as regular object keys, same as keys with an index not
+/// below the number of flattened keys, which no array of this map can reach.
+pub fn unflatten_json_map(
+    flattened: &serde_json::Map<String, Value>,
+) -> serde_json::Map<String, Value> {
+    let mut result = serde_json::Map::new();
+    for (path, value) in flattened {
+        insert_flattened_value(&mut result, path, value.clone(), flattened.len());
+    }
+    result
+}
+
+/// Insert value into the map according to a single flattened path
+///
+/// Array indices are only accepted below `max_index`, so a forged key can't allocate an
+/// arbitrarily large array.
+fn insert_flattened_value(
+    target: &mut serde_json::Map<String, Value>,
+    path: &str,
+    value: Value,
+    max_index: usize,
+) {
+    let (head, rest_path) = match path.split_once('.') {
+        Some((head, rest)) => (head, Some(rest)),
+        None => (path, None),
+    };
+    let (key, array_index) = match parse_array_path(head, false) {
+        Some((key, Some(index))) if index >= 0 && (index as usize) < max_index => {
+            (key, Some(index as usize))
+        }
+        _ => (head, None),
+    };
+
+    let slot = target.entry(key.to_string()).or_insert(Value::Null);
+    let slot = match array_index {
+        None => slot,
+        Some(index) => {
+            if !slot.is_array() {
+                *slot = Value::Array(Vec::new());
+            }
+            let array = slot.as_array_mut().expect("slot is an array");
+            if array.len() <= index {
+                array.resize(index + 1, Value::Null);
+            }
+            &mut array[index]
+        }
+    };
+
+    match rest_path {
+        None => *slot = value,
+        Some(rest_path) => {
+            if !slot.is_object() {
+                *slot = Value::Object(serde_json::Map::new());
+            }
+            if let Value::Object(map) = slot {
+                insert_flattened_value(map, rest_path, value, max_index);
+            }
+        }
+    }
+}
+
+const OPTIONAL_CHAINING: &str = "?.";
+
 /// Light abstraction over a JSON path to avoid concatenating strings
+///
+/// Every intermediate object of the path is optional: a missing or non-object value produces
+/// no values instead of an error. The optional chaining notation `profile?.addresses[]` is
+/// accepted for readability and is equivalent to `profile.addresses[]`.
 #[derive(Debug, Clone)]
 pub struct JsonPathPayload {
     pub path: String,
@@ -337,6 +465,10 @@ pub struct JsonPathPayload {
 
 impl JsonPathPayload {
     pub fn new(path: String) -> Self {
+        let path = match path.contains(OPTIONAL_CHAINING) {
+            true => path.replace(OPTIONAL_CHAINING, "."),
+            false => path,
+        };
         Self { path }
     }
 
@@ -353,6 +485,46 @@ impl JsonPathPayload {
     }
 }
 
+/// Check the syntax of a JSON path, such as `country.cities[].sightseeing` or `reviews[-1]`
+///
+/// Every segment of the path should be a non-empty key, optionally followed by a single
+/// array selector: `[]` for all elements or `[N]` for the element at a position.
+pub fn validate_json_path(path: &str) -> Result<(), String> {
+    if path.is_empty() {
+        return Err("path is empty".to_string());
+    }
+    let path = path.replace(OPTIONAL_CHAINING, ".");
+    for segment in path.split('.') {
+        let (key, selector) = match segment.find('[') {
+            Some(bracket) => segment.split_at(bracket),
+            None => (segment, ""),
+        };
+        if key.is_empty() {
+            return Err(format!("empty key in `{segment}`"));
+        }
+        if let Some(invalid) = key
+            .chars()
+            .find(|c| *c == ']' || c.is_whitespace() || c.is_control())
+        {
+            return Err(format!("invalid character {invalid:?} in key `{key}`"));
+        }
+        if selector.is_empty() {
+            continue;
+        }
+        let index = match selector
+            .strip_prefix('[')
+            .and_then(|selector| selector.strip_suffix(']'))
+        {
+            Some(index) => index,
+            None => return Err(format!("malformed array selector `{selector}`")),
+        };
+        if !index.is_empty() && index.parse::<i64>().is_err() {
+            return Err(format!("array index `{index}` is not an integer"));
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -626,6 +798,125 @@ mod tests {
             ]
         );
     }
+
+    #[test]
+    fn test_negative_array_index_in_json_path() {
+        let map = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "arr": [
+                    { "a": 1 },
+                    { "a": 2 },
+                    { "a": 3 }
+                ]
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            get_nested_value_from_json_map("arr[-1].a", &map).values(),
+            vec![&Value::Number(3.into())]
+        );
+        assert_eq!(
+            get_nested_value_from_json_map("arr[-3].a", &map).values(),
+            vec![&Value::Number(1.into())]
+        );
+        // out of bounds from either side
+        assert!(get_nested_value_from_json_map("arr[-4].a", &map)
+            .values()
+            .is_empty());
+        assert!(get_nested_value_from_json_map("arr[3].a", &map)
+            .values()
+            .is_empty());
+
+        // Other paths don't count from the end, `arr[-1]` is a key of its own
+        assert!(get_value_from_json_map("arr[-1].a", &map)
+            .values()
+            .is_empty());
+
+        // Deleting payload is unchanged, nothing is removed by a negative index
+        let mut map = map;
+        let original = map.clone();
+        assert!(remove_value_from_json_map("arr[-1]", &mut map)
+            .values()
+            .is_empty());
+        assert!(remove_value_from_json_map("arr[-1].a", &mut map)
+            .values()
+            .is_empty());
+        assert_eq!(map, original);
+
+        let mut map = serde_json::from_value::<serde_json::Map<String, Value>>(
+            serde_json::json!({ "arr[-1]": 1, "arr": [1, 2] }),
+        )
+        .unwrap();
+        assert_eq!(
+            remove_value_from_json_map("arr[-1]", &mut map).values(),
+            vec![Value::Number(1.into())]
+        );
+        assert_eq!(
+            map,
+            serde_json::from_value(serde_json::json!({ "arr": [1, 2] })).unwrap()
+        );
+    }
+
+    #[test]
+    fn test_unflatten_json_map() {
+        let flattened = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "title": "book",
+                "reviews[1].rating": 3,
+                "reviews[0].rating": 5,
+                "reviews[0].author.name": "alice",
+                "reviews[3].rating": 1,
+                "meta.tags[0]": "new"
+            }
+            "#,
+        )
+        .unwrap();
+
+        let unflattened = unflatten_json_map(&flattened);
+
+        let expected = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"
+            {
+                "title": "book",
+                "reviews": [
+                    { "rating": 5, "author": { "name": "alice" } },
+                    { "rating": 3 },
+                    null,
+                    { "rating": 1 }
+                ],
+                "meta": { "tags": ["new"] }
+            }
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(unflattened, expected);
+
+        // element positions are retained for nested paths
+        assert_eq!(
+            get_value_from_json_map("reviews[].rating", &unflattened).values(),
+            vec![
+                &Value::Number(5.into()),
+                &Value::Number(3.into()),
+                &Value::Number(1.into()),
+            ]
+        );
+
+        // index out of reach of the flattened keys is not allocated, the key is kept as is
+        let forged = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"{ "arr[4294967295]": 1, "arr[1]": 2 }"#,
+        )
+        .unwrap();
+        let expected = serde_json::from_str::<serde_json::Map<String, Value>>(
+            r#"{ "arr[4294967295]": 1, "arr": [null, 2] }"#,
+        )
+        .unwrap();
+        assert_eq!(unflatten_json_map(&forged), expected);
+    }
 }
 
 pub type IndexesMap = HashMap<PayloadKeyType, Vec<FieldIndex>>;
@@ -54,6 +54,10 @@ pub enum OperationError {
     OutOfMemory { description: String, free: u64 },
     #[error("Operation cancelled: {description}")]
     Cancelled { description: String },
+    #[error("Nested filter expects an array at '{path}', but found a single value")]
+    NestedPathNotArray { path: String },
+    #[error("Nested field condition found a null value at '{path}'")]
+    NestedNullValue { path: String },
 }
 
 impl OperationError {
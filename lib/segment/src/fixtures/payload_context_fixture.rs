@@ -19,6 +19,7 @@ use crate::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
 use crate::payload_storage::query_checker::SimpleConditionChecker;
 use crate::payload_storage::PayloadStorage;
 use crate::types::{PayloadSchemaType, PointIdType, PointOffsetType, SeqNumberType};
+use crate::vector_storage::NamedVectorStorages;
 
 /// Warn: Use for tests only
 ///
@@ -194,9 +195,11 @@ pub fn create_plain_payload_index(path: &Path, num_points: usize, seed: u64) ->
     let payload_storage = create_payload_storage_fixture(num_points, seed);
     let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(num_points)));
 
+    // Fixture points have no vectors
     let condition_checker = Arc::new(SimpleConditionChecker::new(
         Arc::new(AtomicRefCell::new(payload_storage.into())),
         id_tracker.clone(),
+        NamedVectorStorages::default(),
     ));
 
     PlainPayloadIndex::open(condition_checker, id_tracker, path).unwrap()
@@ -224,7 +227,8 @@ pub fn create_struct_payload_index(
     ));
     let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(num_points)));
 
-    let mut index = StructPayloadIndex::open(payload_storage, id_tracker, path).unwrap();
+    let mut index =
+        StructPayloadIndex::open(payload_storage, id_tracker, Default::default(), path).unwrap();
 
     index
         .set_indexed(STR_KEY, PayloadSchemaType::Keyword.into())
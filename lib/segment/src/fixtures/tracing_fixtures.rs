@@ -0,0 +1,80 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+use tracing::field::{Field, Visit};
+use tracing::span::{Attributes, Id, Record};
+use tracing::{Event, Metadata, Subscriber};
+
+/// Span recorded by [`SpanCapture`], with the debug representation of its fields
+#[derive(Debug, Clone, Default)]
+pub struct CapturedSpan {
+    pub name: &'static str,
+    pub fields: HashMap<&'static str, String>,
+}
+
+impl Visit for CapturedSpan {
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.fields.insert(field.name(), value.to_string());
+    }
+
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.fields.insert(field.name(), format!("{value:?}"));
+    }
+}
+
+/// Subscriber collecting all created spans, used to check instrumentation in tests
+///
+/// Install it with `tracing::subscriber::with_default(capture.clone(), || ...)`,
+/// clones share the same list of spans.
+#[derive(Debug, Clone, Default)]
+pub struct SpanCapture {
+    next_id: Arc<AtomicU64>,
+    spans: Arc<Mutex<HashMap<u64, CapturedSpan>>>,
+}
+
+impl SpanCapture {
+    /// All captured spans with the given name, in the order of creation
+    pub fn spans(&self, name: &str) -> Vec<CapturedSpan> {
+        let spans = self.spans.lock().unwrap();
+        let mut ids: Vec<_> = spans
+            .iter()
+            .filter(|(_, span)| span.name == name)
+            .map(|(id, _)| *id)
+            .collect();
+        ids.sort_unstable();
+        ids.into_iter().map(|id| spans[&id].clone()).collect()
+    }
+}
+
+impl Subscriber for SpanCapture {
+    fn enabled(&self, _metadata: &Metadata<'_>) -> bool {
+        true
+    }
+
+    fn new_span(&self, attributes: &Attributes<'_>) -> Id {
+        let id = self.next_id.fetch_add(1, Ordering::Relaxed) + 1;
+        let mut span = CapturedSpan {
+            name: attributes.metadata().name(),
+            fields: HashMap::new(),
+        };
+        attributes.record(&mut span);
+        self.spans.lock().unwrap().insert(id, span);
+        Id::from_u64(id)
+    }
+
+    fn record(&self, span: &Id, values: &Record<'_>) {
+        if let Some(span) = self.spans.lock().unwrap().get_mut(&span.into_u64()) {
+            values.record(span);
+        }
+    }
+
+    fn record_follows_from(&self, _span: &Id, _follows: &Id) {}
+
+    fn event(&self, _event: &Event<'_>) {}
+
+    fn enter(&self, _span: &Id) {}
+
+    fn exit(&self, _span: &Id) {}
+}
@@ -242,6 +242,8 @@ impl InvertedIndex {
                         geo_bounding_box: None,
                         geo_radius: None,
                         values_count: None,
+                        null_policy: None,
+                        inner_match: None,
                     },
                     cardinality: posting.len(),
                 }),
@@ -246,6 +246,8 @@ mod tests {
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
+            null_policy: None,
+            inner_match: None,
         }
     }
 
@@ -795,6 +795,8 @@ mod tests {
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
+            null_policy: None,
+            inner_match: None,
         };
 
         let offsets = index.filter(&condition).unwrap().collect_vec();
@@ -155,7 +155,8 @@ impl<TGraphLinks: GraphLinks> HNSWIndex<TGraphLinks> {
         let deleted_bitslice = vector_storage.deleted_vector_bitslice();
 
         let points_to_index: Vec<_> = payload_index
-            .query_points(&filter)
+            .query_points_cancellable(&filter, stopped)?
+            .into_iter()
             .filter(|&point_id| {
                 !deleted_bitslice
                     .get(point_id as usize)
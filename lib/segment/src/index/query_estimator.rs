@@ -144,7 +144,7 @@ pub fn combine_must_estimations(
     }
 }
 
-fn estimate_condition<F>(
+pub fn estimate_condition<F>(
     estimator: &F,
     condition: &Condition,
     total: usize,
@@ -258,6 +258,8 @@ mod tests {
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
+            null_policy: None,
+            inner_match: None,
         })
     }
 
@@ -310,6 +312,15 @@ mod tests {
                 exp: TOTAL / 2,
                 max: TOTAL,
             },
+            Condition::HasKey(_)
+            | Condition::VectorCount(_)
+            | Condition::VectorNotNormalized(_)
+            | Condition::Aggregate(_)
+            | Condition::Expression(_)
+            | Condition::Compare(_)
+            | Condition::HasDuplicates(_)
+            | Condition::ValueType(_)
+            | Condition::Position(_) => CardinalityEstimation::unknown(TOTAL),
         }
     }
 
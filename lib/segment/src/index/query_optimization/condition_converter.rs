@@ -5,21 +5,58 @@ use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::FieldIndex;
 use crate::index::query_optimization::optimized_filter::ConditionCheckerFn;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
+use crate::payload_storage::condition_checker::{check_regex_match, compile_regex};
 use crate::payload_storage::query_checker::{
-    check_field_condition, check_is_empty_condition, check_is_null_condition,
+    check_aggregate_condition, check_comparison_condition, check_expression_condition,
+    check_field_condition, check_has_duplicates_condition, check_has_key_condition,
+    check_is_empty_condition, check_is_null_condition, check_value_type_condition,
+    check_vector_count_condition, check_vector_not_normalized_condition,
 };
 use crate::types::{
     AnyVariants, Condition, FieldCondition, FloatPayloadType, GeoBoundingBox, GeoRadius, Match,
-    MatchAny, MatchText, MatchValue, PointOffsetType, Range, ValueVariants,
+    MatchAny, MatchRegex, MatchText, MatchValue, PointOffsetType, Range, ValueVariants,
 };
+use crate::vector_storage::NamedVectorStorages;
 
 pub fn condition_converter<'a>(
     condition: &'a Condition,
     field_indexes: &'a IndexesMap,
     payload_provider: PayloadProvider,
     id_tracker: &IdTrackerSS,
+    vector_storages: &NamedVectorStorages,
 ) -> ConditionCheckerFn<'a> {
     match condition {
+        // Regex is compiled once per query, field indexes can't evaluate it.
+        // With values count, only the number of values is checked, as in `FieldCondition::check`
+        Condition::Field(
+            field_condition @ FieldCondition {
+                r#match: Some(Match::Regex(MatchRegex { regex })),
+                values_count: None,
+                ..
+            },
+        ) => {
+            let regex = compile_regex(regex);
+            // Other clauses of the same field condition are combined with it, any must match
+            let rest = FieldCondition {
+                r#match: None,
+                ..field_condition.clone()
+            };
+            let has_rest = rest.range.is_some()
+                || rest.geo_bounding_box.is_some()
+                || rest.geo_radius.is_some();
+            Box::new(move |point_id| {
+                payload_provider.with_payload(point_id, |payload| {
+                    let matches = regex.as_ref().map_or(false, |regex| {
+                        payload
+                            .get_value(&field_condition.key)
+                            .values()
+                            .into_iter()
+                            .any(|value| check_regex_match(regex, value))
+                    });
+                    matches || (has_rest && check_field_condition(&rest, &payload))
+                })
+            })
+        }
         Condition::Field(field_condition) => field_indexes
             .get(&field_condition.key)
             .and_then(|indexes| {
@@ -47,6 +84,11 @@ pub fn condition_converter<'a>(
                 check_is_null_condition(is_null, &payload)
             })
         }),
+        Condition::HasKey(has_key) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_has_key_condition(has_key, &payload)
+            })
+        }),
         // ToDo: It might be possible to make this condition faster by using `VisitedPool` instead of HashSet
         Condition::HasId(has_id) => {
             let segment_ids: HashSet<_> = has_id
@@ -56,6 +98,49 @@ pub fn condition_converter<'a>(
                 .collect();
             Box::new(move |point_id| segment_ids.contains(&point_id))
         }
+        Condition::VectorCount(vector_count) => {
+            let vector_storages = vector_storages.clone();
+            Box::new(move |point_id| {
+                check_vector_count_condition(vector_count, &vector_storages, point_id)
+            })
+        }
+        Condition::VectorNotNormalized(vector_not_normalized) => {
+            let vector_storages = vector_storages.clone();
+            Box::new(move |point_id| {
+                check_vector_not_normalized_condition(
+                    vector_not_normalized,
+                    &vector_storages,
+                    point_id,
+                )
+            })
+        }
+        Condition::Aggregate(aggregate) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_aggregate_condition(aggregate, &payload)
+            })
+        }),
+        Condition::Expression(expression) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_expression_condition(expression, &payload)
+            })
+        }),
+        Condition::Compare(compare) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_comparison_condition(compare, &payload)
+            })
+        }),
+        Condition::HasDuplicates(has_duplicates) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_has_duplicates_condition(has_duplicates, &payload)
+            })
+        }),
+        Condition::ValueType(value_type) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_value_type_condition(value_type, &payload)
+            })
+        }),
+        // Only elements of nested arrays have positions
+        Condition::Position(_) => Box::new(|_| false),
         Condition::Filter(_) => unreachable!(),
         Condition::Nested(_) => unreachable!(),
     }
@@ -209,5 +294,6 @@ pub fn get_match_checkers(index: &FieldIndex, cond_match: Match) -> Option<Condi
             }
             _ => None,
         },
+        Match::Regex(_) => None,
     }
 }
@@ -1,5 +1,7 @@
 pub mod condition_converter;
+pub mod nested_explain;
 pub mod nested_filter;
+pub mod nested_matches;
 pub mod nested_optimizer;
 pub mod optimized_filter;
 pub mod optimizer;
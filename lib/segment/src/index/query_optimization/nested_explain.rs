@@ -0,0 +1,317 @@
+use bitvec::prelude::*;
+
+use crate::common::utils::{IndexesMap, JsonPathPayload};
+use crate::index::query_optimization::nested_filter::nested_condition_converter;
+use crate::index::query_optimization::payload_provider::PayloadProvider;
+use crate::types::{Condition, Filter, PointOffsetType};
+
+/// Clause of a nested filter
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NestedClause {
+    Must,
+    MustNot,
+    Should,
+}
+
+/// Elements of the array matching a single condition of the nested filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct ConditionExplanation {
+    pub clause: NestedClause,
+    /// Position of the condition in its clause
+    pub position: usize,
+    /// Elements matching the condition itself, regardless of the clause
+    pub matches: BitVec,
+}
+
+/// Elements of the array matching a whole clause of the nested filter
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClauseExplanation {
+    pub clause: NestedClause,
+    /// Elements satisfying the clause, e.g. not matching any of the `must_not` conditions
+    pub matches: BitVec,
+    /// Elements satisfying this clause and all the clauses before it
+    pub remaining: BitVec,
+}
+
+/// Step of the reduction which left no matching elements
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NestedElimination {
+    pub clause: NestedClause,
+    /// Position of the condition in the clause after which no element matched,
+    /// `None` if the clause can only fail as a whole, as `should` does
+    pub condition: Option<usize>,
+}
+
+/// Breakdown of the nested filter evaluation for a single point
+///
+/// Only the conditions of the explained filter are broken down, inner filters and nested
+/// conditions are reported as a single condition each.
+#[derive(Debug, Clone, PartialEq)]
+pub struct NestedFilterExplanation {
+    /// Number of elements in the array
+    pub elements: usize,
+    pub conditions: Vec<ConditionExplanation>,
+    /// Clauses present in the filter, in the order they are combined
+    pub clauses: Vec<ClauseExplanation>,
+    /// Elements matching the whole filter
+    pub matches: BitVec,
+    /// Where the last matching elements were eliminated, `None` if the point matches
+    /// or there are no elements to match
+    pub eliminated_by: Option<NestedElimination>,
+}
+
+/// Explain which elements of the array at `nested_path` match `filter` for `point_id`
+///
+/// Clauses are combined as by the nested filter checker: all `must` conditions and none of
+/// the `must_not` conditions should match an element, and at least one of `should` conditions.
+pub fn explain_nested_filter(
+    point_id: PointOffsetType,
+    filter: &Filter,
+    field_indexes: &IndexesMap,
+    payload_provider: PayloadProvider,
+    nested_path: JsonPathPayload,
+) -> NestedFilterExplanation {
+    let clauses = [
+        (NestedClause::Must, &filter.must),
+        (NestedClause::MustNot, &filter.must_not),
+        (NestedClause::Should, &filter.should),
+    ];
+
+    let mut conditions: Vec<ConditionExplanation> = vec![];
+    for (clause, clause_conditions) in clauses {
+        let clause_conditions: &[Condition] = clause_conditions.as_deref().unwrap_or_default();
+        for (position, condition) in clause_conditions.iter().enumerate() {
+            let checker = nested_condition_converter(
+                condition,
+                payload_provider.clone(),
+                field_indexes,
+                nested_path.clone(),
+            );
+            conditions.push(ConditionExplanation {
+                clause,
+                position,
+                matches: checker(point_id),
+            });
+        }
+    }
+
+    let elements = conditions
+        .iter()
+        .map(|condition| condition.matches.len())
+        .max()
+        .unwrap_or_default();
+
+    let mut remaining = bitvec![1; elements];
+    let mut clause_explanations: Vec<ClauseExplanation> = vec![];
+    let mut eliminated_by = None;
+    for (clause, clause_conditions) in clauses {
+        if clause_conditions.as_ref().map_or(true, Vec::is_empty) {
+            continue;
+        }
+        let clause_matches = conditions
+            .iter()
+            .filter(|condition| condition.clause == clause)
+            .map(|condition| &condition.matches);
+
+        // Reduce the conditions one by one, to find the one eliminating the last elements
+        let (matches, eliminating_condition) = match clause {
+            NestedClause::Must => {
+                let mut matches = bitvec![1; elements];
+                let mut eliminating_condition = None;
+                for (position, condition_matches) in clause_matches.enumerate() {
+                    let had_matches = (matches.clone() & remaining.as_bitslice()).any();
+                    matches &= condition_matches.as_bitslice();
+                    let has_matches = (matches.clone() & remaining.as_bitslice()).any();
+                    if had_matches && !has_matches && eliminating_condition.is_none() {
+                        eliminating_condition = Some(position);
+                    }
+                }
+                (matches, eliminating_condition)
+            }
+            NestedClause::MustNot => {
+                let mut matches = bitvec![1; elements];
+                let mut eliminating_condition = None;
+                for (position, condition_matches) in clause_matches.enumerate() {
+                    let had_matches = (matches.clone() & remaining.as_bitslice()).any();
+                    matches &= (!condition_matches.clone()).as_bitslice();
+                    let has_matches = (matches.clone() & remaining.as_bitslice()).any();
+                    if had_matches && !has_matches && eliminating_condition.is_none() {
+                        eliminating_condition = Some(position);
+                    }
+                }
+                (matches, eliminating_condition)
+            }
+            NestedClause::Should => {
+                let mut matches = bitvec![0; elements];
+                for condition_matches in clause_matches {
+                    matches |= condition_matches.as_bitslice();
+                }
+                (matches, None)
+            }
+        };
+
+        let had_matches = remaining.any();
+        remaining &= matches.as_bitslice();
+        if had_matches && !remaining.any() && eliminated_by.is_none() {
+            eliminated_by = Some(NestedElimination {
+                clause,
+                condition: eliminating_condition,
+            });
+        }
+        clause_explanations.push(ClauseExplanation {
+            clause,
+            matches,
+            remaining: remaining.clone(),
+        });
+    }
+
+    NestedFilterExplanation {
+        elements,
+        conditions,
+        clauses: clause_explanations,
+        matches: remaining,
+        eliminated_by,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::index::query_optimization::nested_filter::nested_conditions_converter;
+    use crate::types::{FieldCondition, Range};
+
+    fn color(value: &str) -> Condition {
+        Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            value.to_string().into(),
+        ))
+    }
+
+    fn size_below(value: f64) -> Condition {
+        Condition::Field(FieldCondition::new_range(
+            "size".to_string(),
+            Range {
+                lt: Some(value),
+                gt: None,
+                gte: None,
+                lte: None,
+            },
+        ))
+    }
+
+    fn size_at_least(value: f64) -> FieldCondition {
+        FieldCondition::new_range(
+            "size".to_string(),
+            Range {
+                lt: None,
+                gt: None,
+                gte: Some(value),
+                lte: None,
+            },
+        )
+    }
+
+    #[test]
+    fn test_explain_near_match() {
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([
+            (
+                0,
+                json!({ "items": [
+                    { "color": "red", "size": 10 },
+                    { "color": "blue", "size": 5 },
+                    { "color": "red", "size": 12 },
+                ] }),
+            ),
+            (1, json!({ "items": [{ "color": "red", "size": 5 }] })),
+            (2, json!({ "items": [] })),
+        ]));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let explain = |point_id, filter: &Filter| {
+            explain_nested_filter(
+                point_id,
+                filter,
+                &field_indexes,
+                payload_provider.clone(),
+                nested_path.clone(),
+            )
+        };
+
+        // Both conditions match some elements, but never the same one
+        let small_red = Filter {
+            should: None,
+            must: Some(vec![color("red"), size_below(8.0)]),
+            must_not: None,
+        };
+        let explanation = explain(0, &small_red);
+        assert_eq!(explanation.elements, 3);
+        assert_eq!(explanation.conditions[0].matches, bitvec![1, 0, 1]);
+        assert_eq!(explanation.conditions[1].matches, bitvec![0, 1, 0]);
+        assert!(!explanation.matches.any());
+        assert_eq!(
+            explanation.eliminated_by,
+            Some(NestedElimination {
+                clause: NestedClause::Must,
+                condition: Some(1),
+            })
+        );
+
+        // Per-condition matches are the ones used by the regular nested checkers
+        let checkers = nested_conditions_converter(
+            small_red.must.as_deref().unwrap(),
+            payload_provider.clone(),
+            &field_indexes,
+            nested_path.clone(),
+        );
+        for (checker, condition) in checkers.iter().zip(&explanation.conditions) {
+            assert_eq!(checker(0), condition.matches);
+        }
+
+        // Matching point has nothing eliminated
+        let explanation = explain(1, &small_red);
+        assert_eq!(explanation.matches, bitvec![1]);
+        assert_eq!(explanation.eliminated_by, None);
+
+        // Elements left by `must` are excluded by the second `must_not` condition
+        let not_blue_nor_large = Filter {
+            should: None,
+            must: Some(vec![color("red")]),
+            must_not: Some(vec![color("blue"), Condition::Field(size_at_least(10.0))]),
+        };
+        let explanation = explain(0, &not_blue_nor_large);
+        assert_eq!(explanation.clauses.len(), 2);
+        assert_eq!(explanation.clauses[0].remaining, bitvec![1, 0, 1]);
+        assert_eq!(explanation.clauses[1].matches, bitvec![0, 0, 0]);
+        assert_eq!(
+            explanation.eliminated_by,
+            Some(NestedElimination {
+                clause: NestedClause::MustNot,
+                condition: Some(1),
+            })
+        );
+
+        // None of the `should` conditions match the remaining elements
+        let green_or_yellow = Filter {
+            should: Some(vec![color("green"), color("yellow")]),
+            must: Some(vec![size_below(100.0)]),
+            must_not: None,
+        };
+        let explanation = explain(0, &green_or_yellow);
+        assert_eq!(
+            explanation.eliminated_by,
+            Some(NestedElimination {
+                clause: NestedClause::Should,
+                condition: None,
+            })
+        );
+
+        // Nothing to eliminate in an empty array
+        let explanation = explain(2, &small_red);
+        assert_eq!(explanation.elements, 0);
+        assert_eq!(explanation.eliminated_by, None);
+    }
+}
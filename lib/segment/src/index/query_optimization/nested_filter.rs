@@ -1,11 +1,26 @@
+use std::collections::HashMap;
+use std::ops::Range;
+use std::sync::atomic::AtomicBool;
+use std::sync::mpsc::SyncSender;
+
 use bitvec::prelude::*;
+use parking_lot::Mutex;
 
 use crate::common::utils::{IndexesMap, JsonPathPayload};
+use crate::entry::entry_point::{check_process_stopped, OperationResult};
 use crate::index::query_optimization::payload_provider::PayloadProvider;
+use crate::payload_storage::condition_checker::compile_regex;
 use crate::payload_storage::nested_query_checker::{
-    check_nested_is_empty_condition, check_nested_is_null_condition, nested_check_field_condition,
+    check_nested_comparison_condition, check_nested_expression_condition,
+    check_nested_has_key_condition, check_nested_is_empty_condition,
+    check_nested_is_null_condition, check_nested_position_condition,
+    check_nested_value_type_condition, nested_check_field_condition, nested_check_regex_condition,
+    nested_element_paths, nested_elements_count, nested_group_matches,
+};
+use crate::types::{
+    Condition, FieldCondition, Filter, InnerArrayMatch, Match, MatchRegex, NestedContainer,
+    NestedQuantifier, PointOffsetType, ScoreType,
 };
-use crate::types::{Condition, NestedContainer, PointOffsetType};
 
 /// Given a point_id, returns the list of nested indices matching the condition and the total number of nested elements in the payload
 type NestedMatchingIndicesFn<'a> = Box<dyn Fn(PointOffsetType) -> BitVec + 'a>;
@@ -15,6 +30,12 @@ pub fn find_indices_matching_all_conditions(
     point_id: PointOffsetType,
     nested_checkers: &[NestedMatchingIndicesFn],
 ) -> BitVec {
+    let _span = tracing::debug_span!(
+        "find_indices_matching_all_conditions",
+        point_id,
+        conditions = nested_checkers.len(),
+    )
+    .entered();
     nested_checkers
         .iter()
         .map(|checker| checker(point_id))
@@ -22,6 +43,120 @@ pub fn find_indices_matching_all_conditions(
         .unwrap_or_default()
 }
 
+/// Same as `find_indices_matching_all_conditions`, but checks `stopped` flag before evaluating
+/// each of the `nested_checkers`
+pub fn find_indices_matching_all_conditions_cancellable(
+    point_id: PointOffsetType,
+    nested_checkers: &[NestedMatchingIndicesFn],
+    stopped: &AtomicBool,
+) -> OperationResult<BitVec> {
+    let mut matches: Option<BitVec> = None;
+    for checker in nested_checkers {
+        check_process_stopped(stopped)?;
+        let indices = checker(point_id);
+        matches = Some(match matches {
+            None => indices,
+            Some(acc) => acc & indices,
+        });
+    }
+    Ok(matches.unwrap_or_default())
+}
+
+/// Select `points` which have at least one nested element matching all `nested_checkers`
+///
+/// Returns `Cancelled` error as soon as `stopped` flag is set, remaining points and conditions
+/// are not evaluated.
+pub fn find_points_matching_all_conditions(
+    points: impl IntoIterator<Item = PointOffsetType>,
+    nested_checkers: &[NestedMatchingIndicesFn],
+    stopped: &AtomicBool,
+) -> OperationResult<Vec<PointOffsetType>> {
+    let mut matched_points = vec![];
+    for point_id in points {
+        let matches =
+            find_indices_matching_all_conditions_cancellable(point_id, nested_checkers, stopped)?;
+        if matches.any() {
+            matched_points.push(point_id);
+        }
+    }
+    Ok(matched_points)
+}
+
+/// Lazily select `points` which have at least one nested element matching all `nested_checkers`
+///
+/// Same as `find_points_matching_all_conditions`, but points are evaluated only as the iterator
+/// is advanced. Yields a single `Cancelled` error once `stopped` flag is set.
+pub fn iter_points_matching_all_conditions<'a>(
+    points: impl IntoIterator<Item = PointOffsetType> + 'a,
+    nested_checkers: &'a [NestedMatchingIndicesFn<'a>],
+    stopped: &'a AtomicBool,
+) -> impl Iterator<Item = OperationResult<PointOffsetType>> + 'a {
+    let mut cancelled = false;
+    points
+        .into_iter()
+        .map_while(move |point_id| {
+            if cancelled {
+                return None;
+            }
+            match find_indices_matching_all_conditions_cancellable(
+                point_id,
+                nested_checkers,
+                stopped,
+            ) {
+                Ok(matches) => Some(Ok(matches.any().then_some(point_id))),
+                Err(err) => {
+                    cancelled = true;
+                    Some(Err(err))
+                }
+            }
+        })
+        .filter_map(Result::transpose)
+}
+
+/// Send `points` with at least one nested element matching all `nested_checkers` to `sender`
+///
+/// Points are evaluated lazily, when the bounded channel is full the evaluation blocks until the
+/// consumer receives pending points, so a slow consumer throttles the producer. Stops once the
+/// receiver is dropped, returns the number of sent points.
+///
+/// Only the segment-level producer, none of the APIs streams nested matches to clients yet:
+/// search and scroll responses are still materialized in full.
+pub fn stream_points_matching_all_conditions(
+    points: impl IntoIterator<Item = PointOffsetType>,
+    nested_checkers: &[NestedMatchingIndicesFn],
+    stopped: &AtomicBool,
+    sender: &SyncSender<PointOffsetType>,
+) -> OperationResult<usize> {
+    let mut sent = 0;
+    for point_id in iter_points_matching_all_conditions(points, nested_checkers, stopped) {
+        if sender.send(point_id?).is_err() {
+            break;
+        }
+        sent += 1;
+    }
+    Ok(sent)
+}
+
+/// Check which points of the `range` have at least one nested element matching all
+/// `nested_checkers`
+///
+/// Result is aligned with the range, the bit at position `i` is set if the point
+/// `range.start + i` matches. Allows to evaluate the filter page by page, continuing from the end
+/// of the previous range. Returns `Cancelled` error as soon as `stopped` flag is set.
+pub fn find_points_in_range_matching_all_conditions(
+    range: Range<PointOffsetType>,
+    nested_checkers: &[NestedMatchingIndicesFn],
+    stopped: &AtomicBool,
+) -> OperationResult<BitVec> {
+    let mut matched_points = BitVec::with_capacity(range.len());
+    for point_id in range {
+        let matches =
+            find_indices_matching_all_conditions_cancellable(point_id, nested_checkers, stopped)?;
+        matched_points.push(matches.any());
+    }
+    Ok(matched_points)
+}
+
 /// Apply `point_id` to `nested_checkers` and return the list of indices in the payload matching none of the conditions
 pub fn find_indices_matching_none_conditions(
     point_id: PointOffsetType,
@@ -34,15 +169,360 @@ pub fn find_indices_matching_none_conditions(
     combined_mask.map(|mask| !mask).unwrap_or_default()
 }
 
+/// Apply `point_id` to `must_checkers` and `must_not_checkers` and return the list of indices in
+/// the payload matching all of the former and none of the latter
+///
+/// Same as `find_indices_matching_all_conditions` combined with
+/// `find_indices_matching_none_conditions`, but matches are accumulated in a single bitvec instead
+/// of building a separate mask for `must_not`. Remaining checkers are skipped as soon as no element
+/// matches, as they can't change the result anymore.
+pub fn find_indices_matching_all_and_none_conditions(
+    point_id: PointOffsetType,
+    must_checkers: &[NestedMatchingIndicesFn],
+    must_not_checkers: &[NestedMatchingIndicesFn],
+) -> BitVec {
+    let mut must_checkers = must_checkers.iter();
+    let mut matches = match must_checkers.next() {
+        Some(checker) => checker(point_id),
+        None => return BitVec::default(),
+    };
+    for checker in must_checkers {
+        if !matches.any() {
+            return matches;
+        }
+        matches &= checker(point_id);
+    }
+    for checker in must_not_checkers {
+        if !matches.any() {
+            return matches;
+        }
+        matches &= !checker(point_id);
+    }
+    matches
+}
+
 /// Apply `point_id` to `nested_checkers` and return the list of indices in the payload matching any of the conditions
+///
+/// Array length is determined by the first non-empty result. Remaining checkers are skipped as
+/// soon as all elements of the array match, as they can't change the result anymore.
 pub fn find_indices_matching_any_conditions(
     point_id: PointOffsetType,
     nested_checkers: &[NestedMatchingIndicesFn],
 ) -> Option<BitVec> {
-    nested_checkers
+    let mut matches: Option<BitVec> = None;
+    for checker in nested_checkers {
+        if let Some(acc) = &matches {
+            if !acc.is_empty() && acc.all() {
+                break;
+            }
+        }
+        let indices = checker(point_id);
+        matches = Some(match matches {
+            Some(acc) if !acc.is_empty() => acc | indices,
+            _ => indices,
+        });
+    }
+    matches
+}
+
+/// Apply `point_id` to `nested_checkers` and return the list of indices in the payload matching
+/// at least `min_count` of the conditions
+///
+/// Unlike `min_should` of a filter, which counts matching clauses of the whole point, conditions
+/// are counted separately for each nested element. Elements missing from the result of a checker
+/// are considered as not matching its condition.
+pub fn find_indices_matching_at_least_conditions(
+    point_id: PointOffsetType,
+    nested_checkers: &[NestedMatchingIndicesFn],
+    min_count: usize,
+) -> BitVec {
+    let mut counts: Vec<usize> = vec![];
+    for checker in nested_checkers {
+        let indices = checker(point_id);
+        if counts.len() < indices.len() {
+            counts.resize(indices.len(), 0);
+        }
+        for index in indices.iter_ones() {
+            counts[index] += 1;
+        }
+    }
+    counts.into_iter().map(|count| count >= min_count).collect()
+}
+
+/// Estimate peak memory in bytes of the bitvecs allocated to evaluate nested `conditions`
+/// over an array of `estimated_array_len` elements
+///
+/// Every condition is assumed to produce a bitvec over the whole array, conditions of inner
+/// filters are counted separately. Allows to reject or downgrade overly expensive queries upfront.
+pub fn estimate_nested_memory(conditions: &[Condition], estimated_array_len: usize) -> usize {
+    let bits_per_word = usize::BITS as usize;
+    // Rounded up without overflowing for lengths close to `usize::MAX`
+    let words =
+        estimated_array_len / bits_per_word + usize::from(estimated_array_len % bits_per_word != 0);
+    let bitvec_size = words * std::mem::size_of::<usize>();
+    count_nested_bitvecs(conditions).saturating_mul(bitvec_size)
+}
+
+/// Number of bitvecs produced by `conditions`, including the ones of inner filters
+fn count_nested_bitvecs(conditions: &[Condition]) -> usize {
+    let count_filter = |filter: &Filter| -> usize {
+        [&filter.must, &filter.must_not, &filter.should]
+            .into_iter()
+            .flatten()
+            .map(|conditions| count_nested_bitvecs(conditions))
+            .sum()
+    };
+    conditions
         .iter()
-        .map(|checker| checker(point_id))
-        .reduce(|acc: BitVec, x: BitVec| acc | x)
+        .map(|condition| match condition {
+            Condition::Filter(filter) => 1 + count_filter(filter),
+            Condition::Nested(nested) => 1 + count_filter(nested.filter()),
+            _ => 1,
+        })
+        .sum()
+}
+
+/// Score contribution of the nested elements of `point_id` matching `filter`
+///
+/// Each element of the array located at `nested_path` matching the filter adds `weight` to the
+/// result, so a point without matching elements contributes nothing.
+pub fn nested_match_score(
+    point_id: PointOffsetType,
+    filter: &Filter,
+    field_indexes: &IndexesMap,
+    payload_provider: PayloadProvider,
+    nested_path: JsonPathPayload,
+    weight: ScoreType,
+) -> ScoreType {
+    let matches = check_nested_filter_clauses(
+        point_id,
+        filter,
+        field_indexes,
+        payload_provider,
+        nested_path,
+    );
+    matches.count_ones() as ScoreType * weight
+}
+
+/// Check that at least `min_count` of the independent `nested` filters match `point_id`
+///
+/// Each nested filter is evaluated against its own array and contributes a single boolean,
+/// so different filters may be satisfied by different elements or even different arrays.
+pub fn nested_min_match(
+    point_id: PointOffsetType,
+    nested: &[NestedContainer],
+    min_count: usize,
+    field_indexes: &IndexesMap,
+    payload_provider: PayloadProvider,
+) -> bool {
+    if min_count == 0 {
+        return true;
+    }
+    let mut matched = 0;
+    for container in nested {
+        let filter = container.element_filter();
+        let nested_path = JsonPathPayload::new(container.array_key());
+        let matches = check_nested_filter_clauses(
+            point_id,
+            &filter,
+            field_indexes,
+            payload_provider.clone(),
+            nested_path.clone(),
+        );
+        if nested_container_matches(
+            point_id,
+            container,
+            &filter,
+            &matches,
+            &payload_provider,
+            &nested_path,
+        ) {
+            matched += 1;
+            if matched >= min_count {
+                return true;
+            }
+        }
+    }
+    false
+}
+
+/// Check if `point_id` satisfies the `nested` condition, given the elements of the array at
+/// `nested_path` matching its `filter`
+///
+/// Array length is only read from the payload for the universal quantifiers.
+pub fn nested_container_matches(
+    point_id: PointOffsetType,
+    nested: &NestedContainer,
+    filter: &Filter,
+    matches: &BitVec,
+    payload_provider: &PayloadProvider,
+    nested_path: &JsonPathPayload,
+) -> bool {
+    match nested.quantifier() {
+        NestedQuantifier::Any => nested_group_matches(filter, matches),
+        quantifier => {
+            let elements = payload_provider.with_payload(point_id, |payload| {
+                nested_elements_count(nested_path, &payload)
+            });
+            quantifier.check(matches, elements)
+        }
+    }
+}
+
+/// First index used when reporting positions of matching nested elements
+///
+/// Internally positions are always 0-based, `One` is meant for clients expecting 1-based arrays.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum NestedPositionBase {
+    #[default]
+    Zero,
+    One,
+}
+
+impl NestedPositionBase {
+    fn offset(self) -> usize {
+        match self {
+            NestedPositionBase::Zero => 0,
+            NestedPositionBase::One => 1,
+        }
+    }
+}
+
+/// Convert matching nested elements into a list of positions starting at `base`
+pub fn nested_matches_to_positions(matches: &BitSlice, base: NestedPositionBase) -> Vec<usize> {
+    let offset = base.offset();
+    matches
+        .iter_ones()
+        .map(|position| position + offset)
+        .collect()
+}
+
+/// Convert matching nested elements into a list of `(start, end)` ranges of positions
+///
+/// `end` is exclusive. Contiguous matches collapse into a single range, which is more compact
+/// to transfer than the full bitvec or the list of positions.
+pub fn nested_matches_to_ranges(matches: &BitSlice) -> Vec<(usize, usize)> {
+    let mut ranges: Vec<(usize, usize)> = vec![];
+    for position in matches.iter_ones() {
+        match ranges.last_mut() {
+            Some((_, end)) if *end == position => *end += 1,
+            _ => ranges.push((position, position + 1)),
+        }
+    }
+    ranges
+}
+
+/// Positions of the matching nested elements, recorded by the traced evaluation for debugging
+///
+/// The caller shares the trace with the evaluation and reads the positions afterwards.
+/// Only points with at least one matching element are recorded.
+#[derive(Debug, Default)]
+pub struct NestedMatchTrace {
+    base: NestedPositionBase,
+    positions: Mutex<HashMap<PointOffsetType, Vec<usize>>>,
+}
+
+impl NestedMatchTrace {
+    pub fn new(base: NestedPositionBase) -> Self {
+        Self {
+            base,
+            positions: Default::default(),
+        }
+    }
+
+    /// Record matching elements of `point_id`, replacing the positions recorded before
+    pub fn record(&self, point_id: PointOffsetType, matches: &BitSlice) {
+        if matches.any() {
+            let positions = nested_matches_to_positions(matches, self.base);
+            self.positions.lock().insert(point_id, positions);
+        }
+    }
+
+    /// Positions of the matching elements of `point_id`, `None` if nothing matched
+    pub fn positions(&self, point_id: PointOffsetType) -> Option<Vec<usize>> {
+        self.positions.lock().get(&point_id).cloned()
+    }
+
+    /// Take all recorded positions, leaving the trace empty
+    pub fn take(&self) -> HashMap<PointOffsetType, Vec<usize>> {
+        std::mem::take(&mut *self.positions.lock())
+    }
+
+    /// Number of points with recorded positions
+    pub fn len(&self) -> usize {
+        self.positions.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Name of the condition, if it can't be evaluated for a single nested element
+///
+/// Such conditions are dropped by the nested filters instead of being evaluated.
+fn dropped_nested_condition_name(condition: &Condition) -> Option<&'static str> {
+    match condition {
+        Condition::HasId(_) => Some("has_id"),
+        Condition::VectorCount(_) => Some("vector_count"),
+        Condition::VectorNotNormalized(_) => Some("vector_not_normalized"),
+        Condition::Aggregate(_) => Some("aggregate"),
+        Condition::HasDuplicates(_) => Some("has_duplicates"),
+        Condition::Field(_)
+        | Condition::IsEmpty(_)
+        | Condition::IsNull(_)
+        | Condition::HasKey(_)
+        | Condition::ValueType(_)
+        | Condition::Position(_)
+        | Condition::Expression(_)
+        | Condition::Compare(_)
+        | Condition::Filter(_)
+        | Condition::Nested(_) => None,
+    }
+}
+
+/// Names of the conditions dropped by the nested `filter`, including its inner nested filters
+///
+/// Each name is reported once, in the order of the first occurrence.
+pub fn dropped_nested_conditions(filter: &Filter) -> Vec<&'static str> {
+    fn collect(filter: &Filter, names: &mut Vec<&'static str>) {
+        let conditions = [&filter.must, &filter.must_not, &filter.should]
+            .into_iter()
+            .flatten()
+            .flatten();
+        for condition in conditions {
+            match condition {
+                Condition::Filter(inner) => collect(inner, names),
+                Condition::Nested(nested) => collect(nested.filter(), names),
+                condition => {
+                    if let Some(name) = dropped_nested_condition_name(condition) {
+                        if !names.contains(&name) {
+                            names.push(name);
+                        }
+                    }
+                }
+            }
+        }
+    }
+    let mut names = vec![];
+    collect(filter, &mut names);
+    names
+}
+
+/// Log a warning if the filter of `nested` has conditions it drops
+///
+/// Called once per query, when the nested filter is prepared for evaluation, so the silently
+/// dropped conditions are visible to the operators.
+pub fn warn_dropped_nested_conditions(nested: &NestedContainer) {
+    let dropped = dropped_nested_conditions(nested.filter());
+    if !dropped.is_empty() {
+        log::warn!(
+            "Nested filter of `{}` has conditions not supported inside nested, \
+             which are dropped: {}",
+            nested.raw_key(),
+            dropped.join(", "),
+        );
+    }
 }
 
 pub fn nested_conditions_converter<'a>(
@@ -51,6 +531,12 @@ pub fn nested_conditions_converter<'a>(
     field_indexes: &'a IndexesMap,
     nested_path: JsonPathPayload,
 ) -> Vec<NestedMatchingIndicesFn<'a>> {
+    let _span = tracing::debug_span!(
+        "nested_conditions_converter",
+        conditions = conditions.len(),
+        nested_path = nested_path.path.as_str(),
+    )
+    .entered();
     conditions
         .iter()
         .map(|condition| {
@@ -71,6 +557,47 @@ pub fn nested_condition_converter<'a>(
     nested_path: JsonPathPayload,
 ) -> NestedMatchingIndicesFn<'a> {
     match condition {
+        // Regex is compiled once per query and evaluated for each element.
+        // Other clauses of the same field condition are combined with it as usual, any must match.
+        // Inner arrays matched by all of their values are left to the generic field check
+        Condition::Field(
+            field_condition @ FieldCondition {
+                r#match: Some(Match::Regex(MatchRegex { regex })),
+                inner_match: None | Some(InnerArrayMatch::Any),
+                ..
+            },
+        ) => {
+            let regex = compile_regex(regex);
+            let rest = FieldCondition {
+                r#match: None,
+                ..field_condition.clone()
+            };
+            let has_rest = rest.range.is_some()
+                || rest.geo_bounding_box.is_some()
+                || rest.geo_radius.is_some()
+                || rest.values_count.is_some();
+            Box::new(move |point_id| {
+                payload_provider.with_payload(point_id, |payload| {
+                    let matches = nested_check_regex_condition(
+                        &nested_path,
+                        &field_condition.key,
+                        regex.as_ref(),
+                        &payload,
+                    );
+                    if has_rest {
+                        matches
+                            | nested_check_field_condition(
+                                &rest,
+                                &payload,
+                                &nested_path,
+                                field_indexes,
+                            )
+                    } else {
+                        matches
+                    }
+                })
+            })
+        }
         Condition::Field(field_condition) => {
             // Do not rely on existing indexes for nested fields because
             // they are not retaining the structure of the nested fields (flatten vs unflatten)
@@ -96,75 +623,213 @@ pub fn nested_condition_converter<'a>(
                 check_nested_is_null_condition(&nested_path, is_null, &payload)
             })
         }),
-        Condition::HasId(_) => {
-            // No support for has_id in nested queries
-            Box::new(move |_| BitVec::default())
-        }
+        Condition::HasKey(has_key) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_nested_has_key_condition(&nested_path, has_key, &payload)
+            })
+        }),
+        // Point level conditions can't be evaluated for a single element, no element matches.
+        // Ids and vectors are not part of nested objects, aggregates and duplicates are checked
+        // for the whole array. Result still has an entry per element, to be combined with others
+        Condition::HasId(_)
+        | Condition::VectorCount(_)
+        | Condition::VectorNotNormalized(_)
+        | Condition::Aggregate(_)
+        | Condition::HasDuplicates(_) => Box::new(move |point_id| {
+            payload_provider.with_payload(
+                point_id,
+                |payload| bitvec![0; nested_elements_count(&nested_path, &payload)],
+            )
+        }),
+        Condition::ValueType(value_type) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_nested_value_type_condition(&nested_path, value_type, &payload)
+            })
+        }),
+        Condition::Position(position) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_nested_position_condition(&nested_path, position, &payload)
+            })
+        }),
+        Condition::Expression(expression) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_nested_expression_condition(&nested_path, expression, &payload)
+            })
+        }),
+        Condition::Compare(compare) => Box::new(move |point_id| {
+            payload_provider.with_payload(point_id, |payload| {
+                check_nested_comparison_condition(&nested_path, compare, &payload)
+            })
+        }),
+        // Element of the outer array matches if any element of its own inner array matches
         Condition::Nested(nested) => {
+            let nested_filter = nested.element_filter();
             Box::new(move |point_id| {
-                let mut bitvecs = Vec::with_capacity(3);
+                let element_paths = payload_provider.with_payload(point_id, |payload| {
+                    nested_element_paths(&nested_path, &payload)
+                });
+                element_paths
+                    .into_iter()
+                    .map(|element_path| {
+                        let inner_path = element_path.extend(&nested.array_key());
+                        let matches = check_nested_filter_clauses(
+                            point_id,
+                            &nested_filter,
+                            field_indexes,
+                            payload_provider.clone(),
+                            inner_path.clone(),
+                        );
+                        nested_container_matches(
+                            point_id,
+                            nested,
+                            &nested_filter,
+                            &matches,
+                            &payload_provider,
+                            &inner_path,
+                        )
+                    })
+                    .collect()
+            })
+        }
+        // A group of conditions applied to the same nested element,
+        // allows to express `(A AND B) OR (C AND D)` with `should` of groups
+        Condition::Filter(filter) => Box::new(move |point_id| {
+            check_nested_filter_clauses(
+                point_id,
+                filter,
+                field_indexes,
+                payload_provider.clone(),
+                nested_path.clone(),
+            )
+        }),
+    }
+}
 
-                // must
-                let must_matching = check_nested_must(
-                    point_id,
-                    nested,
-                    field_indexes,
-                    payload_provider.clone(),
-                    nested_path.clone(),
-                );
-                if let Some(must_matching) = must_matching {
-                    bitvecs.push(must_matching);
-                }
+/// Same as `check_nested_filter_clauses`, also recording the matching positions into `trace`
+///
+/// Evaluation is not traced if `trace` is `None`, so the caller can toggle debugging without
+/// switching between the functions.
+pub fn check_nested_filter_clauses_traced(
+    point_id: PointOffsetType,
+    filter: &Filter,
+    field_indexes: &IndexesMap,
+    payload_provider: PayloadProvider,
+    full_path: JsonPathPayload,
+    trace: Option<&NestedMatchTrace>,
+) -> BitVec {
+    let matches =
+        check_nested_filter_clauses(point_id, filter, field_indexes, payload_provider, full_path);
+    if let Some(trace) = trace {
+        trace.record(point_id, &matches);
+    }
+    matches
+}
 
-                // must_not
-                let must_not_matching = check_nested_must_not(
-                    point_id,
-                    nested,
-                    field_indexes,
-                    payload_provider.clone(),
-                    &nested_path,
-                );
-                if let Some(must_not_matching) = must_not_matching {
-                    bitvecs.push(must_not_matching);
-                }
+/// Combine `must`, `must_not` and `should` clauses of the `filter` into the list of matching
+/// elements of the array located at `full_path`
+pub fn check_nested_filter_clauses(
+    point_id: PointOffsetType,
+    filter: &Filter,
+    field_indexes: &IndexesMap,
+    payload_provider: PayloadProvider,
+    full_path: JsonPathPayload,
+) -> BitVec {
+    let mut bitvecs = Vec::with_capacity(3);
 
-                // should
-                let should_matching = check_nested_should(
-                    point_id,
-                    nested,
-                    field_indexes,
-                    payload_provider.clone(),
-                    nested_path.clone(),
-                );
-                if let Some(should_matching) = should_matching {
-                    bitvecs.push(should_matching);
-                }
+    match (&filter.must, &filter.must_not) {
+        // required and forbidden conditions of the same elements are evaluated in a single pass
+        (Some(must_conditions), Some(must_not_conditions)) => {
+            let must_checkers = nested_conditions_converter(
+                must_conditions,
+                payload_provider.clone(),
+                field_indexes,
+                full_path.clone(),
+            );
+            let must_not_checkers = nested_conditions_converter(
+                must_not_conditions,
+                payload_provider.clone(),
+                field_indexes,
+                full_path.clone(),
+            );
+            bitvecs.push(find_indices_matching_all_and_none_conditions(
+                point_id,
+                &must_checkers,
+                &must_not_checkers,
+            ));
+        }
+        _ => {
+            // must
+            let must_matching = check_nested_must(
+                point_id,
+                filter,
+                field_indexes,
+                payload_provider.clone(),
+                full_path.clone(),
+            );
+            if let Some(must_matching) = must_matching {
+                bitvecs.push(must_matching);
+            }
 
-                // combine all bitvecs
-                bitvecs
-                    .into_iter()
-                    .reduce(|acc, x| {
-                        debug_assert_eq!(acc.len(), x.len());
-                        acc & x
-                    })
-                    .unwrap_or_default()
-            })
+            // must_not
+            let must_not_matching = check_nested_must_not(
+                point_id,
+                filter,
+                field_indexes,
+                payload_provider.clone(),
+                full_path.clone(),
+            );
+            if let Some(must_not_matching) = must_not_matching {
+                bitvecs.push(must_not_matching);
+            }
         }
-        Condition::Filter(_) => unreachable!(),
     }
+
+    // should
+    let should_matching =
+        check_nested_should(point_id, filter, field_indexes, payload_provider, full_path);
+    if let Some(should_matching) = should_matching {
+        bitvecs.push(should_matching);
+    }
+
+    // combine all bitvecs
+    bitvecs
+        .into_iter()
+        .reduce(|acc, x| {
+            debug_assert_eq!(acc.len(), x.len());
+            acc & x
+        })
+        .unwrap_or_default()
+}
+
+/// Mask of the elements of the array located at `full_path` matching none of the `conditions`,
+/// same as selected by the `must_not` clause of a nested filter
+///
+/// Mask of empty `conditions` is empty, as the array length is determined by evaluating them.
+pub fn nested_must_not_mask(
+    point_id: PointOffsetType,
+    conditions: &[Condition],
+    payload_provider: PayloadProvider,
+    field_indexes: &IndexesMap,
+    full_path: JsonPathPayload,
+) -> BitVec {
+    if conditions.is_empty() {
+        return BitVec::new();
+    }
+    let nested_checkers =
+        nested_conditions_converter(conditions, payload_provider, field_indexes, full_path);
+    find_indices_matching_none_conditions(point_id, &nested_checkers)
 }
 
 fn check_nested_must(
     point_id: PointOffsetType,
-    nested: &NestedContainer,
+    filter: &Filter,
     field_indexes: &IndexesMap,
     payload_provider: PayloadProvider,
-    nested_path: JsonPathPayload,
+    full_path: JsonPathPayload,
 ) -> Option<BitVec> {
-    match &nested.filter().must {
+    match &filter.must {
         None => None,
         Some(musts_conditions) => {
-            let full_path = nested_path.extend(&nested.array_key());
             let nested_checkers = nested_conditions_converter(
                 musts_conditions,
                 payload_provider,
@@ -179,40 +844,42 @@ fn check_nested_must(
 
 fn check_nested_must_not(
     point_id: PointOffsetType,
-    nested: &NestedContainer,
+    filter: &Filter,
     field_indexes: &IndexesMap,
     payload_provider: PayloadProvider,
-    nested_path: &JsonPathPayload,
+    full_path: JsonPathPayload,
 ) -> Option<BitVec> {
-    match &nested.filter().must_not {
+    match &filter.must_not {
         None => None,
-        Some(musts_not_conditions) => {
-            let full_path = nested_path.extend(&nested.array_key());
-            let matching_indices = nested_conditions_converter(
-                musts_not_conditions,
-                payload_provider,
-                field_indexes,
-                full_path,
-            );
-            let matches = find_indices_matching_none_conditions(point_id, &matching_indices);
-            Some(matches)
-        }
+        Some(musts_not_conditions) => Some(nested_must_not_mask(
+            point_id,
+            musts_not_conditions,
+            payload_provider,
+            field_indexes,
+            full_path,
+        )),
     }
 }
 
 fn check_nested_should(
     point_id: PointOffsetType,
-    nested: &NestedContainer,
+    filter: &Filter,
     field_indexes: &IndexesMap,
     payload_provider: PayloadProvider,
-    nested_path: JsonPathPayload,
+    full_path: JsonPathPayload,
 ) -> Option<BitVec> {
-    match &nested.filter().should {
+    match &filter.should {
         None => None,
-        Some(musts_not_conditions) => {
-            let full_path = nested_path.extend(&nested.array_key());
+        // Empty clause has no condition to satisfy, so no element matches, same as on top level
+        Some(should_conditions) if should_conditions.is_empty() => {
+            let elements = payload_provider.with_payload(point_id, |payload| {
+                nested_elements_count(&full_path, &payload)
+            });
+            Some(bitvec![0; elements])
+        }
+        Some(should_conditions) => {
             let matching_indices = nested_conditions_converter(
-                musts_not_conditions,
+                should_conditions,
                 payload_provider,
                 field_indexes,
                 full_path,
@@ -221,3 +888,1147 @@ fn check_nested_should(
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::{HashMap, HashSet};
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use atomic_refcell::AtomicRefCell;
+    use rand::prelude::StdRng;
+    use rand::{Rng, SeedableRng};
+    use serde_json::json;
+
+    use super::*;
+    use crate::entry::entry_point::OperationError;
+    use crate::fixtures::tracing_fixtures::SpanCapture;
+    use crate::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
+    use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
+    use crate::payload_storage::PayloadStorage;
+    use crate::types::{
+        ArithmeticOperator, Expression, ExpressionCondition, NestedCondition, Payload, PointIdType,
+        Range,
+    };
+
+    #[test]
+    fn test_nested_evaluation_cancellation() {
+        let stopped = AtomicBool::new(false);
+        let invocations = AtomicUsize::new(0);
+
+        // Every element matches, the flag is raised on the 5th invocation
+        let checker = |_point_id: PointOffsetType| {
+            let invocation = invocations.fetch_add(1, Ordering::SeqCst) + 1;
+            if invocation == 5 {
+                stopped.store(true, Ordering::Relaxed);
+            }
+            bitvec![1; 2]
+        };
+        let nested_checkers: Vec<NestedMatchingIndicesFn> =
+            vec![Box::new(checker), Box::new(checker)];
+
+        // Not cancelled - all points are evaluated
+        let never_stopped = AtomicBool::new(false);
+        let matched =
+            find_points_matching_all_conditions(0..2, &nested_checkers, &never_stopped).unwrap();
+        assert_eq!(matched, vec![0, 1]);
+        assert_eq!(invocations.load(Ordering::SeqCst), 4);
+
+        // Cancelled in the middle of the evaluation of the first point
+        let result = find_points_matching_all_conditions(0..100, &nested_checkers, &stopped);
+        assert!(matches!(result, Err(OperationError::Cancelled { .. })));
+        assert_eq!(invocations.load(Ordering::SeqCst), 5);
+
+        // Already cancelled evaluation does not invoke checkers at all
+        let result = find_points_matching_all_conditions(0..100, &nested_checkers, &stopped);
+        assert!(matches!(result, Err(OperationError::Cancelled { .. })));
+        assert_eq!(invocations.load(Ordering::SeqCst), 5);
+    }
+
+    #[test]
+    fn test_stream_nested_matches_with_backpressure() {
+        let evaluations = Arc::new(AtomicUsize::new(0));
+        let (sender, receiver) = std::sync::mpsc::sync_channel(2);
+
+        let producer = {
+            let evaluations = evaluations.clone();
+            std::thread::spawn(move || {
+                // Every odd point matches
+                let checker = move |point_id: PointOffsetType| {
+                    evaluations.fetch_add(1, Ordering::SeqCst);
+                    BitVec::repeat(point_id % 2 == 1, 1)
+                };
+                let nested_checkers: Vec<NestedMatchingIndicesFn> = vec![Box::new(checker)];
+                let stopped = AtomicBool::new(false);
+                stream_points_matching_all_conditions(0..100, &nested_checkers, &stopped, &sender)
+            })
+        };
+
+        let wait_for_evaluations = |expected: usize| {
+            for _ in 0..1000 {
+                if evaluations.load(Ordering::SeqCst) >= expected {
+                    break;
+                }
+                std::thread::sleep(std::time::Duration::from_millis(1));
+            }
+            // Give the producer a chance to run ahead, if it is not blocked
+            std::thread::sleep(std::time::Duration::from_millis(50));
+            evaluations.load(Ordering::SeqCst)
+        };
+
+        // Producer is blocked on the third match, with two matches in the channel
+        assert_eq!(wait_for_evaluations(6), 6);
+        assert_eq!(receiver.recv().unwrap(), 1);
+        assert_eq!(wait_for_evaluations(8), 8);
+        assert_eq!(receiver.recv().unwrap(), 3);
+        assert_eq!(receiver.recv().unwrap(), 5);
+        assert_eq!(wait_for_evaluations(12), 12);
+
+        // Producer stops once the consumer is gone
+        drop(receiver);
+        assert_eq!(producer.join().unwrap().unwrap(), 5);
+        assert_eq!(evaluations.load(Ordering::SeqCst), 12);
+    }
+
+    #[test]
+    fn test_iter_nested_matches_lazily() {
+        let evaluations = AtomicUsize::new(0);
+        let checker = |point_id: PointOffsetType| {
+            evaluations.fetch_add(1, Ordering::SeqCst);
+            BitVec::repeat(point_id % 3 == 0, 1)
+        };
+        let nested_checkers: Vec<NestedMatchingIndicesFn> = vec![Box::new(checker)];
+        let stopped = AtomicBool::new(false);
+
+        let mut matches = iter_points_matching_all_conditions(0..100, &nested_checkers, &stopped);
+        assert_eq!(evaluations.load(Ordering::SeqCst), 0);
+        assert_eq!(matches.next().unwrap().unwrap(), 0);
+        assert_eq!(matches.next().unwrap().unwrap(), 3);
+        assert_eq!(evaluations.load(Ordering::SeqCst), 4);
+
+        stopped.store(true, Ordering::Relaxed);
+        assert!(matches!(
+            matches.next(),
+            Some(Err(OperationError::Cancelled { .. }))
+        ));
+        assert!(matches.next().is_none());
+        assert_eq!(evaluations.load(Ordering::SeqCst), 4);
+    }
+
+    #[test]
+    fn test_nested_evaluation_over_range() {
+        let mut payload_storage: PayloadStorageEnum = InMemoryPayloadStorage::default().into();
+        for point_id in 0..10 {
+            let color = if point_id % 3 == 0 { "red" } else { "blue" };
+            let payload: Payload = json!({
+                "items": [{ "color": "green" }, { "color": color }]
+            })
+            .into();
+            payload_storage.assign(point_id, &payload).unwrap();
+        }
+        let payload_provider = PayloadProvider::new(Arc::new(AtomicRefCell::new(payload_storage)));
+        let field_indexes = IndexesMap::default();
+        let red = [Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "red".to_string().into(),
+        ))];
+        let nested_checkers = nested_conditions_converter(
+            &red,
+            payload_provider,
+            &field_indexes,
+            JsonPathPayload::new("items[]".to_string()),
+        );
+        let stopped = AtomicBool::new(false);
+
+        // Bits are aligned with the start of the range, not with point offsets
+        let matches =
+            find_points_in_range_matching_all_conditions(4..10, &nested_checkers, &stopped)
+                .unwrap();
+        assert_eq!(matches, bitvec![0, 0, 1, 0, 0, 1]);
+
+        // Consecutive pages are the same as a single scan over the whole range
+        let whole = find_points_in_range_matching_all_conditions(0..10, &nested_checkers, &stopped)
+            .unwrap();
+        let mut paged = BitVec::new();
+        for page in [0..3, 3..7, 7..10] {
+            paged.extend(
+                find_points_in_range_matching_all_conditions(page, &nested_checkers, &stopped)
+                    .unwrap(),
+            );
+        }
+        assert_eq!(paged, whole);
+        let matched: Vec<PointOffsetType> =
+            whole.iter_ones().map(|i| i as PointOffsetType).collect();
+        let expected =
+            find_points_matching_all_conditions(0..10, &nested_checkers, &stopped).unwrap();
+        assert_eq!(matched, expected);
+
+        // Empty range has nothing to evaluate
+        let matches =
+            find_points_in_range_matching_all_conditions(5..5, &nested_checkers, &stopped).unwrap();
+        assert!(matches.is_empty());
+
+        stopped.store(true, Ordering::Relaxed);
+        let result =
+            find_points_in_range_matching_all_conditions(0..10, &nested_checkers, &stopped);
+        assert!(matches!(result, Err(OperationError::Cancelled { .. })));
+    }
+
+    #[test]
+    fn test_nested_empty_should() {
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([
+            (
+                0,
+                json!({ "items": [{ "color": "red" }, { "color": "blue" }, { "color": "red" }] }),
+            ),
+            (1, json!({ "items": [] })),
+            (2, json!({})),
+        ]));
+        let field_indexes = IndexesMap::default();
+        let red = Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "red".to_string().into(),
+        ));
+        let check = |point_id, filter: &Filter| {
+            check_nested_filter_clauses(
+                point_id,
+                filter,
+                &field_indexes,
+                payload_provider.clone(),
+                JsonPathPayload::new("items[]".to_string()),
+            )
+        };
+
+        let red_with_empty_should = Filter {
+            should: Some(vec![]),
+            must: Some(vec![red.clone()]),
+            must_not: None,
+        };
+        let red_only = Filter::new_must(red);
+        let empty_should = Filter {
+            should: Some(vec![]),
+            must: None,
+            must_not: None,
+        };
+
+        // Nothing matches an empty clause, positions of the elements are kept
+        assert_eq!(check(0, &red_with_empty_should), bitvec![0, 0, 0]);
+        assert_eq!(check(0, &empty_should), bitvec![0, 0, 0]);
+        assert_eq!(check(0, &red_only), bitvec![1, 0, 1]);
+
+        // Empty and missing arrays have no elements either way
+        for point_id in [1, 2] {
+            assert!(!check(point_id, &empty_should).any());
+        }
+    }
+
+    #[test]
+    fn test_nested_unsupported_conditions() {
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([(
+            0,
+            json!({ "items": [{ "color": "red" }, { "color": "blue" }, { "color": "red" }] }),
+        )]));
+        let field_indexes = IndexesMap::default();
+        let red = Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "red".to_string().into(),
+        ));
+        let has_id = Condition::HasId(HashSet::from([PointIdType::NumId(0)]).into());
+        let check = |filter: &Filter| {
+            check_nested_filter_clauses(
+                0,
+                filter,
+                &field_indexes,
+                payload_provider.clone(),
+                JsonPathPayload::new("items[]".to_string()),
+            )
+        };
+
+        // No element matches a point level condition, but all of them are listed
+        let unsupported_checkers = nested_conditions_converter(
+            std::slice::from_ref(&has_id),
+            payload_provider.clone(),
+            &field_indexes,
+            JsonPathPayload::new("items[]".to_string()),
+        );
+        assert_eq!(unsupported_checkers[0](0), bitvec![0, 0, 0]);
+
+        let red_and_not_has_id = Filter {
+            should: None,
+            must: Some(vec![red.clone()]),
+            must_not: Some(vec![has_id.clone()]),
+        };
+        assert_eq!(check(&red_and_not_has_id), bitvec![1, 0, 1]);
+
+        let red_or_not_has_id = Filter {
+            should: Some(vec![red.clone()]),
+            must: None,
+            must_not: Some(vec![has_id.clone()]),
+        };
+        assert_eq!(check(&red_or_not_has_id), bitvec![1, 0, 1]);
+
+        let red_and_has_id = Filter {
+            should: Some(vec![has_id]),
+            must: Some(vec![red]),
+            must_not: None,
+        };
+        assert_eq!(check(&red_and_has_id), bitvec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_nested_arrays_inside_nested_arrays() {
+        let mut payload_storage: PayloadStorageEnum = InMemoryPayloadStorage::default().into();
+        let payload: Payload = json!({
+            "orders": [
+                { "items": [{ "name": "pear" }, { "name": "plum" }] },
+                { "items": [{ "name": "pear" }, { "name": "apple" }] },
+                { "items": [] },
+            ]
+        })
+        .into();
+        payload_storage.assign(0, &payload).unwrap();
+        let payload_provider = PayloadProvider::new(Arc::new(AtomicRefCell::new(payload_storage)));
+        let field_indexes = IndexesMap::default();
+
+        let has_apple = Condition::new_nested(
+            "items",
+            Filter::new_must(Condition::Field(FieldCondition::new_match(
+                "name".to_string(),
+                "apple".to_string().into(),
+            ))),
+        );
+        let checker = nested_condition_converter(
+            &has_apple,
+            payload_provider,
+            &field_indexes,
+            JsonPathPayload::new("orders[]".to_string()),
+        );
+
+        // One bit per order, not per item of all orders
+        assert_eq!(checker(0), bitvec![0, 1, 0]);
+        // Point without payload has no orders
+        assert_eq!(checker(1), BitVec::default());
+    }
+
+    #[test]
+    fn test_find_indices_matching_any_conditions_short_circuit() {
+        fn counting_checker(evaluated: &AtomicUsize, indices: BitVec) -> NestedMatchingIndicesFn {
+            Box::new(move |_| {
+                evaluated.fetch_add(1, Ordering::Relaxed);
+                indices.clone()
+            })
+        }
+        let evaluated = AtomicUsize::new(0);
+        let checker = |indices: BitVec| counting_checker(&evaluated, indices);
+        let saturated: NestedMatchingIndicesFn =
+            Box::new(|_| panic!("checker evaluated after all elements matched"));
+
+        // Empty result doesn't define the array length
+        let nested_checkers = vec![
+            checker(BitVec::new()),
+            checker(bitvec![1, 0, 0]),
+            checker(bitvec![0, 1, 1]),
+            saturated,
+        ];
+        let matches = find_indices_matching_any_conditions(0, &nested_checkers);
+        assert_eq!(matches, Some(bitvec![1, 1, 1]));
+        assert_eq!(evaluated.load(Ordering::Relaxed), 3);
+
+        // Not saturated, all checkers are evaluated
+        evaluated.store(0, Ordering::Relaxed);
+        let nested_checkers = vec![
+            checker(bitvec![1, 0, 0]),
+            checker(bitvec![0, 0, 0]),
+            checker(bitvec![0, 0, 1]),
+        ];
+        let matches = find_indices_matching_any_conditions(0, &nested_checkers);
+        assert_eq!(matches, Some(bitvec![1, 0, 1]));
+        assert_eq!(evaluated.load(Ordering::Relaxed), 3);
+
+        assert_eq!(find_indices_matching_any_conditions(0, &[]), None);
+        let nested_checkers = vec![checker(BitVec::new()), checker(BitVec::new())];
+        assert_eq!(
+            find_indices_matching_any_conditions(0, &nested_checkers),
+            Some(BitVec::new())
+        );
+    }
+
+    #[test]
+    fn test_find_indices_matching_all_and_none_conditions() {
+        let mut rng = StdRng::seed_from_u64(42);
+        let colors = ["red", "green", "blue"];
+        let payloads: HashMap<PointOffsetType, serde_json::Value> = (0..100)
+            .map(|point_id| {
+                let items: Vec<_> = (0..rng.gen_range(0..8))
+                    .map(|_| {
+                        json!({
+                            "color": colors[rng.gen_range(0..colors.len())],
+                            "size": rng.gen_range(0..10),
+                            "tag": colors[rng.gen_range(0..colors.len())],
+                        })
+                    })
+                    .collect();
+                (point_id, json!({ "items": items }))
+            })
+            .collect();
+        let payload_provider = PayloadProvider::in_memory(payloads);
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let must = vec![
+            Condition::Field(FieldCondition::new_match(
+                "color".to_string(),
+                "red".to_string().into(),
+            )),
+            Condition::Field(FieldCondition::new_range(
+                "size".to_string(),
+                Range {
+                    lt: Some(7.0),
+                    gt: None,
+                    gte: None,
+                    lte: None,
+                },
+            )),
+        ];
+        let must_not = vec![
+            Condition::Field(FieldCondition::new_match(
+                "tag".to_string(),
+                "blue".to_string().into(),
+            )),
+            Condition::Field(FieldCondition::new_range(
+                "size".to_string(),
+                Range {
+                    lt: Some(2.0),
+                    gt: None,
+                    gte: None,
+                    lte: None,
+                },
+            )),
+        ];
+        let must_checkers = nested_conditions_converter(
+            &must,
+            payload_provider.clone(),
+            &field_indexes,
+            nested_path.clone(),
+        );
+        let must_not_checkers = nested_conditions_converter(
+            &must_not,
+            payload_provider.clone(),
+            &field_indexes,
+            nested_path.clone(),
+        );
+
+        let mut matched_points = 0;
+        for point_id in 0..100 {
+            let separate = find_indices_matching_all_conditions(point_id, &must_checkers)
+                & find_indices_matching_none_conditions(point_id, &must_not_checkers);
+            let combined = find_indices_matching_all_and_none_conditions(
+                point_id,
+                &must_checkers,
+                &must_not_checkers,
+            );
+            assert_eq!(combined, separate, "point {point_id}");
+            if combined.any() {
+                matched_points += 1;
+            }
+        }
+        assert!(matched_points > 0);
+
+        // Filter with both clauses is evaluated the same way
+        let filter = Filter {
+            should: None,
+            must: Some(must.clone()),
+            must_not: Some(must_not.clone()),
+        };
+        for point_id in 0..100 {
+            assert_eq!(
+                check_nested_filter_clauses(
+                    point_id,
+                    &filter,
+                    &field_indexes,
+                    payload_provider.clone(),
+                    nested_path.clone(),
+                ),
+                find_indices_matching_all_and_none_conditions(
+                    point_id,
+                    &must_checkers,
+                    &must_not_checkers,
+                ),
+            );
+        }
+
+        // Checkers are not evaluated once no element is left
+        fn counting(matches: BitVec, invocations: &AtomicUsize) -> NestedMatchingIndicesFn<'_> {
+            Box::new(move |_| {
+                invocations.fetch_add(1, Ordering::SeqCst);
+                matches.clone()
+            })
+        }
+        let invocations = AtomicUsize::new(0);
+        let must_checkers = vec![
+            counting(bitvec![1, 0], &invocations),
+            counting(bitvec![0, 1], &invocations),
+        ];
+        let must_not_checkers = vec![counting(bitvec![0, 0], &invocations)];
+        let matches =
+            find_indices_matching_all_and_none_conditions(0, &must_checkers, &must_not_checkers);
+        assert_eq!(matches, bitvec![0, 0]);
+        assert_eq!(invocations.load(Ordering::SeqCst), 2);
+    }
+
+    #[test]
+    fn test_find_indices_matching_at_least_conditions() {
+        let nested_checkers: Vec<NestedMatchingIndicesFn> = vec![
+            Box::new(|_| bitvec![1, 1, 0, 0]),
+            Box::new(|_| bitvec![1, 0, 1, 0]),
+            // Shorter result, last elements do not match
+            Box::new(|_| bitvec![1, 1]),
+        ];
+
+        // 2 of 3 conditions per element
+        assert_eq!(
+            find_indices_matching_at_least_conditions(0, &nested_checkers, 2),
+            bitvec![1, 1, 0, 0]
+        );
+        // Element matching only a single condition is selected with K=1
+        assert_eq!(
+            find_indices_matching_at_least_conditions(0, &nested_checkers, 1),
+            bitvec![1, 1, 1, 0]
+        );
+        // K=N is the same as matching all conditions
+        assert_eq!(
+            find_indices_matching_at_least_conditions(0, &nested_checkers, 3),
+            bitvec![1, 0, 0, 0]
+        );
+        // Per element counting differs from counting matched conditions of the whole point:
+        // every condition matches some element, but no element matches 2 of them
+        let disjoint: Vec<NestedMatchingIndicesFn> = vec![
+            Box::new(|_| bitvec![1, 0, 0]),
+            Box::new(|_| bitvec![0, 1, 0]),
+            Box::new(|_| bitvec![0, 0, 1]),
+        ];
+        assert!(!find_indices_matching_at_least_conditions(0, &disjoint, 2).any());
+    }
+
+    #[test]
+    fn test_nested_tracing_spans() {
+        let mut payload_storage: PayloadStorageEnum = InMemoryPayloadStorage::default().into();
+        let payload: Payload = json!({
+            "items": [{ "name": "pear", "price": 1 }, { "name": "apple", "price": 2 }]
+        })
+        .into();
+        payload_storage.assign(7, &payload).unwrap();
+        let payload_provider = PayloadProvider::new(Arc::new(AtomicRefCell::new(payload_storage)));
+        let field_indexes = IndexesMap::default();
+
+        let conditions = vec![
+            Condition::Field(FieldCondition::new_match(
+                "name".to_string(),
+                "apple".to_string().into(),
+            )),
+            Condition::Field(FieldCondition::new_match(
+                "price".to_string(),
+                Match::from(2_i64),
+            )),
+        ];
+
+        let capture = SpanCapture::default();
+        let matches = tracing::subscriber::with_default(capture.clone(), || {
+            let nested_checkers = nested_conditions_converter(
+                &conditions,
+                payload_provider,
+                &field_indexes,
+                JsonPathPayload::new("items[]".to_string()),
+            );
+            find_indices_matching_all_conditions(7, &nested_checkers)
+        });
+        assert_eq!(matches, bitvec![0, 1]);
+
+        let converter_spans = capture.spans("nested_conditions_converter");
+        assert_eq!(converter_spans.len(), 1);
+        assert_eq!(converter_spans[0].fields["conditions"], "2");
+        assert_eq!(converter_spans[0].fields["nested_path"], "items[]");
+
+        let matching_spans = capture.spans("find_indices_matching_all_conditions");
+        assert_eq!(matching_spans.len(), 1);
+        assert_eq!(matching_spans[0].fields["point_id"], "7");
+        assert_eq!(matching_spans[0].fields["conditions"], "2");
+    }
+
+    #[test]
+    fn test_nested_regex_condition() {
+        let mut payload_storage: PayloadStorageEnum = InMemoryPayloadStorage::default().into();
+        let payload: Payload = json!({
+            "products": [
+                { "sku": "AB-100" },
+                { "sku": "CD-200" },
+                { "sku": ["XY-1", "AB-7"] },
+                { "sku": 100 },
+            ]
+        })
+        .into();
+        payload_storage.assign(0, &payload).unwrap();
+        let payload_provider = PayloadProvider::new(Arc::new(AtomicRefCell::new(payload_storage)));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("products[]".to_string());
+
+        let checker = |pattern: &str| {
+            let condition = Condition::Field(FieldCondition::new_match(
+                "sku".to_string(),
+                Match::new_regex(pattern),
+            ));
+            nested_condition_converter(
+                &condition,
+                payload_provider.clone(),
+                &field_indexes,
+                nested_path.clone(),
+            )(0)
+        };
+
+        assert_eq!(checker(r"^AB-\d+$"), bitvec![1, 0, 1, 0]);
+        assert_eq!(checker(r"\d{3}$"), bitvec![1, 1, 0, 0]);
+        // Invalid pattern matches nothing
+        assert_eq!(checker(r"(AB"), bitvec![0, 0, 0, 0]);
+    }
+
+    #[test]
+    fn test_nested_expression_condition() {
+        let mut payload_storage: PayloadStorageEnum = InMemoryPayloadStorage::default().into();
+        let payload: Payload = json!({
+            "orders": [
+                { "price": 30, "quantity": 4 },
+                { "price": 50, "quantity": 1 },
+                { "price": 25, "quantity": 5 },
+            ]
+        })
+        .into();
+        payload_storage.assign(0, &payload).unwrap();
+        let payload_provider = PayloadProvider::new(Arc::new(AtomicRefCell::new(payload_storage)));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("orders[]".to_string());
+
+        let checker = |range: Range| {
+            let condition = Condition::Expression(ExpressionCondition::from(Expression {
+                left: "price".to_string(),
+                operator: ArithmeticOperator::Mul,
+                right: "quantity".to_string(),
+                range,
+            }));
+            nested_condition_converter(
+                &condition,
+                payload_provider.clone(),
+                &field_indexes,
+                nested_path.clone(),
+            )(0)
+        };
+
+        // price * quantity > 100
+        let over_100 = Range {
+            lt: None,
+            gt: Some(100.0),
+            gte: None,
+            lte: None,
+        };
+        assert_eq!(checker(over_100), bitvec![1, 0, 1]);
+        // price * quantity <= 50
+        let up_to_50 = Range {
+            lt: None,
+            gt: None,
+            gte: None,
+            lte: Some(50.0),
+        };
+        assert_eq!(checker(up_to_50), bitvec![0, 1, 0]);
+    }
+
+    #[test]
+    fn test_in_memory_payload_provider() {
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([
+            (
+                0,
+                json!({ "items": [{ "color": "red", "size": 1 }, { "color": "blue", "size": 5 }] }),
+            ),
+            (1, json!({ "items": [{ "color": "blue", "size": 1 }] })),
+            (2, json!({ "title": "no items" })),
+        ]));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+
+        let blue = Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "blue".to_string().into(),
+        ));
+        let large = Condition::Field(FieldCondition::new_range(
+            "size".to_string(),
+            Range {
+                lt: None,
+                gt: Some(2.0),
+                gte: None,
+                lte: None,
+            },
+        ));
+        let conditions = vec![blue, large];
+        let checkers =
+            nested_conditions_converter(&conditions, payload_provider, &field_indexes, nested_path);
+
+        assert_eq!(
+            find_indices_matching_all_conditions(0, &checkers),
+            bitvec![0, 1]
+        );
+        assert_eq!(
+            find_indices_matching_all_conditions(1, &checkers),
+            bitvec![0]
+        );
+        // Points without payload are treated as empty
+        assert!(!find_indices_matching_all_conditions(2, &checkers).any());
+        assert!(!find_indices_matching_all_conditions(3, &checkers).any());
+    }
+
+    #[test]
+    fn test_nested_must_not_mask() {
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([
+            (
+                0,
+                json!({ "items": [
+                    { "color": "red", "size": 1 },
+                    { "color": "blue", "size": 5 },
+                    { "color": "green", "size": 3 },
+                ] }),
+            ),
+            (1, json!({ "items": [{ "color": "blue", "size": 1 }] })),
+            (2, json!({ "title": "no items" })),
+        ]));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+
+        let blue = Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "blue".to_string().into(),
+        ));
+        let large = Condition::Field(FieldCondition::new_range(
+            "size".to_string(),
+            Range {
+                lt: None,
+                gt: Some(2.0),
+                gte: None,
+                lte: None,
+            },
+        ));
+        let conditions = vec![blue, large];
+        let mask = |point_id, conditions: &[Condition]| {
+            nested_must_not_mask(
+                point_id,
+                conditions,
+                payload_provider.clone(),
+                &field_indexes,
+                nested_path.clone(),
+            )
+        };
+
+        assert_eq!(mask(0, &conditions), bitvec![1, 0, 0]);
+        assert_eq!(mask(0, &conditions[..1]), bitvec![1, 0, 1]);
+        assert_eq!(mask(1, &conditions), bitvec![0]);
+        // Nothing to exclude in a missing array
+        assert!(mask(2, &conditions).is_empty());
+        assert!(mask(0, &[]).is_empty());
+
+        // Same as the `must_not` clause of a nested filter
+        let filter = Filter {
+            should: None,
+            must: None,
+            must_not: Some(conditions.clone()),
+        };
+        for point_id in 0..3 {
+            let clauses = check_nested_filter_clauses(
+                point_id,
+                &filter,
+                &field_indexes,
+                payload_provider.clone(),
+                nested_path.clone(),
+            );
+            assert_eq!(clauses, mask(point_id, &conditions), "point {point_id}");
+        }
+    }
+
+    #[test]
+    fn test_uncommitted_payload_overlay() {
+        let committed = PayloadProvider::in_memory(HashMap::from([
+            (0, json!({ "items": [{ "color": "red" }] })),
+            (
+                1,
+                json!({ "items": [{ "color": "blue" }, { "color": "red" }] }),
+            ),
+        ]));
+        let uncommitted: HashMap<PointOffsetType, Payload> = HashMap::from([
+            // Pending update of a committed point
+            (
+                0,
+                json!({ "items": [{ "color": "green" }, { "color": "blue" }] }).into(),
+            ),
+            // Pending insert
+            (2, json!({ "items": [{ "color": "blue" }] }).into()),
+        ]);
+        let field_indexes = IndexesMap::default();
+        let blue = vec![Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "blue".to_string().into(),
+        ))];
+        let checkers = |payload_provider: PayloadProvider| {
+            nested_conditions_converter(
+                &blue,
+                payload_provider,
+                &field_indexes,
+                JsonPathPayload::new("items[]".to_string()),
+            )
+        };
+
+        let committed_checkers = checkers(committed.clone());
+        assert!(!find_indices_matching_all_conditions(0, &committed_checkers).any());
+        assert!(!find_indices_matching_all_conditions(2, &committed_checkers).any());
+
+        let overlay_checkers = checkers(committed.with_uncommitted(Arc::new(uncommitted)));
+        assert_eq!(
+            find_indices_matching_all_conditions(0, &overlay_checkers),
+            bitvec![0, 1]
+        );
+        // Points outside of the buffer are read from the committed storage
+        assert_eq!(
+            find_indices_matching_all_conditions(1, &overlay_checkers),
+            bitvec![1, 0]
+        );
+        assert_eq!(
+            find_indices_matching_all_conditions(2, &overlay_checkers),
+            bitvec![1]
+        );
+    }
+
+    #[test]
+    fn test_estimate_nested_memory() {
+        let condition = |key: &str| {
+            Condition::Field(FieldCondition::new_match(
+                key.to_string(),
+                "value".to_string().into(),
+            ))
+        };
+        let word = std::mem::size_of::<usize>();
+        let bits = usize::BITS as usize;
+
+        assert_eq!(estimate_nested_memory(&[], 1000), 0);
+        assert_eq!(estimate_nested_memory(&[condition("a")], 0), 0);
+        // Bits are allocated in whole words
+        assert_eq!(estimate_nested_memory(&[condition("a")], 1), word);
+        assert_eq!(estimate_nested_memory(&[condition("a")], bits), word);
+        assert_eq!(
+            estimate_nested_memory(&[condition("a")], bits + 1),
+            2 * word
+        );
+
+        // Proportional to the number of conditions
+        let conditions = vec![condition("a"), condition("b"), condition("c")];
+        let million = 1_000_000;
+        let million_bytes = (million + bits - 1) / bits * word;
+        assert_eq!(
+            estimate_nested_memory(&conditions, million),
+            3 * million_bytes
+        );
+
+        // Conditions of inner filters produce their own bitvecs
+        let grouped = Condition::Filter(Filter {
+            should: Some(vec![condition("a"), condition("b")]),
+            must: None,
+            must_not: Some(vec![condition("c")]),
+        });
+        let inner = Condition::new_nested("inner", Filter::new_must(condition("d")));
+        assert_eq!(
+            estimate_nested_memory(&[grouped], million),
+            4 * million_bytes
+        );
+        assert_eq!(estimate_nested_memory(&[inner], million), 2 * million_bytes);
+
+        // Huge estimates saturate instead of overflowing
+        assert_eq!(
+            estimate_nested_memory(&[condition("a")], usize::MAX),
+            (usize::MAX / bits + 1) * word
+        );
+        let many: Vec<_> = (0..8).map(|_| condition("a")).collect();
+        assert_eq!(estimate_nested_memory(&many, usize::MAX), usize::MAX);
+    }
+
+    #[test]
+    fn test_nested_match_score() {
+        let mut payload_storage: PayloadStorageEnum = InMemoryPayloadStorage::default().into();
+        let matching_two: Payload = json!({
+            "reviews": [
+                { "stars": 5 },
+                { "stars": 2 },
+                { "stars": 4 },
+            ]
+        })
+        .into();
+        let matching_none: Payload = json!({
+            "reviews": [
+                { "stars": 1 },
+            ]
+        })
+        .into();
+        payload_storage.assign(0, &matching_two).unwrap();
+        payload_storage.assign(1, &matching_none).unwrap();
+        let payload_provider = PayloadProvider::new(Arc::new(AtomicRefCell::new(payload_storage)));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("reviews[]".to_string());
+
+        let filter = Filter::new_must(Condition::Field(FieldCondition::new_range(
+            "stars".to_string(),
+            Range {
+                lt: None,
+                gt: None,
+                gte: Some(4.0),
+                lte: None,
+            },
+        )));
+        let score = |point_id: PointOffsetType, weight: ScoreType| {
+            nested_match_score(
+                point_id,
+                &filter,
+                &field_indexes,
+                payload_provider.clone(),
+                nested_path.clone(),
+                weight,
+            )
+        };
+
+        assert_eq!(score(0, 1.0), 2.0);
+        assert_eq!(score(0, 0.5), 1.0);
+        assert_eq!(score(0, -2.0), -4.0);
+        assert_eq!(score(1, 0.5), 0.0);
+        // Points without payload contribute nothing
+        assert_eq!(score(2, 0.5), 0.0);
+    }
+
+    #[test]
+    fn test_nested_min_match() {
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([
+            // Matches all three filters
+            (
+                0,
+                json!({
+                    "reviews": [{ "stars": 5 }],
+                    "authors": [{ "verified": true }],
+                    "tags": [{ "name": "rust" }],
+                }),
+            ),
+            // Matches "reviews" and "tags", with matches spread across elements
+            (
+                1,
+                json!({
+                    "reviews": [{ "stars": 1 }, { "stars": 4 }],
+                    "authors": [{ "verified": false }],
+                    "tags": [{ "name": "go" }, { "name": "rust" }],
+                }),
+            ),
+            // Matches only "authors"
+            (
+                2,
+                json!({
+                    "reviews": [{ "stars": 2 }],
+                    "authors": [{ "verified": true }],
+                }),
+            ),
+            // Matches nothing
+            (3, json!({})),
+        ]));
+        let field_indexes = IndexesMap::default();
+
+        let nested = vec![
+            NestedContainer::new(NestedCondition {
+                key: "reviews".to_string(),
+                filter: Filter::new_must(Condition::Field(FieldCondition::new_range(
+                    "stars".to_string(),
+                    Range {
+                        lt: None,
+                        gt: None,
+                        gte: Some(4.0),
+                        lte: None,
+                    },
+                ))),
+                index: None,
+                after: None,
+                quantifier: None,
+            }),
+            NestedContainer::new(NestedCondition {
+                key: "authors".to_string(),
+                filter: Filter::new_must(Condition::Field(FieldCondition::new_match(
+                    "verified".to_string(),
+                    true.into(),
+                ))),
+                index: None,
+                after: None,
+                quantifier: None,
+            }),
+            NestedContainer::new(NestedCondition {
+                key: "tags".to_string(),
+                filter: Filter::new_must(Condition::Field(FieldCondition::new_match(
+                    "name".to_string(),
+                    "rust".to_string().into(),
+                ))),
+                index: None,
+                after: None,
+                quantifier: None,
+            }),
+        ];
+        let min_match = |point_id: PointOffsetType, min_count: usize| {
+            nested_min_match(
+                point_id,
+                &nested,
+                min_count,
+                &field_indexes,
+                payload_provider.clone(),
+            )
+        };
+
+        // 2 of 3
+        let matching: Vec<_> = (0..4).filter(|&point_id| min_match(point_id, 2)).collect();
+        assert_eq!(matching, vec![0, 1]);
+
+        // Boundaries behave like `should` and `must`
+        let matching: Vec<_> = (0..4).filter(|&point_id| min_match(point_id, 1)).collect();
+        assert_eq!(matching, vec![0, 1, 2]);
+        let matching: Vec<_> = (0..4).filter(|&point_id| min_match(point_id, 3)).collect();
+        assert_eq!(matching, vec![0]);
+
+        // Trivially satisfied, and never satisfied beyond the number of filters
+        assert!(min_match(3, 0));
+        assert!(!min_match(0, 4));
+    }
+
+    #[test]
+    fn test_nested_matches_to_ranges() {
+        assert!(nested_matches_to_ranges(&bitvec![0; 10]).is_empty());
+        assert!(nested_matches_to_ranges(&BitVec::new()).is_empty());
+
+        // Contiguous matches
+        let matches = bitvec![0, 0, 1, 1, 1, 1, 0];
+        assert_eq!(nested_matches_to_ranges(&matches), vec![(2, 6)]);
+        assert_eq!(nested_matches_to_ranges(&bitvec![1; 5]), vec![(0, 5)]);
+
+        // Scattered matches
+        let matches = bitvec![1, 0, 1, 1, 0, 0, 1];
+        assert_eq!(
+            nested_matches_to_ranges(&matches),
+            vec![(0, 1), (2, 4), (6, 7)]
+        );
+
+        // Ranges cover exactly the matching positions
+        let ranges = nested_matches_to_ranges(&matches);
+        let positions: Vec<_> = ranges
+            .into_iter()
+            .flat_map(|(start, end)| start..end)
+            .collect();
+        assert_eq!(positions, matches.iter_ones().collect::<Vec<_>>());
+    }
+
+    #[test]
+    fn test_nested_match_trace() {
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([
+            (
+                0,
+                json!({ "items": [{ "color": "red" }, { "color": "blue" }, { "color": "red" }] }),
+            ),
+            (1, json!({ "items": [{ "color": "blue" }] })),
+        ]));
+        let field_indexes = IndexesMap::default();
+        let red = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "red".to_string().into(),
+        )));
+        let check = |point_id, trace| {
+            check_nested_filter_clauses_traced(
+                point_id,
+                &red,
+                &field_indexes,
+                payload_provider.clone(),
+                JsonPathPayload::new("items[]".to_string()),
+                trace,
+            )
+        };
+
+        let trace = NestedMatchTrace::new(NestedPositionBase::Zero);
+        assert_eq!(check(0, Some(&trace)), bitvec![1, 0, 1]);
+        assert_eq!(check(1, Some(&trace)), bitvec![0]);
+        assert_eq!(trace.positions(0), Some(vec![0, 2]));
+        // Points without matching elements are not recorded
+        assert_eq!(trace.positions(1), None);
+        assert_eq!(trace.len(), 1);
+
+        // Untraced evaluation leaves the trace as is
+        let trace = NestedMatchTrace::new(NestedPositionBase::One);
+        check(0, None);
+        assert!(trace.is_empty());
+        check(0, Some(&trace));
+        assert_eq!(trace.take(), HashMap::from([(0, vec![1, 3])]));
+        assert!(trace.is_empty());
+    }
+
+    #[test]
+    fn test_nested_matches_to_positions() {
+        let matches = bitvec![1, 0, 0, 1, 1, 0];
+        assert_eq!(NestedPositionBase::default(), NestedPositionBase::Zero);
+        assert_eq!(
+            nested_matches_to_positions(&matches, NestedPositionBase::Zero),
+            vec![0, 3, 4]
+        );
+        assert_eq!(
+            nested_matches_to_positions(&matches, NestedPositionBase::One),
+            vec![1, 4, 5]
+        );
+
+        for base in [NestedPositionBase::Zero, NestedPositionBase::One] {
+            assert!(nested_matches_to_positions(&bitvec![0; 4], base).is_empty());
+            assert!(nested_matches_to_positions(&BitVec::new(), base).is_empty());
+        }
+    }
+
+    #[test]
+    fn test_nested_converter_provider_clones() {
+        let mut payload_storage: PayloadStorageEnum = InMemoryPayloadStorage::default().into();
+        let payload: Payload = json!({ "items": [{ "size": 1 }, { "size": 5 }] }).into();
+        payload_storage.assign(0, &payload).unwrap();
+        let payload_storage = Arc::new(AtomicRefCell::new(payload_storage));
+        let payload_provider = PayloadProvider::new(payload_storage.clone());
+        let field_indexes = IndexesMap::default();
+
+        let large = Condition::Field(FieldCondition::new_range(
+            "size".to_string(),
+            Range {
+                lt: None,
+                gt: Some(2.0),
+                gte: None,
+                lte: None,
+            },
+        ));
+        let conditions = vec![
+            large.clone(),
+            Condition::Filter(Filter::new_must(large.clone())),
+            Condition::Filter(Filter::new_must_not(large)),
+        ];
+
+        // A single clone is kept per condition, the original provider is consumed
+        let checkers = nested_conditions_converter(
+            &conditions,
+            payload_provider,
+            &field_indexes,
+            JsonPathPayload::new("items[]".to_string()),
+        );
+        assert_eq!(Arc::strong_count(&payload_storage), 1 + conditions.len());
+
+        // Clones made for inner filters during evaluation are released right after
+        for _ in 0..10 {
+            assert_eq!(
+                find_indices_matching_any_conditions(0, &checkers),
+                Some(bitvec![1, 1])
+            );
+        }
+        assert_eq!(Arc::strong_count(&payload_storage), 1 + conditions.len());
+
+        drop(checkers);
+        assert_eq!(Arc::strong_count(&payload_storage), 1);
+    }
+}
@@ -0,0 +1,312 @@
+use std::cmp::Ordering;
+use std::ops::Not;
+
+use bitvec::prelude::*;
+
+/// Matching elements of a nested array
+///
+/// Dense representation takes a bit per element of the array, which is wasteful for huge arrays
+/// with rare matches. Sparse representation keeps sorted positions of the matching elements
+/// instead. Both operands of a reduction are expected to describe the same array, the result
+/// always has the length of the left operand, as with `BitVec` operators.
+#[derive(Debug, Clone)]
+pub enum NestedMatches {
+    Dense(BitVec),
+    Sparse {
+        /// Number of elements in the array
+        len: usize,
+        /// Sorted positions of the matching elements
+        positions: Vec<u32>,
+    },
+}
+
+impl Default for NestedMatches {
+    fn default() -> Self {
+        NestedMatches::Dense(BitVec::new())
+    }
+}
+
+impl From<BitVec> for NestedMatches {
+    fn from(matches: BitVec) -> Self {
+        NestedMatches::Dense(matches)
+    }
+}
+
+impl From<NestedMatches> for BitVec {
+    fn from(matches: NestedMatches) -> Self {
+        matches.into_bitvec()
+    }
+}
+
+impl PartialEq for NestedMatches {
+    fn eq(&self, other: &Self) -> bool {
+        self.len() == other.len() && self.iter_ones().eq(other.iter_ones())
+    }
+}
+
+impl NestedMatches {
+    /// Sparse representation of `matches`
+    pub fn sparse_from_bitvec(matches: &BitSlice) -> Self {
+        NestedMatches::Sparse {
+            len: matches.len(),
+            positions: matches
+                .iter_ones()
+                .map(|position| position as u32)
+                .collect(),
+        }
+    }
+
+    /// Convert into the representation taking less memory
+    pub fn compact(self) -> Self {
+        let prefer_sparse = self.count_ones() * (u32::BITS as usize) < self.len();
+        match self {
+            NestedMatches::Dense(matches) if prefer_sparse => Self::sparse_from_bitvec(&matches),
+            NestedMatches::Sparse { .. } if !prefer_sparse => {
+                NestedMatches::Dense(self.into_bitvec())
+            }
+            matches => matches,
+        }
+    }
+
+    pub fn is_sparse(&self) -> bool {
+        matches!(self, NestedMatches::Sparse { .. })
+    }
+
+    pub fn into_bitvec(self) -> BitVec {
+        match self {
+            NestedMatches::Dense(matches) => matches,
+            NestedMatches::Sparse { len, positions } => {
+                let mut matches = bitvec![0; len];
+                for position in positions {
+                    matches.set(position as usize, true);
+                }
+                matches
+            }
+        }
+    }
+
+    /// Number of elements in the array
+    pub fn len(&self) -> usize {
+        match self {
+            NestedMatches::Dense(matches) => matches.len(),
+            NestedMatches::Sparse { len, .. } => *len,
+        }
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn count_ones(&self) -> usize {
+        match self {
+            NestedMatches::Dense(matches) => matches.count_ones(),
+            NestedMatches::Sparse { positions, .. } => positions.len(),
+        }
+    }
+
+    pub fn any(&self) -> bool {
+        self.count_ones() > 0
+    }
+
+    /// Positions of the matching elements in ascending order
+    pub fn iter_ones(&self) -> Box<dyn Iterator<Item = usize> + '_> {
+        match self {
+            NestedMatches::Dense(matches) => Box::new(matches.iter_ones()),
+            NestedMatches::Sparse { positions, .. } => {
+                Box::new(positions.iter().map(|&position| position as usize))
+            }
+        }
+    }
+
+    /// Elements matching both `self` and `other`
+    ///
+    /// Stays sparse if any of the operands is sparse, as intersection can only remove matches.
+    pub fn and(self, other: &NestedMatches) -> NestedMatches {
+        match (self, other) {
+            (NestedMatches::Dense(matches), NestedMatches::Dense(other)) => {
+                NestedMatches::Dense(matches & other.as_bitslice())
+            }
+            (NestedMatches::Sparse { len, positions }, other) => {
+                let positions = match other {
+                    NestedMatches::Dense(other) => positions
+                        .into_iter()
+                        .filter(|&position| other.get(position as usize).as_deref() == Some(&true))
+                        .collect(),
+                    NestedMatches::Sparse {
+                        positions: other, ..
+                    } => intersect_sorted(&positions, other),
+                };
+                NestedMatches::Sparse { len, positions }
+            }
+            (NestedMatches::Dense(matches), NestedMatches::Sparse { positions, .. }) => {
+                let len = matches.len();
+                let positions = positions
+                    .iter()
+                    .copied()
+                    .filter(|&position| matches.get(position as usize).as_deref() == Some(&true))
+                    .collect();
+                NestedMatches::Sparse { len, positions }
+            }
+        }
+    }
+
+    /// Elements matching any of `self` and `other`
+    ///
+    /// Stays sparse only if both operands are sparse.
+    pub fn or(self, other: &NestedMatches) -> NestedMatches {
+        match (self, other) {
+            (
+                NestedMatches::Sparse { len, positions },
+                NestedMatches::Sparse {
+                    positions: other, ..
+                },
+            ) => {
+                let mut positions = union_sorted(&positions, other);
+                positions.retain(|&position| (position as usize) < len);
+                NestedMatches::Sparse { len, positions }
+            }
+            (matches, other) => {
+                let mut matches = matches.into_bitvec();
+                let len = matches.len();
+                for position in other.iter_ones().take_while(|&position| position < len) {
+                    matches.set(position, true);
+                }
+                NestedMatches::Dense(matches)
+            }
+        }
+    }
+}
+
+/// Elements not matching `self`
+///
+/// Complement of rare matches is dense, so the result is always dense.
+impl Not for NestedMatches {
+    type Output = NestedMatches;
+
+    fn not(self) -> NestedMatches {
+        match self {
+            NestedMatches::Dense(matches) => NestedMatches::Dense(!matches),
+            NestedMatches::Sparse { len, positions } => {
+                let mut matches = bitvec![1; len];
+                for position in positions {
+                    matches.set(position as usize, false);
+                }
+                NestedMatches::Dense(matches)
+            }
+        }
+    }
+}
+
+fn intersect_sorted(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(left.len().min(right.len()));
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        match left[i].cmp(&right[j]) {
+            Ordering::Less => i += 1,
+            Ordering::Greater => j += 1,
+            Ordering::Equal => {
+                result.push(left[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    result
+}
+
+fn union_sorted(left: &[u32], right: &[u32]) -> Vec<u32> {
+    let mut result = Vec::with_capacity(left.len() + right.len());
+    let (mut i, mut j) = (0, 0);
+    while i < left.len() && j < right.len() {
+        match left[i].cmp(&right[j]) {
+            Ordering::Less => {
+                result.push(left[i]);
+                i += 1;
+            }
+            Ordering::Greater => {
+                result.push(right[j]);
+                j += 1;
+            }
+            Ordering::Equal => {
+                result.push(left[i]);
+                i += 1;
+                j += 1;
+            }
+        }
+    }
+    result.extend_from_slice(&left[i..]);
+    result.extend_from_slice(&right[j..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use rand::prelude::StdRng;
+    use rand::{Rng, SeedableRng};
+
+    use super::*;
+
+    fn random_matches(rng: &mut StdRng, len: usize, density: f64) -> BitVec {
+        (0..len).map(|_| rng.gen_bool(density)).collect()
+    }
+
+    #[test]
+    fn test_sparse_dense_equivalence() {
+        let mut rng = StdRng::seed_from_u64(42);
+        for density in [0.0, 0.001, 0.05, 0.5, 1.0] {
+            let len = 5_000;
+            let left = random_matches(&mut rng, len, density);
+            let right = random_matches(&mut rng, len, density);
+
+            let dense_left = NestedMatches::from(left.clone());
+            let dense_right = NestedMatches::from(right.clone());
+            let sparse_left = NestedMatches::sparse_from_bitvec(&left);
+            let sparse_right = NestedMatches::sparse_from_bitvec(&right);
+
+            assert_eq!(sparse_left.clone().into_bitvec(), left);
+            assert_eq!(sparse_left, dense_left);
+            assert_eq!(sparse_left.count_ones(), left.count_ones());
+
+            let expected_and = left.clone() & right.clone();
+            let expected_or = left.clone() | right.clone();
+            let expected_not = !left.clone();
+
+            for (l, r) in [
+                (&dense_left, &dense_right),
+                (&dense_left, &sparse_right),
+                (&sparse_left, &dense_right),
+                (&sparse_left, &sparse_right),
+            ] {
+                assert_eq!(l.clone().and(r).into_bitvec(), expected_and);
+                assert_eq!(l.clone().or(r).into_bitvec(), expected_or);
+                assert_eq!((!l.clone()).into_bitvec(), expected_not);
+                assert_eq!(l.clone().compact().into_bitvec(), left);
+            }
+
+            // Intersection with a sparse operand stays sparse
+            assert!(dense_left.clone().and(&sparse_right).is_sparse());
+            assert!(sparse_left.clone().or(&sparse_right).is_sparse());
+            assert!(!(!sparse_left.clone()).is_sparse());
+        }
+    }
+
+    #[test]
+    fn test_compact_nested_matches() {
+        let mut rare = bitvec![0; 10_000];
+        rare.set(7, true);
+        rare.set(9_000, true);
+        let compacted = NestedMatches::from(rare.clone()).compact();
+        assert!(compacted.is_sparse());
+        assert_eq!(compacted.iter_ones().collect::<Vec<_>>(), vec![7, 9_000]);
+        assert_eq!(BitVec::from(compacted), rare);
+
+        let frequent = bitvec![1, 0, 1, 1, 0, 0, 1, 0];
+        let compacted = NestedMatches::sparse_from_bitvec(&frequent).compact();
+        assert!(!compacted.is_sparse());
+        assert_eq!(BitVec::from(compacted), frequent);
+
+        let empty = NestedMatches::default().compact();
+        assert!(empty.is_empty());
+        assert!(!empty.any());
+    }
+}
@@ -1,16 +1,27 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+
 use crate::common::utils::{IndexesMap, JsonPathPayload};
 use crate::index::field_index::CardinalityEstimation;
 use crate::index::query_estimator::{
-    combine_must_estimations, combine_should_estimations, invert_estimation,
+    combine_must_estimations, combine_should_estimations, estimate_condition, invert_estimation,
 };
 use crate::index::query_optimization::nested_filter::{
-    find_indices_matching_all_conditions, find_indices_matching_any_conditions,
-    find_indices_matching_none_conditions, nested_conditions_converter,
+    find_indices_matching_all_conditions, find_indices_matching_all_conditions_cancellable,
+    find_indices_matching_any_conditions, find_indices_matching_none_conditions,
+    nested_conditions_converter,
 };
 use crate::index::query_optimization::optimized_filter::OptimizedCondition;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::types::{Condition, PointOffsetType};
 
+/// Check if the evaluation of the query is cancelled, there is nothing to cancel without a flag
+///
+/// Checkers of a cancelled query exclude the point: checkers negated by a `must_not` clause
+/// match, all others don't. The caller is expected to discard the result anyway.
+pub fn is_query_stopped(stopped: Option<&AtomicBool>) -> bool {
+    stopped.map_or(false, |stopped| stopped.load(Ordering::Relaxed))
+}
+
 pub fn optimize_nested_must<'a, F>(
     conditions: &'a [Condition],
     field_indexes: &'a IndexesMap,
@@ -18,16 +29,30 @@ pub fn optimize_nested_must<'a, F>(
     estimator: &F,
     total: usize,
     nested_path: JsonPathPayload,
+    stopped: Option<&'a AtomicBool>,
 ) -> (Vec<OptimizedCondition<'a>>, CardinalityEstimation)
 where
     F: Fn(&Condition) -> CardinalityEstimation,
 {
     let nested_checker_fns =
         nested_conditions_converter(conditions, payload_provider, field_indexes, nested_path);
-    let estimations: Vec<_> = conditions.iter().map(estimator).collect();
+    let estimations: Vec<_> = conditions
+        .iter()
+        .map(|condition| estimate_condition(estimator, condition, total))
+        .collect();
 
     let merged = Box::new(move |point_id: PointOffsetType| {
-        let matches = find_indices_matching_all_conditions(point_id, &nested_checker_fns);
+        let matches = match stopped {
+            None => find_indices_matching_all_conditions(point_id, &nested_checker_fns),
+            Some(stopped) => match find_indices_matching_all_conditions_cancellable(
+                point_id,
+                &nested_checker_fns,
+                stopped,
+            ) {
+                Ok(matches) => matches,
+                Err(_) => return false,
+            },
+        };
         // if any of the nested path is matching for ALL nested condition
         matches.count_ones() > 0
     });
@@ -43,6 +68,7 @@ pub fn optimize_nested_must_not<'a, F>(
     estimator: &F,
     total: usize,
     nested_path: JsonPathPayload,
+    stopped: Option<&'a AtomicBool>,
 ) -> (Vec<OptimizedCondition<'a>>, CardinalityEstimation)
 where
     F: Fn(&Condition) -> CardinalityEstimation,
@@ -51,13 +77,18 @@ where
         nested_conditions_converter(conditions, payload_provider, field_indexes, nested_path);
     let estimations: Vec<_> = conditions
         .iter()
-        .map(|condition| invert_estimation(&estimator(condition), total))
+        .map(|condition| invert_estimation(&estimate_condition(estimator, condition, total), total))
         .collect();
 
     let merged = Box::new(move |point_id: PointOffsetType| {
+        // Checker is negated by the `must_not` clause, so matching excludes the point
+        if is_query_stopped(stopped) {
+            return true;
+        }
         let not_matching = find_indices_matching_none_conditions(point_id, &nested_checker_fns);
-        // if they are no nested path not matching ANY nested conditions
-        not_matching.count_ones() == 0
+        // if they are no nested path not matching ANY nested conditions.
+        // Missing or empty array has nothing matching forbidden conditions, it is not excluded
+        !not_matching.is_empty() && not_matching.count_ones() == 0
     });
 
     let estimation = combine_must_estimations(&estimations, total);
@@ -71,15 +102,22 @@ pub fn optimize_nested_should<'a, F>(
     estimator: &F,
     total: usize,
     nested_path: JsonPathPayload,
+    stopped: Option<&'a AtomicBool>,
 ) -> (Vec<OptimizedCondition<'a>>, CardinalityEstimation)
 where
     F: Fn(&Condition) -> CardinalityEstimation,
 {
     let nested_checker_fns =
         nested_conditions_converter(conditions, payload_provider, field_indexes, nested_path);
-    let estimations: Vec<_> = conditions.iter().map(estimator).collect();
+    let estimations: Vec<_> = conditions
+        .iter()
+        .map(|condition| estimate_condition(estimator, condition, total))
+        .collect();
 
     let merged = Box::new(move |point_id: PointOffsetType| {
+        if is_query_stopped(stopped) {
+            return false;
+        }
         let matches =
             find_indices_matching_any_conditions(point_id, &nested_checker_fns).unwrap_or_default();
         // if any of the nested path is matching for any nested condition
@@ -89,3 +127,46 @@ where
     let estimation = combine_should_estimations(&estimations, total);
     (vec![OptimizedCondition::Checker(merged)], estimation)
 }
+
+#[cfg(test)]
+mod tests {
+    use std::collections::HashMap;
+
+    use serde_json::json;
+
+    use super::*;
+    use crate::index::query_optimization::optimized_filter::{
+        check_optimized_filter, OptimizedFilter,
+    };
+    use crate::types::FieldCondition;
+
+    #[test]
+    fn test_cancelled_nested_must_not_excludes_points() {
+        let payload_provider =
+            PayloadProvider::in_memory(HashMap::from([(0, json!({ "arr": [{ "a": 2 }] }))]));
+        let field_indexes = IndexesMap::default();
+        let conditions = vec![Condition::Field(FieldCondition::new_match("a", 1.into()))];
+        let estimator = |_: &Condition| CardinalityEstimation::exact(1);
+        let stopped = AtomicBool::new(false);
+        let (must_not, _) = optimize_nested_must_not(
+            &conditions,
+            &field_indexes,
+            payload_provider,
+            &estimator,
+            1,
+            JsonPathPayload::new("arr[]".to_string()),
+            Some(&stopped),
+        );
+        let filter = OptimizedFilter {
+            should: None,
+            must: None,
+            must_not: Some(must_not),
+        };
+
+        // The only element is not forbidden
+        assert!(check_optimized_filter(&filter, 0));
+
+        stopped.store(true, Ordering::Relaxed);
+        assert!(!check_optimized_filter(&filter, 0));
+    }
+}
@@ -1,7 +1,24 @@
+use bitvec::prelude::BitVec;
+
 use crate::types::PointOffsetType;
 
 pub type ConditionCheckerFn<'a> = Box<dyn Fn(PointOffsetType) -> bool + 'a>;
 
+/// Evaluate `checker` over a batch of points
+///
+/// Result is indexed by position in `ids`, not by point offset, so duplicated or unordered ids
+/// are reported as given. Accepts a `ConditionCheckerFn` as well as any other checker closure.
+pub fn check_points(checker: impl Fn(PointOffsetType) -> bool, ids: &[PointOffsetType]) -> BitVec {
+    let mut result = BitVec::with_capacity(ids.len());
+    result.extend(ids.iter().map(|&point_id| checker(point_id)));
+    result
+}
+
+/// Checker which matches exactly the points not matched by the given `checker`
+pub fn negate_checker<'a>(checker: ConditionCheckerFn<'a>) -> ConditionCheckerFn<'a> {
+    Box::new(move |point_id| !checker(point_id))
+}
+
 pub enum OptimizedCondition<'a> {
     Checker(ConditionCheckerFn<'a>),
     /// Nested filter
@@ -53,3 +70,69 @@ fn check_must_not(must: &Option<Vec<OptimizedCondition>>, point_id: PointOffsetT
         Some(conditions) => conditions.iter().all(check),
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use bitvec::bitvec;
+
+    use super::*;
+
+    #[test]
+    fn test_check_points() {
+        let checker: ConditionCheckerFn = Box::new(|point_id| point_id % 3 == 0 || point_id > 10);
+        let ids: Vec<PointOffsetType> = vec![0, 1, 2, 3, 11, 5, 3, 0];
+
+        let result = check_points(&checker, &ids);
+        assert_eq!(result.len(), ids.len());
+        for (position, &point_id) in ids.iter().enumerate() {
+            assert_eq!(result[position], checker(point_id));
+        }
+        assert_eq!(result, bitvec![1, 0, 0, 1, 1, 0, 1, 1]);
+
+        // Same as per-id evaluation over a larger range
+        let ids: Vec<PointOffsetType> = (0..100).rev().collect();
+        let per_id: Vec<bool> = ids.iter().map(|&point_id| checker(point_id)).collect();
+        let batch: Vec<bool> = check_points(&checker, &ids).iter().by_vals().collect();
+        assert_eq!(batch, per_id);
+
+        assert!(check_points(&checker, &[]).is_empty());
+
+        // Composes with other checker helpers
+        let negated = check_points(negate_checker(checker), &[0, 1]);
+        assert_eq!(negated, bitvec![0, 1]);
+    }
+
+    #[test]
+    fn test_negate_checker() {
+        let checkers = || -> Vec<ConditionCheckerFn<'static>> {
+            vec![
+                Box::new(|point_id| point_id % 2 == 0),
+                Box::new(|point_id| point_id > 5),
+                Box::new(|_| true),
+            ]
+        };
+
+        for (checker, original) in checkers().into_iter().zip(checkers()) {
+            let negated = negate_checker(checker);
+            for point_id in 0..10 {
+                assert_eq!(negated(point_id), !original(point_id));
+            }
+
+            let double_negated = negate_checker(negated);
+            for point_id in 0..10 {
+                assert_eq!(double_negated(point_id), original(point_id));
+            }
+        }
+
+        // Negated checker can be used in `must_not` to express `must`
+        let filter = OptimizedFilter {
+            should: None,
+            must: None,
+            must_not: Some(vec![OptimizedCondition::Checker(negate_checker(Box::new(
+                |point_id| point_id > 5,
+            )))]),
+        };
+        assert!(!check_optimized_filter(&filter, 3));
+        assert!(check_optimized_filter(&filter, 7));
+    }
+}
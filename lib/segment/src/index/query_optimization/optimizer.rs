@@ -1,4 +1,5 @@
 use std::cmp::Reverse;
+use std::sync::atomic::AtomicBool;
 
 use itertools::Itertools;
 
@@ -9,12 +10,18 @@ use crate::index::query_estimator::{
     combine_must_estimations, combine_should_estimations, invert_estimation,
 };
 use crate::index::query_optimization::condition_converter::condition_converter;
+use crate::index::query_optimization::nested_filter::{
+    check_nested_filter_clauses, nested_container_matches, warn_dropped_nested_conditions,
+};
 use crate::index::query_optimization::nested_optimizer::{
-    optimize_nested_must, optimize_nested_must_not, optimize_nested_should,
+    is_query_stopped, optimize_nested_must, optimize_nested_must_not, optimize_nested_should,
+};
+use crate::index::query_optimization::optimized_filter::{
+    ConditionCheckerFn, OptimizedCondition, OptimizedFilter,
 };
-use crate::index::query_optimization::optimized_filter::{OptimizedCondition, OptimizedFilter};
 use crate::index::query_optimization::payload_provider::PayloadProvider;
-use crate::types::{Condition, Filter};
+use crate::types::{Condition, Filter, NestedQuantifier};
+use crate::vector_storage::NamedVectorStorages;
 
 /// Converts user-provided filtering condition into optimized representation
 ///
@@ -30,20 +37,25 @@ use crate::types::{Condition, Filter};
 ///
 /// * `filter` - original filter
 /// * `id_tracker` - used for converting collection-level ids into segment-level offsets of HasId condition
+/// * `vector_storages` - used for checking presence of named vectors in VectorCount condition
 /// * `estimator` - function to estimate cardinality of individual conditions
 /// * `total` - total number of points in segment (used for cardinality estimation)
+/// * `stopped` - cancels the evaluation of nested conditions, which match nothing once it is set
 ///
 /// # Result
 ///
 /// Optimized query + Cardinality estimation
+#[allow(clippy::too_many_arguments)]
 pub fn optimize_filter<'a, F>(
     filter: &'a Filter,
     id_tracker: &IdTrackerSS,
+    vector_storages: &NamedVectorStorages,
     field_indexes: &'a IndexesMap,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
     nested_path: Option<JsonPathPayload>,
+    stopped: Option<&'a AtomicBool>,
 ) -> (OptimizedFilter<'a>, CardinalityEstimation)
 where
     F: Fn(&Condition) -> CardinalityEstimation,
@@ -51,7 +63,9 @@ where
     let mut filter_estimations: Vec<CardinalityEstimation> = vec![];
 
     let optimized_filter = OptimizedFilter {
-        should: filter.should.as_ref().and_then(|conditions| {
+        // Empty `should` clause has no condition to satisfy, so no point matches it,
+        // same as with the unindexed condition checker
+        should: filter.should.as_ref().map(|conditions| {
             if !conditions.is_empty() {
                 let (optimized_conditions, estimation) = if let Some(np) = &nested_path {
                     optimize_nested_should(
@@ -61,21 +75,24 @@ where
                         estimator,
                         total,
                         np.clone(),
+                        stopped,
                     )
                 } else {
                     optimize_should(
                         conditions,
                         id_tracker,
+                        vector_storages,
                         field_indexes,
                         payload_provider.clone(),
                         estimator,
                         total,
+                        stopped,
                     )
                 };
                 filter_estimations.push(estimation);
-                Some(optimized_conditions)
+                optimized_conditions
             } else {
-                None
+                vec![]
             }
         }),
         must: filter.must.as_ref().and_then(|conditions| {
@@ -88,15 +105,18 @@ where
                         estimator,
                         total,
                         np.clone(),
+                        stopped,
                     )
                 } else {
                     optimize_must(
                         conditions,
                         id_tracker,
+                        vector_storages,
                         field_indexes,
                         payload_provider.clone(),
                         estimator,
                         total,
+                        stopped,
                     )
                 };
                 filter_estimations.push(estimation);
@@ -115,15 +135,18 @@ where
                         estimator,
                         total,
                         np.clone(),
+                        stopped,
                     )
                 } else {
                     optimize_must_not(
                         conditions,
                         id_tracker,
+                        vector_storages,
                         field_indexes,
                         payload_provider.clone(),
                         estimator,
                         total,
+                        stopped,
                     )
                 };
                 filter_estimations.push(estimation);
@@ -143,26 +166,68 @@ where
 fn convert_conditions<'a, F>(
     conditions: &'a [Condition],
     id_tracker: &IdTrackerSS,
+    vector_storages: &NamedVectorStorages,
     field_indexes: &'a IndexesMap,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
+    stopped: Option<&'a AtomicBool>,
 ) -> Vec<(OptimizedCondition<'a>, CardinalityEstimation)>
 where
     F: Fn(&Condition) -> CardinalityEstimation,
 {
+    for condition in conditions {
+        if let Condition::Nested(nested) = condition {
+            warn_dropped_nested_conditions(nested);
+        }
+    }
     conditions
         .iter()
         .map(|condition| match condition {
+            // Sequence marker extends the filter of the nested condition, so it can't be borrowed
+            // by the optimized filter and is evaluated as a whole instead. Same for universal
+            // quantifiers, which need the array length
+            Condition::Nested(nested)
+                if nested.nested.after.is_some()
+                    || nested.quantifier() != NestedQuantifier::Any =>
+            {
+                let estimation = estimator(condition);
+                let nested_filter = nested.element_filter().into_owned();
+                let nested_path = JsonPathPayload::new(nested.array_key());
+                let payload_provider = payload_provider.clone();
+                let condition_checker: ConditionCheckerFn = Box::new(move |point_id| {
+                    if is_query_stopped(stopped) {
+                        return false;
+                    }
+                    let matches = check_nested_filter_clauses(
+                        point_id,
+                        &nested_filter,
+                        field_indexes,
+                        payload_provider.clone(),
+                        nested_path.clone(),
+                    );
+                    nested_container_matches(
+                        point_id,
+                        nested,
+                        &nested_filter,
+                        &matches,
+                        &payload_provider,
+                        &nested_path,
+                    )
+                });
+                (OptimizedCondition::Checker(condition_checker), estimation)
+            }
             Condition::Nested(nested_filter) => {
                 let (optimized_filter, estimation) = optimize_filter(
                     nested_filter.filter(),
                     id_tracker,
+                    vector_storages,
                     field_indexes,
                     payload_provider.clone(),
                     estimator,
                     total,
                     Some(JsonPathPayload::new(nested_filter.array_key())),
+                    stopped,
                 );
                 (OptimizedCondition::Filter(optimized_filter), estimation)
             }
@@ -170,11 +235,13 @@ where
                 let (optimized_filter, estimation) = optimize_filter(
                     filter,
                     id_tracker,
+                    vector_storages,
                     field_indexes,
                     payload_provider.clone(),
                     estimator,
                     total,
                     None,
+                    stopped,
                 );
                 (OptimizedCondition::Filter(optimized_filter), estimation)
             }
@@ -185,6 +252,7 @@ where
                     field_indexes,
                     payload_provider.clone(),
                     id_tracker,
+                    vector_storages,
                 );
                 (OptimizedCondition::Checker(condition_checker), estimation)
             }
@@ -195,10 +263,12 @@ where
 fn optimize_should<'a, F>(
     conditions: &'a [Condition],
     id_tracker: &IdTrackerSS,
+    vector_storages: &NamedVectorStorages,
     field_indexes: &'a IndexesMap,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
+    stopped: Option<&'a AtomicBool>,
 ) -> (Vec<OptimizedCondition<'a>>, CardinalityEstimation)
 where
     F: Fn(&Condition) -> CardinalityEstimation,
@@ -206,10 +276,12 @@ where
     let mut converted = convert_conditions(
         conditions,
         id_tracker,
+        vector_storages,
         field_indexes,
         payload_provider,
         estimator,
         total,
+        stopped,
     );
     // More probable conditions first
     converted.sort_by_key(|(_, estimation)| Reverse(estimation.exp));
@@ -221,10 +293,12 @@ where
 fn optimize_must<'a, F>(
     conditions: &'a [Condition],
     id_tracker: &IdTrackerSS,
+    vector_storages: &NamedVectorStorages,
     field_indexes: &'a IndexesMap,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
+    stopped: Option<&'a AtomicBool>,
 ) -> (Vec<OptimizedCondition<'a>>, CardinalityEstimation)
 where
     F: Fn(&Condition) -> CardinalityEstimation,
@@ -232,10 +306,12 @@ where
     let mut converted = convert_conditions(
         conditions,
         id_tracker,
+        vector_storages,
         field_indexes,
         payload_provider,
         estimator,
         total,
+        stopped,
     );
     // Less probable conditions first
     converted.sort_by_key(|(_, estimation)| estimation.exp);
@@ -247,10 +323,12 @@ where
 fn optimize_must_not<'a, F>(
     conditions: &'a [Condition],
     id_tracker: &IdTrackerSS,
+    vector_storages: &NamedVectorStorages,
     field_indexes: &'a IndexesMap,
     payload_provider: PayloadProvider,
     estimator: &F,
     total: usize,
+    stopped: Option<&'a AtomicBool>,
 ) -> (Vec<OptimizedCondition<'a>>, CardinalityEstimation)
 where
     F: Fn(&Condition) -> CardinalityEstimation,
@@ -258,10 +336,12 @@ where
     let mut converted = convert_conditions(
         conditions,
         id_tracker,
+        vector_storages,
         field_indexes,
         payload_provider,
         estimator,
         total,
+        stopped,
     );
     // More probable conditions first, as it will be reverted
     converted.sort_by_key(|(_, estimation)| estimation.exp);
@@ -1,3 +1,4 @@
+use std::collections::HashMap;
 use std::ops::Deref;
 use std::sync::Arc;
 
@@ -6,9 +7,15 @@ use atomic_refcell::AtomicRefCell;
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::types::{OwnedPayloadRef, Payload, PointOffsetType};
 
+/// Access to payloads of the points of a segment, used by condition checkers
+///
+/// Cheap to clone: the storage is shared by `Arc`, and the empty payload used for points without
+/// one is an empty map, which does not allocate. Checkers produced per condition own a clone each.
 #[derive(Clone)]
 pub struct PayloadProvider {
     payload_storage: Arc<AtomicRefCell<PayloadStorageEnum>>,
+    /// Payloads not written into the storage yet, shadowing the stored ones
+    uncommitted: Option<Arc<HashMap<PointOffsetType, Payload>>>,
     empty_payload: Payload,
 }
 
@@ -16,14 +23,50 @@ impl PayloadProvider {
     pub fn new(payload_storage: Arc<AtomicRefCell<PayloadStorageEnum>>) -> Self {
         Self {
             payload_storage,
+            uncommitted: None,
             empty_payload: Default::default(),
         }
     }
 
+    /// Overlay `uncommitted` payloads on top of the storage of this provider
+    ///
+    /// Points of the buffer are seen with the buffered payload, e.g. pending inserts of a
+    /// transaction, even if the storage has the point. Other points are read from the storage.
+    pub fn with_uncommitted(self, uncommitted: Arc<HashMap<PointOffsetType, Payload>>) -> Self {
+        Self {
+            uncommitted: Some(uncommitted),
+            ..self
+        }
+    }
+
+    /// Provider of the given JSON object payloads, without a segment or storage behind it
+    ///
+    /// Used by tests to evaluate nested conditions end to end.
+    #[cfg(test)]
+    pub fn in_memory(payloads: HashMap<PointOffsetType, serde_json::Value>) -> Self {
+        use crate::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
+
+        let payload_storage = InMemoryPayloadStorage {
+            payload: payloads
+                .into_iter()
+                .map(|(point_id, payload)| (point_id, Payload::from(payload)))
+                .collect(),
+        };
+        Self::new(Arc::new(AtomicRefCell::new(payload_storage.into())))
+    }
+
     pub fn with_payload<F, G>(&self, point_id: PointOffsetType, callback: F) -> G
     where
         F: FnOnce(OwnedPayloadRef) -> G,
     {
+        if let Some(payload) = self
+            .uncommitted
+            .as_ref()
+            .and_then(|uncommitted| uncommitted.get(&point_id))
+        {
+            return callback(payload.into());
+        }
+
         let payload_storage_guard = self.payload_storage.borrow();
         let payload_ptr_opt = match payload_storage_guard.deref() {
             PayloadStorageEnum::InMemoryPayloadStorage(s) => {
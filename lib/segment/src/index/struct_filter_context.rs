@@ -1,3 +1,5 @@
+use std::sync::atomic::AtomicBool;
+
 use crate::common::utils::IndexesMap;
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::CardinalityEstimation;
@@ -6,6 +8,7 @@ use crate::index::query_optimization::optimizer::optimize_filter;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::payload_storage::FilterContext;
 use crate::types::{Condition, Filter, PointOffsetType};
+use crate::vector_storage::NamedVectorStorages;
 
 pub struct StructFilterContext<'a> {
     optimized_filter: OptimizedFilter<'a>,
@@ -15,10 +18,12 @@ impl<'a> StructFilterContext<'a> {
     pub fn new<F>(
         filter: &'a Filter,
         id_tracker: &IdTrackerSS,
+        vector_storages: &NamedVectorStorages,
         payload_provider: PayloadProvider,
         field_indexes: &'a IndexesMap,
         estimator: &F,
         total: usize,
+        stopped: Option<&'a AtomicBool>,
     ) -> Self
     where
         F: Fn(&Condition) -> CardinalityEstimation,
@@ -26,11 +31,13 @@ impl<'a> StructFilterContext<'a> {
         let (optimized_filter, _) = optimize_filter(
             filter,
             id_tracker,
+            vector_storages,
             field_indexes,
             payload_provider,
             estimator,
             total,
             None,
+            stopped,
         );
 
         Self { optimized_filter }
@@ -2,6 +2,7 @@ use std::collections::{HashMap, HashSet};
 use std::fs::{create_dir_all, remove_file};
 use std::ops::Deref;
 use std::path::{Path, PathBuf};
+use std::sync::atomic::AtomicBool;
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
@@ -14,7 +15,7 @@ use crate::common::arc_atomic_ref_cell_iterator::ArcAtomicRefCellIterator;
 use crate::common::rocksdb_wrapper::open_db_with_existing_cf;
 use crate::common::utils::{IndexesMap, JsonPathPayload, MultiValue};
 use crate::common::Flusher;
-use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::entry::entry_point::{check_process_stopped, OperationError, OperationResult};
 use crate::id_tracker::IdTrackerSS;
 use crate::index::field_index::index_selector::index_selector;
 use crate::index::field_index::{
@@ -22,6 +23,8 @@ use crate::index::field_index::{
 };
 use crate::index::payload_config::PayloadConfig;
 use crate::index::query_estimator::estimate_filter;
+use crate::index::query_optimization::nested_filter::{nested_match_score, nested_min_match};
+use crate::index::query_optimization::nested_optimizer::is_query_stopped;
 use crate::index::query_optimization::payload_provider::PayloadProvider;
 use crate::index::struct_filter_context::StructFilterContext;
 use crate::index::visited_pool::VisitedPool;
@@ -31,9 +34,11 @@ use crate::payload_storage::{FilterContext, PayloadStorage};
 use crate::telemetry::PayloadIndexTelemetry;
 use crate::types::{
     infer_collection_value_type, infer_value_type, Condition, FieldCondition, Filter,
-    IsEmptyCondition, IsNullCondition, Payload, PayloadField, PayloadFieldSchema, PayloadKeyType,
-    PayloadKeyTypeRef, PayloadSchemaType, PointOffsetType,
+    IsEmptyCondition, IsNullCondition, NestedContainer, NestedQuantifier, Payload, PayloadField,
+    PayloadFieldSchema, PayloadKeyType, PayloadKeyTypeRef, PayloadSchemaType, PointOffsetType,
+    ScoreType,
 };
+use crate::vector_storage::NamedVectorStorages;
 
 pub const PAYLOAD_FIELD_INDEX_PATH: &str = "fields";
 
@@ -43,6 +48,8 @@ pub struct StructPayloadIndex {
     payload: Arc<AtomicRefCell<PayloadStorageEnum>>,
     /// Used for `has_id` condition and estimating cardinality
     id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+    /// Used for `vector_count` condition
+    vector_storages: NamedVectorStorages,
     /// Indexes, associated with fields
     pub field_indexes: IndexesMap,
     config: PayloadConfig,
@@ -146,6 +153,7 @@ impl StructPayloadIndex {
     pub fn open(
         payload: Arc<AtomicRefCell<PayloadStorageEnum>>,
         id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+        vector_storages: NamedVectorStorages,
         path: &Path,
     ) -> OperationResult<Self> {
         create_dir_all(path)?;
@@ -162,6 +170,7 @@ impl StructPayloadIndex {
         let mut index = StructPayloadIndex {
             payload,
             id_tracker,
+            vector_storages,
             field_indexes: Default::default(),
             config,
             path: path.to_owned(),
@@ -217,20 +226,147 @@ impl StructPayloadIndex {
         self.id_tracker.borrow().available_point_count()
     }
 
-    fn struct_filtered_context<'a>(&'a self, filter: &'a Filter) -> StructFilterContext<'a> {
+    fn struct_filtered_context<'a>(
+        &'a self,
+        filter: &'a Filter,
+        stopped: Option<&'a AtomicBool>,
+    ) -> StructFilterContext<'a> {
         let estimator = |condition: &Condition| self.condition_cardinality(condition, None);
         let id_tracker = self.id_tracker.borrow();
         let payload_provider = PayloadProvider::new(self.payload.clone());
         StructFilterContext::new(
             filter,
             id_tracker.deref(),
+            &self.vector_storages,
             payload_provider,
             &self.field_indexes,
             &estimator,
             self.available_point_count(),
+            stopped,
         )
     }
 
+    /// Same as `query_points`, but stops as soon as `stopped` flag is set, including the
+    /// evaluation of nested conditions of a single point
+    ///
+    /// Returns `Cancelled` error instead of the incomplete list of points.
+    pub fn query_points_cancellable(
+        &self,
+        query: &Filter,
+        stopped: &AtomicBool,
+    ) -> OperationResult<Vec<PointOffsetType>> {
+        let mut matched_points = vec![];
+        for point_id in self.query_points_stoppable(query, Some(stopped)) {
+            check_process_stopped(stopped)?;
+            matched_points.push(point_id);
+        }
+        // Points evaluated after the flag is set are not matched, so the result is incomplete
+        check_process_stopped(stopped)?;
+        Ok(matched_points)
+    }
+
+    fn query_points_stoppable<'a>(
+        &'a self,
+        query: &'a Filter,
+        stopped: Option<&'a AtomicBool>,
+    ) -> Box<dyn Iterator<Item = PointOffsetType> + 'a> {
+        // Assume query is already estimated to be small enough so we can iterate over all matched ids
+
+        let query_cardinality = self.estimate_cardinality(query);
+
+        if query_cardinality.primary_clauses.is_empty() {
+            let full_scan_iterator =
+                ArcAtomicRefCellIterator::new(self.id_tracker.clone(), |points_iterator| {
+                    points_iterator.iter_ids()
+                });
+
+            let struct_filtered_context = self.struct_filtered_context(query, stopped);
+            // Worst case: query expected to return few matches, but index can't be used
+            let matched_points = full_scan_iterator
+                .take_while(move |_| !is_query_stopped(stopped))
+                // Points evaluated while the query is being cancelled are not matched
+                .filter(move |i| struct_filtered_context.check(*i) && !is_query_stopped(stopped));
+
+            Box::new(matched_points)
+        } else {
+            let points_iterator_ref = self.id_tracker.borrow();
+            let struct_filtered_context = self.struct_filtered_context(query, stopped);
+
+            // CPU-optimized strategy here: points are made unique before applying other filters.
+            // TODO: Implement iterator which holds the `visited_pool` and borrowed `vector_storage_ref` to prevent `preselected` array creation
+            let mut visited_list = self
+                .visited_pool
+                .get(points_iterator_ref.total_point_count());
+
+            #[allow(clippy::needless_collect)]
+                let preselected: Vec<PointOffsetType> = query_cardinality
+                .primary_clauses
+                .iter()
+                .flat_map(|clause| {
+                    match clause {
+                        PrimaryCondition::Condition(field_condition) => {
+                            self.query_field(field_condition).unwrap_or_else(
+                                || points_iterator_ref.iter_ids(), /* index is not built */
+                            )
+                        }
+                        PrimaryCondition::Ids(ids) => Box::new(ids.iter().copied()),
+                        PrimaryCondition::IsEmpty(_) => points_iterator_ref.iter_ids(), /* there are no fast index for IsEmpty */
+                        PrimaryCondition::IsNull(_) => points_iterator_ref.iter_ids(),  /* no fast index for IsNull too */
+                    }
+                })
+                .take_while(|_| !is_query_stopped(stopped))
+                .filter(|&id| !visited_list.check_and_update_visited(id))
+                .filter(move |&i| struct_filtered_context.check(i) && !is_query_stopped(stopped))
+                .collect();
+
+            self.visited_pool.return_back(visited_list);
+
+            let matched_points_iter = preselected.into_iter();
+            Box::new(matched_points_iter)
+        }
+    }
+
+    /// Build a function returning the score contribution of a point from its nested elements
+    ///
+    /// Every element of the `nested` array matching the nested filter adds `weight` to the score.
+    pub fn nested_match_scorer<'a>(
+        &'a self,
+        nested: &'a NestedContainer,
+        weight: ScoreType,
+    ) -> impl Fn(PointOffsetType) -> ScoreType + 'a {
+        let payload_provider = PayloadProvider::new(self.payload.clone());
+        let nested_path = JsonPathPayload::new(nested.array_key());
+        let nested_filter = nested.element_filter();
+        move |point_id| {
+            nested_match_score(
+                point_id,
+                &nested_filter,
+                &self.field_indexes,
+                payload_provider.clone(),
+                nested_path.clone(),
+                weight,
+            )
+        }
+    }
+
+    /// Build a function checking that at least `min_count` of the `nested` filters match a point
+    pub fn nested_min_match_checker<'a>(
+        &'a self,
+        nested: &'a [NestedContainer],
+        min_count: usize,
+    ) -> impl Fn(PointOffsetType) -> bool + 'a {
+        let payload_provider = PayloadProvider::new(self.payload.clone());
+        move |point_id| {
+            nested_min_match(
+                point_id,
+                nested,
+                min_count,
+                &self.field_indexes,
+                payload_provider.clone(),
+            )
+        }
+    }
+
     fn condition_cardinality(
         &self,
         condition: &Condition,
@@ -241,7 +377,31 @@ impl StructPayloadIndex {
             Condition::Nested(nested) => {
                 // propagate complete nested path in case of multiple nested layers
                 let full_path = JsonPathPayload::extend_or_new(nested_path, &nested.array_key());
-                self.estimate_nested_cardinality(nested.filter(), &full_path)
+                let estimation =
+                    self.estimate_nested_cardinality(&nested.element_filter(), &full_path);
+                match nested.quantifier() {
+                    NestedQuantifier::Any => estimation,
+                    // Matching points have a matching element, but not the other way around
+                    NestedQuantifier::All => CardinalityEstimation {
+                        min: 0,
+                        ..estimation
+                    },
+                    // Points without elements match too, they can't be selected by the index
+                    NestedQuantifier::AllOrEmpty => {
+                        CardinalityEstimation::unknown(self.available_point_count())
+                    }
+                    // Positive ratio requires a matching element, same as for `All`
+                    NestedQuantifier::MinRatio(min_ratio) if min_ratio > 0.0 => {
+                        CardinalityEstimation {
+                            min: 0,
+                            ..estimation
+                        }
+                    }
+                    // Any non-empty array matches, even without matching elements
+                    NestedQuantifier::MinRatio(_) => {
+                        CardinalityEstimation::unknown(self.available_point_count())
+                    }
+                }
             }
             Condition::IsEmpty(IsEmptyCondition { is_empty: field }) => {
                 let available_points = self.available_point_count();
@@ -316,6 +476,18 @@ impl StructPayloadIndex {
                     max: num_ids,
                 }
             }
+            // No indexes for key presence, vector presence, aggregated and computed values
+            Condition::HasKey(_)
+            | Condition::VectorCount(_)
+            | Condition::VectorNotNormalized(_)
+            | Condition::Aggregate(_)
+            | Condition::Expression(_)
+            | Condition::Compare(_)
+            | Condition::HasDuplicates(_)
+            | Condition::ValueType(_)
+            | Condition::Position(_) => {
+                CardinalityEstimation::unknown(self.available_point_count())
+            }
             Condition::Field(field_condition) => self
                 .estimate_field_condition(field_condition, nested_path)
                 .unwrap_or_else(|| CardinalityEstimation::unknown(self.available_point_count())),
@@ -400,57 +572,7 @@ impl PayloadIndex for StructPayloadIndex {
         &'a self,
         query: &'a Filter,
     ) -> Box<dyn Iterator<Item = PointOffsetType> + 'a> {
-        // Assume query is already estimated to be small enough so we can iterate over all matched ids
-
-        let query_cardinality = self.estimate_cardinality(query);
-
-        if query_cardinality.primary_clauses.is_empty() {
-            let full_scan_iterator =
-                ArcAtomicRefCellIterator::new(self.id_tracker.clone(), |points_iterator| {
-                    points_iterator.iter_ids()
-                });
-
-            let struct_filtered_context = self.struct_filtered_context(query);
-            // Worst case: query expected to return few matches, but index can't be used
-            let matched_points =
-                full_scan_iterator.filter(move |i| struct_filtered_context.check(*i));
-
-            Box::new(matched_points)
-        } else {
-            let points_iterator_ref = self.id_tracker.borrow();
-            let struct_filtered_context = self.struct_filtered_context(query);
-
-            // CPU-optimized strategy here: points are made unique before applying other filters.
-            // TODO: Implement iterator which holds the `visited_pool` and borrowed `vector_storage_ref` to prevent `preselected` array creation
-            let mut visited_list = self
-                .visited_pool
-                .get(points_iterator_ref.total_point_count());
-
-            #[allow(clippy::needless_collect)]
-                let preselected: Vec<PointOffsetType> = query_cardinality
-                .primary_clauses
-                .iter()
-                .flat_map(|clause| {
-                    match clause {
-                        PrimaryCondition::Condition(field_condition) => {
-                            self.query_field(field_condition).unwrap_or_else(
-                                || points_iterator_ref.iter_ids(), /* index is not built */
-                            )
-                        }
-                        PrimaryCondition::Ids(ids) => Box::new(ids.iter().copied()),
-                        PrimaryCondition::IsEmpty(_) => points_iterator_ref.iter_ids(), /* there are no fast index for IsEmpty */
-                        PrimaryCondition::IsNull(_) => points_iterator_ref.iter_ids(),  /* no fast index for IsNull too */
-                    }
-                })
-                .filter(|&id| !visited_list.check_and_update_visited(id))
-                .filter(move |&i| struct_filtered_context.check(i))
-                .collect();
-
-            self.visited_pool.return_back(visited_list);
-
-            let matched_points_iter = preselected.into_iter();
-            Box::new(matched_points_iter)
-        }
+        self.query_points_stoppable(query, None)
     }
 
     fn indexed_points(&self, field: PayloadKeyTypeRef) -> usize {
@@ -467,7 +589,7 @@ impl PayloadIndex for StructPayloadIndex {
     }
 
     fn filter_context<'a>(&'a self, filter: &'a Filter) -> Box<dyn FilterContext + 'a> {
-        Box::new(self.struct_filtered_context(filter))
+        Box::new(self.struct_filtered_context(filter, None))
     }
 
     fn payload_blocks(
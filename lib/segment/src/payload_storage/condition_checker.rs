@@ -1,12 +1,42 @@
 //! Contains functions for interpreting filter queries and defining if given points pass the conditions
 
+use regex::{Regex, RegexBuilder};
 use serde_json::Value;
 
 use crate::types::{
-    AnyVariants, FieldCondition, GeoBoundingBox, GeoRadius, Match, MatchAny, MatchText, MatchValue,
-    Range, ValueVariants, ValuesCount,
+    AnyVariants, FieldCondition, GeoBoundingBox, GeoRadius, Match, MatchAny, MatchRegex, MatchText,
+    MatchValue, Range, ValueVariants, ValuesCount,
 };
 
+/// Max size in bytes of a compiled regex, protects from patterns expanding into huge automatons
+///
+/// Matching time of the compiled regex is linear in the size of the input, so the size limit
+/// is the only protection required against expensive patterns.
+pub const REGEX_SIZE_LIMIT: usize = 1 << 20;
+
+/// Compile `pattern` for matching, `None` if it is invalid or exceeds `REGEX_SIZE_LIMIT`
+pub fn compile_regex(pattern: &str) -> Option<Regex> {
+    RegexBuilder::new(pattern)
+        .size_limit(REGEX_SIZE_LIMIT)
+        .dfa_size_limit(REGEX_SIZE_LIMIT)
+        .build()
+        .ok()
+}
+
+/// Check if a string payload value, or any string of an array, matches `regex`
+///
+/// Non-string values never match.
+pub fn check_regex_match(regex: &Regex, payload: &Value) -> bool {
+    match payload {
+        Value::String(stored) => regex.is_match(stored),
+        Value::Array(values) => values.iter().any(|value| match value {
+            Value::String(stored) => regex.is_match(stored),
+            _ => false,
+        }),
+        _ => false,
+    }
+}
+
 pub trait ValueChecker {
     fn check_match(&self, payload: &Value) -> bool;
 
@@ -79,6 +109,10 @@ impl ValueChecker for Match {
                 Value::String(stored) => stored.contains(text),
                 _ => false,
             },
+            // Compiled for each value, checkers evaluating many values compile the regex once
+            Match::Regex(MatchRegex { regex }) => {
+                compile_regex(regex).map_or(false, |regex| check_regex_match(&regex, payload))
+            }
             Match::Any(MatchAny { any }) => match (payload, any) {
                 (Value::String(stored), AnyVariants::Keywords(list)) => list.contains(stored),
                 (Value::Number(stored), AnyVariants::Integers(list)) => stored
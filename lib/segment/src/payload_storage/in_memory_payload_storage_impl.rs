@@ -110,6 +110,7 @@ mod tests {
                 payload.borrow().as_ref().cloned().unwrap()
             },
             &id_tracker,
+            &Default::default(),
             &query,
             0,
         );
@@ -2,12 +2,21 @@ use std::ops::Deref;
 
 use bitvec::bitvec;
 use bitvec::prelude::BitVec;
+use regex::Regex;
 use serde_json::Value;
+use uuid::Uuid;
 
-use crate::common::utils::{IndexesMap, JsonPathPayload, MultiValue};
-use crate::payload_storage::condition_checker::ValueChecker;
+use crate::common::utils::{
+    get_value_from_json_map, unflatten_json_map, IndexesMap, JsonPathPayload, MultiValue,
+};
+use crate::entry::entry_point::{OperationError, OperationResult};
+use crate::index::field_index::FieldIndex;
+use crate::payload_storage::condition_checker::{check_regex_match, ValueChecker};
 use crate::types::{
-    Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, OwnedPayloadRef, Payload,
+    ComparisonCondition, Condition, ExpressionCondition, FieldCondition, Filter, HasKeyCondition,
+    InnerArrayMatch, IsEmptyCondition, IsNullCondition, JsonValueType, Match, MatchValue,
+    NestedNullPolicy, NestedQuantifier, OwnedPayloadRef, Payload, PositionCondition,
+    ValueTypeCondition, ValueVariants,
 };
 
 /// Executes condition checks for all `must` conditions of the nester objects.
@@ -40,13 +49,27 @@ where
                 .iter()
                 .map(checker)
                 .reduce(|acc, matches| acc | matches)
-                // There is at least one sub-object that does not match any of the conditions
-                .map(|matches: BitVec| matches.count_zeros() > 0)
-                .unwrap_or(true) // If there are no sub-objects, then must_not conditions are satisfied
+                // There is at least one sub-object that does not match any of the conditions.
+                // Missing or empty array has no sub-objects matching forbidden conditions,
+                // so must_not conditions are satisfied
+                .map(|matches: BitVec| matches.is_empty() || matches.count_zeros() > 0)
+                .unwrap_or(true) // If there are no conditions, nothing is forbidden
         }
     }
 }
 
+/// If there is at least one nested object that matches any of the `should` conditions, returns `true`.
+/// If there are no conditions in the clause, nothing matches it, same as on top level.
+fn check_nested_should_conditions<F>(checker: &F, should: &Option<Vec<Condition>>) -> bool
+where
+    F: Fn(&Condition) -> BitVec,
+{
+    match should {
+        None => true,
+        Some(conditions) => conditions.iter().any(|condition| checker(condition).any()),
+    }
+}
+
 pub fn check_nested_filter<'a, F>(
     nested_path: &JsonPathPayload,
     nested_filter: &Filter,
@@ -55,7 +78,95 @@ pub fn check_nested_filter<'a, F>(
 where
     F: Fn() -> OwnedPayloadRef<'a>,
 {
-    let nested_checker = |condition: &Condition| match condition {
+    let nested_checker =
+        |condition: &Condition| nested_condition_checker(condition, nested_path, &get_payload);
+
+    nested_filter_checker(&nested_checker, nested_filter)
+}
+
+/// Behavior of nested filters when the nested path holds a single value instead of an array
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScalarNestedPath {
+    /// Treat the single value as an array of one element
+    Lenient,
+    /// Reject the filter with an error
+    Strict,
+}
+
+/// Resolve path of the nested elements, taking single values at the nested path into account
+fn resolve_nested_path(
+    nested_path: &JsonPathPayload,
+    payload: &Payload,
+    mode: ScalarNestedPath,
+) -> OperationResult<JsonPathPayload> {
+    let base_path = match nested_path.path.strip_suffix("[]") {
+        Some(base_path) => base_path,
+        // Positional paths focus on a single element already
+        None => return Ok(nested_path.clone()),
+    };
+    let values = payload.get_nested_value(base_path).values();
+    if values
+        .iter()
+        .all(|value| value.is_array() || value.is_null())
+    {
+        return Ok(nested_path.clone());
+    }
+    match mode {
+        ScalarNestedPath::Strict => Err(OperationError::NestedPathNotArray {
+            path: base_path.to_string(),
+        }),
+        ScalarNestedPath::Lenient if values.iter().all(|value| !value.is_array()) => {
+            Ok(JsonPathPayload::new(base_path.to_string()))
+        }
+        // Mix of arrays and single values, only the array elements are considered
+        ScalarNestedPath::Lenient => Ok(nested_path.clone()),
+    }
+}
+
+/// Same as `check_nested_filter`, but a nested path holding a single value instead of an array
+/// is handled according to `mode`, instead of matching nothing.
+pub fn check_nested_filter_with_mode<'a, F>(
+    nested_path: &JsonPathPayload,
+    nested_filter: &Filter,
+    get_payload: F,
+    mode: ScalarNestedPath,
+) -> OperationResult<bool>
+where
+    F: Fn() -> OwnedPayloadRef<'a>,
+{
+    let nested_path = resolve_nested_path(nested_path, get_payload().deref(), mode)?;
+    Ok(check_nested_filter(
+        &nested_path,
+        nested_filter,
+        get_payload,
+    ))
+}
+
+/// Executes nested filter against a payload stored in flattened form
+///
+/// Flattened payload does not retain the structure of the nested objects,
+/// so element positions are reconstructed from keys like `reviews[0].rating` first.
+pub fn check_nested_filter_flattened(
+    nested_path: &JsonPathPayload,
+    nested_filter: &Filter,
+    flattened_payload: &Payload,
+) -> bool {
+    let payload = Payload(unflatten_json_map(&flattened_payload.0));
+    check_nested_filter(nested_path, nested_filter, || {
+        OwnedPayloadRef::from(&payload)
+    })
+}
+
+/// Return element indices matching the condition in the payload
+fn nested_condition_checker<'a, F>(
+    condition: &Condition,
+    nested_path: &JsonPathPayload,
+    get_payload: &F,
+) -> BitVec
+where
+    F: Fn() -> OwnedPayloadRef<'a>,
+{
+    match condition {
         Condition::Field(field_condition) => nested_check_field_condition(
             field_condition,
             get_payload().deref(),
@@ -68,12 +179,159 @@ where
         Condition::IsNull(is_null) => {
             check_nested_is_null_condition(nested_path, is_null, get_payload().deref())
         }
-        Condition::HasId(_) => unreachable!(), // Is there a use case for nested HasId?
-        Condition::Nested(_) => unreachable!(), // Several layers of nesting are not supported here
-        Condition::Filter(_) => unreachable!(),
-    };
+        Condition::HasKey(has_key) => {
+            check_nested_has_key_condition(nested_path, has_key, get_payload().deref())
+        }
+        // Point level conditions can't be evaluated for a single element, no element matches.
+        // Ids and vectors are not part of nested objects, aggregates and duplicates are checked
+        // for the whole array
+        Condition::HasId(_)
+        | Condition::VectorCount(_)
+        | Condition::VectorNotNormalized(_)
+        | Condition::Aggregate(_)
+        | Condition::HasDuplicates(_) => {
+            bitvec![0; nested_elements_count(nested_path, get_payload().deref())]
+        }
+        Condition::ValueType(value_type) => {
+            check_nested_value_type_condition(nested_path, value_type, get_payload().deref())
+        }
+        Condition::Position(position) => {
+            check_nested_position_condition(nested_path, position, get_payload().deref())
+        }
+        Condition::Expression(expression) => {
+            check_nested_expression_condition(nested_path, expression, get_payload().deref())
+        }
+        Condition::Compare(compare) => {
+            check_nested_comparison_condition(nested_path, compare, get_payload().deref())
+        }
+        // Element of the outer array matches if any element of its own inner array matches
+        Condition::Nested(nested) => {
+            let nested_filter = nested.element_filter();
+            nested_element_paths(nested_path, get_payload().deref())
+                .into_iter()
+                .map(|element_path| {
+                    let inner_path = element_path.extend(&nested.array_key());
+                    let inner_checker = |condition: &Condition| {
+                        nested_condition_checker(condition, &inner_path, get_payload)
+                    };
+                    let matches = nested_filter_group_checker(&inner_checker, &nested_filter);
+                    match nested.quantifier() {
+                        NestedQuantifier::Any => nested_group_matches(&nested_filter, &matches),
+                        quantifier => quantifier.check(
+                            &matches,
+                            nested_elements_count(&inner_path, get_payload().deref()),
+                        ),
+                    }
+                })
+                .collect()
+        }
+        // Group of conditions applied to the same nested element
+        Condition::Filter(filter) => nested_filter_group_checker(
+            &|condition: &Condition| nested_condition_checker(condition, nested_path, get_payload),
+            filter,
+        ),
+    }
+}
 
-    nested_filter_checker(&nested_checker, nested_filter)
+/// Return positions of the elements of the array at `nested_path` matching `nested_filter`
+///
+/// Unlike `check_nested_filter`, all clauses are applied to the same element, so the result
+/// identifies the elements to modify, e.g. by a nested payload update.
+pub fn nested_filter_positions(
+    nested_path: &JsonPathPayload,
+    nested_filter: &Filter,
+    payload: &Payload,
+) -> BitVec {
+    let get_payload = || OwnedPayloadRef::from(payload);
+    nested_filter_group_checker(
+        &|condition: &Condition| nested_condition_checker(condition, nested_path, &get_payload),
+        nested_filter,
+    )
+}
+
+/// Check the array at `nested_path` with the universal `quantifier`
+///
+/// Unlike `check_nested_filter`, all clauses are applied to the same element, as every element
+/// should satisfy the whole filter.
+pub fn check_nested_filter_quantified(
+    nested_path: &JsonPathPayload,
+    nested_filter: &Filter,
+    payload: &Payload,
+    quantifier: NestedQuantifier,
+) -> bool {
+    let matches = nested_filter_positions(nested_path, nested_filter, payload);
+    quantifier.check(&matches, nested_elements_count(nested_path, payload))
+}
+
+/// Number of elements of the array at `nested_path`, missing array has no elements
+pub fn nested_elements_count(nested_path: &JsonPathPayload, payload: &Payload) -> usize {
+    payload.get_nested_value(&nested_path.path).values().len()
+}
+
+/// Paths of the individual elements of the array at `nested_path`
+///
+/// E.g. `orders[0]`, `orders[1]` for `orders[]` with two elements. Used to evaluate inner nested
+/// arrays separately for each element of the outer array, instead of flattening them together.
+pub fn nested_element_paths(
+    nested_path: &JsonPathPayload,
+    payload: &Payload,
+) -> Vec<JsonPathPayload> {
+    let elements_count = nested_elements_count(nested_path, payload);
+    match nested_path.path.strip_suffix("[]") {
+        Some(array_path) => (0..elements_count)
+            .map(|index| JsonPathPayload::new(format!("{array_path}[{index}]")))
+            .collect(),
+        // Positional path already focuses on a single element
+        None if elements_count > 0 => vec![nested_path.clone()],
+        None => vec![],
+    }
+}
+
+/// Return element indices matching all clauses of the grouped `filter`
+fn nested_filter_group_checker<F>(checker: &F, filter: &Filter) -> BitVec
+where
+    F: Fn(&Condition) -> BitVec,
+{
+    let must = filter.must.as_ref().map(|conditions| {
+        conditions
+            .iter()
+            .map(checker)
+            .reduce(|acc, matches| acc & matches)
+            .unwrap_or_default()
+    });
+    let must_not = filter.must_not.as_ref().map(|conditions| {
+        conditions
+            .iter()
+            .map(checker)
+            .reduce(|acc, matches| acc | matches)
+            .map(|matches| !matches)
+            .unwrap_or_default()
+    });
+    let should = filter.should.as_ref().map(|conditions| {
+        conditions
+            .iter()
+            .map(checker)
+            .reduce(|acc, matches| acc | matches)
+            .unwrap_or_default()
+    });
+
+    [must, must_not, should]
+        .into_iter()
+        .flatten()
+        .reduce(|acc, matches| acc & matches)
+        .unwrap_or_default()
+}
+
+/// Check if the nested `filter` matches, given the element indices matching its grouped clauses
+///
+/// Missing or empty array has no elements matching forbidden conditions,
+/// so a filter consisting only of `must_not` clauses is satisfied.
+pub fn nested_group_matches(filter: &Filter, matches: &BitVec) -> bool {
+    matches.any()
+        || (matches.is_empty()
+            && filter.must.is_none()
+            && filter.should.is_none()
+            && filter.must_not.is_some())
 }
 
 pub fn nested_filter_checker<F>(matching_paths: &F, nested_filter: &Filter) -> bool
@@ -82,25 +340,50 @@ where
 {
     check_nested_must_conditions(matching_paths, &nested_filter.must)
         && check_nested_must_not_conditions(matching_paths, &nested_filter.must_not)
+        && check_nested_should_conditions(matching_paths, &nested_filter.should)
 }
 
 /// Return element indices matching the condition in the payload
+///
+/// Elements without the field are empty.
 pub fn check_nested_is_empty_condition(
     nested_path: &JsonPathPayload,
     is_empty: &IsEmptyCondition,
     payload: &Payload,
 ) -> BitVec {
-    let full_path = nested_path.extend(&is_empty.is_empty.key);
-    let field_values = payload.get_value(&full_path.path).values();
-    let mut result = BitVec::with_capacity(field_values.len());
-    for p in field_values {
-        match p {
-            Value::Null => result.push(true),
-            Value::Array(vec) if vec.is_empty() => result.push(true),
-            _ => result.push(false),
-        }
-    }
-    result
+    nested_element_field_values(nested_path, &is_empty.is_empty.key, payload)
+        .into_iter()
+        .map(|values| {
+            values.iter().all(|value| match value {
+                Value::Null => true,
+                Value::Array(vec) => vec.is_empty(),
+                _ => false,
+            })
+        })
+        .collect()
+}
+
+/// Values of the field at `key` for each element of the array at `nested_path`
+///
+/// One item is returned per element, so that positions of the items are the positions of the
+/// elements. Elements without the field, including elements which are not objects, have no
+/// values. Empty `key` refers to the element itself.
+fn nested_element_field_values<'a>(
+    nested_path: &JsonPathPayload,
+    key: &str,
+    payload: &'a Payload,
+) -> Vec<Vec<&'a Value>> {
+    let key = JsonPathPayload::new(key.to_string());
+    payload
+        .get_nested_value(&nested_path.path)
+        .values()
+        .into_iter()
+        .map(|element| match element {
+            element if key.path.is_empty() => vec![element],
+            Value::Object(object) => get_value_from_json_map(&key.path, object).values(),
+            _ => vec![],
+        })
+        .collect()
 }
 
 /// Return element indices matching the condition in the payload
@@ -110,7 +393,7 @@ pub fn check_nested_is_null_condition(
     payload: &Payload,
 ) -> BitVec {
     let full_path = nested_path.extend(&is_null.is_null.key);
-    let field_values = payload.get_value(&full_path.path);
+    let field_values = payload.get_nested_value(&full_path.path);
     match field_values {
         MultiValue::Single(None) => bitvec![1; 1],
         MultiValue::Single(Some(v)) => {
@@ -135,41 +418,312 @@ pub fn check_nested_is_null_condition(
     }
 }
 
+/// Return element indices which objects contain the key, regardless of its value
+///
+/// Elements which are not objects never contain the key.
+pub fn check_nested_has_key_condition(
+    nested_path: &JsonPathPayload,
+    has_key: &HasKeyCondition,
+    payload: &Payload,
+) -> BitVec {
+    payload
+        .get_nested_value(&nested_path.path)
+        .values()
+        .into_iter()
+        .map(|element| match element {
+            Value::Object(object) => !get_value_from_json_map(&has_key.has_key.key, object)
+                .values()
+                .is_empty(),
+            _ => false,
+        })
+        .collect()
+}
+
+/// Return element indices which field has a value of the expected JSON type
+///
+/// Elements without the field never match.
+pub fn check_nested_value_type_condition(
+    nested_path: &JsonPathPayload,
+    value_type: &ValueTypeCondition,
+    payload: &Payload,
+) -> BitVec {
+    let value_type = &value_type.value_type;
+    nested_element_field_values(nested_path, &value_type.key, payload)
+        .into_iter()
+        .map(|values| {
+            values
+                .iter()
+                .any(|value| JsonValueType::of(value) == value_type.r#type)
+        })
+        .collect()
+}
+
+/// Return indices of the elements at the positions selected by the condition
+///
+/// Positions are relative to the array at `nested_path`, e.g. to the single focused element
+/// of a positional nested path.
+pub fn check_nested_position_condition(
+    nested_path: &JsonPathPayload,
+    position: &PositionCondition,
+    payload: &Payload,
+) -> BitVec {
+    let length = payload.get_nested_value(&nested_path.path).values().len();
+    (0..length)
+        .map(|index| position.position.contains(index, length))
+        .collect()
+}
+
+pub fn check_nested_expression_condition(
+    nested_path: &JsonPathPayload,
+    expression: &ExpressionCondition,
+    payload: &Payload,
+) -> BitVec {
+    payload
+        .get_nested_value(&nested_path.path)
+        .values()
+        .into_iter()
+        .map(|element| match element {
+            Value::Object(object) => expression.expression.check(object),
+            _ => false,
+        })
+        .collect()
+}
+
+pub fn check_nested_comparison_condition(
+    nested_path: &JsonPathPayload,
+    compare: &ComparisonCondition,
+    payload: &Payload,
+) -> BitVec {
+    payload
+        .get_nested_value(&nested_path.path)
+        .values()
+        .into_iter()
+        .map(|element| match element {
+            Value::Object(object) => compare.compare.check(object),
+            _ => false,
+        })
+        .collect()
+}
+
+/// Return indices of the values of `key` in the nested elements, which match `regex`
+///
+/// Used to compile the regex of the condition once per query, instead of once per value.
+/// Pattern which failed to compile matches nothing.
+pub fn nested_check_regex_condition(
+    nested_path: &JsonPathPayload,
+    key: &str,
+    regex: Option<&Regex>,
+    payload: &Payload,
+) -> BitVec {
+    nested_element_field_values(nested_path, key, payload)
+        .into_iter()
+        .map(|values| {
+            regex.map_or(false, |regex| {
+                values.iter().any(|value| check_regex_match(regex, value))
+            })
+        })
+        .collect()
+}
+
+/// Path of the condition `key` inside of the elements of `nested_path`
+///
+/// Empty key refers to the element itself, e.g. to the inner arrays of `matrix: [[1, 2], [3]]`.
+fn nested_field_path(nested_path: &JsonPathPayload, key: &str) -> JsonPathPayload {
+    if key.is_empty() {
+        nested_path.clone()
+    } else {
+        nested_path.extend(key)
+    }
+}
+
 /// Return indexes of the elements matching the condition in the payload values
+///
+/// Elements with a `null` value, e.g. gaps of `[5, null, 3]`, don't match the condition but
+/// keep their position, so the result has a bit for every element of the array.
+/// `null` values are handled according to the `null_policy` of the condition.
 pub fn nested_check_field_condition(
     field_condition: &FieldCondition,
     payload: &Payload,
     nested_path: &JsonPathPayload,
     field_indexes: &IndexesMap,
 ) -> BitVec {
-    let full_path = nested_path.extend(&field_condition.key);
-    let field_values = payload.get_value(&full_path.path).values();
-    let mut result = BitVec::with_capacity(field_values.len());
-
-    let field_indexes = field_indexes.get(&full_path.path);
-
-    for p in field_values {
-        // This covers a case, when a field index affects the result of the condition.
-        // Only required in the nested case,
-        // because non-nested payload is checked by the index directly.
-        let mut index_check_res = None;
-        if let Some(field_indexes) = field_indexes {
-            for index in field_indexes {
-                index_check_res = index.check_condition(field_condition, p);
-                if index_check_res.is_some() {
-                    break;
+    check_nested_field_values_lenient(field_condition, payload, nested_path, field_indexes)
+}
+
+/// Same as `nested_check_field_condition`, but `null` values of the field are handled
+/// according to `null_policy`, instead of the policy of the condition
+///
+/// Elements are projected to the condition key before evaluation: the full path
+/// `nested_path.key` is resolved at once, so only the referenced field of each element is
+/// visited and passed to the condition, regardless of how wide the elements are.
+pub fn nested_check_field_condition_with_policy(
+    field_condition: &FieldCondition,
+    payload: &Payload,
+    nested_path: &JsonPathPayload,
+    field_indexes: &IndexesMap,
+    null_policy: NestedNullPolicy,
+) -> OperationResult<BitVec> {
+    check_nested_field_values(
+        field_condition,
+        payload,
+        nested_path,
+        field_indexes,
+        null_policy,
+    )
+}
+
+fn check_nested_field_value(field_condition: &FieldCondition, value: &Value) -> bool {
+    field_condition.check(value) || check_nested_uuid_match(field_condition, value)
+}
+
+/// Check nested field values with the `null_policy` of the condition
+///
+/// A query can't fail for a single point, so `null` values rejected by `NestedNullPolicy::Error`
+/// are logged and don't match instead.
+fn check_nested_field_values_lenient(
+    field_condition: &FieldCondition,
+    payload: &Payload,
+    nested_path: &JsonPathPayload,
+    field_indexes: &IndexesMap,
+) -> BitVec {
+    let check = |null_policy| {
+        check_nested_field_values(
+            field_condition,
+            payload,
+            nested_path,
+            field_indexes,
+            null_policy,
+        )
+    };
+    check(field_condition.null_policy.unwrap_or_default()).unwrap_or_else(|err| {
+        log::warn!("{err}, the value does not match");
+        check(NestedNullPolicy::NoMatch).expect("null values do not fail with no match policy")
+    })
+}
+
+fn check_nested_field_values(
+    field_condition: &FieldCondition,
+    payload: &Payload,
+    nested_path: &JsonPathPayload,
+    field_indexes: &IndexesMap,
+    null_policy: NestedNullPolicy,
+) -> OperationResult<BitVec> {
+    let full_path = nested_field_path(nested_path, &field_condition.key);
+    let elements = nested_element_field_values(nested_path, &field_condition.key, payload);
+    let mut result = BitVec::with_capacity(elements.len());
+
+    let field_indexes = field_indexes.get(&full_path.path).map(Vec::as_slice);
+    let inner_match = field_condition.inner_match.unwrap_or_default();
+
+    let check_value =
+        |value: &Value| check_nested_field_value_with_index(field_condition, value, field_indexes);
+
+    for field_values in elements {
+        // Element without the field has no values to match, e.g. neither `true` nor `false`
+        // matches an absent boolean field. Only values count condition counts it as no values
+        if field_values.is_empty() {
+            result.push(
+                field_condition.values_count.is_some() && field_condition.check(&Value::Null),
+            );
+            continue;
+        }
+        // Element result is absent, if all of its values are skipped
+        let mut element_res = None;
+        for p in field_values {
+            // Values count condition counts `null` as no values, it is not affected by the policy
+            let res = if p.is_null() && field_condition.values_count.is_none() {
+                match null_policy {
+                    NestedNullPolicy::NoMatch => false,
+                    NestedNullPolicy::Skip => continue,
+                    NestedNullPolicy::Error => {
+                        return Err(OperationError::NestedNullValue {
+                            path: full_path.path.clone(),
+                        })
+                    }
+                }
+            } else {
+                match (p, inner_match) {
+                    // Values count condition counts the inner values, instead of checking them
+                    (Value::Array(inner), InnerArrayMatch::All)
+                        if field_condition.values_count.is_none() =>
+                    {
+                        !inner.is_empty() && inner.iter().all(&check_value)
+                    }
+                    _ => check_value(p),
                 }
+            };
+            element_res = Some(match (element_res, inner_match) {
+                (None, _) => res,
+                (Some(acc), InnerArrayMatch::Any) => acc || res,
+                (Some(acc), InnerArrayMatch::All) => acc && res,
+            });
+        }
+        // Element with only skipped values keeps its position, but does not match
+        result.push(element_res.unwrap_or(false));
+    }
+    Ok(result)
+}
+
+/// Check a single value of a nested element, taking field indexes of the value path into account
+fn check_nested_field_value_with_index(
+    field_condition: &FieldCondition,
+    p: &Value,
+    field_indexes: Option<&[FieldIndex]>,
+) -> bool {
+    // This covers a case, when a field index affects the result of the condition.
+    // Only required in the nested case,
+    // because non-nested payload is checked by the index directly.
+    let mut index_check_res = None;
+    if let Some(field_indexes) = field_indexes {
+        for index in field_indexes {
+            index_check_res = index.check_condition(field_condition, p);
+            if index_check_res.is_some() {
+                break;
             }
         }
-        // Fallback to regular condition check if index-aware check did not return a result
-        let res = index_check_res.unwrap_or_else(|| field_condition.check(p));
-        result.push(res);
     }
-    result
+    // Fallback to regular condition check if index-aware check did not return a result
+    index_check_res.unwrap_or_else(|| check_nested_field_value(field_condition, p))
+}
+
+/// Check if the stored value is the same UUID as the keyword of the match condition
+///
+/// UUIDs of nested elements are compared by value, so any of the textual forms, e.g. hyphenated
+/// or simple in any letter case, as well as an array of 16 bytes match the canonical keyword.
+fn check_nested_uuid_match(field_condition: &FieldCondition, value: &Value) -> bool {
+    let expected = match &field_condition.r#match {
+        Some(Match::Value(MatchValue {
+            value: ValueVariants::Keyword(keyword),
+        })) => match Uuid::parse_str(keyword) {
+            Ok(uuid) => uuid,
+            Err(_) => return false,
+        },
+        _ => return false,
+    };
+    let is_expected = |value: &Value| match value {
+        Value::String(stored) => Uuid::parse_str(stored).map_or(false, |uuid| uuid == expected),
+        _ => false,
+    };
+    match value {
+        Value::Array(values) => {
+            uuid_from_bytes(values) == Some(expected) || values.iter().any(is_expected)
+        }
+        value => is_expected(value),
+    }
+}
+
+/// Parse an array of 16 byte values as UUID
+fn uuid_from_bytes(values: &[Value]) -> Option<Uuid> {
+    let bytes = values
+        .iter()
+        .map(|value| value.as_u64().and_then(|byte| u8::try_from(byte).ok()))
+        .collect::<Option<Vec<u8>>>()?;
+    Uuid::from_slice(&bytes).ok()
 }
 
 #[cfg(test)]
 mod tests {
+    use std::collections::HashMap;
     use std::sync::Arc;
 
     use atomic_refcell::AtomicRefCell;
@@ -180,13 +734,22 @@ mod tests {
     use crate::common::rocksdb_wrapper::{open_db, DB_VECTOR_CF};
     use crate::id_tracker::simple_id_tracker::SimpleIdTracker;
     use crate::id_tracker::IdTracker;
+    use crate::index::query_optimization::nested_filter::{
+        nested_conditions_converter, nested_min_match,
+    };
+    use crate::index::query_optimization::payload_provider::PayloadProvider;
+    use crate::payload_storage::condition_checker::compile_regex;
     use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
     use crate::payload_storage::query_checker::SimpleConditionChecker;
     use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
     use crate::payload_storage::{ConditionChecker, PayloadStorage};
     use crate::types::{
-        FieldCondition, GeoBoundingBox, GeoPoint, GeoRadius, PayloadField, Range, ValuesCount,
+        ArithmeticOperator, CollationLocale, Comparison, ComparisonOperator, FieldCondition,
+        FractionRange, GeoBoundingBox, GeoPoint, GeoRadius, Match, NestedCondition,
+        NestedContainer, PayloadField, PointOffsetType, PositionRange, Range, SequenceMarker,
+        ValuesCount,
     };
+    use crate::vector_storage::NamedVectorStorages;
 
     #[test]
     fn test_nested_condition_checker() {
@@ -306,6 +869,7 @@ mod tests {
         let payload_checker = SimpleConditionChecker::new(
             Arc::new(AtomicRefCell::new(payload_storage)),
             Arc::new(AtomicRefCell::new(id_tracker)),
+            NamedVectorStorages::default(),
         );
 
         // single must range condition nested field in array
@@ -451,4 +1015,1631 @@ mod tests {
         assert!(!payload_checker.check(japan_id, &location_close_to_berlin_radius_condition));
         assert!(!payload_checker.check(boring_id, &location_close_to_berlin_radius_condition));
     }
+
+    #[test]
+    fn test_nested_filter_on_flattened_payload() {
+        let flattened: Payload = json!({
+            "title": "book",
+            "reviews[0].rating": 5,
+            "reviews[0].verified": false,
+            "reviews[1].rating": 2,
+            "reviews[1].verified": true,
+        })
+        .into();
+
+        let nested_path = JsonPathPayload::new("reviews[]".to_string());
+
+        // Same element must be high rated and verified
+        let high_rated_verified = Filter {
+            should: None,
+            must: Some(vec![
+                Condition::Field(FieldCondition::new_range(
+                    "rating".to_string(),
+                    Range {
+                        lt: None,
+                        gt: None,
+                        gte: Some(4.0),
+                        lte: None,
+                    },
+                )),
+                Condition::Field(FieldCondition::new_match(
+                    "verified".to_string(),
+                    true.into(),
+                )),
+            ]),
+            must_not: None,
+        };
+        assert!(!check_nested_filter_flattened(
+            &nested_path,
+            &high_rated_verified,
+            &flattened
+        ));
+
+        // Verified low rated review exists
+        let low_rated_verified = Filter {
+            should: None,
+            must: Some(vec![
+                Condition::Field(FieldCondition::new_range(
+                    "rating".to_string(),
+                    Range {
+                        lt: Some(3.0),
+                        gt: None,
+                        gte: None,
+                        lte: None,
+                    },
+                )),
+                Condition::Field(FieldCondition::new_match(
+                    "verified".to_string(),
+                    true.into(),
+                )),
+            ]),
+            must_not: None,
+        };
+        assert!(check_nested_filter_flattened(
+            &nested_path,
+            &low_rated_verified,
+            &flattened
+        ));
+    }
+
+    #[test]
+    fn test_positional_nested_condition() {
+        let payload: Payload = json!({
+            "reviews": [
+                { "rating": 5 },
+                { "rating": 3 },
+            ]
+        })
+        .into();
+
+        let check_at = |index: usize| {
+            let condition = Condition::new_nested_at(
+                "reviews",
+                index,
+                Filter::new_must(Condition::Field(FieldCondition::new_match(
+                    "rating".to_string(),
+                    5.into(),
+                ))),
+            );
+            let nested = match &condition {
+                Condition::Nested(nested) => nested.clone(),
+                _ => unreachable!(),
+            };
+            let nested_path = JsonPathPayload::new(nested.array_key());
+
+            // Only a single element is considered
+            let matches = nested_check_field_condition(
+                &FieldCondition::new_match("rating".to_string(), 5.into()),
+                &payload,
+                &nested_path,
+                &Default::default(),
+            );
+            assert!(matches.len() <= 1);
+
+            check_nested_filter(&nested_path, nested.filter(), || {
+                OwnedPayloadRef::from(&payload)
+            })
+        };
+
+        // the first review has rating 5
+        assert!(check_at(0));
+        // the second one does not
+        assert!(!check_at(1));
+        // there is no element at this position
+        assert!(!check_at(10));
+    }
+
+    #[test]
+    fn test_last_element_nested_condition() {
+        let condition = Condition::new_nested_last(
+            "reviews",
+            Filter::new_must(Condition::Field(FieldCondition::new_match(
+                "rating".to_string(),
+                5.into(),
+            ))),
+        );
+        let nested = match &condition {
+            Condition::Nested(nested) => nested.clone(),
+            _ => unreachable!(),
+        };
+        assert_eq!(nested.array_key(), "reviews[-1]");
+        let nested_path = JsonPathPayload::new(nested.array_key());
+
+        let check = |payload: Value| {
+            let payload: Payload = payload.into();
+            check_nested_filter(&nested_path, nested.filter(), || {
+                OwnedPayloadRef::from(&payload)
+            })
+        };
+
+        assert!(check(json!({ "reviews": [{ "rating": 5 }] })));
+        assert!(check(
+            json!({ "reviews": [{ "rating": 3 }, { "rating": 5 }] })
+        ));
+        assert!(check(json!({
+            "reviews": [{ "rating": 3 }, { "rating": 4 }, { "rating": 3 }, { "rating": 5 }]
+        })));
+        // Only the last element is considered, earlier matches are ignored
+        assert!(!check(
+            json!({ "reviews": [{ "rating": 5 }, { "rating": 3 }] })
+        ));
+        assert!(!check(json!({
+            "reviews": [{ "rating": 5 }, { "rating": 5 }, { "rating": 4 }]
+        })));
+        assert!(!check(json!({ "reviews": [] })));
+        assert!(!check(json!({ "other": [{ "rating": 5 }] })));
+
+        // Other negative positions count from the end as well
+        let second_to_last: Condition = serde_json::from_value(json!({
+            "nested": {
+                "key": "reviews",
+                "index": -2,
+                "filter": { "must": [{ "key": "rating", "match": { "value": 5 } }] }
+            }
+        }))
+        .unwrap();
+        let nested = match second_to_last {
+            Condition::Nested(nested) => nested,
+            _ => unreachable!(),
+        };
+        let nested_path = JsonPathPayload::new(nested.array_key());
+        let payload: Payload = json!({
+            "reviews": [{ "rating": 4 }, { "rating": 5 }, { "rating": 3 }]
+        })
+        .into();
+        assert!(check_nested_filter(&nested_path, nested.filter(), || {
+            OwnedPayloadRef::from(&payload)
+        }));
+        let payload: Payload = json!({ "reviews": [{ "rating": 5 }] }).into();
+        assert!(!check_nested_filter(&nested_path, nested.filter(), || {
+            OwnedPayloadRef::from(&payload)
+        }));
+    }
+
+    #[test]
+    fn test_nested_condition_after_marker() {
+        let condition: Condition = serde_json::from_value(json!({
+            "nested": {
+                "key": "events",
+                "after": { "key": "seq", "value": 2 },
+                "filter": { "must": [{ "key": "kind", "match": { "value": "error" } }] }
+            }
+        }))
+        .unwrap();
+        let marker = SequenceMarker {
+            key: "seq".to_string(),
+            value: 2.0,
+        };
+        let error = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "kind".to_string(),
+            "error".to_string().into(),
+        )));
+        assert_eq!(
+            condition,
+            Condition::new_nested_after("events", marker, error.clone())
+        );
+        let nested = match condition {
+            Condition::Nested(nested) => nested,
+            _ => unreachable!(),
+        };
+        assert_eq!(nested.filter(), &error);
+        let nested_path = JsonPathPayload::new(nested.array_key());
+
+        let payloads = [
+            // Error is logged after the marker
+            json!({ "events": [
+                { "seq": 1, "kind": "info" },
+                { "seq": 2, "kind": "info" },
+                { "seq": 3, "kind": "error" },
+            ] }),
+            // Errors up to the marker are ignored
+            json!({ "events": [
+                { "seq": 1, "kind": "error" },
+                { "seq": 2, "kind": "error" },
+                { "seq": 3, "kind": "info" },
+            ] }),
+            // Elements without a sequence number are never after the marker
+            json!({ "events": [{ "kind": "error" }] }),
+            json!({ "events": [] }),
+        ];
+        let expected = [true, false, false, false];
+
+        let payload_provider = PayloadProvider::in_memory(HashMap::from_iter(
+            payloads
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(i, p)| (i as u32, p)),
+        ));
+        let field_indexes = IndexesMap::default();
+        for (point_id, (payload, expected)) in payloads.into_iter().zip(expected).enumerate() {
+            let payload: Payload = payload.into();
+            let matches = check_nested_filter(&nested_path, &nested.element_filter(), || {
+                OwnedPayloadRef::from(&payload)
+            });
+            assert_eq!(matches, expected, "point {point_id}");
+            // Same result when evaluated with field indexes
+            let matches = nested_min_match(
+                point_id as PointOffsetType,
+                std::slice::from_ref(&nested),
+                1,
+                &field_indexes,
+                payload_provider.clone(),
+            );
+            assert_eq!(matches, expected, "point {point_id}");
+        }
+    }
+
+    #[test]
+    fn test_nested_numeric_condition_with_null_gaps() {
+        let payload = json!({ "scores": [5, null, 3] });
+        let nested_path = JsonPathPayload::new("scores[]".to_string());
+        let range = |gte: f64| {
+            FieldCondition::new_range(
+                String::new(),
+                Range {
+                    lt: None,
+                    gt: None,
+                    gte: Some(gte),
+                    lte: None,
+                },
+            )
+        };
+
+        // Null element does not match, other elements keep their positions
+        let matches = nested_check_field_condition(
+            &range(3.0),
+            &payload.clone().into(),
+            &nested_path,
+            &Default::default(),
+        );
+        assert_eq!(matches, bitvec![1, 0, 1]);
+        let matches = nested_check_field_condition(
+            &range(4.0),
+            &payload.clone().into(),
+            &nested_path,
+            &Default::default(),
+        );
+        assert_eq!(matches, bitvec![1, 0, 0]);
+
+        // Same positions when evaluated with field indexes
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([(0, payload.clone())]));
+        let conditions = [Condition::Field(range(3.0))];
+        let checkers = nested_conditions_converter(
+            &conditions,
+            payload_provider,
+            &IndexesMap::default(),
+            nested_path.clone(),
+        );
+        assert_eq!(checkers[0](0), bitvec![1, 0, 1]);
+
+        let payload: Payload = payload.into();
+        let check = |gte: f64| {
+            check_nested_filter(
+                &nested_path,
+                &Filter::new_must(Condition::Field(range(gte))),
+                || OwnedPayloadRef::from(&payload),
+            )
+        };
+        assert!(check(5.0));
+        assert!(!check(6.0));
+    }
+
+    #[test]
+    fn test_nested_has_key_condition() {
+        let payload: Payload = json!({
+            "reviews": [
+                { "rating": 5, "comment": "great" },
+                { "rating": 3 },
+                { "rating": 1, "comment": null },
+                { "rating": 4, "author": { "name": "john" } },
+            ]
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("reviews[]".to_string());
+
+        let has_comment = HasKeyCondition::from("comment".to_string());
+        let matches = check_nested_has_key_condition(&nested_path, &has_comment, &payload);
+        // `null` value still means the key is present
+        assert_eq!(matches, bitvec![1, 0, 1, 0]);
+
+        let has_author_name = HasKeyCondition::from("author.name".to_string());
+        let matches = check_nested_has_key_condition(&nested_path, &has_author_name, &payload);
+        assert_eq!(matches, bitvec![0, 0, 0, 1]);
+
+        let has_missing = HasKeyCondition::from("missing".to_string());
+        let matches = check_nested_has_key_condition(&nested_path, &has_missing, &payload);
+        assert_eq!(matches, bitvec![0, 0, 0, 0]);
+
+        // Elements which are not objects have no keys
+        let mixed_payload: Payload = json!({ "reviews": [{ "comment": "ok" }, "comment"] }).into();
+        let matches = check_nested_has_key_condition(&nested_path, &has_comment, &mixed_payload);
+        assert_eq!(matches, bitvec![1, 0]);
+
+        // Same element must be low rated and have a comment
+        let low_rated_with_comment = Filter::new_must(Condition::Filter(Filter {
+            should: None,
+            must: Some(vec![
+                Condition::HasKey(HasKeyCondition::from("comment".to_string())),
+                Condition::Field(FieldCondition::new_range(
+                    "rating".to_string(),
+                    Range {
+                        lt: Some(4.0),
+                        gt: None,
+                        gte: None,
+                        lte: None,
+                    },
+                )),
+            ]),
+            must_not: None,
+        }));
+        assert!(check_nested_filter(
+            &nested_path,
+            &low_rated_with_comment,
+            || OwnedPayloadRef::from(&payload)
+        ));
+
+        // No element without a comment has rating 5
+        let top_rated_without_comment = Filter {
+            should: None,
+            must: Some(vec![Condition::Field(FieldCondition::new_match(
+                "rating".to_string(),
+                5.into(),
+            ))]),
+            must_not: Some(vec![Condition::HasKey(HasKeyCondition::from(
+                "comment".to_string(),
+            ))]),
+        };
+        assert!(!check_nested_filter(
+            &nested_path,
+            &Filter::new_must(Condition::Filter(top_rated_without_comment)),
+            || OwnedPayloadRef::from(&payload)
+        ));
+    }
+
+    #[test]
+    fn test_nested_filter_on_scalar_path() {
+        let scalar_payload: Payload = json!({
+            "country": { "name": "Germany", "capital": "Berlin" }
+        })
+        .into();
+        let array_payload: Payload = json!({
+            "country": [{ "name": "Germany", "capital": "Berlin" }]
+        })
+        .into();
+        let missing_payload: Payload = json!({ "city": "Berlin" }).into();
+
+        let nested_path = JsonPathPayload::new("country[]".to_string());
+        let filter = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "name".to_string(),
+            "Germany".to_string().into(),
+        )));
+
+        let check = |payload: &Payload, mode| {
+            check_nested_filter_with_mode(
+                &nested_path,
+                &filter,
+                || OwnedPayloadRef::from(payload),
+                mode,
+            )
+        };
+
+        // Without a mode, single value is never matched
+        assert!(!check_nested_filter(&nested_path, &filter, || {
+            OwnedPayloadRef::from(&scalar_payload)
+        }));
+
+        // Single value acts as an array of one element
+        assert!(check(&scalar_payload, ScalarNestedPath::Lenient).unwrap());
+        let matches = nested_check_field_condition(
+            &FieldCondition::new_match("name".to_string(), "Germany".to_string().into()),
+            &scalar_payload,
+            &resolve_nested_path(&nested_path, &scalar_payload, ScalarNestedPath::Lenient).unwrap(),
+            &Default::default(),
+        );
+        assert_eq!(matches, bitvec![1]);
+
+        // Single value is rejected
+        let result = check(&scalar_payload, ScalarNestedPath::Strict);
+        match result {
+            Err(OperationError::NestedPathNotArray { path }) => assert_eq!(path, "country"),
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        // Arrays and missing values behave the same in both modes
+        for mode in [ScalarNestedPath::Lenient, ScalarNestedPath::Strict] {
+            assert!(check(&array_payload, mode).unwrap());
+            assert!(!check(&missing_payload, mode).unwrap());
+        }
+    }
+
+    #[test]
+    fn test_nested_arrays_inside_nested_arrays() {
+        // Only the second order has an apple item with large quantity,
+        // the first one has an apple and a large quantity in different items
+        let payload: Payload = json!({
+            "orders": [
+                {
+                    "id": 1,
+                    "items": [
+                        { "name": "apple", "quantity": 1 },
+                        { "name": "pear", "quantity": 5 },
+                    ]
+                },
+                {
+                    "id": 2,
+                    "items": [
+                        { "name": "apple", "quantity": 10 },
+                    ]
+                },
+                { "id": 3 },
+            ]
+        })
+        .into();
+        let get_payload = || OwnedPayloadRef::from(&payload);
+        let nested_path = JsonPathPayload::new("orders[]".to_string());
+
+        let many_apples = Condition::new_nested(
+            "items",
+            Filter::new_must(Condition::Filter(Filter {
+                should: None,
+                must: Some(vec![
+                    Condition::Field(FieldCondition::new_match(
+                        "name".to_string(),
+                        "apple".to_string().into(),
+                    )),
+                    Condition::Field(FieldCondition::new_range(
+                        "quantity".to_string(),
+                        Range {
+                            lt: None,
+                            gt: None,
+                            gte: Some(5.0),
+                            lte: None,
+                        },
+                    )),
+                ]),
+                must_not: None,
+            })),
+        );
+
+        // One bit per order, set if any of its items matches
+        let matches = nested_condition_checker(&many_apples, &nested_path, &get_payload);
+        assert_eq!(matches, bitvec![0, 1, 0]);
+
+        let order_with_many_apples = |order_id: i64| {
+            Filter::new_must(Condition::Filter(Filter {
+                should: None,
+                must: Some(vec![
+                    Condition::Field(FieldCondition::new_match("id".to_string(), order_id.into())),
+                    many_apples.clone(),
+                ]),
+                must_not: None,
+            }))
+        };
+        assert!(!check_nested_filter(
+            &nested_path,
+            &order_with_many_apples(1),
+            get_payload
+        ));
+        assert!(check_nested_filter(
+            &nested_path,
+            &order_with_many_apples(2),
+            get_payload
+        ));
+
+        // Outer `must_not` is evaluated per order as well
+        let orders_without_many_apples = Filter::new_must(Condition::Filter(Filter {
+            should: None,
+            must: None,
+            must_not: Some(vec![many_apples.clone()]),
+        }));
+        let matches = nested_condition_checker(
+            &Condition::Filter(orders_without_many_apples),
+            &nested_path,
+            &get_payload,
+        );
+        assert_eq!(matches, bitvec![1, 0, 1]);
+    }
+
+    #[test]
+    fn test_nested_must_not_on_missing_array() {
+        let nested_path = JsonPathPayload::new("country.cities[]".to_string());
+        let missing_payload: Payload = json!({ "country": { "name": "Germany" } }).into();
+        let empty_payload: Payload = json!({ "country": { "cities": [] } }).into();
+        let berlin_payload: Payload = json!({
+            "country": { "cities": [{ "name": "Berlin" }] }
+        })
+        .into();
+
+        let is_berlin = Condition::Field(FieldCondition::new_match(
+            "name".to_string(),
+            "Berlin".to_string().into(),
+        ));
+        let not_berlin = Filter::new_must_not(is_berlin.clone());
+        let check = |filter: &Filter, payload: &Payload| {
+            check_nested_filter(&nested_path, filter, || OwnedPayloadRef::from(payload))
+        };
+
+        // Nothing matches the forbidden condition if there are no elements
+        assert!(check(&not_berlin, &missing_payload));
+        assert!(check(&not_berlin, &empty_payload));
+        assert!(!check(&not_berlin, &berlin_payload));
+
+        // Required conditions still need some element to match
+        let must_and_must_not = Filter {
+            should: None,
+            must: Some(vec![Condition::HasKey(HasKeyCondition::from(
+                "name".to_string(),
+            ))]),
+            must_not: Some(vec![is_berlin.clone()]),
+        };
+        assert!(!check(&must_and_must_not, &missing_payload));
+
+        // Same semantic for groups of conditions of inner nested arrays
+        let countries_path = JsonPathPayload::new("countries[]".to_string());
+        let countries_payload: Payload = json!({
+            "countries": [
+                { "cities": [{ "name": "Berlin" }] },
+                { "name": "Atlantis" },
+                { "cities": [{ "name": "Paris" }] },
+            ]
+        })
+        .into();
+        let without_berlin = Condition::new_nested("cities", not_berlin);
+        let matches = nested_condition_checker(&without_berlin, &countries_path, &|| {
+            OwnedPayloadRef::from(&countries_payload)
+        });
+        assert_eq!(matches, bitvec![0, 1, 1]);
+    }
+
+    #[test]
+    fn test_nested_filter_through_optional_object() {
+        let missing_profile: Payload = json!({ "name": "John" }).into();
+        let null_profile: Payload = json!({ "profile": null }).into();
+        let scalar_profile: Payload = json!({ "profile": "private" }).into();
+        let other_address: Payload = json!({
+            "profile": { "addresses": [{ "city": "Paris" }] }
+        })
+        .into();
+        let matching_address: Payload = json!({
+            "profile": { "addresses": [{ "city": "Paris" }, { "city": "Berlin" }] }
+        })
+        .into();
+
+        let in_berlin = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "city".to_string(),
+            "Berlin".to_string().into(),
+        )));
+
+        for key in ["profile?.addresses[]", "profile.addresses[]"] {
+            let nested_path = JsonPathPayload::new(key.to_string());
+            assert_eq!(nested_path.path, "profile.addresses[]");
+            let check = |payload: &Payload| {
+                check_nested_filter(&nested_path, &in_berlin, || OwnedPayloadRef::from(payload))
+            };
+
+            for payload in [&missing_profile, &null_profile, &scalar_profile] {
+                assert!(!check(payload));
+                assert!(nested_filter_positions(&nested_path, &in_berlin, payload).is_empty());
+            }
+
+            assert!(!check(&other_address));
+            assert!(check(&matching_address));
+            assert_eq!(
+                nested_filter_positions(&nested_path, &in_berlin, &matching_address),
+                bitvec![0, 1]
+            );
+        }
+
+        // Optional chaining is also accepted in the middle of extended paths
+        let extended = JsonPathPayload::new("user?.profile".to_string()).extend("addresses?.city");
+        assert_eq!(extended.path, "user.profile.addresses.city");
+    }
+
+    #[test]
+    fn test_nested_field_condition_on_wide_elements() {
+        let element = |score: i64, nested_score: i64| {
+            let mut element = serde_json::Map::new();
+            for i in 0..64 {
+                element.insert(
+                    format!("filler_{i}"),
+                    json!({ "score": nested_score, "x": i }),
+                );
+            }
+            element.insert("score".to_string(), json!(score));
+            element.insert("meta".to_string(), json!({ "score": nested_score }));
+            Value::Object(element)
+        };
+        let payload: Payload = json!({
+            "items": [element(10, 1), element(1, 10), element(7, 7)]
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let at_least_five = |key: &str| {
+            FieldCondition::new_range(
+                key.to_string(),
+                Range {
+                    lt: None,
+                    gt: None,
+                    gte: Some(5.0),
+                    lte: None,
+                },
+            )
+        };
+
+        // Only the projected field is evaluated, same-named fields of sub-objects are ignored
+        let matches = nested_check_field_condition(
+            &at_least_five("score"),
+            &payload,
+            &nested_path,
+            &IndexesMap::new(),
+        );
+        assert_eq!(matches, bitvec![1, 0, 1]);
+
+        let matches = nested_check_field_condition(
+            &at_least_five("meta.score"),
+            &payload,
+            &nested_path,
+            &IndexesMap::new(),
+        );
+        assert_eq!(matches, bitvec![0, 1, 1]);
+
+        // Same result as for the elements reduced to the projected field
+        let projected: Payload = json!({
+            "items": [{ "score": 10 }, { "score": 1 }, { "score": 7 }]
+        })
+        .into();
+        let matches = nested_check_field_condition(
+            &at_least_five("score"),
+            &projected,
+            &nested_path,
+            &IndexesMap::new(),
+        );
+        assert_eq!(matches, bitvec![1, 0, 1]);
+    }
+
+    #[test]
+    fn test_nested_field_condition_null_policy() {
+        let payload: Payload = json!({
+            "items": [
+                { "price": 10 },
+                { "price": null },
+                { "price": 30 },
+            ]
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let cheap = FieldCondition::new_range(
+            "price".to_string(),
+            Range {
+                lt: Some(20.0),
+                gt: None,
+                gte: None,
+                lte: None,
+            },
+        );
+        let check = |condition: &FieldCondition, null_policy| {
+            nested_check_field_condition_with_policy(
+                condition,
+                &payload,
+                &nested_path,
+                &Default::default(),
+                null_policy,
+            )
+        };
+
+        // Default policy is the same as the plain check
+        assert_eq!(
+            check(&cheap, NestedNullPolicy::NoMatch).unwrap(),
+            bitvec![1, 0, 0]
+        );
+        assert_eq!(
+            nested_check_field_condition(&cheap, &payload, &nested_path, &Default::default()),
+            bitvec![1, 0, 0]
+        );
+
+        // Null element keeps its position, so the result is aligned with the elements
+        assert_eq!(
+            check(&cheap, NestedNullPolicy::Skip).unwrap(),
+            bitvec![1, 0, 0]
+        );
+
+        match check(&cheap, NestedNullPolicy::Error) {
+            Err(OperationError::NestedNullValue { path }) => assert_eq!(path, "items[].price"),
+            result => panic!("unexpected result: {result:?}"),
+        }
+
+        // Queries take the policy from the condition, rejected null values don't match there
+        let strict_cheap: FieldCondition = serde_json::from_value(json!({
+            "key": "price",
+            "range": { "lt": 20.0 },
+            "null_policy": "error",
+        }))
+        .unwrap();
+        assert_eq!(strict_cheap.null_policy, Some(NestedNullPolicy::Error));
+        assert_eq!(
+            nested_check_field_condition(
+                &strict_cheap,
+                &payload,
+                &nested_path,
+                &Default::default()
+            ),
+            bitvec![1, 0, 0]
+        );
+        let conditions = [Condition::Field(strict_cheap)];
+        let checkers = nested_conditions_converter(
+            &conditions,
+            PayloadProvider::in_memory(HashMap::from([(0, Value::Object(payload.0.clone()))])),
+            &Default::default(),
+            nested_path.clone(),
+        );
+        assert_eq!(checkers[0](0), bitvec![1, 0, 0]);
+
+        // Values count condition is evaluated for null values regardless of the policy
+        let no_price = FieldCondition::new_values_count(
+            "price".to_string(),
+            ValuesCount {
+                lt: Some(1),
+                gt: None,
+                gte: None,
+                lte: None,
+            },
+        );
+        assert_eq!(
+            check(&no_price, NestedNullPolicy::Error).unwrap(),
+            nested_check_field_condition(&no_price, &payload, &nested_path, &Default::default()),
+        );
+    }
+
+    #[test]
+    fn test_nested_regex_match() {
+        let payload: Payload = json!({
+            "products": [
+                { "sku": "AB-100" },
+                { "sku": "CD-200" },
+                { "sku": "ab-300" },
+            ]
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("products[]".to_string());
+        let sku_regex = FieldCondition::new_match("sku".to_string(), Match::new_regex(r"^AB-"));
+
+        // Generic condition check compiles the regex on its own
+        let matches =
+            nested_check_field_condition(&sku_regex, &payload, &nested_path, &Default::default());
+        assert_eq!(matches, bitvec![1, 0, 0]);
+
+        let regex = compile_regex(r"(?i)^ab-\d{3}$").unwrap();
+        let matches = nested_check_regex_condition(&nested_path, "sku", Some(&regex), &payload);
+        assert_eq!(matches, bitvec![1, 0, 1]);
+
+        // Patterns expanding into a huge automaton are rejected
+        assert!(compile_regex(r"\w{1000}{1000}").is_none());
+        let matches = nested_check_regex_condition(&nested_path, "sku", None, &payload);
+        assert_eq!(matches, bitvec![0, 0, 0]);
+    }
+
+    #[test]
+    fn test_nested_expression_condition() {
+        let payload: Payload = json!({
+            "orders": [
+                { "price": 30, "quantity": 4 },
+                { "price": 50.5, "quantity": 1 },
+                { "price": "30", "quantity": 4 },
+                { "price": 30, "quantity": [4, 1] },
+                { "price": 300 },
+                { "price": 120, "quantity": 0 },
+            ]
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("orders[]".to_string());
+
+        // Same shape as accepted by the REST API
+        let expensive: Condition = serde_json::from_value(json!({
+            "expression": {
+                "left": "price",
+                "operator": "mul",
+                "right": "quantity",
+                "range": { "gt": 100 },
+            }
+        }))
+        .unwrap();
+        let expensive_condition = match &expensive {
+            Condition::Expression(expression) => expression.clone(),
+            condition => panic!("unexpected condition: {condition:?}"),
+        };
+
+        // Only single numeric operands are used, other elements do not match
+        let matches =
+            check_nested_expression_condition(&nested_path, &expensive_condition, &payload);
+        assert_eq!(matches, bitvec![1, 0, 0, 0, 0, 0]);
+
+        // Division by zero does not produce a value
+        let mut per_unit = expensive_condition.clone();
+        per_unit.expression.operator = ArithmeticOperator::Div;
+        per_unit.expression.range = Range {
+            lt: None,
+            gt: Some(5.0),
+            gte: None,
+            lte: None,
+        };
+        let matches = check_nested_expression_condition(&nested_path, &per_unit, &payload);
+        assert_eq!(matches, bitvec![1, 1, 0, 0, 0, 0]);
+
+        // Expression and other conditions are applied to the same element
+        let with_quantity = |quantity: Range| {
+            Filter::new_must(Condition::Filter(Filter {
+                should: None,
+                must: Some(vec![
+                    expensive.clone(),
+                    Condition::Field(FieldCondition::new_range("quantity".to_string(), quantity)),
+                ]),
+                must_not: None,
+            }))
+        };
+        let few = Range {
+            lt: Some(2.0),
+            gt: None,
+            gte: None,
+            lte: None,
+        };
+        let many = Range {
+            lt: None,
+            gt: None,
+            gte: Some(2.0),
+            lte: None,
+        };
+        assert!(!check_nested_filter(
+            &nested_path,
+            &with_quantity(few),
+            || OwnedPayloadRef::from(&payload)
+        ));
+        assert!(check_nested_filter(
+            &nested_path,
+            &with_quantity(many),
+            || OwnedPayloadRef::from(&payload)
+        ));
+    }
+
+    #[test]
+    fn test_nested_point_level_condition() {
+        let payload: Payload = json!({
+            "orders": [{ "id": 1 }, { "id": 2 }, { "id": 3 }],
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("orders[]".to_string());
+        let has_id: Condition = serde_json::from_value(json!({ "has_id": [1, 2] })).unwrap();
+
+        // Point level condition matches none of the elements, instead of failing the query
+        let matches =
+            nested_condition_checker(&has_id, &nested_path, &|| OwnedPayloadRef::from(&payload));
+        assert_eq!(matches, bitvec![0, 0, 0]);
+        assert!(!check_nested_filter(
+            &nested_path,
+            &Filter::new_must(has_id.clone()),
+            || OwnedPayloadRef::from(&payload)
+        ));
+        assert!(check_nested_filter(
+            &nested_path,
+            &Filter::new_must_not(has_id),
+            || OwnedPayloadRef::from(&payload)
+        ));
+    }
+
+    #[test]
+    fn test_nested_array_of_arrays() {
+        let payload: Payload = json!({
+            "matrix": [[1, 2], [3, 4], [5], []],
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("matrix[]".to_string());
+        let from = |gte: f64| {
+            FieldCondition::new_range(
+                String::new(),
+                Range {
+                    lt: None,
+                    gt: None,
+                    gte: Some(gte),
+                    lte: None,
+                },
+            )
+        };
+
+        let check = |condition: &FieldCondition, inner_match: Option<InnerArrayMatch>| {
+            let condition = FieldCondition {
+                inner_match,
+                ..condition.clone()
+            };
+            nested_check_field_condition(&condition, &payload, &nested_path, &IndexesMap::new())
+        };
+        assert_eq!(check(&from(2.0), None), bitvec![1, 1, 1, 0]);
+        assert_eq!(
+            check(&from(2.0), Some(InnerArrayMatch::Any)),
+            bitvec![1, 1, 1, 0]
+        );
+        assert_eq!(
+            check(&from(2.0), Some(InnerArrayMatch::All)),
+            bitvec![0, 1, 1, 0]
+        );
+        assert_eq!(
+            check(&from(4.0), Some(InnerArrayMatch::All)),
+            bitvec![0, 0, 1, 0]
+        );
+
+        // All inner values of the element have to match from the DSL as well
+        let all_from_two: FieldCondition = serde_json::from_value(json!({
+            "key": "",
+            "range": { "gte": 2.0 },
+            "inner_match": "all",
+        }))
+        .unwrap();
+        assert_eq!(all_from_two.inner_match, Some(InnerArrayMatch::All));
+        let filter = Filter::new_must(Condition::Field(all_from_two));
+        assert!(check_nested_filter(&nested_path, &filter, || {
+            OwnedPayloadRef::from(&payload)
+        }));
+        let no_such_element: Payload = json!({ "matrix": [[1, 2], []] }).into();
+        assert!(!check_nested_filter(&nested_path, &filter, || {
+            OwnedPayloadRef::from(&no_such_element)
+        }));
+
+        // Nested filter matches the outer element whose inner array contains the value
+        let contains = |value: i64| {
+            Filter::new_must(Condition::Field(FieldCondition::new_match(
+                String::new(),
+                value.into(),
+            )))
+        };
+        let matches =
+            nested_condition_checker(&Condition::Filter(contains(3)), &nested_path, &|| {
+                OwnedPayloadRef::from(&payload)
+            });
+        assert_eq!(matches, bitvec![0, 1, 0, 0]);
+        assert!(check_nested_filter(&nested_path, &contains(5), || {
+            OwnedPayloadRef::from(&payload)
+        }));
+        assert!(!check_nested_filter(&nested_path, &contains(6), || {
+            OwnedPayloadRef::from(&payload)
+        }));
+    }
+
+    #[test]
+    fn test_nested_uuid_match() {
+        let uuid = Uuid::parse_str("67e55044-10b1-426f-9247-bb680e5fe0c8").unwrap();
+        let payload: Payload = json!({
+            "items": [
+                { "id": "67e55044-10b1-426f-9247-bb680e5fe0c8" },
+                { "id": "67E5504410B1426F9247BB680E5FE0C8" },
+                { "id": uuid.as_bytes().to_vec() },
+                { "id": "a0b5c8f2-4e2f-4f6f-a4a5-2f1e7d3f6a11" },
+                {
+                    "id": [
+                        "a0b5c8f2-4e2f-4f6f-a4a5-2f1e7d3f6a11",
+                        "67e5504410b1426f9247bb680e5fe0c8",
+                    ]
+                },
+                { "id": "not a uuid" },
+                { "id": [1, 2, 3] },
+            ],
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let check = |keyword: &str| {
+            let condition = FieldCondition::new_match("id".to_string(), keyword.to_string().into());
+            nested_check_field_condition(&condition, &payload, &nested_path, &Default::default())
+        };
+
+        // Both textual and byte forms are the same UUID
+        let expected = bitvec![1, 1, 1, 0, 1, 0, 0];
+        assert_eq!(check("67e55044-10b1-426f-9247-bb680e5fe0c8"), expected);
+        assert_eq!(check("67e5504410b1426f9247bb680e5fe0c8"), expected);
+
+        // Non-UUID keywords are compared as plain strings
+        assert_eq!(check("not a uuid"), bitvec![0, 0, 0, 0, 0, 1, 0]);
+    }
+
+    #[test]
+    fn test_nested_boolean_tri_state() {
+        let payload: Payload = json!({
+            "items": [
+                { "flag": true, "name": "a" },
+                { "flag": false, "name": "b" },
+                { "name": "c" },
+                { "flag": true },
+                { "flag": null, "name": "e" },
+            ]
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let check = |value: bool| {
+            let condition = FieldCondition::new_match("flag".to_string(), value.into());
+            nested_check_field_condition(&condition, &payload, &nested_path, &Default::default())
+        };
+
+        // Absent and null flags match neither `true` nor `false`
+        assert_eq!(check(true), bitvec![1, 0, 0, 1, 0]);
+        assert_eq!(check(false), bitvec![0, 1, 0, 0, 0]);
+
+        let is_empty = IsEmptyCondition {
+            is_empty: PayloadField {
+                key: "flag".to_string(),
+            },
+        };
+        assert_eq!(
+            check_nested_is_empty_condition(&nested_path, &is_empty, &payload),
+            bitvec![0, 0, 1, 0, 1]
+        );
+
+        // Positions are not shifted by elements without the flag
+        let has_name = FieldCondition::new_values_count(
+            "name".to_string(),
+            ValuesCount {
+                lt: None,
+                gt: None,
+                gte: Some(1),
+                lte: None,
+            },
+        );
+        let not_flagged_named = Filter {
+            should: None,
+            must: Some(vec![Condition::Field(has_name)]),
+            must_not: Some(vec![Condition::Field(FieldCondition::new_match(
+                "flag".to_string(),
+                true.into(),
+            ))]),
+        };
+        assert_eq!(
+            nested_filter_positions(&nested_path, &not_flagged_named, &payload),
+            bitvec![0, 1, 1, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_nested_value_type_condition() {
+        let payload: Payload = json!({
+            "items": [
+                { "price": 10 },
+                { "price": "10" },
+                { },
+                { "price": null },
+                { "price": [1.5, "2"] },
+            ]
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let check = |key: &str, r#type: JsonValueType| {
+            let condition = ValueTypeCondition::new(key, r#type);
+            check_nested_value_type_condition(&nested_path, &condition, &payload)
+        };
+
+        assert_eq!(
+            check("price", JsonValueType::Number),
+            bitvec![1, 0, 0, 0, 0]
+        );
+        assert_eq!(
+            check("price", JsonValueType::String),
+            bitvec![0, 1, 0, 0, 0]
+        );
+        // Element without the field is not null
+        assert_eq!(check("price", JsonValueType::Null), bitvec![0, 0, 0, 1, 0]);
+        assert_eq!(check("price", JsonValueType::Array), bitvec![0, 0, 0, 0, 1]);
+        assert_eq!(
+            check("price[]", JsonValueType::String),
+            bitvec![0, 0, 0, 0, 1]
+        );
+
+        // Combined with other conditions of the nested filter
+        let numeric_prices = Filter {
+            should: None,
+            must: None,
+            must_not: Some(vec![Condition::ValueType(ValueTypeCondition::new(
+                "price",
+                JsonValueType::Number,
+            ))]),
+        };
+        assert_eq!(
+            nested_filter_positions(&nested_path, &numeric_prices, &payload),
+            bitvec![0, 1, 1, 1, 1]
+        );
+    }
+
+    #[test]
+    fn test_nested_position_condition() {
+        let condition: Condition = serde_json::from_value(json!({
+            "position": { "fraction": { "from": 0.0, "to": 0.5 } }
+        }))
+        .unwrap();
+        let first_half = PositionRange::Fraction(FractionRange { from: 0.0, to: 0.5 });
+        assert_eq!(
+            condition,
+            Condition::Position(PositionCondition::from(first_half))
+        );
+        let last_two = Condition::Position(PositionRange::Last(2).into());
+
+        let value = json!({
+            "items": [
+                { "color": "red" },
+                { "color": "blue" },
+                { "color": "red" },
+                { "color": "blue" },
+                { "color": "red" },
+            ]
+        });
+        let payload: Payload = value.clone().into();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let positions = |condition: &Condition| match condition {
+            Condition::Position(position) => {
+                check_nested_position_condition(&nested_path, position, &payload)
+            }
+            _ => unreachable!(),
+        };
+        // Half of an odd length is rounded up by the exclusive end
+        assert_eq!(positions(&condition), bitvec![1, 1, 1, 0, 0]);
+        assert_eq!(positions(&last_two), bitvec![0, 0, 0, 1, 1]);
+        // Ranges longer than the array select all of it
+        assert!(PositionRange::Last(10).contains(0, 5));
+        assert!(PositionRange::First(10).contains(4, 5));
+        assert!(PositionRange::Last(u64::MAX as usize).contains(0, 5));
+        assert!(PositionRange::Last(usize::MAX).contains(0, 5));
+        assert!(!PositionRange::Last(0).contains(4, 5));
+
+        // Restricts other conditions of the nested filter to the positions
+        let red = Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "red".to_string().into(),
+        ));
+        let blue = Condition::Field(FieldCondition::new_match(
+            "color".to_string(),
+            "blue".to_string().into(),
+        ));
+        let first_half_red = Filter {
+            should: None,
+            must: Some(vec![condition.clone(), red]),
+            must_not: None,
+        };
+        assert_eq!(
+            nested_filter_positions(&nested_path, &first_half_red, &payload),
+            bitvec![1, 0, 1, 0, 0]
+        );
+        let last_two_blue = Filter {
+            should: None,
+            must: Some(vec![last_two.clone(), blue.clone()]),
+            must_not: None,
+        };
+        assert_eq!(
+            nested_filter_positions(&nested_path, &last_two_blue, &payload),
+            bitvec![0, 0, 0, 1, 0]
+        );
+
+        // Same positions when evaluated with field indexes
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([(0, value)]));
+        let checkers = nested_conditions_converter(
+            &[condition, last_two.clone(), blue],
+            payload_provider,
+            &IndexesMap::default(),
+            nested_path.clone(),
+        );
+        assert_eq!(checkers[0](0), bitvec![1, 1, 1, 0, 0]);
+        assert_eq!(checkers[1](0), bitvec![0, 0, 0, 1, 1]);
+        assert_eq!(checkers[2](0), bitvec![0, 1, 0, 1, 0]);
+
+        // Positions are meaningless outside of nested filters
+        let issues = |condition: Condition| Filter::new_must(condition).validate_deep();
+        assert_eq!(issues(last_two.clone()).len(), 1);
+        let nested = |condition| Condition::new_nested("items", Filter::new_must(condition));
+        assert!(issues(nested(last_two)).is_empty());
+        let reversed = Condition::Position(
+            PositionRange::Fraction(FractionRange { from: 0.5, to: 0.0 }).into(),
+        );
+        assert_eq!(issues(nested(reversed)).len(), 1);
+    }
+
+    #[test]
+    fn test_nested_comparison_condition() {
+        let condition: Condition = serde_json::from_value(json!({
+            "compare": { "left": "sale_price", "operator": "lt", "right": "list_price" }
+        }))
+        .unwrap();
+        let compare = |operator| {
+            Condition::Compare(ComparisonCondition::from(Comparison {
+                left: "sale_price".to_string(),
+                operator,
+                right: "list_price".to_string(),
+                collation: None,
+            }))
+        };
+        assert_eq!(condition, compare(ComparisonOperator::Lt));
+
+        let value = json!({
+            "items": [
+                { "sale_price": 8, "list_price": 10 },
+                { "sale_price": 10, "list_price": 10.0 },
+                { "sale_price": 12, "list_price": 10 },
+                { "sale_price": "8", "list_price": 10 },
+                { "sale_price": 8 },
+                { "sale_price": [8, 9], "list_price": 10 },
+                { "sale_price": "sale", "list_price": "sale" },
+            ]
+        });
+        let payload: Payload = value.clone().into();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let check = |operator| match compare(operator) {
+            Condition::Compare(condition) => {
+                check_nested_comparison_condition(&nested_path, &condition, &payload)
+            }
+            _ => unreachable!(),
+        };
+
+        // Integer and float representations of the same number are equal
+        assert_eq!(check(ComparisonOperator::Lt), bitvec![1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(check(ComparisonOperator::Eq), bitvec![0, 1, 0, 0, 0, 0, 1]);
+        assert_eq!(check(ComparisonOperator::Lte), bitvec![1, 1, 0, 0, 0, 0, 0]);
+        // Values of different types and missing values are not comparable, even for `ne`
+        assert_eq!(check(ComparisonOperator::Ne), bitvec![1, 0, 1, 0, 0, 0, 0]);
+        assert_eq!(check(ComparisonOperator::Gte), bitvec![0, 1, 1, 0, 0, 0, 0]);
+
+        // Combined with other conditions of the nested filter
+        let discounted_and_cheap = Filter {
+            should: None,
+            must: Some(vec![
+                compare(ComparisonOperator::Lte),
+                Condition::Field(FieldCondition::new_range(
+                    "sale_price".to_string(),
+                    Range {
+                        lt: Some(9.0),
+                        gt: None,
+                        gte: None,
+                        lte: None,
+                    },
+                )),
+            ]),
+            must_not: None,
+        };
+        assert_eq!(
+            nested_filter_positions(&nested_path, &discounted_and_cheap, &payload),
+            bitvec![1, 0, 0, 0, 0, 0, 0]
+        );
+
+        // Same positions when evaluated with field indexes
+        let payload_provider = PayloadProvider::in_memory(HashMap::from([(0, value)]));
+        let conditions = [
+            compare(ComparisonOperator::Lt),
+            compare(ComparisonOperator::Eq),
+        ];
+        let checkers = nested_conditions_converter(
+            &conditions,
+            payload_provider,
+            &IndexesMap::default(),
+            nested_path.clone(),
+        );
+        assert_eq!(checkers[0](0), bitvec![1, 0, 0, 0, 0, 0, 0]);
+        assert_eq!(checkers[1](0), bitvec![0, 1, 0, 0, 0, 0, 1]);
+
+        let check_filter = |condition| {
+            check_nested_filter(&nested_path, &Filter::new_must(condition), || {
+                OwnedPayloadRef::from(&payload)
+            })
+        };
+        assert!(check_filter(compare(ComparisonOperator::Gt)));
+        let same_field = Condition::Compare(ComparisonCondition::from(Comparison {
+            left: "list_price".to_string(),
+            operator: ComparisonOperator::Ne,
+            right: "list_price".to_string(),
+            collation: None,
+        }));
+        assert!(!check_filter(same_field));
+    }
+
+    #[test]
+    fn test_nested_comparison_with_collation() {
+        let condition: Condition = serde_json::from_value(json!({
+            "compare": { "left": "first", "operator": "lt", "right": "second", "collation": "sv" }
+        }))
+        .unwrap();
+        let compare = |operator, collation| {
+            Condition::Compare(ComparisonCondition::from(Comparison {
+                left: "first".to_string(),
+                operator,
+                right: "second".to_string(),
+                collation,
+            }))
+        };
+        assert_eq!(
+            condition,
+            compare(ComparisonOperator::Lt, Some(CollationLocale::Sv))
+        );
+
+        let payload: Payload = json!({
+            "words": [
+                { "first": "école", "second": "zèbre" },
+                { "first": "ecole", "second": "école" },
+                { "first": "öl", "second": "zebra" },
+                { "first": "Öl", "second": "öl" },
+                { "first": "école", "second": 10 },
+            ]
+        })
+        .into();
+        let nested_path = JsonPathPayload::new("words[]".to_string());
+        let check = |operator, collation| match compare(operator, collation) {
+            Condition::Compare(condition) => {
+                check_nested_comparison_condition(&nested_path, &condition, &payload)
+            }
+            _ => unreachable!(),
+        };
+
+        // Without collation strings are not ordered at all
+        assert_eq!(check(ComparisonOperator::Lt, None), bitvec![0, 0, 0, 0, 0]);
+        assert_eq!(check(ComparisonOperator::Ne, None), bitvec![1, 1, 1, 1, 0]);
+
+        // Accented letters follow the same letters without accents
+        let root = Some(CollationLocale::Root);
+        assert_eq!(check(ComparisonOperator::Lt, root), bitvec![1, 1, 1, 0, 0]);
+        assert_eq!(check(ComparisonOperator::Gt, root), bitvec![0, 0, 0, 1, 0]);
+
+        // Swedish `ö` is a separate letter following `z`
+        let swedish = Some(CollationLocale::Sv);
+        assert_eq!(
+            check(ComparisonOperator::Lt, swedish),
+            bitvec![1, 1, 0, 0, 0]
+        );
+        assert_eq!(
+            check(ComparisonOperator::Gte, swedish),
+            bitvec![0, 0, 1, 1, 0]
+        );
+
+        // Equality is not affected by collation
+        assert_eq!(
+            check(ComparisonOperator::Eq, swedish),
+            bitvec![0, 0, 0, 0, 0]
+        );
+    }
+
+    #[test]
+    fn test_nested_all_elements_condition() {
+        let condition: Condition = serde_json::from_value(json!({
+            "nested": {
+                "key": "items",
+                "filter": { "must": [{ "key": "in_stock", "match": { "value": true } }] },
+                "quantifier": "all"
+            }
+        }))
+        .unwrap();
+        let in_stock = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "in_stock".to_string(),
+            true.into(),
+        )));
+        assert_eq!(
+            condition,
+            Condition::new_nested_all("items", in_stock.clone(), false)
+        );
+
+        let payloads = [
+            json!({ "items": [{ "in_stock": true }, { "in_stock": true }] }),
+            json!({ "items": [{ "in_stock": true }, { "in_stock": false }] }),
+            json!({ "items": [{ "in_stock": true }, {}] }),
+            json!({ "items": [] }),
+            json!({}),
+        ];
+        // Expected results for `any`, `all` and `all_or_empty` quantifiers
+        let expected = [
+            [true, true, true],
+            [true, false, false],
+            [true, false, false],
+            [false, false, true],
+            [false, false, true],
+        ];
+
+        let payload_provider = PayloadProvider::in_memory(HashMap::from_iter(
+            payloads
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(i, p)| (i as u32, p)),
+        ));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let quantifiers = [
+            NestedQuantifier::Any,
+            NestedQuantifier::All,
+            NestedQuantifier::AllOrEmpty,
+        ];
+        for (point_id, (payload, expected)) in payloads.into_iter().zip(expected).enumerate() {
+            let payload: Payload = payload.into();
+            for (quantifier, expected) in quantifiers.into_iter().zip(expected) {
+                let matches =
+                    check_nested_filter_quantified(&nested_path, &in_stock, &payload, quantifier);
+                assert_eq!(matches, expected, "point {point_id}, {quantifier:?}");
+                // Same result when evaluated with field indexes
+                let nested = NestedContainer::new(NestedCondition {
+                    key: "items".to_string(),
+                    filter: in_stock.clone(),
+                    index: None,
+                    after: None,
+                    quantifier: Some(quantifier),
+                });
+                let matches = nested_min_match(
+                    point_id as PointOffsetType,
+                    std::slice::from_ref(&nested),
+                    1,
+                    &field_indexes,
+                    payload_provider.clone(),
+                );
+                assert_eq!(matches, expected, "point {point_id}, {quantifier:?}");
+            }
+        }
+
+        // Orders with all of their items in stock, evaluated for each order separately
+        let payload: Payload = json!({ "orders": [
+            { "items": [{ "in_stock": true }, { "in_stock": true }] },
+            { "items": [{ "in_stock": true }, { "in_stock": false }] },
+            { "items": [] },
+        ] })
+        .into();
+        let orders_path = JsonPathPayload::new("orders[]".to_string());
+        let all_in_stock = |match_empty| {
+            Filter::new_must(Condition::new_nested_all(
+                "items",
+                in_stock.clone(),
+                match_empty,
+            ))
+        };
+        assert_eq!(
+            nested_filter_positions(&orders_path, &all_in_stock(false), &payload),
+            bitvec![1, 0, 0]
+        );
+        assert_eq!(
+            nested_filter_positions(&orders_path, &all_in_stock(true), &payload),
+            bitvec![1, 0, 1]
+        );
+    }
+
+    #[test]
+    fn test_nested_complete_elements_condition() {
+        let payloads = [
+            json!({ "items": [{ "name": "A", "sku": "1" }, { "name": "B", "sku": "2" }] }),
+            json!({ "items": [{ "name": "A", "sku": "1" }, { "name": null, "sku": "2" }] }),
+            json!({ "items": [{ "name": "A", "sku": "1" }, {}] }),
+            json!({ "items": [{ "name": "A", "sku": [] }, { "name": "B", "sku": "2" }] }),
+            json!({ "items": [] }),
+            json!({}),
+        ];
+        // Expected results without and with `match_empty`
+        let expected = [
+            [true, true],
+            [false, false],
+            [false, false],
+            [false, false],
+            [false, true],
+            [false, true],
+        ];
+
+        let payload_provider = PayloadProvider::in_memory(HashMap::from_iter(
+            payloads
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(i, p)| (i as u32, p)),
+        ));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("items[]".to_string());
+        let complete = |match_empty| match Condition::new_nested_complete(
+            "items",
+            ["name", "sku"],
+            match_empty,
+        ) {
+            Condition::Nested(nested) => nested,
+            _ => unreachable!(),
+        };
+        for (point_id, (payload, expected)) in payloads.into_iter().zip(expected).enumerate() {
+            let payload: Payload = payload.into();
+            for (match_empty, expected) in [false, true].into_iter().zip(expected) {
+                let nested = complete(match_empty);
+                let matches = check_nested_filter_quantified(
+                    &nested_path,
+                    nested.filter(),
+                    &payload,
+                    nested.quantifier(),
+                );
+                assert_eq!(matches, expected, "point {point_id}, {match_empty}");
+                // Same result when evaluated with field indexes
+                let matches = nested_min_match(
+                    point_id as PointOffsetType,
+                    std::slice::from_ref(&nested),
+                    1,
+                    &field_indexes,
+                    payload_provider.clone(),
+                );
+                assert_eq!(matches, expected, "point {point_id}, {match_empty}");
+            }
+        }
+
+        // Without required fields any element is complete
+        let any_fields = Condition::new_nested_complete("items", Vec::<String>::new(), false);
+        assert!(Filter::new_must(any_fields).validate_deep().is_empty());
+    }
+
+    #[test]
+    fn test_nested_min_ratio_condition() {
+        let condition: Condition = serde_json::from_value(json!({
+            "nested": {
+                "key": "reviews",
+                "filter": { "must": [{ "key": "stars", "match": { "value": 5 } }] },
+                "quantifier": { "min_ratio": 0.8 }
+            }
+        }))
+        .unwrap();
+        let five_stars = Filter::new_must(Condition::Field(FieldCondition::new_match(
+            "stars".to_string(),
+            5.into(),
+        )));
+        let nested = NestedContainer::new(NestedCondition {
+            key: "reviews".to_string(),
+            filter: five_stars.clone(),
+            index: None,
+            after: None,
+            quantifier: Some(NestedQuantifier::MinRatio(0.8)),
+        });
+        assert_eq!(condition, Condition::Nested(nested.clone()));
+
+        let payloads = [
+            json!({ "reviews": [{ "stars": 5 }, { "stars": 5 }, { "stars": 4 }, { "stars": 5 }] }),
+            json!({ "reviews": [{ "stars": 5 }, { "stars": 5 }, { "stars": 5 }, { "stars": 5 }] }),
+            json!({ "reviews": [] }),
+            json!({}),
+        ];
+        // Ratio of 3/4 is below 80%, ratio of empty or missing array is undefined
+        let expected = [false, true, false, false];
+
+        let payload_provider = PayloadProvider::in_memory(HashMap::from_iter(
+            payloads
+                .iter()
+                .cloned()
+                .enumerate()
+                .map(|(i, p)| (i as u32, p)),
+        ));
+        let field_indexes = IndexesMap::default();
+        let nested_path = JsonPathPayload::new("reviews[]".to_string());
+        for (point_id, (payload, expected)) in payloads.into_iter().zip(expected).enumerate() {
+            let payload: Payload = payload.into();
+            let matches = check_nested_filter_quantified(
+                &nested_path,
+                &five_stars,
+                &payload,
+                NestedQuantifier::MinRatio(0.8),
+            );
+            assert_eq!(matches, expected, "point {point_id}");
+            // Same result when evaluated with field indexes
+            let matches = nested_min_match(
+                point_id as PointOffsetType,
+                std::slice::from_ref(&nested),
+                1,
+                &field_indexes,
+                payload_provider.clone(),
+            );
+            assert_eq!(matches, expected, "point {point_id}");
+        }
+
+        // Threshold is inclusive
+        let payload: Payload =
+            json!({ "reviews": [{ "stars": 5 }, { "stars": 5 }, { "stars": 5 }, { "stars": 4 }] })
+                .into();
+        let check_ratio = |min_ratio| {
+            check_nested_filter_quantified(
+                &nested_path,
+                &five_stars,
+                &payload,
+                NestedQuantifier::MinRatio(min_ratio),
+            )
+        };
+        assert!(check_ratio(0.75));
+        assert!(!check_ratio(0.76));
+
+        // Ratio outside of `[0, 1]` is rejected
+        let out_of_range =
+            Filter::new_must(Condition::Nested(NestedContainer::new(NestedCondition {
+                quantifier: Some(NestedQuantifier::MinRatio(80.0)),
+                ..nested.nested
+            })));
+        assert_eq!(out_of_range.validate_deep().len(), 1);
+    }
 }
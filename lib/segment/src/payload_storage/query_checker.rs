@@ -1,19 +1,28 @@
 use std::cell::RefCell;
+use std::collections::HashSet;
 use std::ops::Deref;
 use std::sync::Arc;
 
 use atomic_refcell::AtomicRefCell;
+use serde_json::Value;
 
 use crate::common::utils::JsonPathPayload;
+use crate::data_types::vectors::VectorElementType;
 use crate::id_tracker::IdTrackerSS;
 use crate::payload_storage::condition_checker::ValueChecker;
-use crate::payload_storage::nested_query_checker::check_nested_filter;
+use crate::payload_storage::nested_query_checker::{
+    check_nested_filter, check_nested_filter_quantified,
+};
 use crate::payload_storage::payload_storage_enum::PayloadStorageEnum;
 use crate::payload_storage::ConditionChecker;
 use crate::types::{
-    Condition, FieldCondition, Filter, IsEmptyCondition, IsNullCondition, OwnedPayloadRef, Payload,
-    PointOffsetType,
+    AggregateCondition, AggregateFunction, ComparisonCondition, Condition, ExpressionCondition,
+    FieldCondition, Filter, FloatPayloadType, HasDuplicatesCondition, HasKeyCondition,
+    IsEmptyCondition, IsNullCondition, JsonValueType, NestedQuantifier, OwnedPayloadRef, Payload,
+    PointOffsetType, ValueTypeCondition, VectorCountCondition, VectorNotNormalized,
+    VectorNotNormalizedCondition,
 };
+use crate::vector_storage::{NamedVectorStorages, VectorStorage};
 
 fn check_condition<F>(checker: &F, condition: &Condition) -> bool
 where
@@ -70,6 +79,7 @@ where
 pub fn check_payload<'a, F>(
     get_payload: F,
     id_tracker: &IdTrackerSS,
+    vector_storages: &NamedVectorStorages,
     query: &Filter,
     point_id: PointOffsetType,
 ) -> bool
@@ -82,6 +92,7 @@ where
         }
         Condition::IsEmpty(is_empty) => check_is_empty_condition(is_empty, get_payload().deref()),
         Condition::IsNull(is_null) => check_is_null_condition(is_null, get_payload().deref()),
+        Condition::HasKey(has_key) => check_has_key_condition(has_key, get_payload().deref()),
         Condition::HasId(has_id) => {
             let external_id = match id_tracker.external_id(point_id) {
                 None => return false,
@@ -89,10 +100,41 @@ where
             };
             has_id.has_id.contains(&external_id)
         }
+        Condition::VectorCount(vector_count) => {
+            check_vector_count_condition(vector_count, vector_storages, point_id)
+        }
+        Condition::VectorNotNormalized(vector_not_normalized) => {
+            check_vector_not_normalized_condition(vector_not_normalized, vector_storages, point_id)
+        }
+        Condition::Aggregate(aggregate) => {
+            check_aggregate_condition(aggregate, get_payload().deref())
+        }
+        Condition::Expression(expression) => {
+            check_expression_condition(expression, get_payload().deref())
+        }
+        Condition::Compare(compare) => check_comparison_condition(compare, get_payload().deref()),
+        Condition::HasDuplicates(has_duplicates) => {
+            check_has_duplicates_condition(has_duplicates, get_payload().deref())
+        }
+        Condition::ValueType(value_type) => {
+            check_value_type_condition(value_type, get_payload().deref())
+        }
+        // Only elements of nested arrays have positions
+        Condition::Position(_) => false,
         Condition::Nested(nested) => {
-            let nested_filter = nested.filter();
+            let nested_filter = nested.element_filter();
             let nested_path = JsonPathPayload::new(nested.array_key());
-            check_nested_filter(&nested_path, nested_filter, &get_payload)
+            match nested.quantifier() {
+                NestedQuantifier::Any => {
+                    check_nested_filter(&nested_path, &nested_filter, &get_payload)
+                }
+                quantifier => check_nested_filter_quantified(
+                    &nested_path,
+                    &nested_filter,
+                    get_payload().deref(),
+                    quantifier,
+                ),
+            }
         }
         Condition::Filter(_) => unreachable!(),
     };
@@ -108,6 +150,123 @@ pub fn check_is_null_condition(is_null: &IsNullCondition, payload: &Payload) ->
     payload.get_value(&is_null.is_null.key).check_is_null()
 }
 
+pub fn check_has_key_condition(has_key: &HasKeyCondition, payload: &Payload) -> bool {
+    !payload.get_value(&has_key.has_key.key).values().is_empty()
+}
+
+/// Any of the values at the key has the expected JSON type
+pub fn check_value_type_condition(value_type: &ValueTypeCondition, payload: &Payload) -> bool {
+    let value_type = &value_type.value_type;
+    payload
+        .get_value(&value_type.key)
+        .values()
+        .into_iter()
+        .any(|value| JsonValueType::of(value) == value_type.r#type)
+}
+
+pub fn check_aggregate_condition(aggregate: &AggregateCondition, payload: &Payload) -> bool {
+    let aggregate = &aggregate.aggregate;
+    let field_values = payload.get_value(&aggregate.key).values();
+    let values = field_values.into_iter().flat_map(|value| match value {
+        Value::Array(array) => array.iter().collect(),
+        value => vec![value],
+    });
+
+    if let AggregateFunction::DistinctCount = aggregate.function {
+        let distinct: HashSet<_> = values.filter_map(distinct_value_key).collect();
+        return aggregate
+            .range
+            .check_range(distinct.len() as FloatPayloadType);
+    }
+
+    let numbers = values.filter_map(Value::as_f64);
+
+    aggregate
+        .function
+        .aggregate(numbers)
+        .map_or(false, |aggregated| aggregate.range.check_range(aggregated))
+}
+
+pub fn check_expression_condition(expression: &ExpressionCondition, payload: &Payload) -> bool {
+    expression.expression.check(&payload.0)
+}
+
+pub fn check_comparison_condition(compare: &ComparisonCondition, payload: &Payload) -> bool {
+    compare.compare.check(&payload.0)
+}
+
+pub fn check_has_duplicates_condition(
+    has_duplicates: &HasDuplicatesCondition,
+    payload: &Payload,
+) -> bool {
+    let field_values = payload
+        .get_value(&has_duplicates.has_duplicates.key)
+        .values();
+    let mut seen = HashSet::new();
+    field_values
+        .into_iter()
+        .flat_map(|value| match value {
+            Value::Array(array) => array.iter().collect(),
+            value => vec![value],
+        })
+        .filter_map(distinct_value_key)
+        .any(|value| !seen.insert(value))
+}
+
+/// Key identifying equal scalar values, `None` for values which are not compared
+fn distinct_value_key(value: &Value) -> Option<String> {
+    match value {
+        Value::Bool(flag) => Some(flag.to_string()),
+        Value::Number(number) => number.as_f64().map(|number| number.to_string()),
+        Value::String(string) => Some(format!("{string:?}")),
+        Value::Null | Value::Array(_) | Value::Object(_) => None,
+    }
+}
+
+/// Count named vectors which are stored for the point and not deleted
+pub fn check_vector_count_condition(
+    vector_count: &VectorCountCondition,
+    vector_storages: &NamedVectorStorages,
+    point_id: PointOffsetType,
+) -> bool {
+    let count = vector_storages
+        .values()
+        .filter(|vector_storage| {
+            let vector_storage = vector_storage.borrow();
+            (point_id as usize) < vector_storage.total_vector_count()
+                && !vector_storage.is_deleted_vector(point_id)
+        })
+        .count();
+    vector_count.vector_count.check_count_value(count)
+}
+
+/// Check if the norm of the named vector deviates from 1.0 by more than the tolerance
+///
+/// Points without the vector, or with the vector deleted, do not match
+pub fn check_vector_not_normalized_condition(
+    condition: &VectorNotNormalizedCondition,
+    vector_storages: &NamedVectorStorages,
+    point_id: PointOffsetType,
+) -> bool {
+    let VectorNotNormalized { name, tolerance } = &condition.vector_not_normalized;
+    let vector_storage = match vector_storages.get(name) {
+        None => return false,
+        Some(vector_storage) => vector_storage.borrow(),
+    };
+    if (point_id as usize) >= vector_storage.total_vector_count()
+        || vector_storage.is_deleted_vector(point_id)
+    {
+        return false;
+    }
+    let norm = vector_storage
+        .get_vector(point_id)
+        .iter()
+        .map(|value| value * value)
+        .sum::<VectorElementType>()
+        .sqrt();
+    (norm - 1.0).abs() > *tolerance
+}
+
 pub fn check_field_condition(field_condition: &FieldCondition, payload: &Payload) -> bool {
     let field_values = payload.get_value(&field_condition.key);
 
@@ -122,6 +281,7 @@ pub fn check_field_condition(field_condition: &FieldCondition, payload: &Payload
 pub struct SimpleConditionChecker {
     payload_storage: Arc<AtomicRefCell<PayloadStorageEnum>>,
     id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+    vector_storages: NamedVectorStorages,
     empty_payload: Payload,
 }
 
@@ -129,10 +289,12 @@ impl SimpleConditionChecker {
     pub fn new(
         payload_storage: Arc<AtomicRefCell<PayloadStorageEnum>>,
         id_tracker: Arc<AtomicRefCell<IdTrackerSS>>,
+        vector_storages: NamedVectorStorages,
     ) -> Self {
         SimpleConditionChecker {
             payload_storage,
             id_tracker,
+            vector_storages,
             empty_payload: Default::default(),
         }
     }
@@ -181,6 +343,7 @@ impl ConditionChecker for SimpleConditionChecker {
                 payload_ref_cell.borrow().as_ref().cloned().unwrap()
             },
             self.id_tracker.borrow().deref(),
+            &self.vector_storages,
             query,
             point_id,
         )
@@ -189,7 +352,7 @@ impl ConditionChecker for SimpleConditionChecker {
 
 #[cfg(test)]
 mod tests {
-    use std::collections::HashSet;
+    use std::collections::{HashMap, HashSet};
 
     use serde_json::json;
     use tempfile::Builder;
@@ -201,8 +364,10 @@ mod tests {
     use crate::payload_storage::simple_payload_storage::SimplePayloadStorage;
     use crate::payload_storage::PayloadStorage;
     use crate::types::{
-        FieldCondition, GeoBoundingBox, GeoPoint, PayloadField, Range, ValuesCount,
+        Aggregate, AggregateFunction, Distance, FieldCondition, GeoBoundingBox, GeoPoint,
+        PayloadField, Range, ValuesCount,
     };
+    use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
 
     #[test]
     fn test_condition_checker() {
@@ -239,6 +404,7 @@ mod tests {
         let payload_checker = SimpleConditionChecker::new(
             Arc::new(AtomicRefCell::new(payload_storage)),
             Arc::new(AtomicRefCell::new(id_tracker)),
+            NamedVectorStorages::default(),
         );
 
         let is_empty_condition = Filter::new_must(Condition::IsEmpty(IsEmptyCondition {
@@ -304,6 +470,18 @@ mod tests {
         }));
         assert!(!payload_checker.check(0, &is_null_condition));
 
+        // Null and empty values are still present
+        for key in ["packaging", "parts", "not_null", "location.lat"] {
+            let has_key_condition =
+                Filter::new_must(Condition::HasKey(HasKeyCondition::from(key.to_string())));
+            assert!(payload_checker.check(0, &has_key_condition), "{key}");
+        }
+
+        let has_key_condition = Filter::new_must(Condition::HasKey(HasKeyCondition::from(
+            "something_new".to_string(),
+        )));
+        assert!(!payload_checker.check(0, &has_key_condition));
+
         let match_red = Condition::Field(FieldCondition::new_match(
             "color".to_string(),
             "red".to_owned().into(),
@@ -491,4 +669,265 @@ mod tests {
         };
         assert!(payload_checker.check(2, &query));
     }
+
+    #[test]
+    fn test_vector_conditions_checker() {
+        let dir = Builder::new().prefix("db_dir").tempdir().unwrap();
+        let db = open_db(dir.path(), &[DB_VECTOR_CF]).unwrap();
+
+        let payload_storage: PayloadStorageEnum =
+            SimplePayloadStorage::open(db.clone()).unwrap().into();
+        let mut id_tracker = SimpleIdTracker::open(db.clone()).unwrap();
+        let vector_storage =
+            open_simple_vector_storage(db, DB_VECTOR_CF, 2, Distance::Dot).unwrap();
+        {
+            let mut vector_storage = vector_storage.borrow_mut();
+            vector_storage.insert_vector(0, &[3.0, 4.0]).unwrap();
+            vector_storage.insert_vector(1, &[0.6, 0.8]).unwrap();
+            vector_storage.insert_vector(2, &[3.0, 4.0]).unwrap();
+            vector_storage.delete_vector(2).unwrap();
+        }
+        for point_id in 0..3u64 {
+            id_tracker
+                .set_link(point_id.into(), point_id as PointOffsetType)
+                .unwrap();
+        }
+
+        let payload_checker = SimpleConditionChecker::new(
+            Arc::new(AtomicRefCell::new(payload_storage)),
+            Arc::new(AtomicRefCell::new(id_tracker)),
+            HashMap::from([("image".to_string(), vector_storage)]),
+        );
+
+        let has_vector = Filter::new_must(Condition::VectorCount(
+            ValuesCount {
+                lt: None,
+                gt: None,
+                gte: Some(1),
+                lte: None,
+            }
+            .into(),
+        ));
+        assert!(payload_checker.check(0, &has_vector));
+        assert!(payload_checker.check(1, &has_vector));
+        assert!(!payload_checker.check(2, &has_vector));
+
+        let not_normalized = Filter::new_must(Condition::VectorNotNormalized(
+            VectorNotNormalizedCondition {
+                vector_not_normalized: VectorNotNormalized {
+                    name: "image".to_string(),
+                    tolerance: 0.01,
+                },
+            },
+        ));
+        assert!(payload_checker.check(0, &not_normalized));
+        assert!(!payload_checker.check(1, &not_normalized));
+        assert!(!payload_checker.check(2, &not_normalized));
+    }
+
+    #[test]
+    fn test_has_duplicates_condition() {
+        let payload: Payload = json!({
+            "unique_tags": ["red", "green", "blue"],
+            "duplicate_tags": ["red", "green", "red"],
+            "numbers": [1, 2.0, 2],
+            "mixed": [1, "1", true, "true", null, null],
+            "objects": [{ "a": 1 }, { "a": 1 }],
+            "single": "red",
+            "products": [
+                { "tags": ["new"] },
+                { "tags": ["sale", "new"] },
+            ],
+        })
+        .into();
+
+        let has_duplicates = |key: &str| {
+            check_has_duplicates_condition(&HasDuplicatesCondition::from(key.to_string()), &payload)
+        };
+
+        assert!(!has_duplicates("unique_tags"));
+        assert!(has_duplicates("duplicate_tags"));
+        // Same number in integer and float representation
+        assert!(has_duplicates("numbers"));
+        // Values of different types are distinct, non-primitive values are ignored
+        assert!(!has_duplicates("mixed"));
+        assert!(!has_duplicates("objects"));
+        assert!(!has_duplicates("single"));
+        assert!(!has_duplicates("missing"));
+        // Values of the nested array elements are compared together
+        assert!(has_duplicates("products[].tags"));
+        assert!(!has_duplicates("products[0].tags"));
+    }
+
+    #[test]
+    fn test_value_type_condition() {
+        let payload: Payload = json!({
+            "price": 10,
+            "code": "10",
+            "tags": ["red", 1],
+            "nothing": null,
+            "meta": { "rating": 4.5 },
+        })
+        .into();
+
+        let check = |key: &str, r#type: JsonValueType| {
+            check_value_type_condition(&ValueTypeCondition::new(key, r#type), &payload)
+        };
+
+        assert!(check("price", JsonValueType::Number));
+        assert!(!check("price", JsonValueType::String));
+        assert!(check("code", JsonValueType::String));
+        assert!(!check("code", JsonValueType::Number));
+        assert!(check("meta", JsonValueType::Object));
+        assert!(check("meta.rating", JsonValueType::Number));
+        assert!(check("nothing", JsonValueType::Null));
+        // Missing key has no value of any type
+        assert!(!check("missing", JsonValueType::Null));
+        // Array is matched as a whole, unless its elements are selected
+        assert!(check("tags", JsonValueType::Array));
+        assert!(!check("tags", JsonValueType::String));
+        assert!(check("tags[]", JsonValueType::String));
+        assert!(check("tags[]", JsonValueType::Number));
+        assert!(!check("tags[]", JsonValueType::Array));
+    }
+
+    #[test]
+    fn test_aggregate_condition() {
+        let payload: Payload = json!({
+            "orders": [
+                { "value": 10 },
+                { "value": 50.5 },
+                { "value": 60, "discounts": [1, 2] },
+                { "value": "not a number" },
+            ],
+        })
+        .into();
+
+        let check = |key: &str, function: AggregateFunction, range: Range| {
+            let condition = AggregateCondition::from(Aggregate {
+                key: key.to_string(),
+                function,
+                range,
+            });
+            check_aggregate_condition(&condition, &payload)
+        };
+        let around = |value: f64| Range {
+            lt: None,
+            gt: None,
+            gte: Some(value - 0.01),
+            lte: Some(value + 0.01),
+        };
+
+        assert!(check(
+            "orders[].value",
+            AggregateFunction::Sum,
+            around(120.5)
+        ));
+        assert!(check(
+            "orders[].value",
+            AggregateFunction::Min,
+            around(10.0)
+        ));
+        assert!(check(
+            "orders[].value",
+            AggregateFunction::Max,
+            around(60.0)
+        ));
+        assert!(check(
+            "orders[].value",
+            AggregateFunction::Avg,
+            around(40.1666)
+        ));
+
+        // total order value > 100
+        let more_than_100 = Range {
+            lt: None,
+            gt: Some(100.0),
+            gte: None,
+            lte: None,
+        };
+        assert!(check(
+            "orders[].value",
+            AggregateFunction::Sum,
+            more_than_100.clone()
+        ));
+        assert!(!check(
+            "orders[].value",
+            AggregateFunction::Max,
+            more_than_100.clone()
+        ));
+
+        // leaf arrays are aggregated as individual values
+        assert!(check(
+            "orders[].discounts",
+            AggregateFunction::Sum,
+            around(3.0)
+        ));
+
+        // no numeric values - no match
+        assert!(!check(
+            "orders[].missing",
+            AggregateFunction::Sum,
+            around(0.0)
+        ));
+        assert!(!check(
+            "orders[].missing",
+            AggregateFunction::Avg,
+            more_than_100
+        ));
+    }
+
+    #[test]
+    fn test_distinct_count_aggregate_condition() {
+        let payload: Payload = json!({
+            "reviews": [
+                { "author": "alice", "score": 5 },
+                { "author": "bob", "score": 5 },
+                { "author": "alice", "score": 4.0 },
+                { "author": "carol", "score": 3 },
+                { "author": ["bob", "dave"] },
+                { "author": null },
+            ],
+        })
+        .into();
+
+        let distinct_count = |key: &str, range: Range| {
+            let condition = AggregateCondition::from(Aggregate {
+                key: key.to_string(),
+                function: AggregateFunction::DistinctCount,
+                range,
+            });
+            check_aggregate_condition(&condition, &payload)
+        };
+        let at_least = |count: f64| Range {
+            lt: None,
+            gt: None,
+            gte: Some(count),
+            lte: None,
+        };
+        let exactly = |count: f64| Range {
+            lt: None,
+            gt: None,
+            gte: Some(count),
+            lte: Some(count),
+        };
+
+        // repeated authors are counted once, nulls are ignored
+        assert!(distinct_count("reviews[].author", exactly(4.0)));
+        assert!(distinct_count("reviews[].author", at_least(3.0)));
+        assert!(!distinct_count("reviews[].author", at_least(5.0)));
+
+        // equal numbers are the same value, regardless of their representation
+        assert!(distinct_count("reviews[].score", exactly(3.0)));
+
+        // no values at all
+        assert!(distinct_count("reviews[].missing", exactly(0.0)));
+        assert!(!distinct_count("reviews[].missing", at_least(1.0)));
+
+        assert_eq!(
+            AggregateFunction::DistinctCount.aggregate([1.0, 2.0, 1.0]),
+            Some(2.0)
+        );
+        assert_eq!(AggregateFunction::DistinctCount.aggregate([]), Some(0.0));
+    }
 }
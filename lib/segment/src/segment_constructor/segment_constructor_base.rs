@@ -31,7 +31,7 @@ use crate::types::{
 use crate::vector_storage::appendable_mmap_vector_storage::open_appendable_memmap_vector_storage;
 use crate::vector_storage::memmap_vector_storage::open_memmap_vector_storage;
 use crate::vector_storage::simple_vector_storage::open_simple_vector_storage;
-use crate::vector_storage::VectorStorage;
+use crate::vector_storage::{NamedVectorStorages, VectorStorage};
 
 pub const PAYLOAD_INDEX_PATH: &str = "payload_index";
 pub const VECTOR_STORAGE_PATH: &str = "vector_storage";
@@ -80,17 +80,9 @@ fn create_segment(
 
     let id_tracker = sp(SimpleIdTracker::open(database.clone())?);
 
-    let payload_index_path = segment_path.join(PAYLOAD_INDEX_PATH);
-    let payload_index: Arc<AtomicRefCell<StructPayloadIndex>> = sp(StructPayloadIndex::open(
-        payload_storage,
-        id_tracker.clone(),
-        &payload_index_path,
-    )?);
-
-    let mut vector_data = HashMap::new();
+    let mut vector_storages: NamedVectorStorages = HashMap::new();
     for (vector_name, vector_config) in &config.vector_data {
         let vector_storage_path = get_vector_storage_path(segment_path, vector_name);
-        let vector_index_path = get_vector_index_path(segment_path, vector_name);
 
         // Select suitable vector storage type based on configuration
         let vector_storage = match vector_config.storage_type {
@@ -127,6 +119,23 @@ fn create_segment(
                 .load_quantization(&quantized_data_path)?;
         }
 
+        vector_storages.insert(vector_name.to_owned(), vector_storage);
+    }
+
+    // Payload index needs vector storages to check presence of named vectors
+    let payload_index_path = segment_path.join(PAYLOAD_INDEX_PATH);
+    let payload_index: Arc<AtomicRefCell<StructPayloadIndex>> = sp(StructPayloadIndex::open(
+        payload_storage,
+        id_tracker.clone(),
+        vector_storages.clone(),
+        &payload_index_path,
+    )?);
+
+    let mut vector_data = HashMap::new();
+    for (vector_name, vector_storage) in vector_storages {
+        let vector_config = &config.vector_data[&vector_name];
+        let vector_index_path = get_vector_index_path(segment_path, &vector_name);
+
         let vector_index: Arc<AtomicRefCell<VectorIndexEnum>> = match &vector_config.index {
             Indexes::Plain {} => sp(VectorIndexEnum::Plain(PlainIndex::new(
                 id_tracker.clone(),
@@ -153,7 +162,7 @@ fn create_segment(
         };
 
         vector_data.insert(
-            vector_name.to_owned(),
+            vector_name,
             VectorData {
                 vector_storage,
                 vector_index,
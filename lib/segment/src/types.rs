@@ -1,3 +1,4 @@
+use std::borrow::Cow;
 use std::cmp::Ordering;
 use std::collections::{BTreeMap, HashMap, HashSet};
 use std::fmt::Formatter;
@@ -7,6 +8,7 @@ use std::ops::Deref;
 use std::rc::Rc;
 use std::str::FromStr;
 
+use bitvec::slice::BitSlice;
 use geo::prelude::HaversineDistance;
 use geo::Point;
 use itertools::Itertools;
@@ -15,13 +17,14 @@ use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use serde_json::{Map, Value};
 use uuid::Uuid;
-use validator::{Validate, ValidationErrors};
+use validator::{Validate, ValidationError, ValidationErrors};
 
 use crate::common::utils;
 use crate::common::utils::MultiValue;
 use crate::data_types::groups::GroupId;
 use crate::data_types::text_index::TextIndexParams;
 use crate::data_types::vectors::{VectorElementType, VectorStruct};
+use crate::payload_storage::condition_checker::compile_regex;
 use crate::spaces::metric::Metric;
 use crate::spaces::simple::{CosineMetric, DotProductMetric, EuclidMetric};
 
@@ -681,6 +684,11 @@ impl Payload {
         utils::get_value_from_json_map(path, &self.0)
     }
 
+    /// Same as `get_value`, but negative array indices of nested filter paths count from the end
+    pub fn get_nested_value(&self, path: &str) -> MultiValue<&Value> {
+        utils::get_nested_value_from_json_map(path, &self.0)
+    }
+
     pub fn remove(&mut self, path: &str) -> Vec<Value> {
         utils::remove_value_from_json_map(path, &mut self.0).values()
     }
@@ -945,6 +953,15 @@ pub struct MatchAny {
     pub any: AnyVariants,
 }
 
+/// Match of the strings by a regular expression
+///
+/// Patterns compiling into a too large automaton are rejected and match nothing.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct MatchRegex {
+    pub regex: String,
+}
+
 /// Match filter request
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -953,6 +970,7 @@ pub enum MatchInterface {
     Value(MatchValue),
     Text(MatchText),
     Any(MatchAny),
+    Regex(MatchRegex),
 }
 
 /// Match filter request
@@ -963,6 +981,7 @@ pub enum Match {
     Value(MatchValue),
     Text(MatchText),
     Any(MatchAny),
+    Regex(MatchRegex),
 }
 
 impl Match {
@@ -979,6 +998,12 @@ impl Match {
     pub fn new_any(any: AnyVariants) -> Self {
         Self::Any(MatchAny { any })
     }
+
+    pub fn new_regex(regex: impl Into<String>) -> Self {
+        Self::Regex(MatchRegex {
+            regex: regex.into(),
+        })
+    }
 }
 
 impl From<AnyVariants> for Match {
@@ -993,6 +1018,7 @@ impl From<MatchInterface> for Match {
             MatchInterface::Value(value) => Self::Value(MatchValue { value: value.value }),
             MatchInterface::Text(text) => Self::Text(MatchText { text: text.text }),
             MatchInterface::Any(any) => Self::Any(MatchAny { any: any.any }),
+            MatchInterface::Regex(regex) => Self::Regex(MatchRegex { regex: regex.regex }),
         }
     }
 }
@@ -1060,6 +1086,237 @@ impl Range {
     }
 }
 
+/// Function used to aggregate numeric values of a field
+///
+/// `distinct_count` counts distinct values, which are not required to be numeric
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AggregateFunction {
+    Sum,
+    Min,
+    Max,
+    Avg,
+    DistinctCount,
+}
+
+impl AggregateFunction {
+    /// Aggregate given numbers, returns `None` if there are no numbers
+    ///
+    /// Distinct count of no numbers is zero.
+    pub fn aggregate(
+        &self,
+        numbers: impl IntoIterator<Item = FloatPayloadType>,
+    ) -> Option<FloatPayloadType> {
+        if let AggregateFunction::DistinctCount = self {
+            let distinct: HashSet<_> = numbers.into_iter().map(OrderedFloat).collect();
+            return Some(distinct.len() as FloatPayloadType);
+        }
+        let mut count = 0;
+        let mut aggregated: Option<FloatPayloadType> = None;
+        for number in numbers {
+            count += 1;
+            aggregated = Some(match aggregated {
+                None => number,
+                Some(acc) => match self {
+                    AggregateFunction::Sum | AggregateFunction::Avg => acc + number,
+                    AggregateFunction::Min => acc.min(number),
+                    AggregateFunction::Max => acc.max(number),
+                    AggregateFunction::DistinctCount => unreachable!("counted above"),
+                },
+            });
+        }
+        match self {
+            AggregateFunction::Avg => aggregated.map(|sum| sum / count as FloatPayloadType),
+            _ => aggregated,
+        }
+    }
+}
+
+/// Aggregated value filter request
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct Aggregate {
+    /// Payload key of the numeric values, e.g. `orders[].value`
+    ///
+    /// Values of any type are counted by `distinct_count`, e.g. `reviews[].author`.
+    pub key: PayloadKeyType,
+    /// Function used to aggregate the values
+    pub function: AggregateFunction,
+    /// Aggregated value should be in this range
+    pub range: Range,
+}
+
+/// Arithmetic operator of a computed value expression
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ArithmeticOperator {
+    Add,
+    Sub,
+    Mul,
+    Div,
+}
+
+impl ArithmeticOperator {
+    /// Apply operator to the operands, returns `None` if the result is not a finite number
+    pub fn apply(
+        &self,
+        left: FloatPayloadType,
+        right: FloatPayloadType,
+    ) -> Option<FloatPayloadType> {
+        let result = match self {
+            ArithmeticOperator::Add => left + right,
+            ArithmeticOperator::Sub => left - right,
+            ArithmeticOperator::Mul => left * right,
+            ArithmeticOperator::Div => left / right,
+        };
+        result.is_finite().then_some(result)
+    }
+}
+
+/// Computed value filter request, e.g. `price * quantity > 100`
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub struct Expression {
+    /// Payload key of the left operand, relative to the nested element inside of nested filters
+    pub left: PayloadKeyType,
+    /// Operator applied to the operands
+    pub operator: ArithmeticOperator,
+    /// Payload key of the right operand, relative to the nested element inside of nested filters
+    pub right: PayloadKeyType,
+    /// Computed value should be in this range
+    pub range: Range,
+}
+
+impl Expression {
+    /// Compute the value of the expression from the fields of the given object
+    ///
+    /// Each operand has to be a single number, arrays and other values are not used.
+    pub fn evaluate(&self, object: &Map<String, Value>) -> Option<FloatPayloadType> {
+        let operand = |key: &str| match utils::get_value_from_json_map(key, object)
+            .values()
+            .as_slice()
+        {
+            [value] => value.as_f64(),
+            _ => None,
+        };
+        self.operator
+            .apply(operand(&self.left)?, operand(&self.right)?)
+    }
+
+    /// Check if the computed value of the object is in the range
+    pub fn check(&self, object: &Map<String, Value>) -> bool {
+        self.evaluate(object)
+            .map_or(false, |value| self.range.check_range(value))
+    }
+}
+
+/// Operator comparing two payload values
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ComparisonOperator {
+    Eq,
+    Ne,
+    Lt,
+    Lte,
+    Gt,
+    Gte,
+}
+
+impl ComparisonOperator {
+    /// Compare the operands, returns `None` if they are not comparable with the operator
+    ///
+    /// Numbers are compared by value, regardless of their integer or float representation.
+    /// Strings and booleans are only compared for equality.
+    pub fn apply(&self, left: &Value, right: &Value) -> Option<bool> {
+        self.apply_with_collation(left, right, None)
+    }
+
+    /// Same as `apply`, but strings are also ordered according to the `collation`, if specified
+    pub fn apply_with_collation(
+        &self,
+        left: &Value,
+        right: &Value,
+        collation: Option<CollationLocale>,
+    ) -> Option<bool> {
+        let ordering = match (left, right) {
+            (Value::Number(left), Value::Number(right)) => {
+                left.as_f64()?.partial_cmp(&right.as_f64()?)?
+            }
+            (Value::String(left), Value::String(right)) if collation.is_some() => {
+                crate::common::collation::collate(collation?, left, right)
+            }
+            (Value::String(_), Value::String(_)) | (Value::Bool(_), Value::Bool(_)) => {
+                return match self {
+                    ComparisonOperator::Eq => Some(left == right),
+                    ComparisonOperator::Ne => Some(left != right),
+                    _ => None,
+                };
+            }
+            _ => return None,
+        };
+        Some(match self {
+            ComparisonOperator::Eq => ordering.is_eq(),
+            ComparisonOperator::Ne => ordering.is_ne(),
+            ComparisonOperator::Lt => ordering.is_lt(),
+            ComparisonOperator::Lte => ordering.is_le(),
+            ComparisonOperator::Gt => ordering.is_gt(),
+            ComparisonOperator::Gte => ordering.is_ge(),
+        })
+    }
+}
+
+/// Comparison of two fields, e.g. `sale_price < list_price`
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub struct Comparison {
+    /// Payload key of the left operand, relative to the nested element inside of nested filters
+    pub left: PayloadKeyType,
+    /// Operator comparing the operands
+    pub operator: ComparisonOperator,
+    /// Payload key of the right operand, relative to the nested element inside of nested filters
+    pub right: PayloadKeyType,
+    /// If specified - strings are ordered as in the dictionary of the locale.
+    /// Otherwise strings are only compared for equality
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub collation: Option<CollationLocale>,
+}
+
+/// Locale defining the order of strings, e.g. of letters with accents
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CollationLocale {
+    /// Letters with accents are ordered right after the same letters without them
+    Root,
+    /// German, same as the root order
+    De,
+    /// Spanish, `ñ` is a separate letter following `n`
+    Es,
+    /// Swedish, `å`, `ä` and `ö` are separate letters following `z`
+    Sv,
+}
+
+impl Comparison {
+    /// Check if the fields of the given object satisfy the comparison
+    ///
+    /// Each operand has to be a single primitive value, arrays and other values do not match.
+    pub fn check(&self, object: &Map<String, Value>) -> bool {
+        let operand = |key: &str| match utils::get_value_from_json_map(key, object)
+            .values()
+            .as_slice()
+        {
+            [value] => Some(*value),
+            _ => None,
+        };
+        match (operand(&self.left), operand(&self.right)) {
+            (Some(left), Some(right)) => self
+                .operator
+                .apply_with_collation(left, right, self.collation)
+                .unwrap_or(false),
+            _ => false,
+        }
+    }
+}
+
 /// Values count filter request
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq)]
 #[serde(rename_all = "snake_case")]
@@ -1082,6 +1339,14 @@ impl ValuesCount {
             _ => 1,
         };
 
+        self.check_count_value(count)
+    }
+
+    /// Compare already computed number of values against the thresholds
+    ///
+    /// Counting and comparison are done in `usize` entirely, so there is no truncation
+    /// or overflow even for counts close to `usize::MAX`.
+    pub fn check_count_value(&self, count: usize) -> bool {
         self.lt.map_or(true, |x| count < x)
             && self.gt.map_or(true, |x| count > x)
             && self.lte.map_or(true, |x| count <= x)
@@ -1145,6 +1410,39 @@ pub struct FieldCondition {
     pub geo_radius: Option<GeoRadius>,
     /// Check number of values of the field
     pub values_count: Option<ValuesCount>,
+    /// Handling of `null` values of the field inside of nested filters, non-matching by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub null_policy: Option<NestedNullPolicy>,
+    /// Matching of elements holding inner arrays inside of nested filters, any inner value by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub inner_match: Option<InnerArrayMatch>,
+}
+
+/// Behavior of nested field conditions for elements with a `null` value of the field
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NestedNullPolicy {
+    /// Element with `null` value does not match the condition
+    #[default]
+    NoMatch,
+    /// `null` values are ignored, element with only `null` values does not match
+    Skip,
+    /// Reject the condition with an error.
+    /// A query can't fail for a single point, so `null` values don't match there and are logged
+    Error,
+}
+
+/// How an element of the nested array, which is an array itself, matches a field condition
+///
+/// Elements with a single value are treated as an inner array of one value.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Eq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum InnerArrayMatch {
+    /// Element matches if any of its inner values matches
+    #[default]
+    Any,
+    /// Element matches if all of its inner values match, empty inner array does not match
+    All,
 }
 
 impl FieldCondition {
@@ -1156,6 +1454,8 @@ impl FieldCondition {
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
+            null_policy: None,
+            inner_match: None,
         }
     }
 
@@ -1167,6 +1467,8 @@ impl FieldCondition {
             geo_bounding_box: None,
             geo_radius: None,
             values_count: None,
+            null_policy: None,
+            inner_match: None,
         }
     }
 
@@ -1181,6 +1483,8 @@ impl FieldCondition {
             geo_bounding_box: Some(geo_bounding_box),
             geo_radius: None,
             values_count: None,
+            null_policy: None,
+            inner_match: None,
         }
     }
 
@@ -1192,6 +1496,8 @@ impl FieldCondition {
             geo_bounding_box: None,
             geo_radius: Some(geo_radius),
             values_count: None,
+            null_policy: None,
+            inner_match: None,
         }
     }
 
@@ -1203,6 +1509,8 @@ impl FieldCondition {
             geo_bounding_box: None,
             geo_radius: None,
             values_count: Some(values_count),
+            null_policy: None,
+            inner_match: None,
         }
     }
 }
@@ -1234,6 +1542,147 @@ impl From<String> for IsNullCondition {
     }
 }
 
+/// Select points which payload contains a specified field, regardless of its value
+///
+/// Unlike `is_empty`, the field with `null` or empty array value is considered present
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct HasKeyCondition {
+    pub has_key: PayloadField,
+}
+
+impl From<String> for HasKeyCondition {
+    fn from(key: String) -> Self {
+        HasKeyCondition {
+            has_key: PayloadField { key },
+        }
+    }
+}
+
+/// Select points which array field contains the same primitive value more than once
+///
+/// Matches the point as a whole, values of all array elements at the key are compared together.
+/// Integer and float representations of the same number are considered equal
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct HasDuplicatesCondition {
+    pub has_duplicates: PayloadField,
+}
+
+impl From<String> for HasDuplicatesCondition {
+    fn from(key: String) -> Self {
+        HasDuplicatesCondition {
+            has_duplicates: PayloadField { key },
+        }
+    }
+}
+
+/// JSON type of a payload value
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "snake_case")]
+pub enum JsonValueType {
+    Null,
+    Bool,
+    Number,
+    String,
+    Array,
+    Object,
+}
+
+impl JsonValueType {
+    pub fn of(value: &Value) -> Self {
+        match value {
+            Value::Null => JsonValueType::Null,
+            Value::Bool(_) => JsonValueType::Bool,
+            Value::Number(_) => JsonValueType::Number,
+            Value::String(_) => JsonValueType::String,
+            Value::Array(_) => JsonValueType::Array,
+            Value::Object(_) => JsonValueType::Object,
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct ValueType {
+    /// Payload key of the value
+    pub key: PayloadKeyType,
+    /// Expected JSON type of the value
+    pub r#type: JsonValueType,
+}
+
+/// Select points which payload field has a value of the given JSON type
+///
+/// Array value has the `array` type, use `key[]` to check the types of its elements instead.
+/// Points without the field do not match, even for the `null` type
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct ValueTypeCondition {
+    pub value_type: ValueType,
+}
+
+impl ValueTypeCondition {
+    pub fn new(key: impl Into<PayloadKeyType>, r#type: JsonValueType) -> Self {
+        Self {
+            value_type: ValueType {
+                key: key.into(),
+                r#type,
+            },
+        }
+    }
+}
+
+/// Range of positions as fractions of the array length
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
+pub struct FractionRange {
+    /// Start of the range, inclusive
+    pub from: f64,
+    /// End of the range, exclusive
+    pub to: f64,
+}
+
+/// Positions of the array elements, relative to the length of the array
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionRange {
+    /// Elements from `from * length` to `to * length`, e.g. `{ "from": 0.0, "to": 0.5 }` is the
+    /// first half of the array
+    Fraction(FractionRange),
+    /// First `n` elements of the array
+    First(usize),
+    /// Last `n` elements of the array
+    Last(usize),
+}
+
+impl PositionRange {
+    /// Whether the element at `position` of an array of `length` elements is in the range
+    ///
+    /// `position` is expected to be less than `length`.
+    pub fn contains(&self, position: usize, length: usize) -> bool {
+        match *self {
+            PositionRange::Fraction(FractionRange { from, to }) => {
+                let position = position as f64;
+                let length = length as f64;
+                position >= from * length && position < to * length
+            }
+            PositionRange::First(n) => position < n,
+            // `n` comes from the client and may be as large as `usize::MAX`
+            PositionRange::Last(n) => length - position <= n,
+        }
+    }
+}
+
+/// Select elements of a nested array by their position, relative to the array length
+///
+/// Only applicable inside of nested filters, e.g. to match the first half of the array or
+/// its last `n` elements. Generalizes the `index` of the nested condition to ranges.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, Copy, PartialEq)]
+pub struct PositionCondition {
+    pub position: PositionRange,
+}
+
+impl From<PositionRange> for PositionCondition {
+    fn from(position: PositionRange) -> Self {
+        PositionCondition { position }
+    }
+}
+
 /// ID-based filtering condition
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
 pub struct HasIdCondition {
@@ -1246,11 +1695,169 @@ impl From<HashSet<PointIdType>> for HasIdCondition {
     }
 }
 
+/// Select points by the aggregated value of a numeric payload field
+///
+/// Points without numeric values in the field do not match.
+/// Not supported inside of nested filters, as the aggregated value is not associated with a
+/// single element: such condition matches no elements.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct AggregateCondition {
+    pub aggregate: Aggregate,
+}
+
+impl From<Aggregate> for AggregateCondition {
+    fn from(aggregate: Aggregate) -> Self {
+        AggregateCondition { aggregate }
+    }
+}
+
+/// Select points by a value computed from two numeric fields
+///
+/// Inside of nested filters, the value is computed for each element of the array separately.
+/// Points or elements without single numeric values in both fields do not match
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct ExpressionCondition {
+    pub expression: Expression,
+}
+
+impl From<Expression> for ExpressionCondition {
+    fn from(expression: Expression) -> Self {
+        ExpressionCondition { expression }
+    }
+}
+
+/// Select points by comparing values of two payload fields
+///
+/// Inside of nested filters, fields of each element of the array are compared separately.
+/// Points or elements without single comparable values in both fields do not match
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct ComparisonCondition {
+    pub compare: Comparison,
+}
+
+impl From<Comparison> for ComparisonCondition {
+    fn from(compare: Comparison) -> Self {
+        ComparisonCondition { compare }
+    }
+}
+
+/// Select points by the number of named vectors they have
+///
+/// Only vectors which are present and not deleted are counted
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq, Eq)]
+pub struct VectorCountCondition {
+    pub vector_count: ValuesCount,
+}
+
+impl From<ValuesCount> for VectorCountCondition {
+    fn from(vector_count: ValuesCount) -> Self {
+        VectorCountCondition { vector_count }
+    }
+}
+
+/// Select points which named vector is not unit-normalized
+///
+/// Points without the named vector, or with the vector deleted, do not match.
+/// Vectors with `Cosine` distance are normalized on insertion, so this is mostly useful for other
+/// distances
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct VectorNotNormalizedCondition {
+    pub vector_not_normalized: VectorNotNormalized,
+}
+
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct VectorNotNormalized {
+    /// Name of the vector to check
+    pub name: String,
+    /// Maximal allowed deviation of the vector norm from 1.0
+    pub tolerance: f32,
+}
+
+impl VectorNotNormalizedCondition {
+    pub fn new(name: impl Into<String>, tolerance: f32) -> Self {
+        VectorNotNormalizedCondition {
+            vector_not_normalized: VectorNotNormalized {
+                name: name.into(),
+                tolerance,
+            },
+        }
+    }
+}
+
 /// Select points with payload for a specified nested field
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
 pub struct NestedCondition {
     pub key: PayloadKeyType,
     pub filter: Filter,
+    /// If specified - only the array element at this position is checked against the filter.
+    /// Negative positions count from the end of the array, e.g. `-1` is the last element
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub index: Option<isize>,
+    /// If specified - only the array elements added after the marker are checked against the filter
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub after: Option<SequenceMarker>,
+    /// How many of the array elements should match the filter, at least one by default
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quantifier: Option<NestedQuantifier>,
+}
+
+/// How many elements of the array should match the filter of a nested condition
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Copy, Clone, PartialEq, Default)]
+#[serde(rename_all = "snake_case")]
+pub enum NestedQuantifier {
+    /// At least one element matches
+    #[default]
+    Any,
+    /// Every element matches, missing or empty array does not match
+    All,
+    /// Every element matches, missing or empty array matches as well
+    AllOrEmpty,
+    /// Share of the matching elements is at least the given ratio, e.g. `0.8` for 80%.
+    /// Missing or empty array does not match, as the ratio is undefined for it
+    MinRatio(f64),
+}
+
+impl NestedQuantifier {
+    /// Check the array of `elements` elements, given the indices of its elements matching the
+    /// filter
+    pub fn check(&self, matches: &BitSlice, elements: usize) -> bool {
+        match self {
+            NestedQuantifier::Any => matches.any(),
+            NestedQuantifier::All => elements > 0 && matches.count_ones() == elements,
+            // Empty array vacuously satisfies the filter
+            NestedQuantifier::AllOrEmpty => matches.count_ones() == elements,
+            NestedQuantifier::MinRatio(_) if elements == 0 => false,
+            NestedQuantifier::MinRatio(min_ratio) => {
+                matches.count_ones() as f64 / elements as f64 >= *min_ratio
+            }
+        }
+    }
+}
+
+/// Version marker of the elements in an append-only array, e.g. an event log
+///
+/// Elements are selected by the sequence number they carry, elements without it are skipped.
+#[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
+pub struct SequenceMarker {
+    /// Field of the array element holding its sequence number
+    pub key: PayloadKeyType,
+    /// Only elements with a sequence number greater than this one are selected
+    pub value: FloatPayloadType,
+}
+
+impl SequenceMarker {
+    /// Condition selecting the elements after the marker, relative to the array element
+    pub fn condition(&self) -> Condition {
+        Condition::Field(FieldCondition::new_range(
+            self.key.clone(),
+            Range {
+                lt: None,
+                gt: Some(self.value),
+                gte: None,
+                lte: None,
+            },
+        ))
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
@@ -1270,18 +1877,38 @@ impl NestedContainer {
     }
 
     /// Nested is made to be used with arrays, so we add `[]` to the key if it is not present for convenience
+    ///
+    /// Positional condition focuses on a single element instead, e.g. `key[0]` or `key[-1]`
     pub fn array_key(&self) -> String {
         let raw = self.raw_key();
-        if raw.ends_with("[]") {
-            raw.to_string()
-        } else {
-            format!("{}[]", raw)
+        match self.nested.index {
+            Some(index) => format!("{}[{}]", raw.trim_end_matches("[]"), index),
+            None if raw.ends_with("[]") => raw.to_string(),
+            None => format!("{}[]", raw),
         }
     }
 
     pub fn filter(&self) -> &Filter {
         &self.nested.filter
     }
+
+    pub fn quantifier(&self) -> NestedQuantifier {
+        self.nested.quantifier.unwrap_or_default()
+    }
+
+    /// Filter checked against each array element, including the sequence marker if any
+    pub fn element_filter(&self) -> Cow<'_, Filter> {
+        match &self.nested.after {
+            None => Cow::Borrowed(&self.nested.filter),
+            Some(marker) => {
+                let mut filter = self.nested.filter.clone();
+                let mut must = vec![marker.condition()];
+                must.extend(filter.must.take().unwrap_or_default());
+                filter.must = Some(must);
+                Cow::Owned(filter)
+            }
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Serialize, JsonSchema, Clone, PartialEq)]
@@ -1294,8 +1921,26 @@ pub enum Condition {
     IsEmpty(IsEmptyCondition),
     /// Check if payload field equals `NULL`
     IsNull(IsNullCondition),
+    /// Check if payload contains the field, regardless of its value
+    HasKey(HasKeyCondition),
     /// Check if points id is in a given set
     HasId(HasIdCondition),
+    /// Check if number of named vectors of the point is in a given range
+    VectorCount(VectorCountCondition),
+    /// Check if named vector of the point is not normalized
+    VectorNotNormalized(VectorNotNormalizedCondition),
+    /// Check if aggregated value of numeric payload field is in a given range
+    Aggregate(AggregateCondition),
+    /// Check if value computed from two numeric payload fields is in a given range
+    Expression(ExpressionCondition),
+    /// Check if values of two payload fields satisfy the comparison
+    Compare(ComparisonCondition),
+    /// Check if payload array contains duplicate values
+    HasDuplicates(HasDuplicatesCondition),
+    /// Check if payload field has a value of the given JSON type
+    ValueType(ValueTypeCondition),
+    /// Check if element of the nested array is at the given positions
+    Position(PositionCondition),
     /// Nested filter
     Filter(Filter),
     /// Nested filters
@@ -1308,6 +1953,97 @@ impl Condition {
             nested: NestedCondition {
                 key: key.into(),
                 filter,
+                index: None,
+                after: None,
+                quantifier: None,
+            },
+        })
+    }
+
+    /// Nested filter applied only to the array element at the given position
+    pub fn new_nested_at(key: impl Into<String>, index: usize, filter: Filter) -> Self {
+        Self::Nested(NestedContainer {
+            nested: NestedCondition {
+                key: key.into(),
+                filter,
+                index: Some(isize::try_from(index).unwrap_or(isize::MAX)),
+                after: None,
+                quantifier: None,
+            },
+        })
+    }
+
+    /// Nested filter applied only to the last element of the array, whatever its length is
+    pub fn new_nested_last(key: impl Into<String>, filter: Filter) -> Self {
+        Self::Nested(NestedContainer {
+            nested: NestedCondition {
+                key: key.into(),
+                filter,
+                index: Some(-1),
+                after: None,
+                quantifier: None,
+            },
+        })
+    }
+
+    /// Nested filter which every element of the array should match
+    ///
+    /// Missing or empty array matches only if `match_empty` is set.
+    pub fn new_nested_all(key: impl Into<String>, filter: Filter, match_empty: bool) -> Self {
+        let quantifier = if match_empty {
+            NestedQuantifier::AllOrEmpty
+        } else {
+            NestedQuantifier::All
+        };
+        Self::Nested(NestedContainer {
+            nested: NestedCondition {
+                key: key.into(),
+                filter,
+                index: None,
+                after: None,
+                quantifier: Some(quantifier),
+            },
+        })
+    }
+
+    /// Nested condition requiring none of the array elements to be empty, i.e. every element
+    /// has all of the `required_fields` with a non-empty value
+    ///
+    /// Missing or empty array matches only if `match_empty` is set.
+    pub fn new_nested_complete(
+        key: impl Into<String>,
+        required_fields: impl IntoIterator<Item = impl Into<String>>,
+        match_empty: bool,
+    ) -> Self {
+        let missing_fields: Vec<_> = required_fields
+            .into_iter()
+            .map(|field| {
+                Condition::IsEmpty(IsEmptyCondition {
+                    is_empty: PayloadField { key: field.into() },
+                })
+            })
+            .collect();
+        let filter = Filter {
+            should: None,
+            must: None,
+            must_not: (!missing_fields.is_empty()).then_some(missing_fields),
+        };
+        Self::new_nested_all(key, filter, match_empty)
+    }
+
+    /// Nested filter applied only to the array elements added after the `marker`
+    pub fn new_nested_after(
+        key: impl Into<String>,
+        marker: SequenceMarker,
+        filter: Filter,
+    ) -> Self {
+        Self::Nested(NestedContainer {
+            nested: NestedCondition {
+                key: key.into(),
+                filter,
+                index: None,
+                after: Some(marker),
+                quantifier: None,
             },
         })
     }
@@ -1537,6 +2273,485 @@ impl Filter {
     }
 }
 
+impl Filter {
+    /// Normalized form of the filter, equal for filters differing only in the order of conditions
+    ///
+    /// Conditions of each clause are sorted and deduplicated, nested keys are stored without the
+    /// trailing `[]`, empty `must` and `must_not` lists are dropped.
+    /// Useful as a key for caching filter results.
+    pub fn canonicalize(&self) -> Filter {
+        let canonical_clause = |conditions: &Option<Vec<Condition>>| {
+            conditions.as_ref().map(|conditions| {
+                let mut conditions: Vec<_> = conditions
+                    .iter()
+                    .map(|condition| {
+                        let condition = condition.canonicalize();
+                        (condition.canonical_key(), condition)
+                    })
+                    .collect();
+                conditions.sort_by(|(a, _), (b, _)| a.cmp(b));
+                conditions.dedup_by(|(a, _), (b, _)| a == b);
+                conditions
+                    .into_iter()
+                    .map(|(_, condition)| condition)
+                    .collect::<Vec<_>>()
+            })
+        };
+        // Empty `should` matches nothing, so it is kept as is
+        let non_empty = |conditions: Option<Vec<Condition>>| conditions.filter(|c| !c.is_empty());
+        Filter {
+            should: canonical_clause(&self.should),
+            must: non_empty(canonical_clause(&self.must)),
+            must_not: non_empty(canonical_clause(&self.must_not)),
+        }
+    }
+
+    fn canonical_key(&self) -> String {
+        let clause_key = |conditions: &Option<Vec<Condition>>| match conditions {
+            Some(conditions) => {
+                let keys: Vec<_> = conditions.iter().map(Condition::canonical_key).collect();
+                format!("[{}]", keys.join(","))
+            }
+            None => "null".to_string(),
+        };
+        format!(
+            "{{\"should\":{},\"must\":{},\"must_not\":{}}}",
+            clause_key(&self.should),
+            clause_key(&self.must),
+            clause_key(&self.must_not),
+        )
+    }
+}
+
+impl Condition {
+    /// Normalized form of the condition, see [`Filter::canonicalize`]
+    pub fn canonicalize(&self) -> Condition {
+        match self {
+            Condition::Field(field) => {
+                let mut field = field.clone();
+                if let Some(Match::Any(MatchAny { any })) = &mut field.r#match {
+                    match any {
+                        AnyVariants::Keywords(keywords) => {
+                            keywords.sort_unstable();
+                            keywords.dedup();
+                        }
+                        AnyVariants::Integers(integers) => {
+                            integers.sort_unstable();
+                            integers.dedup();
+                        }
+                    }
+                }
+                Condition::Field(field)
+            }
+            Condition::Filter(filter) => Condition::Filter(filter.canonicalize()),
+            Condition::Nested(nested) => {
+                let raw_key = nested.raw_key();
+                let key = raw_key.strip_suffix("[]").unwrap_or(raw_key);
+                Condition::Nested(NestedContainer::new(NestedCondition {
+                    key: key.to_string(),
+                    filter: nested.filter().canonicalize(),
+                    index: nested.nested.index,
+                    after: nested.nested.after.clone(),
+                    quantifier: nested.nested.quantifier,
+                }))
+            }
+            condition => condition.clone(),
+        }
+    }
+
+    /// Deterministic textual representation, used for ordering of canonical conditions
+    fn canonical_key(&self) -> String {
+        match self {
+            Condition::HasId(HasIdCondition { has_id }) => {
+                let mut ids: Vec<_> = has_id.iter().collect();
+                ids.sort_unstable();
+                serde_json::json!({ "has_id": ids }).to_string()
+            }
+            Condition::Filter(filter) => filter.canonical_key(),
+            Condition::Nested(nested) => format!(
+                "{{\"nested\":{{\"key\":{},\"index\":{},\"after\":{},\"quantifier\":{},\"filter\":{}}}}}",
+                serde_json::json!(nested.raw_key()),
+                serde_json::json!(nested.nested.index),
+                serde_json::json!(nested.nested.after),
+                serde_json::json!(nested.quantifier()),
+                nested.filter().canonical_key(),
+            ),
+            condition => serde_json::to_string(condition).unwrap_or_default(),
+        }
+    }
+}
+
+/// Problem found in a filter by [`Filter::validate_deep`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FilterIssue {
+    /// Location of the problematic condition, e.g. `must[0].nested.filter.should`
+    pub path: String,
+    pub message: String,
+}
+
+impl std::fmt::Display for FilterIssue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}: {}", self.path, self.message)
+    }
+}
+
+impl Validate for Filter {
+    /// Report issues found by [`Filter::validate_deep`], grouped by the top level clause
+    fn validate(&self) -> Result<(), ValidationErrors> {
+        let mut errors = ValidationErrors::new();
+        for issue in self.validate_deep() {
+            let clause = ["should", "must_not", "must"]
+                .into_iter()
+                .find(|clause| issue.path.starts_with(clause))
+                .unwrap_or("filter");
+            let mut err = ValidationError::new("invalid_condition");
+            err.add_param(Cow::from("path"), &issue.path);
+            err.message.replace(Cow::from(issue.to_string()));
+            errors.add(clause, err);
+        }
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+impl Filter {
+    /// Check all conditions of the filter, including ones of nested filters
+    ///
+    /// Unlike failing on the first problem, all found issues are reported at once.
+    /// Empty list means the filter is valid.
+    pub fn validate_deep(&self) -> Vec<FilterIssue> {
+        let mut issues = vec![];
+        self.collect_issues("", false, &mut issues);
+        issues
+    }
+
+    /// Check that conditions of every nested filter go through the same inner array
+    ///
+    /// Keys of conditions in a nested filter are relative to the array element, so conditions
+    /// like `tags[].name` and `labels[].name` check unrelated inner arrays, while being combined
+    /// as if they were checking the same values. Fields of the element itself are consistent with
+    /// any inner array. Conditions of inner nested filters are checked as separate blocks.
+    /// Nothing is reported if `allow_heterogeneous` is set.
+    pub fn validate_nested_array_keys(&self, allow_heterogeneous: bool) -> Vec<FilterIssue> {
+        let mut issues = vec![];
+        if !allow_heterogeneous {
+            self.collect_array_key_issues("", None, &mut issues);
+        }
+        issues
+    }
+
+    /// `block_array` is the first inner array of the current nested block, with the path of the
+    /// condition it was found in, `None` outside of nested filters
+    fn collect_array_key_issues(
+        &self,
+        path: &str,
+        mut block_array: Option<&mut Option<(String, String)>>,
+        issues: &mut Vec<FilterIssue>,
+    ) {
+        for (clause, conditions) in [
+            ("should", &self.should),
+            ("must", &self.must),
+            ("must_not", &self.must_not),
+        ] {
+            let clause_path = if path.is_empty() {
+                clause.to_string()
+            } else {
+                format!("{path}.{clause}")
+            };
+            for (i, condition) in conditions.iter().flatten().enumerate() {
+                let condition_path = format!("{clause_path}[{i}]");
+                match condition {
+                    Condition::Filter(filter) => filter.collect_array_key_issues(
+                        &condition_path,
+                        block_array.as_deref_mut(),
+                        issues,
+                    ),
+                    Condition::Nested(nested) => nested.filter().collect_array_key_issues(
+                        &format!("{condition_path}.nested.filter"),
+                        Some(&mut None),
+                        issues,
+                    ),
+                    condition => {
+                        let block_array = match block_array.as_deref_mut() {
+                            Some(block_array) => block_array,
+                            None => continue,
+                        };
+                        for array in condition.payload_keys().into_iter().filter_map(inner_array) {
+                            match block_array.as_mut() {
+                                None => {
+                                    *block_array = Some((array.to_string(), condition_path.clone()))
+                                }
+                                Some((first_array, first_path))
+                                    if first_array.as_str() != array =>
+                                {
+                                    issues.push(FilterIssue {
+                                        path: condition_path.clone(),
+                                        message: format!(
+                                            "condition goes through array `{array}`, \
+                                             while `{first_path}` goes through `{first_array}` \
+                                             of the same nested filter"
+                                        ),
+                                    })
+                                }
+                                Some(_) => {}
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn collect_issues(&self, path: &str, in_nested: bool, issues: &mut Vec<FilterIssue>) {
+        for (clause, conditions) in [
+            ("should", &self.should),
+            ("must", &self.must),
+            ("must_not", &self.must_not),
+        ] {
+            let conditions = match conditions {
+                Some(conditions) => conditions,
+                None => continue,
+            };
+            let clause_path = if path.is_empty() {
+                clause.to_string()
+            } else {
+                format!("{path}.{clause}")
+            };
+            // Empty `must` and `must_not` do not restrict anything, while empty `should` matches
+            // nothing, which is unlikely to be intended
+            if clause == "should" && conditions.is_empty() {
+                issues.push(FilterIssue {
+                    path: clause_path.clone(),
+                    message: "empty list of conditions".to_string(),
+                });
+            }
+            for (i, condition) in conditions.iter().enumerate() {
+                condition.collect_issues(&format!("{clause_path}[{i}]"), in_nested, issues);
+            }
+        }
+    }
+}
+
+impl Condition {
+    /// Payload keys read by the condition itself, not including the ones of inner filters
+    fn payload_keys(&self) -> Vec<&str> {
+        match self {
+            Condition::Field(field) => vec![&field.key],
+            Condition::IsEmpty(IsEmptyCondition { is_empty: field })
+            | Condition::IsNull(IsNullCondition { is_null: field })
+            | Condition::HasKey(HasKeyCondition { has_key: field }) => vec![&field.key],
+            Condition::ValueType(ValueTypeCondition { value_type }) => vec![&value_type.key],
+            Condition::Aggregate(AggregateCondition { aggregate }) => vec![&aggregate.key],
+            Condition::Expression(ExpressionCondition { expression }) => {
+                vec![&expression.left, &expression.right]
+            }
+            Condition::Compare(ComparisonCondition { compare }) => {
+                vec![&compare.left, &compare.right]
+            }
+            Condition::HasId(_)
+            | Condition::VectorCount(_)
+            | Condition::VectorNotNormalized(_)
+            | Condition::HasDuplicates(_)
+            | Condition::Position(_)
+            | Condition::Filter(_)
+            | Condition::Nested(_) => vec![],
+        }
+    }
+
+    fn collect_issues(&self, path: &str, in_nested: bool, issues: &mut Vec<FilterIssue>) {
+        let mut issue = |message: String| {
+            issues.push(FilterIssue {
+                path: path.to_string(),
+                message,
+            })
+        };
+        match self {
+            Condition::Field(field) => {
+                // Inside of nested filters empty key refers to the array element itself
+                if field.key.is_empty() && !in_nested {
+                    issue("empty payload key".to_string());
+                }
+                let FieldCondition {
+                    key: _,
+                    r#match,
+                    range,
+                    geo_bounding_box,
+                    geo_radius,
+                    values_count,
+                    null_policy: _,
+                    inner_match: _,
+                } = field;
+                if r#match.is_none()
+                    && range.is_none()
+                    && geo_bounding_box.is_none()
+                    && geo_radius.is_none()
+                    && values_count.is_none()
+                {
+                    issue(
+                        "field condition must specify one of match, range, \
+                         geo_bounding_box, geo_radius or values_count"
+                            .to_string(),
+                    );
+                }
+                if let Some(Match::Regex(MatchRegex { regex })) = r#match {
+                    if compile_regex(regex).is_none() {
+                        issue(format!("regex `{regex}` is invalid or too complex"));
+                    }
+                }
+                if let Some(range) = range {
+                    if let Some(message) = bounds_issue(range.gt, range.gte, range.lt, range.lte) {
+                        issue(format!("range {message}"));
+                    }
+                }
+                if let Some(count) = values_count {
+                    if let Some(message) = bounds_issue(count.gt, count.gte, count.lt, count.lte) {
+                        issue(format!("values_count {message}"));
+                    }
+                }
+            }
+            Condition::IsEmpty(_)
+            | Condition::IsNull(_)
+            | Condition::HasKey(_)
+            | Condition::ValueType(_) => {}
+            Condition::HasId(_) => {
+                if in_nested {
+                    issue("has_id is not supported inside nested".to_string());
+                }
+            }
+            Condition::Position(PositionCondition { position }) => {
+                if !in_nested {
+                    issue("position is only supported inside nested".to_string());
+                }
+                if let PositionRange::Fraction(FractionRange { from, to }) = position {
+                    if !(0.0 <= *from && from <= to && *to <= 1.0) {
+                        issue(format!(
+                            "position fractions must satisfy 0 <= from <= to <= 1, \
+                             got from {from} and to {to}"
+                        ));
+                    }
+                }
+            }
+            Condition::VectorCount(VectorCountCondition {
+                vector_count: count,
+            }) => {
+                if in_nested {
+                    issue("vector_count is not supported inside nested".to_string());
+                }
+                if let Some(message) = bounds_issue(count.gt, count.gte, count.lt, count.lte) {
+                    issue(format!("vector_count {message}"));
+                }
+            }
+            Condition::VectorNotNormalized(VectorNotNormalizedCondition {
+                vector_not_normalized,
+            }) => {
+                if in_nested {
+                    issue("vector_not_normalized is not supported inside nested".to_string());
+                }
+                let tolerance = vector_not_normalized.tolerance;
+                if !tolerance.is_finite() || tolerance < 0.0 {
+                    issue(format!(
+                        "vector_not_normalized tolerance must be non-negative, got {tolerance}"
+                    ));
+                }
+            }
+            Condition::Aggregate(AggregateCondition { aggregate }) => {
+                if in_nested {
+                    issue("aggregate is not supported inside nested".to_string());
+                }
+                let range = &aggregate.range;
+                if let Some(message) = bounds_issue(range.gt, range.gte, range.lt, range.lte) {
+                    issue(format!("aggregate range {message}"));
+                }
+            }
+            Condition::HasDuplicates(_) => {
+                if in_nested {
+                    issue("has_duplicates is not supported inside nested".to_string());
+                }
+            }
+            Condition::Expression(ExpressionCondition { expression }) => {
+                let range = &expression.range;
+                if let Some(message) = bounds_issue(range.gt, range.gte, range.lt, range.lte) {
+                    issue(format!("expression range {message}"));
+                }
+            }
+            Condition::Compare(ComparisonCondition { compare }) => {
+                if compare.left.is_empty() || compare.right.is_empty() {
+                    issue("empty payload key".to_string());
+                }
+            }
+            Condition::Filter(filter) => filter.collect_issues(path, in_nested, issues),
+            Condition::Nested(nested) => {
+                if nested.raw_key().is_empty() {
+                    issue("empty nested key".to_string());
+                } else if let Err(message) = utils::validate_json_path(nested.raw_key()) {
+                    issue(format!(
+                        "invalid nested key `{}`: {message}",
+                        nested.raw_key()
+                    ));
+                }
+                if let Some(marker) = &nested.nested.after {
+                    if let Err(message) = utils::validate_json_path(&marker.key) {
+                        issue(format!("invalid sequence marker key: {message}"));
+                    }
+                }
+                if let NestedQuantifier::MinRatio(min_ratio) = nested.quantifier() {
+                    if !(0.0..=1.0).contains(&min_ratio) {
+                        issue(format!(
+                            "nested min_ratio must be between 0 and 1, got {min_ratio}"
+                        ));
+                    }
+                }
+                nested
+                    .filter()
+                    .collect_issues(&format!("{path}.nested.filter"), true, issues)
+            }
+        }
+    }
+}
+
+/// Array traversed by the payload `key`, e.g. `tags[]` for `tags[].name`
+fn inner_array(key: &str) -> Option<&str> {
+    key.rfind("[]").map(|position| &key[..position + 2])
+}
+
+/// Describe why no value can satisfy the given bounds, if so
+fn bounds_issue<T: PartialOrd + Copy>(
+    gt: Option<T>,
+    gte: Option<T>,
+    lt: Option<T>,
+    lte: Option<T>,
+) -> Option<&'static str> {
+    let bounds = [gt, gte, lt, lte];
+    if bounds
+        .iter()
+        .flatten()
+        .any(|bound| bound.partial_cmp(bound).is_none())
+    {
+        return Some("bound is not a number");
+    }
+    if bounds.iter().all(Option::is_none) {
+        return Some("must specify at least one bound");
+    }
+    let lower = [(gt, true), (gte, false)];
+    let upper = [(lt, true), (lte, false)];
+    for (lower, lower_strict) in lower.into_iter().filter_map(|(b, s)| Some((b?, s))) {
+        for (upper, upper_strict) in upper.into_iter().filter_map(|(b, s)| Some((b?, s))) {
+            let empty = if lower_strict || upper_strict {
+                lower >= upper
+            } else {
+                lower > upper
+            };
+            if empty {
+                return Some("can not contain any value");
+            }
+        }
+    }
+    None
+}
+
 #[cfg(test)]
 mod tests {
     use serde::de::DeserializeOwned;
@@ -1800,6 +3015,40 @@ mod tests {
         assert_eq!(c.is_null.key.as_str(), "Jason");
     }
 
+    #[test]
+    fn test_values_count_near_usize_limits() {
+        // Use the count directly to mock arrays of enormous size
+        let huge = usize::MAX;
+
+        let at_least_near_max = ValuesCount {
+            lt: None,
+            gt: None,
+            gte: Some(usize::MAX - 1),
+            lte: None,
+        };
+        assert!(at_least_near_max.check_count_value(huge));
+        assert!(at_least_near_max.check_count_value(huge - 1));
+        assert!(!at_least_near_max.check_count_value(huge - 2));
+
+        let less_than_max = ValuesCount {
+            lt: Some(usize::MAX),
+            gt: None,
+            gte: None,
+            lte: None,
+        };
+        assert!(!less_than_max.check_count_value(huge));
+        assert!(less_than_max.check_count_value(huge - 1));
+
+        let more_than_max = ValuesCount {
+            lt: None,
+            gt: Some(usize::MAX),
+            gte: None,
+            lte: None,
+        };
+        assert!(!more_than_max.check_count_value(huge));
+        assert!(!more_than_max.check_count_value(0));
+    }
+
     #[test]
     fn test_parse_nested_filter_query() {
         let query = r#"
@@ -1836,6 +3085,7 @@ mod tests {
             Some(Condition::Nested(nested_condition)) => {
                 assert_eq!(nested_condition.raw_key(), "country.cities");
                 assert_eq!(nested_condition.array_key(), "country.cities[]");
+                assert!(nested_condition.nested.index.is_none());
                 let nested_musts = nested_condition.filter().must.as_ref().unwrap();
                 assert_eq!(nested_musts.len(), 2);
                 let first_must = nested_musts.get(0).unwrap();
@@ -2111,6 +3361,294 @@ mod tests {
         assert!(merged.must.as_ref().unwrap().contains(&condition2));
         assert!(merged.should.as_ref().unwrap().contains(&condition1));
     }
+
+    #[test]
+    fn test_filter_validate_deep() {
+        let filter: Filter = serde_json::from_value(json!({
+            "must": [
+                { "key": "price", "range": { "gt": 10.0, "lt": 5.0 } },
+                { "key": "city" },
+                { "key": "sku", "match": { "regex": "(AB" } },
+                {
+                    "nested": {
+                        "key": "orders",
+                        "filter": {
+                            "must": [
+                                { "has_id": [1, 2] },
+                                { "key": "count", "values_count": { "gte": 2, "lte": 1 } },
+                                { "key": "value", "range": { "gte": 1.0, "lte": 1.0 } }
+                            ],
+                            "should": []
+                        }
+                    }
+                }
+            ],
+            "must_not": [
+                { "has_id": [3] },
+                { "key": "city", "match": { "value": "Berlin" } }
+            ]
+        }))
+        .unwrap();
+
+        let issues = filter.validate_deep();
+        let issues: Vec<_> = issues.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            issues,
+            vec![
+                "must[0]: range can not contain any value".to_string(),
+                "must[1]: field condition must specify one of match, range, \
+                 geo_bounding_box, geo_radius or values_count"
+                    .to_string(),
+                "must[2]: regex `(AB` is invalid or too complex".to_string(),
+                "must[3].nested.filter.should: empty list of conditions".to_string(),
+                "must[3].nested.filter.must[0]: has_id is not supported inside nested".to_string(),
+                "must[3].nested.filter.must[1]: values_count can not contain any value".to_string(),
+            ]
+        );
+
+        // Request validation reports the same issues, by the top level clause
+        let errors = filter.validate().unwrap_err();
+        assert_eq!(errors.field_errors()["must"].len(), issues.len());
+        assert!(!errors.field_errors().contains_key("must_not"));
+
+        // Empty `must` and `must_not` do not restrict anything, but are fine
+        let no_op = Filter {
+            should: None,
+            must: Some(vec![]),
+            must_not: Some(vec![]),
+        };
+        assert!(no_op.validate_deep().is_empty());
+        assert!(no_op.validate().is_ok());
+
+        // has_id outside of nested and a point range are fine
+        let valid = Filter::new_must(Condition::Field(FieldCondition::new_range(
+            "price",
+            Range {
+                lt: None,
+                gt: None,
+                gte: Some(1.0),
+                lte: Some(1.0),
+            },
+        )));
+        assert!(valid.validate_deep().is_empty());
+
+        // Empty key refers to the element itself inside of nested only
+        let element = Condition::Field(FieldCondition::new_match("", 5i64.into()));
+        let issues = Filter::new_must(element.clone()).validate_deep();
+        assert_eq!(issues.len(), 1);
+        let nested = Filter::new_must(Condition::new_nested("matrix", Filter::new_must(element)));
+        assert!(nested.validate_deep().is_empty());
+    }
+
+    #[test]
+    fn test_validate_nested_key() {
+        let nested = |key: &str| {
+            Filter::new_must(Condition::new_nested(
+                key,
+                Filter::new_must(Condition::Field(FieldCondition::new_match(
+                    "color",
+                    "red".to_string().into(),
+                ))),
+            ))
+        };
+        let issues = |key: &str| -> Vec<String> {
+            nested(key)
+                .validate_deep()
+                .iter()
+                .map(ToString::to_string)
+                .collect()
+        };
+
+        assert_eq!(issues(""), vec!["must[0]: empty nested key".to_string()]);
+        assert_eq!(
+            issues("reviews[x]"),
+            vec![
+                "must[0]: invalid nested key `reviews[x]`: array index `x` is not an integer"
+                    .to_string()
+            ]
+        );
+        for key in [
+            "a..b",
+            ".reviews",
+            "reviews.",
+            "[]",
+            "reviews[0",
+            "reviews[0]x",
+            "my key",
+        ] {
+            assert_eq!(issues(key).len(), 1, "key `{key}` should be rejected");
+        }
+
+        for key in [
+            "reviews",
+            "reviews[]",
+            "country.cities[].sightseeing",
+            "a?.b",
+            "b[-1]",
+        ] {
+            assert!(issues(key).is_empty(), "key `{key}` should be accepted");
+        }
+    }
+
+    #[test]
+    fn test_validate_nested_array_keys() {
+        let filter: Filter = serde_json::from_value(json!({
+            "must": [
+                { "key": "tags[].name", "match": { "value": "top-level" } },
+                { "key": "labels[].name", "match": { "value": "top-level" } },
+                {
+                    "nested": {
+                        "key": "products",
+                        "filter": {
+                            "must": [
+                                { "key": "color", "match": { "value": "red" } },
+                                { "key": "tags[].name", "match": { "value": "sale" } }
+                            ],
+                            "should": [
+                                { "key": "tags[].weight", "range": { "gt": 1.0 } },
+                                {
+                                    "must": [
+                                        { "key": "labels[].name", "match": { "value": "new" } }
+                                    ]
+                                }
+                            ],
+                            "must_not": [
+                                { "is_empty": { "key": "variants[].size" } },
+                                {
+                                    "nested": {
+                                        "key": "variants",
+                                        "filter": {
+                                            "must": [
+                                                { "key": "sizes[].eu", "range": { "gt": 40 } }
+                                            ]
+                                        }
+                                    }
+                                }
+                            ]
+                        }
+                    }
+                }
+            ]
+        }))
+        .unwrap();
+        assert!(filter.validate_deep().is_empty());
+
+        let issues: Vec<_> = filter
+            .validate_nested_array_keys(false)
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            issues,
+            vec![
+                "must[2].nested.filter.should[1].must[0]: condition goes through array \
+                 `labels[]`, while `must[2].nested.filter.should[0]` goes through `tags[]` \
+                 of the same nested filter"
+                    .to_string(),
+                "must[2].nested.filter.must_not[0]: condition goes through array \
+                 `variants[]`, while `must[2].nested.filter.should[0]` goes through `tags[]` \
+                 of the same nested filter"
+                    .to_string(),
+            ]
+        );
+
+        // Heterogeneous keys are allowed explicitly
+        assert!(filter.validate_nested_array_keys(true).is_empty());
+
+        // Both operands of a condition are checked
+        let compare = Filter::new_must(Condition::new_nested(
+            "products",
+            Filter::new_must(Condition::Compare(ComparisonCondition::from(Comparison {
+                left: "prices[].sale".to_string(),
+                operator: ComparisonOperator::Lt,
+                right: "offers[].price".to_string(),
+                collation: None,
+            }))),
+        ));
+        assert_eq!(compare.validate_nested_array_keys(false).len(), 1);
+    }
+
+    #[test]
+    fn test_canonicalize_filter() {
+        let filter_str = r#"
+        {
+            "must": [
+                { "key": "color", "match": { "any": ["red", "blue", "red"] } },
+                { "has_id": [1, 2, 3] },
+                {
+                    "nested": {
+                        "key": "country.cities",
+                        "filter": {
+                            "must": [
+                                { "key": "population", "range": { "gte": 8.0 } },
+                                { "key": "sightseeing", "values_count": { "lt": 3 } }
+                            ]
+                        }
+                    }
+                }
+            ],
+            "must_not": []
+        }
+        "#;
+        let reordered_str = r#"
+        {
+            "must": [
+                {
+                    "nested": {
+                        "key": "country.cities[]",
+                        "filter": {
+                            "must": [
+                                { "key": "sightseeing", "values_count": { "lt": 3 } },
+                                { "key": "population", "range": { "gte": 8.0 } }
+                            ]
+                        }
+                    }
+                },
+                { "has_id": [3, 1, 2] },
+                { "key": "color", "match": { "any": ["blue", "red"] } }
+            ]
+        }
+        "#;
+        let filter: Filter = serde_json::from_str(filter_str).unwrap();
+        let reordered: Filter = serde_json::from_str(reordered_str).unwrap();
+        assert_ne!(filter, reordered);
+        assert_eq!(filter.canonicalize(), reordered.canonicalize());
+        assert_eq!(filter.canonicalize(), filter.canonicalize().canonicalize());
+
+        // Moving a condition into another clause changes the meaning
+        let mut different = filter.clone();
+        let condition = different.must.as_mut().unwrap().pop().unwrap();
+        different.must_not = Some(vec![condition]);
+        assert_ne!(filter.canonicalize(), different.canonicalize());
+
+        // Empty `should` matches nothing and is not dropped
+        let empty_should = Filter {
+            should: Some(vec![]),
+            must: None,
+            must_not: None,
+        };
+        assert_ne!(empty_should.canonicalize(), Filter::default());
+
+        // Nested conditions differing only by the quantifier are not deduplicated
+        let quantified = |quantifier: &str| {
+            serde_json::from_str::<Condition>(&format!(
+                r#"{{
+                    "nested": {{
+                        "key": "country.cities",
+                        "filter": {{ "must": [{{ "key": "population", "range": {{ "gte": 8.0 }} }}] }},
+                        "quantifier": "{quantifier}"
+                    }}
+                }}"#
+            ))
+            .unwrap()
+        };
+        let any_and_all = Filter {
+            should: None,
+            must: Some(vec![quantified("all"), quantified("any")]),
+            must_not: None,
+        };
+        assert_eq!(any_and_all.canonicalize().must.unwrap().len(), 2);
+    }
 }
 
 pub type TheMap<K, V> = BTreeMap<K, V>;
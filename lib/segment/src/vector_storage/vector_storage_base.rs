@@ -1,8 +1,11 @@
 use std::cmp::Ordering;
+use std::collections::HashMap;
 use std::ops::Range;
 use std::path::{Path, PathBuf};
 use std::sync::atomic::AtomicBool;
+use std::sync::Arc;
 
+use atomic_refcell::AtomicRefCell;
 use bitvec::prelude::BitSlice;
 use ordered_float::OrderedFloat;
 
@@ -35,6 +38,9 @@ impl PartialOrd for ScoredPointOffset {
     }
 }
 
+/// Vector storages of a segment by vector name
+pub type NamedVectorStorages = HashMap<String, Arc<AtomicRefCell<VectorStorageEnum>>>;
+
 /// Trait for vector storage
 /// El - type of vector element, expected numerical type
 /// Storage operates with internal IDs (`PointOffsetType`), which always starts with zero and have no skips
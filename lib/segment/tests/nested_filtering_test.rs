@@ -1,17 +1,23 @@
 #[cfg(test)]
 mod tests {
-    use std::collections::HashMap;
-    use std::sync::Arc;
+    use std::collections::{HashMap, HashSet};
+    use std::path::Path;
+    use std::sync::atomic::{AtomicBool, Ordering};
+    use std::sync::{Arc, Mutex};
 
     use atomic_refcell::AtomicRefCell;
+    use segment::entry::entry_point::OperationError;
     use segment::fixtures::payload_context_fixture::FixtureIdTracker;
     use segment::index::struct_payload_index::StructPayloadIndex;
     use segment::index::PayloadIndex;
     use segment::payload_storage::in_memory_payload_storage::InMemoryPayloadStorage;
-    use segment::payload_storage::PayloadStorage;
+    use segment::payload_storage::query_checker::SimpleConditionChecker;
+    use segment::payload_storage::{ConditionChecker, PayloadStorage};
     use segment::types::{
-        Condition, FieldCondition, Filter, Match, Payload, PayloadSchemaType, PointOffsetType,
+        Condition, FieldCondition, Filter, HasIdCondition, Match, Payload, PayloadSchemaType,
+        PointOffsetType,
     };
+    use segment::vector_storage::NamedVectorStorages;
     use serde_json::json;
     use tempfile::Builder;
 
@@ -73,8 +79,13 @@ mod tests {
         let wrapped_payload_storage = Arc::new(AtomicRefCell::new(payload_storage.into()));
         let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(NUM_POINTS)));
 
-        let mut index =
-            StructPayloadIndex::open(wrapped_payload_storage, id_tracker, dir.path()).unwrap();
+        let mut index = StructPayloadIndex::open(
+            wrapped_payload_storage,
+            id_tracker,
+            Default::default(),
+            dir.path(),
+        )
+        .unwrap();
 
         index
             .set_indexed("f", PayloadSchemaType::Integer.into())
@@ -203,4 +214,334 @@ mod tests {
         assert_eq!(res3, check_res3);
         assert!(!res3.is_empty());
     }
+
+    /// Builds an index over `nested_payloads` with the `arr1` nested fields indexed
+    fn build_nested_index(path: &std::path::Path) -> StructPayloadIndex {
+        let mut payload_storage = InMemoryPayloadStorage::default();
+
+        for (idx, payload) in nested_payloads().into_iter().enumerate() {
+            payload_storage
+                .assign(idx as PointOffsetType, &payload)
+                .unwrap();
+        }
+
+        let wrapped_payload_storage = Arc::new(AtomicRefCell::new(payload_storage.into()));
+        let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(NUM_POINTS)));
+
+        let mut index = StructPayloadIndex::open(
+            wrapped_payload_storage,
+            id_tracker,
+            Default::default(),
+            path,
+        )
+        .unwrap();
+
+        for field in ["arr1[].a", "arr1[].b", "arr1[].c", "arr1[].d"] {
+            index
+                .set_indexed(field, PayloadSchemaType::Integer.into())
+                .unwrap();
+        }
+
+        index
+    }
+
+    #[test]
+    fn test_nested_disjunction_of_groups() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let index = build_nested_index(dir.path());
+
+        // Same element must satisfy (a = 1 AND b = 1) OR (a = 2 AND b = 5)
+        // E.g. idx = 10 => arr1[0] = { "a" = 1, "b" = 1 }
+        // E.g. idx = 13 => arr1[1] = { "a" = 2, "b" = 5 }
+        // But not idx = 14 => arr1[0] = { "a" = 1, "b" = 5 }
+        let group = |a: i64, b: i64| {
+            Condition::Filter(Filter {
+                must: Some(vec![
+                    Condition::Field(FieldCondition::new_match("a", a.into())),
+                    Condition::Field(FieldCondition::new_match("b", b.into())),
+                ]),
+                should: None,
+                must_not: None,
+            })
+        };
+
+        let nested_filter = Filter::new_must(Condition::new_nested(
+            "arr1",
+            Filter {
+                must: None,
+                should: Some(vec![group(1, 1), group(2, 5)]),
+                must_not: None,
+            },
+        ));
+
+        let mut res: Vec<_> = index.query_points(&nested_filter).collect();
+        // Primary clauses of the `should` groups may come in any order
+        res.sort_unstable();
+
+        let filter_context = index.filter_context(&nested_filter);
+        let check_res: Vec<_> = (0..NUM_POINTS as PointOffsetType)
+            .filter(|point_id| filter_context.check(*point_id))
+            .collect();
+        assert_eq!(res, check_res);
+
+        let expected: Vec<_> = (0..NUM_POINTS as PointOffsetType)
+            .filter(|point_id| point_id % 10 == 0 || point_id % 10 == 3)
+            .collect();
+        assert_eq!(res, expected);
+        assert!(!res.contains(&14));
+    }
+
+    /// Index over `payloads` with `arr1[].a` field indexed
+    fn build_index_with_payloads(path: &Path, payloads: &[Payload]) -> StructPayloadIndex {
+        let mut payload_storage = InMemoryPayloadStorage::default();
+        for (idx, payload) in payloads.iter().enumerate() {
+            payload_storage
+                .assign(idx as PointOffsetType, payload)
+                .unwrap();
+        }
+        let wrapped_payload_storage = Arc::new(AtomicRefCell::new(payload_storage.into()));
+        let id_tracker = Arc::new(AtomicRefCell::new(FixtureIdTracker::new(payloads.len())));
+        let mut index = StructPayloadIndex::open(
+            wrapped_payload_storage,
+            id_tracker,
+            Default::default(),
+            path,
+        )
+        .unwrap();
+        index
+            .set_indexed("arr1[].a", PayloadSchemaType::Integer.into())
+            .unwrap();
+        index
+    }
+
+    #[test]
+    fn test_nested_must_not_on_missing_array() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+        let payloads: Vec<Payload> = vec![
+            json!({ "arr1": [{ "a": 1 }] }).into(),
+            json!({ "arr1": [{ "a": 2 }] }).into(),
+            json!({ "f": 1 }).into(),
+            json!({ "arr1": [] }).into(),
+        ];
+        let index = build_index_with_payloads(dir.path(), &payloads);
+
+        // Points without elements in `arr1` have nothing matching the forbidden condition
+        let nested_filter = Filter::new_must(Condition::new_nested(
+            "arr1",
+            Filter::new_must_not(Condition::Field(FieldCondition::new_match("a", 1.into()))),
+        ));
+
+        let res: Vec<_> = index.query_points(&nested_filter).collect();
+        let filter_context = index.filter_context(&nested_filter);
+        let check_res: Vec<_> = (0..payloads.len() as PointOffsetType)
+            .filter(|point_id| filter_context.check(*point_id))
+            .collect();
+        assert_eq!(res, check_res);
+        assert_eq!(res, vec![1, 2, 3]);
+    }
+
+    #[test]
+    fn test_empty_should() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+        let payloads: Vec<Payload> = vec![
+            json!({ "arr1": [{ "a": 1 }] }).into(),
+            json!({ "arr1": [{ "a": 2 }, { "a": 1 }] }).into(),
+            json!({ "f": 1 }).into(),
+            json!({ "arr1": [] }).into(),
+        ];
+        let index = build_index_with_payloads(dir.path(), &payloads);
+
+        let mut payload_storage = InMemoryPayloadStorage::default();
+        for (idx, payload) in payloads.iter().enumerate() {
+            payload_storage
+                .assign(idx as PointOffsetType, payload)
+                .unwrap();
+        }
+        let unindexed_checker = SimpleConditionChecker::new(
+            Arc::new(AtomicRefCell::new(payload_storage.into())),
+            Arc::new(AtomicRefCell::new(FixtureIdTracker::new(payloads.len()))),
+            NamedVectorStorages::default(),
+        );
+
+        let check = |filter: &Filter| {
+            let res: Vec<_> = index.query_points(filter).collect();
+            let unindexed_res: Vec<_> = (0..payloads.len() as PointOffsetType)
+                .filter(|point_id| unindexed_checker.check(*point_id, filter))
+                .collect();
+            assert_eq!(res, unindexed_res, "{filter:?}");
+            res
+        };
+        let a_is_1 = || Condition::Field(FieldCondition::new_match("a", 1.into()));
+        let with_empty_should = |must| Filter {
+            should: Some(vec![]),
+            must,
+            must_not: None,
+        };
+
+        // Empty clause has no condition to satisfy, so nothing matches it
+        assert!(check(&with_empty_should(None)).is_empty());
+        assert!(check(&with_empty_should(Some(vec![Condition::new_nested(
+            "arr1",
+            Filter::new_must(a_is_1()),
+        )])))
+        .is_empty());
+        assert!(check(&Filter::new_must(Condition::new_nested(
+            "arr1",
+            with_empty_should(None),
+        )))
+        .is_empty());
+        assert!(check(&Filter::new_must(Condition::new_nested(
+            "arr1",
+            with_empty_should(Some(vec![a_is_1()])),
+        )))
+        .is_empty());
+
+        // Non-empty clause is applied the same way on both paths
+        let nested_should =
+            Filter::new_must(Condition::new_nested("arr1", Filter::new_should(a_is_1())));
+        assert_eq!(check(&nested_should), vec![0, 1]);
+    }
+
+    #[test]
+    fn test_top_level_must_not_nested() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+
+        let payloads: Vec<Payload> = vec![
+            json!({ "arr1": [{ "a": 1 }] }).into(),
+            json!({ "arr1": [{ "a": 2 }] }).into(),
+            json!({ "f": 1 }).into(),
+            json!({ "arr1": [] }).into(),
+            json!({ "arr1": [{ "a": 2 }, { "a": 1 }] }).into(),
+            json!({ "arr1": [{ "a": [2, 1] }] }).into(),
+        ];
+        let index = build_index_with_payloads(dir.path(), &payloads);
+        let a_is_1 = || Condition::Field(FieldCondition::new_match("a", 1.into()));
+
+        let check = |filter: &Filter| {
+            let res: Vec<_> = index.query_points(filter).collect();
+            let filter_context = index.filter_context(filter);
+            let check_res: Vec<_> = (0..payloads.len() as PointOffsetType)
+                .filter(|point_id| filter_context.check(*point_id))
+                .collect();
+            assert_eq!(res, check_res);
+            res
+        };
+
+        // No element satisfies the condition, points without elements included
+        let no_element_matches =
+            Filter::new_must_not(Condition::new_nested("arr1", Filter::new_must(a_is_1())));
+        assert_eq!(check(&no_element_matches), vec![1, 2, 3]);
+
+        // Negations are not collapsed: there are elements, and all of them satisfy the condition
+        let all_elements_match = Filter::new_must_not(Condition::new_nested(
+            "arr1",
+            Filter::new_must_not(a_is_1()),
+        ));
+        assert_eq!(check(&all_elements_match), vec![0, 5]);
+    }
+
+    #[test]
+    fn test_query_points_cancellable() {
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let payloads: Vec<Payload> = (0..10)
+            .map(|i| json!({ "arr1": [{ "a": i % 2 }, { "a": 2 }] }).into())
+            .collect();
+        let index = build_index_with_payloads(dir.path(), &payloads);
+        let nested_filter = Filter::new_must(Condition::new_nested(
+            "arr1",
+            Filter::new_must(Condition::Field(FieldCondition::new_match("a", 1.into()))),
+        ));
+
+        // Not cancelled - same points as the regular query
+        let stopped = AtomicBool::new(false);
+        let mut expected: Vec<_> = index.query_points(&nested_filter).collect();
+        assert_eq!(
+            index
+                .query_points_cancellable(&nested_filter, &stopped)
+                .unwrap(),
+            expected
+        );
+        expected.sort_unstable();
+        assert_eq!(expected, vec![1, 3, 5, 7, 9]);
+
+        // Cancelled query reports an error instead of the incomplete result
+        stopped.store(true, Ordering::Relaxed);
+        let result = index.query_points_cancellable(&nested_filter, &stopped);
+        assert!(matches!(result, Err(OperationError::Cancelled { .. })));
+    }
+
+    /// Warning messages logged by the tests
+    struct WarningsCapture {
+        messages: Mutex<Vec<String>>,
+    }
+
+    impl log::Log for WarningsCapture {
+        fn enabled(&self, metadata: &log::Metadata) -> bool {
+            metadata.level() <= log::Level::Warn
+        }
+
+        fn log(&self, record: &log::Record) {
+            if self.enabled(record.metadata()) {
+                self.messages
+                    .lock()
+                    .unwrap()
+                    .push(record.args().to_string());
+            }
+        }
+
+        fn flush(&self) {}
+    }
+
+    static WARNINGS: WarningsCapture = WarningsCapture {
+        messages: Mutex::new(Vec::new()),
+    };
+
+    #[test]
+    fn test_warn_dropped_nested_conditions() {
+        log::set_logger(&WARNINGS).unwrap();
+        log::set_max_level(log::LevelFilter::Warn);
+        let has_id_warnings = || {
+            WARNINGS
+                .messages
+                .lock()
+                .unwrap()
+                .iter()
+                .filter(|message| message.contains("has_id"))
+                .cloned()
+                .collect::<Vec<_>>()
+        };
+
+        let dir = Builder::new().prefix("storage_dir").tempdir().unwrap();
+        let payloads: Vec<Payload> = (0..10)
+            .map(|i| json!({ "arr1": [{ "a": i }, { "a": i + 1 }] }).into())
+            .collect();
+        let index = build_index_with_payloads(dir.path(), &payloads);
+
+        // Supported conditions are not reported
+        let supported = Filter::new_must(Condition::new_nested(
+            "arr1",
+            Filter::new_must(Condition::Field(FieldCondition::new_match("a", 1.into()))),
+        ));
+        assert_eq!(index.query_points(&supported).count(), 2);
+        assert!(has_id_warnings().is_empty());
+
+        // Dropped condition is reported once for the whole query, not for each point
+        let with_has_id = Filter::new_must(Condition::new_nested(
+            "arr1",
+            Filter {
+                should: None,
+                must: Some(vec![
+                    Condition::Field(FieldCondition::new_match("a", 1.into())),
+                    Condition::HasId(HasIdCondition::from(HashSet::from([1.into()]))),
+                ]),
+                must_not: None,
+            },
+        ));
+        let _ = index.query_points(&with_has_id).count();
+        let warnings = has_id_warnings();
+        assert_eq!(warnings.len(), 1, "{warnings:?}");
+        assert!(warnings[0].contains("`arr1`"));
+    }
 }
@@ -9,7 +9,10 @@ mod tests {
     use segment::data_types::vectors::DEFAULT_VECTOR_NAME;
     use segment::entry::entry_point::{OperationError, SegmentEntry};
     use segment::segment_constructor::load_segment;
-    use segment::types::{Condition, Filter, WithPayload};
+    use segment::types::{
+        Condition, Filter, ValuesCount, VectorCountCondition, VectorNotNormalizedCondition,
+        WithPayload,
+    };
     use tempfile::Builder;
 
     use crate::fixtures::segment::{build_segment_1, build_segment_3};
@@ -209,4 +212,104 @@ mod tests {
         let best_match = res.get(0).expect("Non-empty result");
         assert_eq!(best_match.id, 3.into());
     }
+
+    #[test]
+    fn test_vector_count_condition() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let mut segment = build_segment_3(dir.path());
+
+        // point 1 keeps 1 of 3 vectors, point 2 keeps 2 of 3, others keep all of them
+        assert!(segment.delete_vector(6, 1.into(), "vector2").unwrap());
+        assert!(segment.delete_vector(7, 1.into(), "vector3").unwrap());
+        assert!(segment.delete_vector(8, 2.into(), "vector3").unwrap());
+
+        let vector_count_filter = |vector_count: ValuesCount| {
+            Filter::new_must(Condition::VectorCount(VectorCountCondition::from(
+                vector_count,
+            )))
+        };
+        let read_ids = |filter: &Filter| {
+            let mut ids = segment.read_filtered(None, None, Some(filter));
+            ids.sort();
+            ids
+        };
+
+        let exactly_one = vector_count_filter(ValuesCount {
+            lt: None,
+            gt: None,
+            gte: Some(1),
+            lte: Some(1),
+        });
+        assert_eq!(read_ids(&exactly_one), vec![1.into()]);
+
+        let exactly_two = vector_count_filter(ValuesCount {
+            lt: None,
+            gt: None,
+            gte: Some(2),
+            lte: Some(2),
+        });
+        assert_eq!(read_ids(&exactly_two), vec![2.into()]);
+
+        let complete = vector_count_filter(ValuesCount {
+            lt: None,
+            gt: None,
+            gte: Some(3),
+            lte: None,
+        });
+        assert_eq!(read_ids(&complete), vec![3.into(), 4.into(), 5.into()]);
+
+        let incomplete = vector_count_filter(ValuesCount {
+            lt: Some(3),
+            gt: None,
+            gte: None,
+            lte: None,
+        });
+        assert_eq!(read_ids(&incomplete), vec![1.into(), 2.into()]);
+    }
+
+    #[test]
+    fn test_vector_not_normalized_condition() {
+        let dir = Builder::new().prefix("segment_dir").tempdir().unwrap();
+
+        let mut segment = build_segment_1(dir.path());
+
+        let not_normalized_filter = |name: &str, tolerance: f32| {
+            Filter::new_must(Condition::VectorNotNormalized(
+                VectorNotNormalizedCondition::new(name, tolerance),
+            ))
+        };
+        let read_ids = |filter: &Filter| {
+            let mut ids = segment.read_filtered(None, None, Some(filter));
+            ids.sort();
+            ids
+        };
+
+        // Norms of the vectors are: sqrt(3), sqrt(2), 2, sqrt(3) and 1
+        let strict = not_normalized_filter(DEFAULT_VECTOR_NAME, 0.01);
+        assert_eq!(
+            read_ids(&strict),
+            vec![1.into(), 2.into(), 3.into(), 4.into()]
+        );
+
+        let tolerant = not_normalized_filter(DEFAULT_VECTOR_NAME, 0.5);
+        assert_eq!(read_ids(&tolerant), vec![1.into(), 3.into(), 4.into()]);
+
+        let very_tolerant = not_normalized_filter(DEFAULT_VECTOR_NAME, 0.8);
+        assert_eq!(read_ids(&very_tolerant), vec![3.into()]);
+
+        let unknown_vector = not_normalized_filter("unknown", 0.01);
+        assert!(read_ids(&unknown_vector).is_empty());
+
+        // Points without the vector do not match
+        assert!(segment
+            .delete_vector(7, 1.into(), DEFAULT_VECTOR_NAME)
+            .unwrap());
+        let read_ids = |filter: &Filter| {
+            let mut ids = segment.read_filtered(None, None, Some(filter));
+            ids.sort();
+            ids
+        };
+        assert_eq!(read_ids(&strict), vec![2.into(), 3.into(), 4.into()]);
+    }
 }
@@ -278,6 +278,16 @@ pub struct SetShardReplicaState {
     pub from_state: Option<ReplicaState>,
 }
 
+/// Sets the shards of the ring routing points of the collection
+///
+/// Points are expected to be moved to their shards in the new ring already, see
+/// `Collection::reshard_points`.
+#[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
+pub struct SetRingShards {
+    pub collection_name: String,
+    pub shards: Vec<ShardId>,
+}
+
 /// Enumeration of all possible collection update operations
 #[derive(Debug, Deserialize, Serialize, PartialEq, Eq, Hash, Clone)]
 #[serde(rename_all = "snake_case")]
@@ -288,6 +298,7 @@ pub enum CollectionMetaOperations {
     ChangeAliases(ChangeAliasesOperation),
     TransferShard(CollectionId, ShardTransferOperations),
     SetShardReplicaState(SetShardReplicaState),
+    SetRingShards(SetRingShards),
     Nop { token: usize }, // Empty operation
 }
 
@@ -86,6 +86,9 @@ impl StorageError {
                 description: overriding_description,
                 backtrace: None,
             },
+            CollectionError::UnknownVectorName(_) => StorageError::BadInput {
+                description: overriding_description,
+            },
         }
     }
 }
@@ -124,6 +127,9 @@ impl From<CollectionError> for StorageError {
                 description: format!("{err}"),
                 backtrace: None,
             },
+            CollectionError::UnknownVectorName(_) => StorageError::BadInput {
+                description: format!("{err}"),
+            },
         }
     }
 }
@@ -26,8 +26,8 @@ pub mod consensus_ops {
     use serde::{Deserialize, Serialize};
 
     use crate::content_manager::collection_meta_ops::{
-        CollectionMetaOperations, SetShardReplicaState, ShardTransferOperations, UpdateCollection,
-        UpdateCollectionOperation,
+        CollectionMetaOperations, SetRingShards, SetShardReplicaState, ShardTransferOperations,
+        UpdateCollection, UpdateCollectionOperation,
     };
 
     /// Operation that should pass consensus
@@ -145,6 +145,16 @@ pub mod consensus_ops {
             )))
         }
 
+        pub fn set_ring_shards(collection_name: CollectionId, shards: Vec<u32>) -> Self {
+            ConsensusOperations::CollectionMeta(
+                CollectionMetaOperations::SetRingShards(SetRingShards {
+                    collection_name,
+                    shards,
+                })
+                .into(),
+            )
+        }
+
         pub fn request_snapshot() -> Self {
             Self::RequestSnapshot
         }
@@ -27,6 +27,7 @@ use collection::recommendations::{recommend_batch_by, recommend_by};
 use collection::shards::channel_service::ChannelService;
 use collection::shards::collection_shard_distribution::CollectionShardDistribution;
 use collection::shards::replica_set::ReplicaState;
+use collection::shards::resharding::VectorTransferPlan;
 use collection::shards::shard::{PeerId, ShardId};
 use collection::shards::transfer::shard_transfer::{
     validate_transfer, validate_transfer_exists, ShardTransfer,
@@ -40,7 +41,7 @@ use tokio::sync::{RwLock, RwLockReadGuard, Semaphore};
 use uuid::Uuid;
 
 use super::collection_meta_ops::{
-    CreateCollectionOperation, SetShardReplicaState, ShardTransferOperations,
+    CreateCollectionOperation, SetRingShards, SetShardReplicaState, ShardTransferOperations,
     UpdateCollectionOperation,
 };
 use super::{consensus_manager, CollectionContainer};
@@ -863,6 +864,10 @@ impl TableOfContent {
                 log::debug!("Set shard replica state {:?}", operation);
                 self.set_shard_replica_state(operation).await.map(|()| true)
             }
+            CollectionMetaOperations::SetRingShards(operation) => {
+                log::debug!("Set ring shards {:?}", operation);
+                self.set_ring_shards(operation).await.map(|()| true)
+            }
             CollectionMetaOperations::Nop { .. } => Ok(true),
         }
     }
@@ -883,6 +888,29 @@ impl TableOfContent {
         Ok(())
     }
 
+    pub async fn set_ring_shards(&self, operation: SetRingShards) -> Result<(), StorageError> {
+        self.get_collection(&operation.collection_name)
+            .await?
+            .set_ring_shards(operation.shards)
+            .await?;
+        Ok(())
+    }
+
+    /// Move points of the collection to the ring of `shards`, then let other peers route with it
+    pub async fn reshard_collection(
+        &self,
+        collection_name: &str,
+        shards: Vec<ShardId>,
+    ) -> Result<VectorTransferPlan, StorageError> {
+        let collection = self.get_collection(collection_name).await?;
+        let plan = collection.reshard_points(shards.clone()).await?;
+        if let Some(proposal_sender) = &self.consensus_proposal_sender {
+            let operation = ConsensusOperations::set_ring_shards(collection.name(), shards);
+            proposal_sender.send(operation)?;
+        }
+        Ok(plan)
+    }
+
     /// Cancels all transfers where the source peer is the current peer.
     pub async fn cancel_outgoing_all_transfers(&self, reason: &str) -> Result<(), StorageError> {
         let collections = self.collections.read().await;
@@ -921,6 +949,7 @@ impl TableOfContent {
                     config: _,
                     shards,
                     transfers,
+                    ring_shards: _,
                 } = collection.state().await;
                 let all_peers: HashSet<_> = self
                     .channel_service
@@ -1277,6 +1306,28 @@ impl TableOfContent {
         shard_selection: Option<ShardId>,
         wait: bool,
         ordering: WriteOrdering,
+    ) -> Result<UpdateResult, StorageError> {
+        self.update_with_target(
+            collection_name,
+            operation,
+            shard_selection,
+            None,
+            wait,
+            ordering,
+        )
+        .await
+    }
+
+    /// Same as `update`, but an update from a client is applied to `target_shard` only, if set,
+    /// regardless of the ring. Updates from peers are addressed to their shard already.
+    pub async fn update_with_target(
+        &self,
+        collection_name: &str,
+        operation: CollectionUpdateOperations,
+        shard_selection: Option<ShardId>,
+        target_shard: Option<ShardId>,
+        wait: bool,
+        ordering: WriteOrdering,
     ) -> Result<UpdateResult, StorageError> {
         let collection = self.get_collection(collection_name).await?;
         let result = match shard_selection {
@@ -1294,7 +1345,7 @@ impl TableOfContent {
                     self.check_write_lock()?;
                 }
                 collection
-                    .update_from_client(operation, wait, ordering)
+                    .update_from_client_with_target(operation, target_shard, wait, ordering)
                     .await
             }
         };
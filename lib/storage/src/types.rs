@@ -61,6 +61,9 @@ pub struct StorageConfig {
     /// Provided value will be used error message for unavailable requests.
     #[serde(default)]
     pub recovery_mode: Option<String>,
+    /// Maximum number of named vectors of a single point in an update, larger updates are rejected
+    #[serde(default)]
+    pub max_named_vectors_per_point: Option<usize>,
 }
 
 impl StorageConfig {
@@ -70,6 +73,7 @@ impl StorageConfig {
             self.node_type,
             self.handle_collection_load_errors,
             self.recovery_mode.clone(),
+            self.max_named_vectors_per_point,
         )
     }
 }
@@ -52,6 +52,7 @@ fn test_alias_operation() {
         update_queue_size: Default::default(),
         handle_collection_load_errors: false,
         recovery_mode: None,
+        max_named_vectors_per_point: None,
     };
 
     let search_runtime = Runtime::new().unwrap();
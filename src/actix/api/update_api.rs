@@ -1,9 +1,13 @@
 use actix_web::rt::time::Instant;
 use actix_web::{delete, post, put, web, Responder};
 use actix_web_validator::{Json, Path, Query};
-use collection::operations::payload_ops::{DeletePayload, SetPayload};
+use collection::operations::payload_ops::{DeletePayload, SetNestedPayload, SetPayload};
 use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
-use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
+use collection::operations::vector_ops::{
+    ConcatVectors, DeleteVectors, ReindexVectors, RepairVectors, SetDefaultVectorByFilter,
+    UpdateVectors,
+};
+use collection::shards::shard::ShardId;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use storage::content_manager::toc::TableOfContent;
@@ -12,9 +16,10 @@ use validator::Validate;
 use super::CollectionPath;
 use crate::actix::helpers::process_response;
 use crate::common::points::{
-    do_clear_payload, do_create_index, do_delete_index, do_delete_payload, do_delete_points,
-    do_delete_vectors, do_overwrite_payload, do_set_payload, do_update_vectors, do_upsert_points,
-    CreateFieldIndex,
+    do_clear_payload, do_concat_vectors, do_create_index, do_delete_index, do_delete_payload,
+    do_delete_points, do_delete_vectors, do_overwrite_payload, do_reindex_vectors,
+    do_repair_vectors, do_set_default_vector, do_set_nested_payload, do_set_payload,
+    do_update_vectors, do_upsert_points, CreateFieldIndex,
 };
 
 #[derive(Deserialize, Validate)]
@@ -30,6 +35,14 @@ pub struct UpdateParam {
     pub ordering: Option<WriteOrdering>,
 }
 
+#[derive(Deserialize, Serialize, JsonSchema, Validate)]
+pub struct VectorUpdateParam {
+    pub wait: Option<bool>,
+    pub ordering: Option<WriteOrdering>,
+    /// Apply the whole operation to this shard, regardless of the ids of the points
+    pub target_shard: Option<ShardId>,
+}
+
 #[put("/collections/{name}/points")]
 async fn upsert_points(
     toc: web::Data<TableOfContent>,
@@ -83,7 +96,7 @@ async fn update_vectors(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     operation: Json<UpdateVectors>,
-    params: Query<UpdateParam>,
+    params: Query<VectorUpdateParam>,
 ) -> impl Responder {
     let timing = Instant::now();
     let operation = operation.into_inner();
@@ -95,6 +108,7 @@ async fn update_vectors(
         &collection.name,
         operation,
         None,
+        params.target_shard,
         wait,
         ordering,
     )
@@ -107,7 +121,7 @@ async fn delete_vectors(
     toc: web::Data<TableOfContent>,
     collection: Path<CollectionPath>,
     operation: Json<DeleteVectors>,
-    params: Query<UpdateParam>,
+    params: Query<VectorUpdateParam>,
 ) -> impl Responder {
     let timing = Instant::now();
     let operation = operation.into_inner();
@@ -119,6 +133,107 @@ async fn delete_vectors(
         &collection.name,
         operation,
         None,
+        params.target_shard,
+        wait,
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/vectors/reindex")]
+async fn reindex_vectors(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<ReindexVectors>,
+    params: Query<VectorUpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = do_reindex_vectors(
+        toc.get_ref(),
+        &collection.name,
+        operation,
+        None,
+        params.target_shard,
+        wait,
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/vectors/concat")]
+async fn concat_vectors(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<ConcatVectors>,
+    params: Query<VectorUpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = do_concat_vectors(
+        toc.get_ref(),
+        &collection.name,
+        operation,
+        None,
+        params.target_shard,
+        wait,
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/vectors/repair")]
+async fn repair_vectors(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<RepairVectors>,
+    params: Query<VectorUpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = do_repair_vectors(
+        toc.get_ref(),
+        &collection.name,
+        operation,
+        None,
+        params.target_shard,
+        wait,
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
+#[post("/collections/{name}/points/vectors/default")]
+async fn set_default_vector(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<SetDefaultVectorByFilter>,
+    params: Query<VectorUpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = do_set_default_vector(
+        toc.get_ref(),
+        &collection.name,
+        operation,
+        None,
+        params.target_shard,
         wait,
         ordering,
     )
@@ -150,6 +265,30 @@ async fn set_payload(
     process_response(response, timing)
 }
 
+#[post("/collections/{name}/points/payload/nested")]
+async fn set_nested_payload(
+    toc: web::Data<TableOfContent>,
+    collection: Path<CollectionPath>,
+    operation: Json<SetNestedPayload>,
+    params: Query<UpdateParam>,
+) -> impl Responder {
+    let timing = Instant::now();
+    let operation = operation.into_inner();
+    let wait = params.wait.unwrap_or(false);
+    let ordering = params.ordering.unwrap_or_default();
+
+    let response = do_set_nested_payload(
+        toc.get_ref(),
+        &collection.name,
+        operation,
+        None,
+        wait,
+        ordering,
+    )
+    .await;
+    process_response(response, timing)
+}
+
 #[put("/collections/{name}/points/payload")]
 async fn overwrite_payload(
     toc: web::Data<TableOfContent>,
@@ -275,7 +414,12 @@ pub fn config_update_api(cfg: &mut web::ServiceConfig) {
         .service(delete_points)
         .service(update_vectors)
         .service(delete_vectors)
+        .service(reindex_vectors)
+        .service(concat_vectors)
+        .service(repair_vectors)
+        .service(set_default_vector)
         .service(set_payload)
+        .service(set_nested_payload)
         .service(overwrite_payload)
         .service(delete_payload)
         .service(clear_payload)
@@ -1,5 +1,7 @@
 use collection::operations::consistency_params::ReadConsistency;
-use collection::operations::payload_ops::{DeletePayload, PayloadOps, SetPayload};
+use collection::operations::payload_ops::{
+    DeletePayload, PayloadOps, SetNestedPayload, SetPayload,
+};
 use collection::operations::point_ops::{
     PointInsertOperations, PointOperations, PointsSelector, WriteOrdering,
 };
@@ -8,7 +10,10 @@ use collection::operations::types::{
     ScrollRequest, ScrollResult, SearchGroupsRequest, SearchRequest, SearchRequestBatch,
     UpdateResult,
 };
-use collection::operations::vector_ops::{DeleteVectors, UpdateVectors, VectorOperations};
+use collection::operations::vector_ops::{
+    ConcatVectors, DeleteVectors, ReindexVectors, RepairVectors, SetDefaultVectorByFilter,
+    UpdateVectors, VectorOperations,
+};
 use collection::operations::{CollectionUpdateOperations, CreateIndex, FieldIndexOperations};
 use collection::shards::shard::ShardId;
 use schemars::JsonSchema;
@@ -77,15 +82,17 @@ pub async fn do_update_vectors(
     collection_name: &str,
     operation: UpdateVectors,
     shard_selection: Option<ShardId>,
+    target_shard: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
     let collection_operation =
         CollectionUpdateOperations::VectorOperation(VectorOperations::UpdateVectors(operation));
-    toc.update(
+    toc.update_with_target(
         collection_name,
         collection_operation,
         shard_selection,
+        target_shard,
         wait,
         ordering,
     )
@@ -97,6 +104,7 @@ pub async fn do_delete_vectors(
     collection_name: &str,
     operation: DeleteVectors,
     shard_selection: Option<ShardId>,
+    target_shard: Option<ShardId>,
     wait: bool,
     ordering: WriteOrdering,
 ) -> Result<UpdateResult, StorageError> {
@@ -105,14 +113,21 @@ pub async fn do_delete_vectors(
     let mut result = None;
 
     if let Some(filter) = operation.filter {
+        if let Some(max_points) = operation.max_points {
+            toc.get_collection(collection_name)
+                .await?
+                .check_filter_points_limit(&filter, max_points, shard_selection.or(target_shard))
+                .await?;
+        }
         let vectors_operation =
             VectorOperations::DeleteVectorsByFilter(filter, vector_names.clone());
         let collection_operation = CollectionUpdateOperations::VectorOperation(vectors_operation);
         result = Some(
-            toc.update(
+            toc.update_with_target(
                 collection_name,
                 collection_operation,
                 shard_selection,
+                target_shard,
                 wait,
                 ordering,
             )
@@ -124,10 +139,11 @@ pub async fn do_delete_vectors(
         let vectors_operation = VectorOperations::DeleteVectors(points.into(), vector_names);
         let collection_operation = CollectionUpdateOperations::VectorOperation(vectors_operation);
         result = Some(
-            toc.update(
+            toc.update_with_target(
                 collection_name,
                 collection_operation,
                 shard_selection,
+                target_shard,
                 wait,
                 ordering,
             )
@@ -138,6 +154,95 @@ pub async fn do_delete_vectors(
     result.ok_or_else(|| StorageError::bad_request("No filter or points provided"))
 }
 
+pub async fn do_reindex_vectors(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operation: ReindexVectors,
+    shard_selection: Option<ShardId>,
+    target_shard: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation =
+        CollectionUpdateOperations::VectorOperation(VectorOperations::ReindexVectors(operation));
+    toc.update_with_target(
+        collection_name,
+        collection_operation,
+        shard_selection,
+        target_shard,
+        wait,
+        ordering,
+    )
+    .await
+}
+
+pub async fn do_concat_vectors(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operation: ConcatVectors,
+    shard_selection: Option<ShardId>,
+    target_shard: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation =
+        CollectionUpdateOperations::VectorOperation(VectorOperations::ConcatVectors(operation));
+    toc.update_with_target(
+        collection_name,
+        collection_operation,
+        shard_selection,
+        target_shard,
+        wait,
+        ordering,
+    )
+    .await
+}
+
+pub async fn do_repair_vectors(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operation: RepairVectors,
+    shard_selection: Option<ShardId>,
+    target_shard: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation =
+        CollectionUpdateOperations::VectorOperation(VectorOperations::RepairVectors(operation));
+    toc.update_with_target(
+        collection_name,
+        collection_operation,
+        shard_selection,
+        target_shard,
+        wait,
+        ordering,
+    )
+    .await
+}
+
+pub async fn do_set_default_vector(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operation: SetDefaultVectorByFilter,
+    shard_selection: Option<ShardId>,
+    target_shard: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation = CollectionUpdateOperations::VectorOperation(
+        VectorOperations::SetDefaultVectorByFilter(operation),
+    );
+    toc.update_with_target(
+        collection_name,
+        collection_operation,
+        shard_selection,
+        target_shard,
+        wait,
+        ordering,
+    )
+    .await
+}
+
 pub async fn do_set_payload(
     toc: &TableOfContent,
     collection_name: &str,
@@ -178,6 +283,26 @@ pub async fn do_overwrite_payload(
     .await
 }
 
+pub async fn do_set_nested_payload(
+    toc: &TableOfContent,
+    collection_name: &str,
+    operation: SetNestedPayload,
+    shard_selection: Option<ShardId>,
+    wait: bool,
+    ordering: WriteOrdering,
+) -> Result<UpdateResult, StorageError> {
+    let collection_operation =
+        CollectionUpdateOperations::PayloadOperation(PayloadOps::SetNestedPayload(operation));
+    toc.update(
+        collection_name,
+        collection_operation,
+        shard_selection,
+        wait,
+        ordering,
+    )
+    .await
+}
+
 pub async fn do_delete_payload(
     toc: &TableOfContent,
     collection_name: &str,
@@ -1,7 +1,7 @@
 use api::grpc::models::CollectionsResponse;
 use collection::operations::cluster_ops::ClusterOperations;
 use collection::operations::consistency_params::ReadConsistency;
-use collection::operations::payload_ops::{DeletePayload, SetPayload};
+use collection::operations::payload_ops::{DeletePayload, SetNestedPayload, SetPayload};
 use collection::operations::point_ops::{PointInsertOperations, PointsSelector, WriteOrdering};
 use collection::operations::snapshot_ops::{SnapshotDescription, SnapshotRecover};
 use collection::operations::types::{
@@ -10,7 +10,10 @@ use collection::operations::types::{
     RecommendRequest, RecommendRequestBatch, Record, ScrollRequest, ScrollResult,
     SearchGroupsRequest, SearchRequest, SearchRequestBatch, UpdateResult,
 };
-use collection::operations::vector_ops::{DeleteVectors, UpdateVectors};
+use collection::operations::vector_ops::{
+    ConcatVectors, DeleteVectors, ReindexVectors, RepairVectors, SetDefaultVectorByFilter,
+    UpdateVectors,
+};
 use schemars::gen::SchemaSettings;
 use schemars::JsonSchema;
 use segment::types::{PointGroup, ScoredPoint};
@@ -71,6 +74,11 @@ struct AllDefinitions {
     b5: SearchGroupsRequest,
     b6: RecommendGroupsRequest,
     b7: GroupsResult,
+    b8: ReindexVectors,
+    b9: ConcatVectors,
+    b10: SetNestedPayload,
+    b11: RepairVectors,
+    b12: SetDefaultVectorByFilter,
 }
 
 fn save_schema<T: JsonSchema>() {
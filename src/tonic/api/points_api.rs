@@ -2,12 +2,13 @@ use std::sync::Arc;
 
 use api::grpc::qdrant::points_server::Points;
 use api::grpc::qdrant::{
-    ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
+    ClearPayloadPoints, ConcatPointVectors, CountPoints, CountResponse, CreateFieldIndexCollection,
     DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints, GetPoints,
     GetResponse, PointsOperationResponse, RecommendBatchPoints, RecommendBatchResponse,
     RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
-    ScrollPoints, ScrollResponse, SearchBatchPoints, SearchBatchResponse, SearchGroupsResponse,
-    SearchPointGroups, SearchPoints, SearchResponse, SetPayloadPoints, UpdatePointVectors,
+    ReindexPointVectors, RepairPointVectors, ScrollPoints, ScrollResponse, SearchBatchPoints,
+    SearchBatchResponse, SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse,
+    SetDefaultPointVectors, SetNestedPayloadPoints, SetPayloadPoints, UpdatePointVectors,
     UpsertPoints,
 };
 use storage::content_manager::toc::TableOfContent;
@@ -16,9 +17,10 @@ use tonic::{Request, Response, Status};
 use super::points_common::{delete_vectors, recommend_groups, search_groups, update_vectors};
 use super::validate;
 use crate::tonic::api::points_common::{
-    clear_payload, count, create_field_index, delete, delete_field_index, delete_payload, get,
-    overwrite_payload, recommend, recommend_batch, scroll, search, search_batch, set_payload,
-    upsert,
+    clear_payload, concat_vectors, count, create_field_index, delete, delete_field_index,
+    delete_payload, get, overwrite_payload, recommend, recommend_batch, reindex_vectors,
+    repair_vectors, scroll, search, search_batch, set_default_vector, set_nested_payload,
+    set_payload, upsert,
 };
 
 pub struct PointsService {
@@ -70,6 +72,38 @@ impl Points for PointsService {
         delete_vectors(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    async fn reindex_vectors(
+        &self,
+        request: Request<ReindexPointVectors>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        reindex_vectors(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
+    async fn concat_vectors(
+        &self,
+        request: Request<ConcatPointVectors>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        concat_vectors(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
+    async fn repair_vectors(
+        &self,
+        request: Request<RepairPointVectors>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        repair_vectors(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
+    async fn set_default_vector(
+        &self,
+        request: Request<SetDefaultPointVectors>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        set_default_vector(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
     async fn set_payload(
         &self,
         request: Request<SetPayloadPoints>,
@@ -78,6 +112,14 @@ impl Points for PointsService {
         set_payload(self.toc.as_ref(), request.into_inner(), None).await
     }
 
+    async fn set_nested_payload(
+        &self,
+        request: Request<SetNestedPayloadPoints>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate(request.get_ref())?;
+        set_nested_payload(self.toc.as_ref(), request.into_inner(), None).await
+    }
+
     async fn overwrite_payload(
         &self,
         request: Request<SetPayloadPoints>,
@@ -3,16 +3,19 @@ use std::time::Instant;
 use api::grpc::conversions::proto_to_payloads;
 use api::grpc::qdrant::payload_index_params::IndexParams;
 use api::grpc::qdrant::{
-    BatchResult, ClearPayloadPoints, CountPoints, CountResponse, CreateFieldIndexCollection,
-    DeleteFieldIndexCollection, DeletePayloadPoints, DeletePointVectors, DeletePoints, FieldType,
-    GetPoints, GetResponse, PayloadIndexParams, PointsOperationResponse,
-    ReadConsistency as ReadConsistencyGrpc, RecommendBatchResponse, RecommendGroupsResponse,
-    RecommendPointGroups, RecommendPoints, RecommendResponse, ScrollPoints, ScrollResponse,
-    SearchBatchResponse, SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse,
-    SetPayloadPoints, SyncPoints, UpdatePointVectors, UpsertPoints,
+    BatchResult, ClearPayloadPoints, ConcatPointVectors, CountPoints, CountResponse,
+    CreateFieldIndexCollection, DeleteFieldIndexCollection, DeletePayloadPoints,
+    DeletePointVectors, DeletePoints, FieldType, GetPoints, GetResponse, PayloadIndexParams,
+    PointsOperationResponse, ReadConsistency as ReadConsistencyGrpc, RecommendBatchResponse,
+    RecommendGroupsResponse, RecommendPointGroups, RecommendPoints, RecommendResponse,
+    ReindexPointVectors, RepairPointVectors, ScrollPoints, ScrollResponse, SearchBatchResponse,
+    SearchGroupsResponse, SearchPointGroups, SearchPoints, SearchResponse, SetDefaultPointVectors,
+    SetNestedPayloadPoints, SetPayloadPoints, SyncPoints, UpdatePointVectors, UpsertPoints,
 };
 use collection::operations::consistency_params::ReadConsistency;
-use collection::operations::conversions::write_ordering_from_proto;
+use collection::operations::conversions::{
+    dimension_repair_policy_from_proto, write_ordering_from_proto,
+};
 use collection::operations::payload_ops::DeletePayload;
 use collection::operations::point_ops::{
     PointInsertOperations, PointOperations, PointSyncOperation, PointsSelector,
@@ -21,7 +24,10 @@ use collection::operations::types::{
     default_exact_count, PointRequest, RecommendRequestBatch, ScrollRequest, SearchRequest,
     SearchRequestBatch,
 };
-use collection::operations::vector_ops::{DeleteVectors, PointVectors, UpdateVectors};
+use collection::operations::vector_ops::{
+    ConcatVectors, DeleteVectors, PointVectors, ReindexVectors, RepairVectors,
+    SetDefaultVectorByFilter, UpdateVectors,
+};
 use collection::operations::CollectionUpdateOperations;
 use collection::shards::shard::ShardId;
 use segment::data_types::named_vectors::NamedVectors;
@@ -32,10 +38,11 @@ use storage::content_manager::toc::TableOfContent;
 use tonic::{Response, Status};
 
 use crate::common::points::{
-    do_clear_payload, do_count_points, do_create_index, do_delete_index, do_delete_payload,
-    do_delete_points, do_delete_vectors, do_get_points, do_overwrite_payload, do_scroll_points,
-    do_search_batch_points, do_search_points, do_set_payload, do_update_vectors, do_upsert_points,
-    CreateFieldIndex,
+    do_clear_payload, do_concat_vectors, do_count_points, do_create_index, do_delete_index,
+    do_delete_payload, do_delete_points, do_delete_vectors, do_get_points, do_overwrite_payload,
+    do_reindex_vectors, do_repair_vectors, do_scroll_points, do_search_batch_points,
+    do_search_points, do_set_default_vector, do_set_nested_payload, do_set_payload,
+    do_update_vectors, do_upsert_points, CreateFieldIndex,
 };
 
 pub fn points_operation_response(
@@ -166,6 +173,8 @@ pub async fn update_vectors(
         wait,
         points,
         ordering,
+        partial,
+        target_shard,
     } = update_point_vectors;
 
     // Build list of operation points
@@ -192,7 +201,10 @@ pub async fn update_vectors(
         });
     }
 
-    let operation = UpdateVectors { points: op_points };
+    let operation = UpdateVectors {
+        points: op_points,
+        partial: partial.unwrap_or(false),
+    };
 
     let timing = Instant::now();
     let result = do_update_vectors(
@@ -200,6 +212,7 @@ pub async fn update_vectors(
         &collection_name,
         operation,
         shard_selection,
+        target_shard,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -221,6 +234,8 @@ pub async fn delete_vectors(
         points_selector,
         vectors,
         ordering,
+        max_points,
+        target_shard,
     } = delete_point_vectors;
 
     let (points, filter) = if let Some(points_selector) = points_selector {
@@ -242,6 +257,7 @@ pub async fn delete_vectors(
         points,
         filter,
         vector: vector_names.into_iter().collect(),
+        max_points: max_points.map(|max_points| max_points as usize),
     };
 
     let timing = Instant::now();
@@ -250,6 +266,149 @@ pub async fn delete_vectors(
         &collection_name,
         operation,
         shard_selection,
+        target_shard,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
+pub async fn reindex_vectors(
+    toc: &TableOfContent,
+    reindex_point_vectors: ReindexPointVectors,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let ReindexPointVectors {
+        collection_name,
+        wait,
+        vector_name,
+        ordering,
+        target_shard,
+    } = reindex_point_vectors;
+
+    let operation = ReindexVectors { name: vector_name };
+
+    let timing = Instant::now();
+    let result = do_reindex_vectors(
+        toc,
+        &collection_name,
+        operation,
+        shard_selection,
+        target_shard,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
+pub async fn concat_vectors(
+    toc: &TableOfContent,
+    concat_point_vectors: ConcatPointVectors,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let ConcatPointVectors {
+        collection_name,
+        wait,
+        sources,
+        target,
+        ordering,
+        target_shard,
+    } = concat_point_vectors;
+
+    let operation = ConcatVectors { sources, target };
+
+    let timing = Instant::now();
+    let result = do_concat_vectors(
+        toc,
+        &collection_name,
+        operation,
+        shard_selection,
+        target_shard,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
+pub async fn repair_vectors(
+    toc: &TableOfContent,
+    repair_point_vectors: RepairPointVectors,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let RepairPointVectors {
+        collection_name,
+        wait,
+        vector_name,
+        policy,
+        ordering,
+        target_shard,
+    } = repair_point_vectors;
+
+    let operation = RepairVectors {
+        name: vector_name,
+        policy: dimension_repair_policy_from_proto(policy)?,
+    };
+
+    let timing = Instant::now();
+    let result = do_repair_vectors(
+        toc,
+        &collection_name,
+        operation,
+        shard_selection,
+        target_shard,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
+pub async fn set_default_vector(
+    toc: &TableOfContent,
+    set_default_point_vectors: SetDefaultPointVectors,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let SetDefaultPointVectors {
+        collection_name,
+        wait,
+        filter,
+        vector_name,
+        default_vector,
+        ordering,
+        target_shard,
+    } = set_default_point_vectors;
+
+    let filter = filter.ok_or_else(|| Status::invalid_argument("filter is expected"))?;
+    let default_vector =
+        default_vector.ok_or_else(|| Status::invalid_argument("default vector is expected"))?;
+    let operation = SetDefaultVectorByFilter {
+        filter: filter.try_into()?,
+        name: vector_name,
+        default: default_vector.data.into(),
+    };
+
+    let timing = Instant::now();
+    let result = do_set_default_vector(
+        toc,
+        &collection_name,
+        operation,
+        shard_selection,
+        target_shard,
         wait.unwrap_or(false),
         write_ordering_from_proto(ordering)?,
     )
@@ -350,6 +509,48 @@ pub async fn overwrite_payload(
     Ok(Response::new(response))
 }
 
+pub async fn set_nested_payload(
+    toc: &TableOfContent,
+    set_nested_payload_points: SetNestedPayloadPoints,
+    shard_selection: Option<ShardId>,
+) -> Result<Response<PointsOperationResponse>, Status> {
+    let SetNestedPayloadPoints {
+        collection_name,
+        wait,
+        payload,
+        key,
+        filter,
+        points,
+        ordering,
+    } = set_nested_payload_points;
+
+    let filter = filter.ok_or_else(|| Status::invalid_argument("filter is expected"))?;
+    let operation = collection::operations::payload_ops::SetNestedPayload {
+        payload: proto_to_payloads(payload)?,
+        key,
+        filter: filter.try_into()?,
+        points: points
+            .into_iter()
+            .map(|id| id.try_into())
+            .collect::<Result<_, _>>()?,
+    };
+
+    let timing = Instant::now();
+    let result = do_set_nested_payload(
+        toc,
+        &collection_name,
+        operation,
+        shard_selection,
+        wait.unwrap_or(false),
+        write_ordering_from_proto(ordering)?,
+    )
+    .await
+    .map_err(error_to_status)?;
+
+    let response = points_operation_response(timing, result);
+    Ok(Response::new(response))
+}
+
 pub async fn delete_payload(
     toc: &TableOfContent,
     delete_payload_points: DeletePayloadPoints,
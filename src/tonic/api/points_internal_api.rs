@@ -2,22 +2,24 @@ use std::sync::Arc;
 
 use api::grpc::qdrant::points_internal_server::PointsInternal;
 use api::grpc::qdrant::{
-    ClearPayloadPointsInternal, CountPointsInternal, CountResponse,
+    ClearPayloadPointsInternal, ConcatVectorsInternal, CountPointsInternal, CountResponse,
     CreateFieldIndexCollectionInternal, DeleteFieldIndexCollectionInternal,
     DeletePayloadPointsInternal, DeletePointsInternal, DeleteVectorsInternal, GetPointsInternal,
     GetResponse, PointsOperationResponse, RecommendPointsInternal, RecommendResponse,
-    ScrollPointsInternal, ScrollResponse, SearchBatchPointsInternal, SearchBatchResponse,
-    SearchPointsInternal, SearchResponse, SetPayloadPointsInternal, SyncPointsInternal,
-    UpdateVectorsInternal, UpsertPointsInternal,
+    ReindexVectorsInternal, RepairVectorsInternal, ScrollPointsInternal, ScrollResponse,
+    SearchBatchPointsInternal, SearchBatchResponse, SearchPointsInternal, SearchResponse,
+    SetDefaultVectorInternal, SetNestedPayloadPointsInternal, SetPayloadPointsInternal,
+    SyncPointsInternal, UpdateVectorsInternal, UpsertPointsInternal,
 };
 use storage::content_manager::toc::TableOfContent;
 use tonic::{Request, Response, Status};
 
 use super::validate_and_log;
 use crate::tonic::api::points_common::{
-    clear_payload, count, create_field_index, delete, delete_field_index, delete_payload,
-    delete_vectors, get, overwrite_payload, recommend, scroll, search, search_batch, set_payload,
-    sync, update_vectors, upsert,
+    clear_payload, concat_vectors, count, create_field_index, delete, delete_field_index,
+    delete_payload, delete_vectors, get, overwrite_payload, recommend, reindex_vectors,
+    repair_vectors, scroll, search, search_batch, set_default_vector, set_nested_payload,
+    set_payload, sync, update_vectors, upsert,
 };
 
 /// This API is intended for P2P communication within a distributed deployment.
@@ -95,6 +97,70 @@ impl PointsInternal for PointsInternalService {
         delete_vectors(self.toc.as_ref(), delete_point_vectors, shard_id).await
     }
 
+    async fn reindex_vectors(
+        &self,
+        request: Request<ReindexVectorsInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let ReindexVectorsInternal {
+            reindex_vectors: reindex_point_vectors,
+            shard_id,
+        } = request.into_inner();
+
+        let reindex_point_vectors = reindex_point_vectors
+            .ok_or_else(|| Status::invalid_argument("ReindexVectors is missing"))?;
+
+        reindex_vectors(self.toc.as_ref(), reindex_point_vectors, shard_id).await
+    }
+
+    async fn concat_vectors(
+        &self,
+        request: Request<ConcatVectorsInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let ConcatVectorsInternal {
+            concat_vectors: concat_point_vectors,
+            shard_id,
+        } = request.into_inner();
+
+        let concat_point_vectors = concat_point_vectors
+            .ok_or_else(|| Status::invalid_argument("ConcatVectors is missing"))?;
+
+        concat_vectors(self.toc.as_ref(), concat_point_vectors, shard_id).await
+    }
+
+    async fn repair_vectors(
+        &self,
+        request: Request<RepairVectorsInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let RepairVectorsInternal {
+            repair_vectors: repair_point_vectors,
+            shard_id,
+        } = request.into_inner();
+
+        let repair_point_vectors = repair_point_vectors
+            .ok_or_else(|| Status::invalid_argument("RepairVectors is missing"))?;
+
+        repair_vectors(self.toc.as_ref(), repair_point_vectors, shard_id).await
+    }
+
+    async fn set_default_vector(
+        &self,
+        request: Request<SetDefaultVectorInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let SetDefaultVectorInternal {
+            set_default_vector: set_default_point_vectors,
+            shard_id,
+        } = request.into_inner();
+
+        let set_default_point_vectors = set_default_point_vectors
+            .ok_or_else(|| Status::invalid_argument("SetDefaultVector is missing"))?;
+
+        set_default_vector(self.toc.as_ref(), set_default_point_vectors, shard_id).await
+    }
+
     async fn set_payload(
         &self,
         request: Request<SetPayloadPointsInternal>,
@@ -319,4 +385,20 @@ impl PointsInternal for PointsInternalService {
 
         overwrite_payload(self.toc.as_ref(), set_payload_points, shard_id).await
     }
+
+    async fn set_nested_payload(
+        &self,
+        request: Request<SetNestedPayloadPointsInternal>,
+    ) -> Result<Response<PointsOperationResponse>, Status> {
+        validate_and_log(request.get_ref());
+        let SetNestedPayloadPointsInternal {
+            set_nested_payload_points,
+            shard_id,
+        } = request.into_inner();
+
+        let set_nested_payload_points = set_nested_payload_points
+            .ok_or_else(|| Status::invalid_argument("SetNestedPayloadPoints is missing"))?;
+
+        set_nested_payload(self.toc.as_ref(), set_nested_payload_points, shard_id).await
+    }
 }